@@ -1,5 +1,9 @@
+use crate::api::error::ApiError;
 use crate::api::types::*;
+use crate::manifest::plan::Change;
 use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::time::Duration;
 
 /// Actions flow through the app as a message bus.
 /// Components emit actions, the app loop dispatches them.
@@ -27,6 +31,15 @@ pub enum Action {
     SchedulesLoaded(Vec<Schedule>),
     VariationsLoaded(Vec<Variation>),
     DashboardLoaded(DashboardData),
+    SimulationDataLoaded {
+        environments: Vec<Environment>,
+        variations: HashMap<String, Vec<Variation>>, // keyed by environment ID
+    },
+    EnvironmentStateLoaded {
+        environments: Vec<Environment>,
+        flags: Vec<ManagedFlag>,
+        configs: Vec<ManagedConfig>,
+    },
 
     // Mutations completed
     FlagCreated(Box<ManagedFlag>),
@@ -38,7 +51,12 @@ pub enum Action {
     VariationsUpdated(Vec<Variation>),
     VariationsDeleted,
     ScheduleCreated(Box<Schedule>),
+    /// The server rejected a new schedule; the message goes on the form.
+    ScheduleCreateFailed(String),
     ScheduleCancelled(String),
+    RolloutPlanCreated(Vec<Schedule>),
+    RolloutPlanCancelled(Option<String>), // remaining steps, when paused
+    PromotionApplied(usize),              // number of changes applied
     ConfigCreated(Box<ManagedConfig>),
     ConfigUpdated(Box<ManagedConfig>),
     ConfigDeleted(String),
@@ -59,15 +77,28 @@ pub enum Action {
     ConfirmAccepted,
     ConfirmDismissed,
     ApiError(String),
+    /// The server rejected the session token; the user has to log in again.
+    Unauthorized,
+    /// The server rate limited a request; it may be retried after the wait.
+    RateLimited(Duration),
+    OpenSimulator(String), // flag key
+    LoadSchedules {
+        flag_key: String,
+        environment_id: String,
+    },
+    LoadEnvironmentState,
     Tick,
     SetLoading(bool),
 
     // Form submissions
     SubmitFlagCreate,
-    SubmitFlagUpdate(String),    // original key
-    SubmitFlagToggle(String),    // flag key
-    SubmitRolloutUpdate(String), // flag key
-    SubmitRulesUpdate(String),   // flag key
+    SubmitFlagUpdate(String),       // original key
+    SubmitFlagToggle(String),       // flag key
+    SubmitRolloutUpdate(String),    // flag key
+    SubmitRulesUpdate(String),      // flag key
+    SubmitVariationsUpdate(String), // flag key
+    SubmitScheduleCreate(String),   // flag key
+    SubmitRolloutPlan(String),      // flag key
     SubmitConfigCreate,
     SubmitConfigUpdate(String),      // original key
     SubmitConfigValueUpdate(String), // config key
@@ -101,6 +132,23 @@ pub enum Action {
         environment_name: String,
     },
     EnvironmentSwitcherDismissed,
+
+    // Profile switcher
+    ProfileSwitched(String),
+    ProfileSwitcherDismissed,
+}
+
+/// API errors become `ApiError` actions, except a rejected session, which
+/// starts a new login, and rate limits with a known wait, which the status
+/// bar counts down.
+impl From<ApiError> for Action {
+    fn from(e: ApiError) -> Self {
+        match e {
+            ApiError::Unauthorized => Action::Unauthorized,
+            ApiError::RateLimited(Some(wait)) => Action::RateLimited(wait),
+            e => Action::ApiError(e.to_string()),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -126,7 +174,23 @@ pub enum ConfirmAction {
         flag_key: String,
         schedule_id: String,
     },
-    DeleteVariations(String),
+    DeleteVariations {
+        flag_key: String,
+        environment_id: String,
+    },
+    /// Cancel the pending steps of a rollout plan. `resume_steps` is set when
+    /// pausing, so the plan can be started again from where it stopped.
+    CancelRolloutPlan {
+        flag_key: String,
+        schedule_ids: Vec<String>,
+        resume_steps: Option<String>,
+    },
+    /// Apply promotion changes, in order, from one environment (slug) to another.
+    Promote {
+        from: String,
+        to: String,
+        changes: Vec<Change>,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -167,6 +231,8 @@ pub enum View {
     WebhookCreate,
     WebhookEdit(String),
     EnvironmentList,
+    EnvironmentMatrix,
+    Promote,
 }
 
 #[derive(Debug, Clone)]
@@ -0,0 +1,288 @@
+use crate::api::client::ApiClient;
+use crate::api::error::ApiError;
+use crate::api::fixtures::Recorder;
+use crate::api::memory::MemoryBackend;
+use crate::api::types::*;
+use crate::config::AppConfig;
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Every FlagDash endpoint the CLI uses. `ApiClient` talks to a server over
+/// HTTP; `MemoryBackend` simulates one in-process for tests and demos.
+#[async_trait]
+pub trait FlagDashApi: Send + Sync {
+    // ── Device Auth ─────────────────────────────────────────────────
+
+    async fn request_device_auth(
+        &self,
+        device_name: Option<&str>,
+    ) -> Result<DeviceAuthResponse, ApiError>;
+
+    async fn poll_device_token(&self, device_code: &str) -> Result<DeviceTokenResponse, ApiError>;
+
+    /// Poll until the device code is approved, denied or expires. Waits
+    /// `interval` seconds between polls, five more after each `slow_down`.
+    /// Returns the final response: either with a session token or with a
+    /// terminal error (`expired_token` when the code ran out).
+    async fn wait_for_device_token(
+        &self,
+        auth: &DeviceAuthResponse,
+    ) -> Result<DeviceTokenResponse, ApiError> {
+        let deadline =
+            tokio::time::Instant::now() + std::time::Duration::from_secs(auth.expires_in);
+        let mut interval = auth.interval.max(1);
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(interval)).await;
+            if tokio::time::Instant::now() >= deadline {
+                break;
+            }
+            let resp = self.poll_device_token(&auth.device_code).await?;
+            match resp.error.as_deref() {
+                Some("authorization_pending") => {}
+                Some("slow_down") => interval += 5,
+                _ => return Ok(resp),
+            }
+        }
+        Ok(DeviceTokenResponse {
+            session_token: None,
+            account: None,
+            user: None,
+            expires_at: None,
+            error: Some("expired_token".to_string()),
+        })
+    }
+
+    // ── Flags ────────────────────────────────────────────────────────
+
+    async fn list_flags(&self, project_id: &str) -> Result<Vec<ManagedFlag>, ApiError>;
+
+    async fn get_flag(&self, key: &str, project_id: &str) -> Result<ManagedFlag, ApiError>;
+
+    async fn create_flag(&self, req: &CreateFlagRequest) -> Result<ManagedFlag, ApiError>;
+
+    async fn update_flag(
+        &self,
+        key: &str,
+        project_id: &str,
+        req: &UpdateFlagRequest,
+    ) -> Result<ManagedFlag, ApiError>;
+
+    async fn delete_flag(&self, key: &str, project_id: &str) -> Result<(), ApiError>;
+
+    async fn toggle_flag(
+        &self,
+        key: &str,
+        project_id: &str,
+        environment_id: &str,
+    ) -> Result<FlagEnvironmentResponse, ApiError>;
+
+    async fn set_rollout(
+        &self,
+        key: &str,
+        project_id: &str,
+        environment_id: &str,
+        percentage: i32,
+    ) -> Result<FlagEnvironmentResponse, ApiError>;
+
+    async fn update_rules(
+        &self,
+        key: &str,
+        project_id: &str,
+        environment_id: &str,
+        rules: serde_json::Value,
+    ) -> Result<FlagEnvironmentResponse, ApiError>;
+
+    async fn list_variations(
+        &self,
+        key: &str,
+        project_id: &str,
+        environment_id: &str,
+    ) -> Result<Vec<Variation>, ApiError>;
+
+    async fn set_variations(
+        &self,
+        key: &str,
+        project_id: &str,
+        environment_id: &str,
+        variations: Vec<VariationInput>,
+    ) -> Result<Vec<Variation>, ApiError>;
+
+    async fn delete_variations(
+        &self,
+        key: &str,
+        project_id: &str,
+        environment_id: &str,
+    ) -> Result<(), ApiError>;
+
+    // ── Schedules ────────────────────────────────────────────────────
+
+    async fn list_schedules(
+        &self,
+        key: &str,
+        project_id: &str,
+        environment_id: &str,
+    ) -> Result<Vec<Schedule>, ApiError>;
+
+    async fn create_schedule(
+        &self,
+        key: &str,
+        project_id: &str,
+        environment_id: &str,
+        req: &CreateScheduleRequest,
+    ) -> Result<Schedule, ApiError>;
+
+    async fn cancel_schedule(
+        &self,
+        key: &str,
+        project_id: &str,
+        schedule_id: &str,
+    ) -> Result<(), ApiError>;
+
+    // ── Configs ──────────────────────────────────────────────────────
+
+    async fn list_configs(&self, project_id: &str) -> Result<Vec<ManagedConfig>, ApiError>;
+
+    async fn get_config(&self, key: &str, project_id: &str) -> Result<ManagedConfig, ApiError>;
+
+    async fn create_config(&self, req: &CreateConfigRequest) -> Result<ManagedConfig, ApiError>;
+
+    async fn update_config(
+        &self,
+        key: &str,
+        project_id: &str,
+        req: &UpdateConfigRequest,
+    ) -> Result<ManagedConfig, ApiError>;
+
+    async fn delete_config(&self, key: &str, project_id: &str) -> Result<(), ApiError>;
+
+    async fn set_config_value(
+        &self,
+        key: &str,
+        project_id: &str,
+        environment_id: &str,
+        value: serde_json::Value,
+    ) -> Result<ConfigEnvironmentResponse, ApiError>;
+
+    // ── AI Configs ───────────────────────────────────────────────────
+
+    async fn list_ai_configs(
+        &self,
+        project_id: &str,
+        environment_id: &str,
+    ) -> Result<Vec<ManagedAiConfig>, ApiError>;
+
+    async fn get_ai_config(
+        &self,
+        file_name: &str,
+        project_id: &str,
+        environment_id: &str,
+    ) -> Result<ManagedAiConfig, ApiError>;
+
+    async fn create_ai_config(
+        &self,
+        req: &CreateAiConfigRequest,
+    ) -> Result<ManagedAiConfig, ApiError>;
+
+    async fn update_ai_config(
+        &self,
+        file_name: &str,
+        project_id: &str,
+        environment_id: &str,
+        req: &UpdateAiConfigRequest,
+    ) -> Result<ManagedAiConfig, ApiError>;
+
+    async fn delete_ai_config(
+        &self,
+        file_name: &str,
+        project_id: &str,
+        environment_id: &str,
+    ) -> Result<(), ApiError>;
+
+    async fn initialize_ai_configs(
+        &self,
+        project_id: &str,
+        environment_id: &str,
+    ) -> Result<Vec<ManagedAiConfig>, ApiError>;
+
+    // ── Webhooks ─────────────────────────────────────────────────────
+
+    async fn list_webhooks(&self, project_id: &str) -> Result<Vec<WebhookEndpoint>, ApiError>;
+
+    async fn get_webhook(&self, id: &str) -> Result<WebhookEndpoint, ApiError>;
+
+    async fn create_webhook(&self, req: &CreateWebhookRequest)
+        -> Result<WebhookEndpoint, ApiError>;
+
+    async fn update_webhook(
+        &self,
+        id: &str,
+        req: &UpdateWebhookRequest,
+    ) -> Result<WebhookEndpoint, ApiError>;
+
+    async fn delete_webhook(&self, id: &str) -> Result<(), ApiError>;
+
+    async fn regenerate_webhook_secret(&self, id: &str) -> Result<WebhookEndpoint, ApiError>;
+
+    async fn reactivate_webhook(&self, id: &str) -> Result<WebhookEndpoint, ApiError>;
+
+    async fn list_webhook_deliveries(
+        &self,
+        id: &str,
+        limit: i32,
+        offset: i32,
+    ) -> Result<Vec<WebhookDelivery>, ApiError>;
+
+    // ── Projects & Environments ─────────────────────────────────────
+
+    async fn list_projects(&self) -> Result<Vec<Project>, ApiError>;
+
+    async fn list_environments(&self, project_id: &str) -> Result<Vec<Environment>, ApiError>;
+
+    // ── Validation ───────────────────────────────────────────────────
+
+    /// Quick health check: tries to list projects. Returns Ok if the key is valid.
+    async fn validate_key(&self) -> Result<(), ApiError> {
+        self.list_projects().await?;
+        Ok(())
+    }
+}
+
+/// A backend shared between the UI and the tasks it spawns.
+pub type SharedApi = Arc<dyn FlagDashApi>;
+
+/// Where API calls go.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Backend {
+    /// The FlagDash server at the profile's base URL.
+    #[default]
+    Http,
+    /// A simulated server with demo data, kept in memory for this run only.
+    Memory,
+}
+
+/// Connect to the configured backend with the profile's session token.
+pub fn connect(config: &AppConfig) -> Result<SharedApi> {
+    match config.backend {
+        Backend::Http => http_client(
+            config,
+            ApiClient::new(&config.connection, &config.auth.session_token)?,
+        ),
+        Backend::Memory => Ok(Arc::new(MemoryBackend::shared())),
+    }
+}
+
+/// Connect without a session, for the device login flow.
+pub fn connect_unauthenticated(config: &AppConfig) -> Result<SharedApi> {
+    match config.backend {
+        Backend::Http => http_client(config, ApiClient::new_unauthenticated(&config.connection)?),
+        Backend::Memory => Ok(Arc::new(MemoryBackend::shared())),
+    }
+}
+
+fn http_client(config: &AppConfig, client: ApiClient) -> Result<SharedApi> {
+    Ok(Arc::new(match &config.record_fixtures {
+        Some(path) => client.recording_to(Recorder::open(path)?),
+        None => client,
+    }))
+}
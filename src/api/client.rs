@@ -1,8 +1,15 @@
+use crate::api::backend::FlagDashApi;
 use crate::api::error::ApiError;
+use crate::api::fixtures::{Recorder, Replay};
+use crate::api::retry::{parse_retry_after, RetryPolicy};
+use crate::api::transport::build_http_client;
 use crate::api::types::*;
-use reqwest::Client;
+use crate::config::ConnectionConfig;
+use async_trait::async_trait;
+use reqwest::{Client, Request, RequestBuilder, Response};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
+use std::sync::Arc;
 
 /// HTTP client for the FlagDash management API.
 #[derive(Clone)]
@@ -10,26 +17,51 @@ pub struct ApiClient {
     client: Client,
     base_url: String,
     session_token: String,
+    retry: RetryPolicy,
+    fixtures: Option<Fixtures>,
+}
+
+/// Recording to or replaying from a fixture file, for tests.
+#[derive(Clone)]
+enum Fixtures {
+    Record(Arc<Recorder>),
+    Replay(Arc<Replay>),
 }
 
 impl ApiClient {
-    pub fn new(base_url: &str, session_token: &str) -> Self {
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .build()
-            .expect("failed to build HTTP client");
-
-        Self {
-            client,
-            base_url: base_url.trim_end_matches('/').to_string(),
+    /// Create a client for a profile's connection settings. Fails if those
+    /// settings can't be applied, e.g. an unreadable CA certificate.
+    pub fn new(connection: &ConnectionConfig, session_token: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            client: build_http_client(connection)?,
+            base_url: connection.base_url.trim_end_matches('/').to_string(),
             session_token: session_token.to_string(),
-        }
+            retry: connection.retry.clone(),
+            fixtures: None,
+        })
+    }
+
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Send requests as usual and append each exchange to a fixture file.
+    pub fn recording_to(mut self, recorder: Recorder) -> Self {
+        self.fixtures = Some(Fixtures::Record(Arc::new(recorder)));
+        self
+    }
+
+    /// Answer requests from a fixture instead of the network.
+    pub fn replaying(mut self, replay: Arc<Replay>) -> Self {
+        self.fixtures = Some(Fixtures::Replay(replay));
+        self
     }
 
     /// Create a client that can only make unauthenticated requests.
     /// Used for device auth flow before the user has a session token.
-    pub fn new_unauthenticated(base_url: &str) -> Self {
-        Self::new(base_url, "")
+    pub fn new_unauthenticated(connection: &ConnectionConfig) -> anyhow::Result<Self> {
+        Self::new(connection, "")
     }
 
     fn url(&self, path: &str) -> String {
@@ -38,12 +70,12 @@ impl ApiClient {
 
     async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, ApiError> {
         let resp = self
-            .client
-            .get(self.url(path))
-            .bearer_auth(&self.session_token)
-            .send()
-            .await
-            .map_err(|e| ApiError::Network(e.to_string()))?;
+            .send(
+                self.client
+                    .get(self.url(path))
+                    .bearer_auth(&self.session_token),
+            )
+            .await?;
 
         self.handle_response(resp).await
     }
@@ -62,10 +94,7 @@ impl ApiClient {
             req = req.json(b);
         }
 
-        let resp = req
-            .send()
-            .await
-            .map_err(|e| ApiError::Network(e.to_string()))?;
+        let resp = self.send(req).await?;
         self.handle_response(resp).await
     }
 
@@ -75,25 +104,25 @@ impl ApiClient {
         body: &B,
     ) -> Result<T, ApiError> {
         let resp = self
-            .client
-            .put(self.url(path))
-            .bearer_auth(&self.session_token)
-            .json(body)
-            .send()
-            .await
-            .map_err(|e| ApiError::Network(e.to_string()))?;
+            .send(
+                self.client
+                    .put(self.url(path))
+                    .bearer_auth(&self.session_token)
+                    .json(body),
+            )
+            .await?;
 
         self.handle_response(resp).await
     }
 
     async fn delete(&self, path: &str) -> Result<(), ApiError> {
         let resp = self
-            .client
-            .delete(self.url(path))
-            .bearer_auth(&self.session_token)
-            .send()
-            .await
-            .map_err(|e| ApiError::Network(e.to_string()))?;
+            .send(
+                self.client
+                    .delete(self.url(path))
+                    .bearer_auth(&self.session_token),
+            )
+            .await?;
 
         let status = resp.status().as_u16();
         if (200..300).contains(&status) {
@@ -104,6 +133,47 @@ impl ApiClient {
         }
     }
 
+    /// Send a request, retrying transient failures as the retry policy allows.
+    async fn send(&self, req: RequestBuilder) -> Result<Response, ApiError> {
+        let request = req.build().map_err(network_error)?;
+        let retryable = self.retry.allows(request.method());
+        let mut attempt = 0;
+        loop {
+            let Some(this) = request.try_clone() else {
+                // Streaming bodies can only be sent once
+                return self.execute(request).await?.map_err(network_error);
+            };
+            let result = self.execute(this).await?;
+            let delay = match &result {
+                Ok(resp) if retryable => {
+                    self.retry
+                        .delay_for(resp.status(), retry_after(resp), attempt)
+                }
+                Ok(_) => None,
+                // A connection that was never made can't have been applied
+                Err(e) if e.is_connect() || (retryable && e.is_timeout()) => {
+                    self.retry.retry_delay(attempt)
+                }
+                Err(_) => None,
+            };
+            match delay {
+                Some(delay) => tokio::time::sleep(delay).await,
+                None => return result.map_err(network_error),
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Send a request once. The outer error is a request missing from the
+    /// replayed fixture; the inner one a network failure that may be retried.
+    async fn execute(&self, request: Request) -> Result<reqwest::Result<Response>, ApiError> {
+        Ok(match &self.fixtures {
+            None => self.client.execute(request).await,
+            Some(Fixtures::Record(recorder)) => recorder.execute(&self.client, request).await,
+            Some(Fixtures::Replay(replay)) => Ok(replay.respond(&request)?),
+        })
+    }
+
     async fn handle_response<T: DeserializeOwned>(
         &self,
         resp: reqwest::Response,
@@ -146,7 +216,7 @@ impl ApiClient {
                 });
                 ApiError::Validation(body.detail().to_string())
             }
-            429 => ApiError::RateLimited,
+            429 => ApiError::RateLimited(retry_after(&resp)),
             _ => {
                 let body: ErrorResponse = resp.json().await.unwrap_or(ErrorResponse {
                     error: format!("HTTP {status}"),
@@ -173,17 +243,17 @@ impl ApiClient {
             req = req.json(b);
         }
 
-        let resp = req
-            .send()
-            .await
-            .map_err(|e| ApiError::Network(e.to_string()))?;
+        let resp = self.send(req).await?;
         self.handle_response(resp).await
     }
+}
 
+#[async_trait]
+impl FlagDashApi for ApiClient {
     // ── Device Auth ─────────────────────────────────────────────────
 
     /// POST /api/v1/auth/device -- Request device authorization
-    pub async fn request_device_auth(
+    async fn request_device_auth(
         &self,
         device_name: Option<&str>,
     ) -> Result<DeviceAuthResponse, ApiError> {
@@ -194,10 +264,7 @@ impl ApiClient {
     }
 
     /// POST /api/v1/auth/device/token -- Poll for token
-    pub async fn poll_device_token(
-        &self,
-        device_code: &str,
-    ) -> Result<DeviceTokenResponse, ApiError> {
+    async fn poll_device_token(&self, device_code: &str) -> Result<DeviceTokenResponse, ApiError> {
         let body = DeviceTokenRequest {
             device_code: device_code.to_string(),
         };
@@ -206,7 +273,7 @@ impl ApiClient {
 
     // ── Flags ────────────────────────────────────────────────────────
 
-    pub async fn list_flags(&self, project_id: &str) -> Result<Vec<ManagedFlag>, ApiError> {
+    async fn list_flags(&self, project_id: &str) -> Result<Vec<ManagedFlag>, ApiError> {
         let resp: ManagedFlagsResponse = self
             .get(&format!(
                 "/manage/flags?project_id={}",
@@ -216,7 +283,7 @@ impl ApiClient {
         Ok(resp.flags)
     }
 
-    pub async fn get_flag(&self, key: &str, project_id: &str) -> Result<ManagedFlag, ApiError> {
+    async fn get_flag(&self, key: &str, project_id: &str) -> Result<ManagedFlag, ApiError> {
         let resp: ManagedFlagResponse = self
             .get(&format!(
                 "/manage/flags/{}?project_id={}",
@@ -227,12 +294,12 @@ impl ApiClient {
         Ok(resp.flag)
     }
 
-    pub async fn create_flag(&self, req: &CreateFlagRequest) -> Result<ManagedFlag, ApiError> {
+    async fn create_flag(&self, req: &CreateFlagRequest) -> Result<ManagedFlag, ApiError> {
         let resp: ManagedFlagResponse = self.post("/manage/flags", Some(req)).await?;
         Ok(resp.flag)
     }
 
-    pub async fn update_flag(
+    async fn update_flag(
         &self,
         key: &str,
         project_id: &str,
@@ -251,7 +318,7 @@ impl ApiClient {
         Ok(resp.flag)
     }
 
-    pub async fn delete_flag(&self, key: &str, project_id: &str) -> Result<(), ApiError> {
+    async fn delete_flag(&self, key: &str, project_id: &str) -> Result<(), ApiError> {
         self.delete(&format!(
             "/manage/flags/{}?project_id={}",
             urlencoding(key),
@@ -260,7 +327,7 @@ impl ApiClient {
         .await
     }
 
-    pub async fn toggle_flag(
+    async fn toggle_flag(
         &self,
         key: &str,
         project_id: &str,
@@ -278,7 +345,7 @@ impl ApiClient {
         .await
     }
 
-    pub async fn set_rollout(
+    async fn set_rollout(
         &self,
         key: &str,
         project_id: &str,
@@ -300,7 +367,7 @@ impl ApiClient {
         .await
     }
 
-    pub async fn update_rules(
+    async fn update_rules(
         &self,
         key: &str,
         project_id: &str,
@@ -320,7 +387,24 @@ impl ApiClient {
         .await
     }
 
-    pub async fn set_variations(
+    async fn list_variations(
+        &self,
+        key: &str,
+        project_id: &str,
+        environment_id: &str,
+    ) -> Result<Vec<Variation>, ApiError> {
+        let resp: VariationsResponse = self
+            .get(&format!(
+                "/manage/flags/{}/variations?project_id={}&environment_id={}",
+                urlencoding(key),
+                urlencoding(project_id),
+                urlencoding(environment_id)
+            ))
+            .await?;
+        Ok(resp.variations)
+    }
+
+    async fn set_variations(
         &self,
         key: &str,
         project_id: &str,
@@ -342,7 +426,7 @@ impl ApiClient {
         Ok(resp.variations)
     }
 
-    pub async fn delete_variations(
+    async fn delete_variations(
         &self,
         key: &str,
         project_id: &str,
@@ -359,7 +443,7 @@ impl ApiClient {
 
     // ── Schedules ────────────────────────────────────────────────────
 
-    pub async fn list_schedules(
+    async fn list_schedules(
         &self,
         key: &str,
         project_id: &str,
@@ -376,7 +460,7 @@ impl ApiClient {
         Ok(resp.schedules)
     }
 
-    pub async fn create_schedule(
+    async fn create_schedule(
         &self,
         key: &str,
         project_id: &str,
@@ -397,7 +481,7 @@ impl ApiClient {
         Ok(resp.schedule)
     }
 
-    pub async fn cancel_schedule(
+    async fn cancel_schedule(
         &self,
         key: &str,
         project_id: &str,
@@ -414,7 +498,7 @@ impl ApiClient {
 
     // ── Configs ──────────────────────────────────────────────────────
 
-    pub async fn list_configs(&self, project_id: &str) -> Result<Vec<ManagedConfig>, ApiError> {
+    async fn list_configs(&self, project_id: &str) -> Result<Vec<ManagedConfig>, ApiError> {
         let resp: ManagedConfigsResponse = self
             .get(&format!(
                 "/manage/configs?project_id={}",
@@ -424,7 +508,7 @@ impl ApiClient {
         Ok(resp.configs)
     }
 
-    pub async fn get_config(&self, key: &str, project_id: &str) -> Result<ManagedConfig, ApiError> {
+    async fn get_config(&self, key: &str, project_id: &str) -> Result<ManagedConfig, ApiError> {
         let resp: ManagedConfigResponse = self
             .get(&format!(
                 "/manage/configs/{}?project_id={}",
@@ -435,15 +519,12 @@ impl ApiClient {
         Ok(resp.config)
     }
 
-    pub async fn create_config(
-        &self,
-        req: &CreateConfigRequest,
-    ) -> Result<ManagedConfig, ApiError> {
+    async fn create_config(&self, req: &CreateConfigRequest) -> Result<ManagedConfig, ApiError> {
         let resp: ManagedConfigResponse = self.post("/manage/configs", Some(req)).await?;
         Ok(resp.config)
     }
 
-    pub async fn update_config(
+    async fn update_config(
         &self,
         key: &str,
         project_id: &str,
@@ -462,7 +543,7 @@ impl ApiClient {
         Ok(resp.config)
     }
 
-    pub async fn delete_config(&self, key: &str, project_id: &str) -> Result<(), ApiError> {
+    async fn delete_config(&self, key: &str, project_id: &str) -> Result<(), ApiError> {
         self.delete(&format!(
             "/manage/configs/{}?project_id={}",
             urlencoding(key),
@@ -471,7 +552,7 @@ impl ApiClient {
         .await
     }
 
-    pub async fn set_config_value(
+    async fn set_config_value(
         &self,
         key: &str,
         project_id: &str,
@@ -493,7 +574,7 @@ impl ApiClient {
 
     // ── AI Configs ───────────────────────────────────────────────────
 
-    pub async fn list_ai_configs(
+    async fn list_ai_configs(
         &self,
         project_id: &str,
         environment_id: &str,
@@ -508,7 +589,7 @@ impl ApiClient {
         Ok(resp.ai_configs)
     }
 
-    pub async fn get_ai_config(
+    async fn get_ai_config(
         &self,
         file_name: &str,
         project_id: &str,
@@ -525,7 +606,7 @@ impl ApiClient {
         Ok(resp.ai_config)
     }
 
-    pub async fn create_ai_config(
+    async fn create_ai_config(
         &self,
         req: &CreateAiConfigRequest,
     ) -> Result<ManagedAiConfig, ApiError> {
@@ -533,7 +614,7 @@ impl ApiClient {
         Ok(resp.ai_config)
     }
 
-    pub async fn update_ai_config(
+    async fn update_ai_config(
         &self,
         file_name: &str,
         project_id: &str,
@@ -554,7 +635,7 @@ impl ApiClient {
         Ok(resp.ai_config)
     }
 
-    pub async fn delete_ai_config(
+    async fn delete_ai_config(
         &self,
         file_name: &str,
         project_id: &str,
@@ -569,7 +650,7 @@ impl ApiClient {
         .await
     }
 
-    pub async fn initialize_ai_configs(
+    async fn initialize_ai_configs(
         &self,
         project_id: &str,
         environment_id: &str,
@@ -586,7 +667,7 @@ impl ApiClient {
 
     // ── Webhooks ─────────────────────────────────────────────────────
 
-    pub async fn list_webhooks(&self, project_id: &str) -> Result<Vec<WebhookEndpoint>, ApiError> {
+    async fn list_webhooks(&self, project_id: &str) -> Result<Vec<WebhookEndpoint>, ApiError> {
         let resp: WebhookEndpointsResponse = self
             .get(&format!(
                 "/manage/webhooks?project_id={}",
@@ -596,14 +677,14 @@ impl ApiClient {
         Ok(resp.endpoints)
     }
 
-    pub async fn get_webhook(&self, id: &str) -> Result<WebhookEndpoint, ApiError> {
+    async fn get_webhook(&self, id: &str) -> Result<WebhookEndpoint, ApiError> {
         let resp: WebhookEndpointResponse = self
             .get(&format!("/manage/webhooks/{}", urlencoding(id)))
             .await?;
         Ok(resp.endpoint)
     }
 
-    pub async fn create_webhook(
+    async fn create_webhook(
         &self,
         req: &CreateWebhookRequest,
     ) -> Result<WebhookEndpoint, ApiError> {
@@ -611,7 +692,7 @@ impl ApiClient {
         Ok(resp.endpoint)
     }
 
-    pub async fn update_webhook(
+    async fn update_webhook(
         &self,
         id: &str,
         req: &UpdateWebhookRequest,
@@ -622,12 +703,12 @@ impl ApiClient {
         Ok(resp.endpoint)
     }
 
-    pub async fn delete_webhook(&self, id: &str) -> Result<(), ApiError> {
+    async fn delete_webhook(&self, id: &str) -> Result<(), ApiError> {
         self.delete(&format!("/manage/webhooks/{}", urlencoding(id)))
             .await
     }
 
-    pub async fn regenerate_webhook_secret(&self, id: &str) -> Result<WebhookEndpoint, ApiError> {
+    async fn regenerate_webhook_secret(&self, id: &str) -> Result<WebhookEndpoint, ApiError> {
         let resp: WebhookEndpointResponse = self
             .post::<(), WebhookEndpointResponse>(
                 &format!("/manage/webhooks/{}/regenerate-secret", urlencoding(id)),
@@ -637,7 +718,7 @@ impl ApiClient {
         Ok(resp.endpoint)
     }
 
-    pub async fn reactivate_webhook(&self, id: &str) -> Result<WebhookEndpoint, ApiError> {
+    async fn reactivate_webhook(&self, id: &str) -> Result<WebhookEndpoint, ApiError> {
         let resp: WebhookEndpointResponse = self
             .post::<(), WebhookEndpointResponse>(
                 &format!("/manage/webhooks/{}/reactivate", urlencoding(id)),
@@ -647,7 +728,7 @@ impl ApiClient {
         Ok(resp.endpoint)
     }
 
-    pub async fn list_webhook_deliveries(
+    async fn list_webhook_deliveries(
         &self,
         id: &str,
         limit: i32,
@@ -666,12 +747,12 @@ impl ApiClient {
 
     // ── Projects & Environments ─────────────────────────────────────
 
-    pub async fn list_projects(&self) -> Result<Vec<Project>, ApiError> {
+    async fn list_projects(&self) -> Result<Vec<Project>, ApiError> {
         let resp: ProjectsResponse = self.get("/manage/projects").await?;
         Ok(resp.projects)
     }
 
-    pub async fn list_environments(&self, project_id: &str) -> Result<Vec<Environment>, ApiError> {
+    async fn list_environments(&self, project_id: &str) -> Result<Vec<Environment>, ApiError> {
         let resp: EnvironmentsResponse = self
             .get(&format!(
                 "/manage/environments?project_id={}",
@@ -680,14 +761,6 @@ impl ApiClient {
             .await?;
         Ok(resp.environments)
     }
-
-    // ── Validation ───────────────────────────────────────────────────
-
-    /// Quick health check: tries to list projects. Returns Ok if the key is valid.
-    pub async fn validate_key(&self) -> Result<(), ApiError> {
-        self.list_projects().await?;
-        Ok(())
-    }
 }
 
 fn urlencoding(s: &str) -> String {
@@ -708,3 +781,505 @@ fn urlencoding_encode(s: &str) -> String {
     }
     result
 }
+
+/// reqwest's own message ("error sending request for url ...") leaves out
+/// the cause, which is what matters for proxy and certificate problems.
+fn network_error(e: reqwest::Error) -> ApiError {
+    let mut message = e.to_string();
+    let mut source = std::error::Error::source(&e);
+    while let Some(cause) = source {
+        message.push_str(&format!(": {}", cause));
+        source = cause.source();
+    }
+    ApiError::Network(message)
+}
+
+fn retry_after(resp: &Response) -> Option<std::time::Duration> {
+    let value = resp.headers().get(reqwest::header::RETRY_AFTER)?;
+    parse_retry_after(value.to_str().ok()?, chrono::Utc::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+    use std::time::Duration;
+
+    const PROJECT: &str = "prj_acme";
+    const ENV: &str = "env_prod";
+
+    /// A client answered by `tests/fixtures/api/<name>.json`, with retries off.
+    fn replay(name: &str) -> (ApiClient, Arc<Replay>) {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/api")
+            .join(format!("{name}.json"));
+        let replay = Arc::new(Replay::load(&path).unwrap());
+        let connection = ConnectionConfig {
+            base_url: "https://flagdash.test/".into(),
+            ..ConnectionConfig::default()
+        };
+        let client = ApiClient::new(&connection, "sess_test")
+            .unwrap()
+            .with_retry_policy(RetryPolicy::none())
+            .replaying(replay.clone());
+        (client, replay)
+    }
+
+    #[tokio::test]
+    async fn test_device_auth_endpoints() {
+        let connection = ConnectionConfig::default();
+        let (_, replay) = replay("device_auth");
+        let client = ApiClient::new_unauthenticated(&connection)
+            .unwrap()
+            .replaying(replay.clone());
+
+        let auth = client.request_device_auth(Some("ci-runner")).await.unwrap();
+        assert_eq!(auth.user_code, "WDJB-MJHT");
+        assert_eq!(auth.interval, 5);
+
+        let pending = client.poll_device_token(&auth.device_code).await.unwrap();
+        assert_eq!(pending.error.as_deref(), Some("authorization_pending"));
+        let approved = client.poll_device_token(&auth.device_code).await.unwrap();
+        assert_eq!(approved.session_token.as_deref(), Some("[REDACTED]"));
+        assert_eq!(approved.user.unwrap().role, "admin");
+        assert!(replay.unused().is_empty());
+    }
+
+    /// A code from `device_auth.json` that expires after `expires_in` seconds.
+    fn device_code(expires_in: u64) -> DeviceAuthResponse {
+        DeviceAuthResponse {
+            device_code: "dc_8f2e".into(),
+            user_code: "WDJB-MJHT".into(),
+            verification_url: "https://flagdash.io/device".into(),
+            expires_in,
+            interval: 5,
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_for_device_token_honours_slow_down() {
+        let connection = ConnectionConfig::default();
+        let (_, replay) = replay("device_token_polling");
+        let client = ApiClient::new_unauthenticated(&connection)
+            .unwrap()
+            .replaying(replay.clone());
+
+        let started = tokio::time::Instant::now();
+        let resp = client
+            .wait_for_device_token(&device_code(900))
+            .await
+            .unwrap();
+        assert_eq!(resp.session_token.as_deref(), Some("[REDACTED]"));
+        assert!(resp.error.is_none());
+        // pending and slow_down after 5s each, then 10s between polls
+        assert_eq!(started.elapsed(), Duration::from_secs(30));
+        assert!(replay.unused().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_for_device_token_expires() {
+        let connection = ConnectionConfig::default();
+        let (_, replay) = replay("device_token_polling");
+        let client = ApiClient::new_unauthenticated(&connection)
+            .unwrap()
+            .replaying(replay.clone());
+
+        let resp = client
+            .wait_for_device_token(&device_code(12))
+            .await
+            .unwrap();
+        assert_eq!(resp.error.as_deref(), Some("expired_token"));
+        assert!(resp.session_token.is_none());
+        // Polled at 5s and 10s; the slowed-down next poll would land after expiry
+        assert_eq!(replay.unused().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_flag_endpoints() {
+        let (client, replay) = replay("flags");
+
+        let flags = client.list_flags(PROJECT).await.unwrap();
+        assert_eq!(flags.len(), 1);
+        assert_eq!(flags[0].environments[0].rollout_percentage, 50);
+
+        let flag = client.get_flag("new-checkout", PROJECT).await.unwrap();
+        assert_eq!(flag.tags, vec!["checkout", "web"]);
+
+        let created = client
+            .create_flag(&CreateFlagRequest {
+                project_id: PROJECT.into(),
+                key: "beta-banner".into(),
+                name: "Beta banner".into(),
+                description: String::new(),
+                flag_type: "boolean".into(),
+                tags: vec!["web".into()],
+                default_value: Some(json!(false)),
+            })
+            .await
+            .unwrap();
+        // null description and environments fall back to defaults
+        assert_eq!(created.description, "");
+        assert!(created.environments.is_empty());
+
+        let updated = client
+            .update_flag(
+                "beta-banner",
+                PROJECT,
+                &UpdateFlagRequest {
+                    name: Some("Beta Banner".into()),
+                    description: None,
+                    tags: None,
+                    default_value: None,
+                    is_archived: Some(true),
+                },
+            )
+            .await
+            .unwrap();
+        assert!(updated.is_archived);
+        client.delete_flag("beta-banner", PROJECT).await.unwrap();
+
+        let toggled = client
+            .toggle_flag("new-checkout", PROJECT, ENV)
+            .await
+            .unwrap();
+        assert!(!toggled.flag_environment.enabled);
+        let rollout = client
+            .set_rollout("new-checkout", PROJECT, ENV, 25)
+            .await
+            .unwrap();
+        assert_eq!(rollout.flag_environment.rollout_percentage, 25);
+        let rules = json!([{"attribute": "country", "operator": "in", "values": ["DE", "FR"]}]);
+        let with_rules = client
+            .update_rules("new-checkout", PROJECT, ENV, rules.clone())
+            .await
+            .unwrap();
+        assert_eq!(with_rules.flag_environment.rules, rules);
+
+        assert!(client
+            .list_variations("new-checkout", PROJECT, ENV)
+            .await
+            .unwrap()
+            .is_empty());
+        let variations = client
+            .set_variations(
+                "new-checkout",
+                PROJECT,
+                ENV,
+                vec![
+                    VariationInput {
+                        key: "control".into(),
+                        name: "Control".into(),
+                        value: json!(false),
+                        weight: 50,
+                    },
+                    VariationInput {
+                        key: "treatment".into(),
+                        name: "Treatment".into(),
+                        value: json!(true),
+                        weight: 50,
+                    },
+                ],
+            )
+            .await
+            .unwrap();
+        assert_eq!(variations[1].id, "var_2");
+        client
+            .delete_variations("new-checkout", PROJECT, ENV)
+            .await
+            .unwrap();
+        assert!(replay.unused().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_schedule_endpoints() {
+        let (client, replay) = replay("schedules");
+
+        let schedules = client
+            .list_schedules("new-checkout", PROJECT, ENV)
+            .await
+            .unwrap();
+        assert_eq!(schedules[0].status, "pending");
+        assert_eq!(schedules[0].executed_at, None);
+
+        let at = Utc.with_ymd_and_hms(2026, 11, 1, 9, 0, 0).unwrap();
+        let schedule = client
+            .create_schedule(
+                "new-checkout",
+                PROJECT,
+                ENV,
+                &CreateScheduleRequest::set_rollout(at, 75),
+            )
+            .await
+            .unwrap();
+        assert_eq!(schedule.scheduled_at, at);
+        assert_eq!(schedule.payload, json!({"rollout_percentage": 75}));
+
+        client
+            .cancel_schedule("new-checkout", PROJECT, "sch_1")
+            .await
+            .unwrap();
+        assert!(replay.unused().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_config_endpoints() {
+        let (client, replay) = replay("configs");
+
+        let configs = client.list_configs(PROJECT).await.unwrap();
+        assert_eq!(configs[0].environments[0].value, json!(100));
+        let config = client.get_config("max-cart-items", PROJECT).await.unwrap();
+        assert_eq!(config.config_type, "number");
+
+        let created = client
+            .create_config(&CreateConfigRequest {
+                project_id: PROJECT.into(),
+                key: "theme".into(),
+                name: "Theme".into(),
+                description: String::new(),
+                config_type: "json".into(),
+                default_value: Some(json!({"primary": "#0af"})),
+                tags: vec!["ui".into()],
+            })
+            .await
+            .unwrap();
+        assert_eq!(created.default_value["primary"], "#0af");
+
+        let updated = client
+            .update_config(
+                "theme",
+                PROJECT,
+                &UpdateConfigRequest {
+                    name: None,
+                    description: Some("Brand colours".into()),
+                    tags: Some(vec!["ui".into(), "brand".into()]),
+                    default_value: None,
+                    is_archived: None,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(updated.description, "Brand colours");
+        client.delete_config("theme", PROJECT).await.unwrap();
+
+        let value = client
+            .set_config_value("max-cart-items", PROJECT, ENV, json!(150))
+            .await
+            .unwrap();
+        assert_eq!(value.config_environment.value, json!(150));
+        assert!(replay.unused().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_ai_config_endpoints() {
+        let (client, replay) = replay("ai_configs");
+
+        let ai_configs = client.list_ai_configs(PROJECT, ENV).await.unwrap();
+        assert_eq!(ai_configs[0].folder, "skills");
+        let skill = client
+            .get_ai_config("code-review.md", PROJECT, ENV)
+            .await
+            .unwrap();
+        assert_eq!(skill.metadata["model"], "claude");
+
+        let created = client
+            .create_ai_config(&CreateAiConfigRequest {
+                project_id: PROJECT.into(),
+                environment_id: ENV.into(),
+                file_name: "style.md".into(),
+                file_type: "rule".into(),
+                content: "Prefer small functions.".into(),
+                folder: String::new(),
+                is_active: None,
+                metadata: None,
+            })
+            .await
+            .unwrap();
+        // null metadata becomes Value::Null rather than failing
+        assert_eq!(created.metadata, serde_json::Value::Null);
+
+        let updated = client
+            .update_ai_config(
+                "style.md",
+                PROJECT,
+                ENV,
+                &UpdateAiConfigRequest {
+                    content: Some("Prefer small, pure functions.".into()),
+                    is_active: Some(false),
+                    metadata: None,
+                    folder: None,
+                },
+            )
+            .await
+            .unwrap();
+        assert!(!updated.is_active);
+        client
+            .delete_ai_config("style.md", PROJECT, ENV)
+            .await
+            .unwrap();
+
+        let starters = client.initialize_ai_configs(PROJECT, ENV).await.unwrap();
+        let types: Vec<_> = starters.iter().map(|c| c.file_type.as_str()).collect();
+        assert_eq!(types, ["skill", "rule", "agent"]);
+        assert!(replay.unused().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_webhook_endpoints() {
+        let (client, replay) = replay("webhooks");
+
+        let webhooks = client.list_webhooks(PROJECT).await.unwrap();
+        assert_eq!(webhooks[0].event_types, ["flag.updated", "flag.toggled"]);
+        let webhook = client.get_webhook("whe_1").await.unwrap();
+        assert_eq!(webhook.disabled_reason, "");
+
+        let created = client
+            .create_webhook(&CreateWebhookRequest {
+                project_id: PROJECT.into(),
+                environment_id: ENV.into(),
+                url: "https://hooks.slack.com/services/T000/B000".into(),
+                description: String::new(),
+                event_types: vec!["flag.toggled".into()],
+            })
+            .await
+            .unwrap();
+        assert_eq!(created.id, "whe_2");
+        let updated = client
+            .update_webhook(
+                "whe_2",
+                &UpdateWebhookRequest {
+                    url: None,
+                    description: Some("#releases".into()),
+                    event_types: Some(vec!["flag.toggled".into(), "flag.updated".into()]),
+                    is_active: None,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(updated.description, "#releases");
+        client.delete_webhook("whe_2").await.unwrap();
+
+        let rotated = client.regenerate_webhook_secret("whe_1").await.unwrap();
+        assert_eq!(rotated.signing_secret, "whsec_c04d58");
+        let reactivated = client.reactivate_webhook("whe_1").await.unwrap();
+        assert!(reactivated.is_active);
+
+        let deliveries = client
+            .list_webhook_deliveries("whe_1", 20, 0)
+            .await
+            .unwrap();
+        assert_eq!(deliveries[0].http_status, 500);
+        assert_eq!(deliveries[1].error_message, "");
+        assert!(replay.unused().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_project_endpoints() {
+        let (client, replay) = replay("projects");
+
+        let projects = client.list_projects().await.unwrap();
+        assert_eq!(projects[0].slug, "acme-web");
+        // IDs are percent-encoded into the query string
+        let environments = client.list_environments("prj acme").await.unwrap();
+        assert!(environments[0].is_default);
+        assert!(!environments[1].is_default);
+        client.validate_key().await.unwrap();
+        assert!(replay.unused().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_error_responses() {
+        let (client, replay) = replay("errors");
+
+        assert!(matches!(
+            client.list_projects().await,
+            Err(ApiError::Unauthorized)
+        ));
+        assert!(matches!(
+            client.list_flags(PROJECT).await,
+            Err(ApiError::Forbidden)
+        ));
+        match client.get_flag("ghost", PROJECT).await {
+            Err(ApiError::NotFound(message)) => assert_eq!(message, "Flag 'ghost' not found"),
+            other => panic!("expected NotFound, got {other:?}"),
+        }
+        // A body that isn't an ErrorResponse falls back to a generic message
+        match client.get_config("ghost", PROJECT).await {
+            Err(ApiError::NotFound(message)) => assert_eq!(message, "Not found"),
+            other => panic!("expected NotFound, got {other:?}"),
+        }
+        match client.set_rollout("new-checkout", PROJECT, ENV, 150).await {
+            Err(ApiError::Validation(message)) => {
+                assert_eq!(message, "rollout_percentage must be between 0 and 100")
+            }
+            other => panic!("expected Validation, got {other:?}"),
+        }
+        // `error` is used when there's no `message`
+        let duplicate = CreateConfigRequest {
+            project_id: PROJECT.into(),
+            key: "theme".into(),
+            name: "Theme".into(),
+            description: String::new(),
+            config_type: "json".into(),
+            default_value: None,
+            tags: Vec::new(),
+        };
+        match client.create_config(&duplicate).await {
+            Err(ApiError::Validation(message)) => {
+                assert_eq!(message, "Key has already been taken")
+            }
+            other => panic!("expected Validation, got {other:?}"),
+        }
+        assert!(matches!(
+            client.list_webhooks(PROJECT).await,
+            Err(ApiError::RateLimited(Some(wait))) if wait == Duration::from_secs(120)
+        ));
+        assert!(matches!(
+            client.list_environments(PROJECT).await,
+            Err(ApiError::RateLimited(None))
+        ));
+        match client.delete_flag("new-checkout", PROJECT).await {
+            Err(ApiError::Http { status, message }) => {
+                assert_eq!(status, 500);
+                assert_eq!(message, "Something went wrong");
+            }
+            other => panic!("expected Http, got {other:?}"),
+        }
+        assert!(replay.unused().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_retries_replayed_failures() {
+        let (client, replay) = replay("retries");
+        let client = client.with_retry_policy(RetryPolicy {
+            base_delay_ms: 0,
+            ..RetryPolicy::default()
+        });
+
+        assert!(client.list_flags(PROJECT).await.unwrap().is_empty());
+        // A POST may already have been applied, so it isn't retried
+        assert!(matches!(
+            client.toggle_flag("new-checkout", PROJECT, ENV).await,
+            Err(ApiError::Http { status: 503, .. })
+        ));
+        assert!(replay.unused().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_replay_rejects_requests_that_differ_from_the_recording() {
+        let (client, replay) = replay("flags");
+
+        let err = client
+            .set_rollout("new-checkout", PROJECT, ENV, 30)
+            .await
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains(r#"sent body {"rollout_percentage":30}"#));
+        let err = client.list_flags("prj_other").await.unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("no recorded response for GET /api/v1/manage/flags?project_id=prj_other"));
+        assert_eq!(replay.unused().len(), 11);
+    }
+}
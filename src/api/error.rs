@@ -1,3 +1,4 @@
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Error, Debug, Clone)]
@@ -23,6 +24,14 @@ pub enum ApiError {
     #[error("Validation error: {0}")]
     Validation(String),
 
-    #[error("Rate limited: try again later")]
-    RateLimited,
+    /// Carries the server's `Retry-After`, when it sent one.
+    #[error("Rate limited: {}", retry_hint(.0))]
+    RateLimited(Option<Duration>),
+}
+
+fn retry_hint(retry_after: &Option<Duration>) -> String {
+    match retry_after {
+        Some(wait) => format!("try again in {}s", wait.as_secs().max(1)),
+        None => "try again later".to_string(),
+    }
 }
@@ -0,0 +1,384 @@
+use crate::api::error::ApiError;
+use anyhow::{Context, Result};
+use reqwest::header::{HeaderMap, AUTHORIZATION, CONTENT_LENGTH, TRANSFER_ENCODING};
+use reqwest::{Client, Request, Response};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// What bearer tokens are replaced with in recorded fixtures.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Response fields that carry a bearer token.
+const TOKEN_FIELDS: [&str; 1] = ["session_token"];
+
+/// Request/response pairs captured from `ApiClient`, in the order they were
+/// made. Stored as pretty-printed JSON so fixtures can be read and edited.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Fixture {
+    pub interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// Path and query, e.g. `/api/v1/manage/flags?project_id=prj_1`.
+    pub path: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    /// The body, when it's JSON.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+    /// The body, when it isn't.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+impl Fixture {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents =
+            std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        serde_json::from_str(&contents).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, json + "\n").with_context(|| format!("writing {}", path.display()))
+    }
+}
+
+impl RecordedRequest {
+    /// Capture a request as it's about to be sent, with its bearer token redacted.
+    pub fn from_request(request: &Request) -> Self {
+        let url = request.url();
+        let path = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        let mut headers = recorded_headers(request.headers());
+        if headers.contains_key(AUTHORIZATION.as_str()) {
+            headers.insert(AUTHORIZATION.to_string(), format!("Bearer {}", REDACTED));
+        }
+        Self {
+            method: request.method().to_string(),
+            path,
+            headers,
+            body: request.body().and_then(|b| b.as_bytes()).map(body_value),
+        }
+    }
+
+    fn is_authenticated(&self) -> bool {
+        self.headers.contains_key(AUTHORIZATION.as_str())
+    }
+
+    fn describe(&self) -> String {
+        format!("{} {}", self.method, self.path)
+    }
+}
+
+impl RecordedResponse {
+    fn new(status: u16, headers: &HeaderMap, bytes: &[u8]) -> Self {
+        let (mut body, text) = match serde_json::from_slice::<Value>(bytes) {
+            Ok(value) => (Some(value), None),
+            Err(_) if bytes.is_empty() => (None, None),
+            Err(_) => (None, Some(String::from_utf8_lossy(bytes).into_owned())),
+        };
+        if let Some(body) = &mut body {
+            redact_tokens(body);
+        }
+        Self {
+            status,
+            headers: recorded_headers(headers),
+            body,
+            text,
+        }
+    }
+
+    fn to_response(&self) -> Result<Response, ApiError> {
+        let mut builder = http::Response::builder().status(self.status);
+        for (name, value) in &self.headers {
+            builder = builder.header(name, value);
+        }
+        let body = match (&self.body, &self.text) {
+            (Some(value), _) => value.to_string(),
+            (None, Some(text)) => text.clone(),
+            (None, None) => String::new(),
+        };
+        builder
+            .body(body)
+            .map(Response::from)
+            .map_err(|e| ApiError::Parse(format!("invalid recorded response: {e}")))
+    }
+}
+
+/// Headers worth keeping. Framing headers are dropped since replayed bodies
+/// are re-serialized.
+fn recorded_headers(headers: &HeaderMap) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .filter(|(name, _)| **name != CONTENT_LENGTH && **name != TRANSFER_ENCODING)
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect()
+}
+
+fn body_value(bytes: &[u8]) -> Value {
+    serde_json::from_slice(bytes)
+        .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(bytes).into_owned()))
+}
+
+fn redact_tokens(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                if TOKEN_FIELDS.contains(&key.as_str()) && field.is_string() {
+                    *field = Value::String(REDACTED.to_string());
+                } else {
+                    redact_tokens(field);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_tokens),
+        _ => {}
+    }
+}
+
+// ── Recording ───────────────────────────────────────────────────────
+
+/// Several clients may record to the same file in one run.
+static RECORDING_LOCK: Mutex<()> = Mutex::new(());
+
+/// Sends requests over the network and appends each exchange to a fixture file.
+pub struct Recorder {
+    path: PathBuf,
+}
+
+impl Recorder {
+    /// Record to `path`, adding to the interactions already in it. Fails if
+    /// the file can't be written.
+    pub fn open(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            Fixture::default().save(path)?;
+        } else {
+            Fixture::load(path)?;
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+        })
+    }
+
+    pub async fn execute(&self, client: &Client, request: Request) -> reqwest::Result<Response> {
+        let recorded = RecordedRequest::from_request(&request);
+        let resp = client.execute(request).await?;
+        let status = resp.status();
+        let version = resp.version();
+        let headers = resp.headers().clone();
+        let bytes = resp.bytes().await?;
+
+        // A fixture that can't be written shouldn't break the request itself
+        let _ = self.append(Interaction {
+            request: recorded,
+            response: RecordedResponse::new(status.as_u16(), &headers, &bytes),
+        });
+
+        let mut rebuilt = http::Response::new(bytes);
+        *rebuilt.status_mut() = status;
+        *rebuilt.version_mut() = version;
+        *rebuilt.headers_mut() = headers;
+        Ok(Response::from(rebuilt))
+    }
+
+    fn append(&self, interaction: Interaction) -> Result<()> {
+        let _guard = RECORDING_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut fixture = Fixture::load(&self.path).unwrap_or_default();
+        fixture.interactions.push(interaction);
+        fixture.save(&self.path)
+    }
+}
+
+// ── Replay ──────────────────────────────────────────────────────────
+
+/// Answers requests from a fixture instead of the network.
+///
+/// Each request is matched to the first unused interaction with the same
+/// method and path, and must then have the recorded body and send a bearer
+/// token exactly when the recorded one did. Anything else is an error, so
+/// tests catch changes to how requests are built.
+pub struct Replay {
+    interactions: Mutex<Vec<(Interaction, bool)>>,
+}
+
+impl Replay {
+    pub fn new(fixture: Fixture) -> Self {
+        Self {
+            interactions: Mutex::new(
+                fixture
+                    .interactions
+                    .into_iter()
+                    .map(|interaction| (interaction, false))
+                    .collect(),
+            ),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        Ok(Self::new(Fixture::load(path)?))
+    }
+
+    pub fn respond(&self, request: &Request) -> Result<Response, ApiError> {
+        let actual = RecordedRequest::from_request(request);
+        let mut interactions = self.interactions.lock().unwrap_or_else(|e| e.into_inner());
+        let Some((interaction, used)) = interactions.iter_mut().find(|(i, used)| {
+            !used && i.request.method == actual.method && i.request.path == actual.path
+        }) else {
+            return Err(ApiError::Network(format!(
+                "no recorded response for {}",
+                actual.describe()
+            )));
+        };
+        let expected = &interaction.request;
+        if expected.body != actual.body {
+            return Err(ApiError::Network(format!(
+                "{} sent body {} but the recording has {}",
+                actual.describe(),
+                json_or_none(&actual.body),
+                json_or_none(&expected.body)
+            )));
+        }
+        if expected.is_authenticated() != actual.is_authenticated() {
+            return Err(ApiError::Network(format!(
+                "{} {} a bearer token but the recorded request {}",
+                actual.describe(),
+                if actual.is_authenticated() {
+                    "sent"
+                } else {
+                    "didn't send"
+                },
+                if expected.is_authenticated() {
+                    "did"
+                } else {
+                    "didn't"
+                }
+            )));
+        }
+        *used = true;
+        interaction.response.to_response()
+    }
+
+    /// Recorded requests that were never made, as `METHOD /path`.
+    pub fn unused(&self) -> Vec<String> {
+        self.interactions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|(_, used)| !used)
+            .map(|(interaction, _)| interaction.request.describe())
+            .collect()
+    }
+}
+
+fn json_or_none(body: &Option<Value>) -> String {
+    body.as_ref()
+        .map(Value::to_string)
+        .unwrap_or_else(|| "(none)".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Serve one canned HTTP response on a local port.
+    async fn serve_once(response: &'static str) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 4096];
+            let _ = socket.read(&mut buf).await;
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay() {
+        let body = r#"{"session_token":"sess_live_123","user":{"name":"Ada"}}"#;
+        let response: &'static str = Box::leak(
+            format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .into_boxed_str(),
+        );
+        let base_url = serve_once(response).await;
+
+        let dir = std::env::temp_dir().join(format!("flagdash-fixtures-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("recorded.json");
+        let recorder = Recorder::open(&path).unwrap();
+
+        let client = Client::new();
+        let request = client
+            .post(format!("{}/api/v1/manage/flags?project_id=prj_1", base_url))
+            .bearer_auth("sess_live_123")
+            .json(&json!({"key": "beta"}))
+            .build()
+            .unwrap();
+        let resp = recorder
+            .execute(&client, request.try_clone().unwrap())
+            .await
+            .unwrap();
+        // The caller still gets the real response
+        assert_eq!(resp.text().await.unwrap(), body);
+
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(!saved.contains("sess_live_123"));
+        let fixture = Fixture::load(&path).unwrap();
+        let interaction = &fixture.interactions[0];
+        assert_eq!(interaction.request.method, "POST");
+        assert_eq!(
+            interaction.request.path,
+            "/api/v1/manage/flags?project_id=prj_1"
+        );
+        assert_eq!(
+            interaction.request.headers["authorization"],
+            "Bearer [REDACTED]"
+        );
+        assert_eq!(interaction.request.body, Some(json!({"key": "beta"})));
+        assert_eq!(
+            interaction.response.body.as_ref().unwrap()["session_token"],
+            REDACTED
+        );
+
+        let replay = Replay::new(fixture);
+        let resp = replay.respond(&request).unwrap();
+        assert_eq!(resp.status(), 200);
+        assert_eq!(
+            resp.json::<Value>().await.unwrap()["user"]["name"],
+            json!("Ada")
+        );
+        assert!(replay.unused().is_empty());
+        // Each interaction answers once
+        assert!(replay.respond(&request).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
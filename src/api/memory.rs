@@ -0,0 +1,1246 @@
+use crate::api::backend::FlagDashApi;
+use crate::api::error::ApiError;
+use crate::api::types::*;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+
+/// Session token handed out by the simulated device login.
+pub const SESSION_TOKEN: &str = "memory-session";
+
+/// Everything a simulated server knows. Serializable so the state can be
+/// seeded from, or saved to, a JSON file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoryState {
+    #[serde(default)]
+    pub projects: Vec<MemoryProject>,
+}
+
+/// One project and everything in it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryProject {
+    #[serde(flatten)]
+    pub project: Project,
+    #[serde(default)]
+    pub environments: Vec<Environment>,
+    #[serde(default)]
+    pub flags: Vec<ManagedFlag>,
+    /// Variations by flag key, then environment ID.
+    #[serde(default)]
+    pub variations: BTreeMap<String, BTreeMap<String, Vec<Variation>>>,
+    /// Schedules by flag key, then environment ID.
+    #[serde(default)]
+    pub schedules: BTreeMap<String, BTreeMap<String, Vec<Schedule>>>,
+    #[serde(default)]
+    pub configs: Vec<ManagedConfig>,
+    #[serde(default)]
+    pub ai_configs: Vec<ManagedAiConfig>,
+    #[serde(default)]
+    pub webhooks: Vec<WebhookEndpoint>,
+    /// Deliveries by webhook ID, newest first.
+    #[serde(default)]
+    pub deliveries: BTreeMap<String, Vec<WebhookDelivery>>,
+}
+
+/// A FlagDash server simulated in memory. Clones share the same state.
+///
+/// Requests are checked the way the server checks them, so unknown keys give
+/// `NotFound` and invalid input gives `Validation`. The device login is
+/// approved on the first poll.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryBackend {
+    state: Arc<Mutex<MemoryState>>,
+}
+
+impl MemoryBackend {
+    pub fn new(state: MemoryState) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    /// A backend with the demo project.
+    pub fn demo() -> Self {
+        Self::new(MemoryState::demo(Utc::now()))
+    }
+
+    /// The demo backend for this process, so every client made during a run
+    /// (before and after logging in, in the UI and its tasks) sees the same data.
+    pub fn shared() -> Self {
+        static SHARED: OnceLock<MemoryBackend> = OnceLock::new();
+        SHARED.get_or_init(MemoryBackend::demo).clone()
+    }
+
+    /// A copy of the current state.
+    pub fn snapshot(&self) -> MemoryState {
+        self.lock().clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, MemoryState> {
+        // A panic while holding the lock can't leave the data half-written in
+        // a way that matters for a simulation
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Run `f` against a project, or fail with `NotFound` if it doesn't exist.
+    fn with_project<T>(
+        &self,
+        project_id: &str,
+        f: impl FnOnce(&mut MemoryProject) -> Result<T, ApiError>,
+    ) -> Result<T, ApiError> {
+        let mut state = self.lock();
+        let project = state
+            .projects
+            .iter_mut()
+            .find(|p| p.project.id == project_id)
+            .ok_or_else(|| not_found("Project"))?;
+        f(project)
+    }
+
+    /// Run `f` against the project that has a webhook.
+    fn with_webhook<T>(
+        &self,
+        id: &str,
+        f: impl FnOnce(&mut MemoryProject, usize) -> Result<T, ApiError>,
+    ) -> Result<T, ApiError> {
+        let mut state = self.lock();
+        for project in state.projects.iter_mut() {
+            if let Some(index) = project.webhooks.iter().position(|w| w.id == id) {
+                return f(project, index);
+            }
+        }
+        Err(not_found("Webhook endpoint"))
+    }
+}
+
+impl MemoryProject {
+    fn environment(&self, id: &str) -> Result<&Environment, ApiError> {
+        self.environments
+            .iter()
+            .find(|e| e.id == id)
+            .ok_or_else(|| not_found("Environment"))
+    }
+
+    fn flag(&mut self, key: &str) -> Result<&mut ManagedFlag, ApiError> {
+        self.flags
+            .iter_mut()
+            .find(|f| f.key == key)
+            .ok_or_else(|| not_found("Flag"))
+    }
+
+    /// A flag's state in an environment, created disabled if it has none yet.
+    fn flag_environment(
+        &mut self,
+        key: &str,
+        environment_id: &str,
+    ) -> Result<(String, &mut FlagEnvironmentData), ApiError> {
+        self.environment(environment_id)?;
+        let flag = self.flag(key)?;
+        flag.updated_at = Utc::now();
+        let index = match flag
+            .environments
+            .iter()
+            .position(|e| e.environment_id == environment_id)
+        {
+            Some(index) => index,
+            None => {
+                flag.environments
+                    .push(flag_environment_data(environment_id, false, 100));
+                flag.environments.len() - 1
+            }
+        };
+        Ok((flag.id.clone(), &mut flag.environments[index]))
+    }
+
+    fn config(&mut self, key: &str) -> Result<&mut ManagedConfig, ApiError> {
+        self.configs
+            .iter_mut()
+            .find(|c| c.key == key)
+            .ok_or_else(|| not_found("Remote config"))
+    }
+
+    fn ai_config(
+        &mut self,
+        file_name: &str,
+        environment_id: &str,
+    ) -> Result<&mut ManagedAiConfig, ApiError> {
+        self.ai_configs
+            .iter_mut()
+            .find(|c| c.file_name == file_name && c.environment_id == environment_id)
+            .ok_or_else(|| not_found("AI config"))
+    }
+}
+
+fn not_found(what: &str) -> ApiError {
+    ApiError::NotFound(format!("{} not found", what))
+}
+
+fn invalid(message: impl Into<String>) -> ApiError {
+    ApiError::Validation(message.into())
+}
+
+/// A fresh ID such as `flg_3f9a0c12`.
+fn new_id(prefix: &str) -> String {
+    let mut bytes = [0u8; 4];
+    let _ = getrandom::getrandom(&mut bytes);
+    format!("{}_{:08x}", prefix, u32::from_le_bytes(bytes))
+}
+
+fn flag_environment_data(environment_id: &str, enabled: bool, rollout: i32) -> FlagEnvironmentData {
+    FlagEnvironmentData {
+        id: new_id("fe"),
+        environment_id: environment_id.to_string(),
+        enabled,
+        value: Value::Null,
+        rules: json!([]),
+        rollout_percentage: rollout,
+    }
+}
+
+fn flag_environment_response(
+    flag_id: String,
+    data: &FlagEnvironmentData,
+) -> FlagEnvironmentResponse {
+    FlagEnvironmentResponse {
+        flag_environment: FlagEnvironmentResponseData {
+            id: data.id.clone(),
+            enabled: data.enabled,
+            environment_id: data.environment_id.clone(),
+            feature_flag_id: flag_id,
+            rules: data.rules.clone(),
+            rollout_percentage: data.rollout_percentage,
+        },
+    }
+}
+
+/// The value a new flag or config starts with when none is given.
+fn zero_value(value_type: &str) -> Value {
+    match value_type {
+        "boolean" => json!(false),
+        "number" => json!(0),
+        "json" => json!({}),
+        _ => json!(""),
+    }
+}
+
+fn check_key(key: &str, taken: bool) -> Result<(), ApiError> {
+    if key.trim().is_empty() {
+        return Err(invalid("key can't be blank"));
+    }
+    if taken {
+        return Err(invalid(format!("key '{}' has already been taken", key)));
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl FlagDashApi for MemoryBackend {
+    // ── Device Auth ─────────────────────────────────────────────────
+
+    async fn request_device_auth(
+        &self,
+        _device_name: Option<&str>,
+    ) -> Result<DeviceAuthResponse, ApiError> {
+        Ok(DeviceAuthResponse {
+            device_code: new_id("dev"),
+            user_code: "DEMO-CODE".to_string(),
+            verification_url: "memory://approve".to_string(),
+            expires_in: 600,
+            interval: 1,
+        })
+    }
+
+    async fn poll_device_token(&self, _device_code: &str) -> Result<DeviceTokenResponse, ApiError> {
+        Ok(DeviceTokenResponse {
+            session_token: Some(SESSION_TOKEN.to_string()),
+            account: Some(DeviceTokenAccount {
+                id: "acc_demo".to_string(),
+                name: "Demo Account".to_string(),
+            }),
+            user: Some(DeviceTokenUser {
+                id: "usr_demo".to_string(),
+                name: "Demo User".to_string(),
+                email: "demo@flagdash.local".to_string(),
+                role: "owner".to_string(),
+            }),
+            expires_at: Some((Utc::now() + Duration::days(30)).to_rfc3339()),
+            error: None,
+        })
+    }
+
+    // ── Flags ────────────────────────────────────────────────────────
+
+    async fn list_flags(&self, project_id: &str) -> Result<Vec<ManagedFlag>, ApiError> {
+        self.with_project(project_id, |p| Ok(p.flags.clone()))
+    }
+
+    async fn get_flag(&self, key: &str, project_id: &str) -> Result<ManagedFlag, ApiError> {
+        self.with_project(project_id, |p| p.flag(key).cloned())
+    }
+
+    async fn create_flag(&self, req: &CreateFlagRequest) -> Result<ManagedFlag, ApiError> {
+        self.with_project(&req.project_id, |p| {
+            check_key(&req.key, p.flags.iter().any(|f| f.key == req.key))?;
+            let now = Utc::now();
+            let flag = ManagedFlag {
+                id: new_id("flg"),
+                key: req.key.clone(),
+                name: req.name.clone(),
+                description: req.description.clone(),
+                flag_type: req.flag_type.clone(),
+                default_value: req
+                    .default_value
+                    .clone()
+                    .unwrap_or_else(|| zero_value(&req.flag_type)),
+                tags: req.tags.clone(),
+                is_archived: false,
+                created_at: now,
+                updated_at: now,
+                environments: p
+                    .environments
+                    .iter()
+                    .map(|e| flag_environment_data(&e.id, false, 100))
+                    .collect(),
+            };
+            p.flags.push(flag.clone());
+            Ok(flag)
+        })
+    }
+
+    async fn update_flag(
+        &self,
+        key: &str,
+        project_id: &str,
+        req: &UpdateFlagRequest,
+    ) -> Result<ManagedFlag, ApiError> {
+        self.with_project(project_id, |p| {
+            let flag = p.flag(key)?;
+            if let Some(name) = &req.name {
+                flag.name = name.clone();
+            }
+            if let Some(description) = &req.description {
+                flag.description = description.clone();
+            }
+            if let Some(tags) = &req.tags {
+                flag.tags = tags.clone();
+            }
+            if let Some(value) = &req.default_value {
+                flag.default_value = value.clone();
+            }
+            if let Some(archived) = req.is_archived {
+                flag.is_archived = archived;
+            }
+            flag.updated_at = Utc::now();
+            Ok(flag.clone())
+        })
+    }
+
+    async fn delete_flag(&self, key: &str, project_id: &str) -> Result<(), ApiError> {
+        self.with_project(project_id, |p| {
+            p.flag(key)?;
+            p.flags.retain(|f| f.key != key);
+            p.variations.remove(key);
+            p.schedules.remove(key);
+            Ok(())
+        })
+    }
+
+    async fn toggle_flag(
+        &self,
+        key: &str,
+        project_id: &str,
+        environment_id: &str,
+    ) -> Result<FlagEnvironmentResponse, ApiError> {
+        self.with_project(project_id, |p| {
+            let (flag_id, data) = p.flag_environment(key, environment_id)?;
+            data.enabled = !data.enabled;
+            Ok(flag_environment_response(flag_id, data))
+        })
+    }
+
+    async fn set_rollout(
+        &self,
+        key: &str,
+        project_id: &str,
+        environment_id: &str,
+        percentage: i32,
+    ) -> Result<FlagEnvironmentResponse, ApiError> {
+        if !(0..=100).contains(&percentage) {
+            return Err(invalid("rollout_percentage must be between 0 and 100"));
+        }
+        self.with_project(project_id, |p| {
+            let (flag_id, data) = p.flag_environment(key, environment_id)?;
+            data.rollout_percentage = percentage;
+            Ok(flag_environment_response(flag_id, data))
+        })
+    }
+
+    async fn update_rules(
+        &self,
+        key: &str,
+        project_id: &str,
+        environment_id: &str,
+        rules: Value,
+    ) -> Result<FlagEnvironmentResponse, ApiError> {
+        if !rules.is_array() {
+            return Err(invalid("rules must be a list"));
+        }
+        self.with_project(project_id, |p| {
+            let (flag_id, data) = p.flag_environment(key, environment_id)?;
+            data.rules = rules;
+            Ok(flag_environment_response(flag_id, data))
+        })
+    }
+
+    async fn list_variations(
+        &self,
+        key: &str,
+        project_id: &str,
+        environment_id: &str,
+    ) -> Result<Vec<Variation>, ApiError> {
+        self.with_project(project_id, |p| {
+            p.environment(environment_id)?;
+            p.flag(key)?;
+            Ok(p.variations
+                .get(key)
+                .and_then(|by_env| by_env.get(environment_id))
+                .cloned()
+                .unwrap_or_default())
+        })
+    }
+
+    async fn set_variations(
+        &self,
+        key: &str,
+        project_id: &str,
+        environment_id: &str,
+        variations: Vec<VariationInput>,
+    ) -> Result<Vec<Variation>, ApiError> {
+        self.with_project(project_id, |p| {
+            p.environment(environment_id)?;
+            p.flag(key)?;
+            let variations: Vec<Variation> = variations
+                .into_iter()
+                .map(|v| Variation {
+                    id: new_id("var"),
+                    key: v.key,
+                    name: v.name,
+                    value: v.value,
+                    weight: v.weight,
+                })
+                .collect();
+            p.variations
+                .entry(key.to_string())
+                .or_default()
+                .insert(environment_id.to_string(), variations.clone());
+            Ok(variations)
+        })
+    }
+
+    async fn delete_variations(
+        &self,
+        key: &str,
+        project_id: &str,
+        environment_id: &str,
+    ) -> Result<(), ApiError> {
+        self.with_project(project_id, |p| {
+            p.environment(environment_id)?;
+            p.flag(key)?;
+            if let Some(by_env) = p.variations.get_mut(key) {
+                by_env.remove(environment_id);
+            }
+            Ok(())
+        })
+    }
+
+    // ── Schedules ────────────────────────────────────────────────────
+
+    async fn list_schedules(
+        &self,
+        key: &str,
+        project_id: &str,
+        environment_id: &str,
+    ) -> Result<Vec<Schedule>, ApiError> {
+        self.with_project(project_id, |p| {
+            p.environment(environment_id)?;
+            p.flag(key)?;
+            Ok(p.schedules
+                .get(key)
+                .and_then(|by_env| by_env.get(environment_id))
+                .cloned()
+                .unwrap_or_default())
+        })
+    }
+
+    async fn create_schedule(
+        &self,
+        key: &str,
+        project_id: &str,
+        environment_id: &str,
+        req: &CreateScheduleRequest,
+    ) -> Result<Schedule, ApiError> {
+        if ScheduleAction::parse(&req.action).is_none() {
+            return Err(invalid(format!("unknown action '{}'", req.action)));
+        }
+        let now = Utc::now();
+        let scheduled_at = DateTime::parse_from_rfc3339(&req.scheduled_at)
+            .map_err(|_| invalid("scheduled_at must be an RFC 3339 timestamp"))?
+            .with_timezone(&Utc);
+        if scheduled_at <= now {
+            return Err(invalid("scheduled_at must be in the future"));
+        }
+        self.with_project(project_id, |p| {
+            p.environment(environment_id)?;
+            p.flag(key)?;
+            let schedule = Schedule {
+                id: new_id("sch"),
+                action: req.action.clone(),
+                scheduled_at,
+                executed_at: None,
+                status: "pending".to_string(),
+                payload: req.payload.clone().unwrap_or(Value::Null),
+                error_message: String::new(),
+                created_at: now,
+            };
+            p.schedules
+                .entry(key.to_string())
+                .or_default()
+                .entry(environment_id.to_string())
+                .or_default()
+                .push(schedule.clone());
+            Ok(schedule)
+        })
+    }
+
+    async fn cancel_schedule(
+        &self,
+        key: &str,
+        project_id: &str,
+        schedule_id: &str,
+    ) -> Result<(), ApiError> {
+        self.with_project(project_id, |p| {
+            p.flag(key)?;
+            let schedule = p
+                .schedules
+                .get_mut(key)
+                .into_iter()
+                .flat_map(|by_env| by_env.values_mut())
+                .flatten()
+                .find(|s| s.id == schedule_id)
+                .ok_or_else(|| not_found("Schedule"))?;
+            if schedule.status != "pending" {
+                return Err(invalid(format!(
+                    "only pending schedules can be cancelled; this one is {}",
+                    schedule.status
+                )));
+            }
+            schedule.status = "cancelled".to_string();
+            Ok(())
+        })
+    }
+
+    // ── Configs ──────────────────────────────────────────────────────
+
+    async fn list_configs(&self, project_id: &str) -> Result<Vec<ManagedConfig>, ApiError> {
+        self.with_project(project_id, |p| Ok(p.configs.clone()))
+    }
+
+    async fn get_config(&self, key: &str, project_id: &str) -> Result<ManagedConfig, ApiError> {
+        self.with_project(project_id, |p| p.config(key).cloned())
+    }
+
+    async fn create_config(&self, req: &CreateConfigRequest) -> Result<ManagedConfig, ApiError> {
+        self.with_project(&req.project_id, |p| {
+            check_key(&req.key, p.configs.iter().any(|c| c.key == req.key))?;
+            let now = Utc::now();
+            let config = ManagedConfig {
+                id: new_id("cfg"),
+                key: req.key.clone(),
+                name: req.name.clone(),
+                description: req.description.clone(),
+                config_type: req.config_type.clone(),
+                default_value: req
+                    .default_value
+                    .clone()
+                    .unwrap_or_else(|| zero_value(&req.config_type)),
+                tags: req.tags.clone(),
+                is_archived: false,
+                created_at: now,
+                updated_at: now,
+                environments: Vec::new(),
+                metadata: Value::Null,
+            };
+            p.configs.push(config.clone());
+            Ok(config)
+        })
+    }
+
+    async fn update_config(
+        &self,
+        key: &str,
+        project_id: &str,
+        req: &UpdateConfigRequest,
+    ) -> Result<ManagedConfig, ApiError> {
+        self.with_project(project_id, |p| {
+            let config = p.config(key)?;
+            if let Some(name) = &req.name {
+                config.name = name.clone();
+            }
+            if let Some(description) = &req.description {
+                config.description = description.clone();
+            }
+            if let Some(tags) = &req.tags {
+                config.tags = tags.clone();
+            }
+            if let Some(value) = &req.default_value {
+                config.default_value = value.clone();
+            }
+            if let Some(archived) = req.is_archived {
+                config.is_archived = archived;
+            }
+            config.updated_at = Utc::now();
+            Ok(config.clone())
+        })
+    }
+
+    async fn delete_config(&self, key: &str, project_id: &str) -> Result<(), ApiError> {
+        self.with_project(project_id, |p| {
+            p.config(key)?;
+            p.configs.retain(|c| c.key != key);
+            Ok(())
+        })
+    }
+
+    async fn set_config_value(
+        &self,
+        key: &str,
+        project_id: &str,
+        environment_id: &str,
+        value: Value,
+    ) -> Result<ConfigEnvironmentResponse, ApiError> {
+        self.with_project(project_id, |p| {
+            p.environment(environment_id)?;
+            let config = p.config(key)?;
+            config.updated_at = Utc::now();
+            let entry = match config
+                .environments
+                .iter_mut()
+                .find(|e| e.environment_id == environment_id)
+            {
+                Some(entry) => entry,
+                None => {
+                    config.environments.push(ConfigEnvironmentValue {
+                        id: new_id("ce"),
+                        environment_id: environment_id.to_string(),
+                        value: Value::Null,
+                        is_active: true,
+                    });
+                    config.environments.last_mut().expect("just pushed")
+                }
+            };
+            entry.value = value;
+            Ok(ConfigEnvironmentResponse {
+                config_environment: ConfigEnvironmentResponseData {
+                    id: entry.id.clone(),
+                    value: entry.value.clone(),
+                    environment_id: entry.environment_id.clone(),
+                    remote_config_id: config.id.clone(),
+                },
+            })
+        })
+    }
+
+    // ── AI Configs ───────────────────────────────────────────────────
+
+    async fn list_ai_configs(
+        &self,
+        project_id: &str,
+        environment_id: &str,
+    ) -> Result<Vec<ManagedAiConfig>, ApiError> {
+        self.with_project(project_id, |p| {
+            p.environment(environment_id)?;
+            Ok(p.ai_configs
+                .iter()
+                .filter(|c| c.environment_id == environment_id)
+                .cloned()
+                .collect())
+        })
+    }
+
+    async fn get_ai_config(
+        &self,
+        file_name: &str,
+        project_id: &str,
+        environment_id: &str,
+    ) -> Result<ManagedAiConfig, ApiError> {
+        self.with_project(project_id, |p| {
+            p.ai_config(file_name, environment_id).cloned()
+        })
+    }
+
+    async fn create_ai_config(
+        &self,
+        req: &CreateAiConfigRequest,
+    ) -> Result<ManagedAiConfig, ApiError> {
+        self.with_project(&req.project_id, |p| {
+            p.environment(&req.environment_id)?;
+            if req.file_name.trim().is_empty() {
+                return Err(invalid("file_name can't be blank"));
+            }
+            if p.ai_configs
+                .iter()
+                .any(|c| c.file_name == req.file_name && c.environment_id == req.environment_id)
+            {
+                return Err(invalid(format!(
+                    "file_name '{}' has already been taken",
+                    req.file_name
+                )));
+            }
+            let now = Utc::now();
+            let config = ManagedAiConfig {
+                id: new_id("aic"),
+                file_name: req.file_name.clone(),
+                file_type: req.file_type.clone(),
+                content: req.content.clone(),
+                is_active: req.is_active.unwrap_or(true),
+                metadata: req.metadata.clone().unwrap_or(Value::Null),
+                folder: req.folder.clone(),
+                created_at: now,
+                updated_at: now,
+                project_id: req.project_id.clone(),
+                environment_id: req.environment_id.clone(),
+            };
+            p.ai_configs.push(config.clone());
+            Ok(config)
+        })
+    }
+
+    async fn update_ai_config(
+        &self,
+        file_name: &str,
+        project_id: &str,
+        environment_id: &str,
+        req: &UpdateAiConfigRequest,
+    ) -> Result<ManagedAiConfig, ApiError> {
+        self.with_project(project_id, |p| {
+            let config = p.ai_config(file_name, environment_id)?;
+            if let Some(content) = &req.content {
+                config.content = content.clone();
+            }
+            if let Some(active) = req.is_active {
+                config.is_active = active;
+            }
+            if let Some(metadata) = &req.metadata {
+                config.metadata = metadata.clone();
+            }
+            if let Some(folder) = &req.folder {
+                config.folder = folder.clone();
+            }
+            config.updated_at = Utc::now();
+            Ok(config.clone())
+        })
+    }
+
+    async fn delete_ai_config(
+        &self,
+        file_name: &str,
+        project_id: &str,
+        environment_id: &str,
+    ) -> Result<(), ApiError> {
+        self.with_project(project_id, |p| {
+            p.ai_config(file_name, environment_id)?;
+            p.ai_configs
+                .retain(|c| !(c.file_name == file_name && c.environment_id == environment_id));
+            Ok(())
+        })
+    }
+
+    async fn initialize_ai_configs(
+        &self,
+        project_id: &str,
+        environment_id: &str,
+    ) -> Result<Vec<ManagedAiConfig>, ApiError> {
+        self.with_project(project_id, |p| {
+            p.environment(environment_id)?;
+            let now = Utc::now();
+            for (file_name, file_type, content) in STARTER_AI_CONFIGS {
+                if p.ai_configs
+                    .iter()
+                    .any(|c| c.file_name == *file_name && c.environment_id == environment_id)
+                {
+                    continue;
+                }
+                p.ai_configs.push(ManagedAiConfig {
+                    id: new_id("aic"),
+                    file_name: file_name.to_string(),
+                    file_type: file_type.to_string(),
+                    content: content.to_string(),
+                    is_active: true,
+                    metadata: Value::Null,
+                    folder: String::new(),
+                    created_at: now,
+                    updated_at: now,
+                    project_id: project_id.to_string(),
+                    environment_id: environment_id.to_string(),
+                });
+            }
+            Ok(p.ai_configs
+                .iter()
+                .filter(|c| c.environment_id == environment_id)
+                .cloned()
+                .collect())
+        })
+    }
+
+    // ── Webhooks ─────────────────────────────────────────────────────
+
+    async fn list_webhooks(&self, project_id: &str) -> Result<Vec<WebhookEndpoint>, ApiError> {
+        self.with_project(project_id, |p| Ok(p.webhooks.clone()))
+    }
+
+    async fn get_webhook(&self, id: &str) -> Result<WebhookEndpoint, ApiError> {
+        self.with_webhook(id, |p, i| Ok(p.webhooks[i].clone()))
+    }
+
+    async fn create_webhook(
+        &self,
+        req: &CreateWebhookRequest,
+    ) -> Result<WebhookEndpoint, ApiError> {
+        check_webhook_url(&req.url)?;
+        if req.event_types.is_empty() {
+            return Err(invalid("event_types can't be empty"));
+        }
+        self.with_project(&req.project_id, |p| {
+            p.environment(&req.environment_id)?;
+            let now = Utc::now();
+            let endpoint = WebhookEndpoint {
+                id: new_id("whe"),
+                url: req.url.clone(),
+                description: req.description.clone(),
+                environment_id: req.environment_id.clone(),
+                event_types: req.event_types.clone(),
+                is_active: true,
+                consecutive_failures: 0,
+                disabled_at: None,
+                disabled_reason: String::new(),
+                signing_secret: new_id("whsec"),
+                created_at: now,
+                updated_at: now,
+            };
+            p.webhooks.push(endpoint.clone());
+            Ok(endpoint)
+        })
+    }
+
+    async fn update_webhook(
+        &self,
+        id: &str,
+        req: &UpdateWebhookRequest,
+    ) -> Result<WebhookEndpoint, ApiError> {
+        if let Some(url) = &req.url {
+            check_webhook_url(url)?;
+        }
+        self.with_webhook(id, |p, i| {
+            let endpoint = &mut p.webhooks[i];
+            if let Some(url) = &req.url {
+                endpoint.url = url.clone();
+            }
+            if let Some(description) = &req.description {
+                endpoint.description = description.clone();
+            }
+            if let Some(event_types) = &req.event_types {
+                endpoint.event_types = event_types.clone();
+            }
+            if let Some(active) = req.is_active {
+                endpoint.is_active = active;
+            }
+            endpoint.updated_at = Utc::now();
+            Ok(endpoint.clone())
+        })
+    }
+
+    async fn delete_webhook(&self, id: &str) -> Result<(), ApiError> {
+        self.with_webhook(id, |p, i| {
+            p.webhooks.remove(i);
+            p.deliveries.remove(id);
+            Ok(())
+        })
+    }
+
+    async fn regenerate_webhook_secret(&self, id: &str) -> Result<WebhookEndpoint, ApiError> {
+        self.with_webhook(id, |p, i| {
+            let endpoint = &mut p.webhooks[i];
+            endpoint.signing_secret = new_id("whsec");
+            endpoint.updated_at = Utc::now();
+            Ok(endpoint.clone())
+        })
+    }
+
+    async fn reactivate_webhook(&self, id: &str) -> Result<WebhookEndpoint, ApiError> {
+        self.with_webhook(id, |p, i| {
+            let endpoint = &mut p.webhooks[i];
+            endpoint.is_active = true;
+            endpoint.consecutive_failures = 0;
+            endpoint.disabled_at = None;
+            endpoint.disabled_reason.clear();
+            endpoint.updated_at = Utc::now();
+            Ok(endpoint.clone())
+        })
+    }
+
+    async fn list_webhook_deliveries(
+        &self,
+        id: &str,
+        limit: i32,
+        offset: i32,
+    ) -> Result<Vec<WebhookDelivery>, ApiError> {
+        self.with_webhook(id, |p, _| {
+            Ok(p.deliveries
+                .get(id)
+                .into_iter()
+                .flatten()
+                .skip(offset.max(0) as usize)
+                .take(limit.max(0) as usize)
+                .cloned()
+                .collect())
+        })
+    }
+
+    // ── Projects & Environments ─────────────────────────────────────
+
+    async fn list_projects(&self) -> Result<Vec<Project>, ApiError> {
+        Ok(self
+            .lock()
+            .projects
+            .iter()
+            .map(|p| p.project.clone())
+            .collect())
+    }
+
+    async fn list_environments(&self, project_id: &str) -> Result<Vec<Environment>, ApiError> {
+        self.with_project(project_id, |p| Ok(p.environments.clone()))
+    }
+}
+
+fn check_webhook_url(url: &str) -> Result<(), ApiError> {
+    if url.starts_with("https://") || url.starts_with("http://") {
+        Ok(())
+    } else {
+        Err(invalid("url must start with http:// or https://"))
+    }
+}
+
+/// Files created by `initialize_ai_configs`: name, type and content.
+const STARTER_AI_CONFIGS: &[(&str, &str, &str)] = &[
+    (
+        "code-review.md",
+        "skill",
+        "# Code review\n\nReview changes for correctness first, then readability.\n",
+    ),
+    (
+        "conventions.md",
+        "rule",
+        "# Conventions\n\nFollow the existing style of the file you are editing.\n",
+    ),
+    (
+        "assistant.md",
+        "agent",
+        "# Assistant\n\nAnswer questions about this project's feature flags.\n",
+    ),
+];
+
+impl MemoryState {
+    /// One project with three environments and a little of everything, so
+    /// each view has something to show. Schedules are placed relative to `now`.
+    pub fn demo(now: DateTime<Utc>) -> Self {
+        let ts = |offset: Duration| (now + offset).to_rfc3339();
+        let created = ts(-Duration::days(30));
+        let state = json!({
+            "projects": [{
+                "id": "prj_demo",
+                "name": "Demo Shop",
+                "slug": "demo-shop",
+                "created_at": created,
+                "environments": [
+                    {"id": "env_dev", "name": "Development", "slug": "development", "is_default": true, "created_at": created},
+                    {"id": "env_stg", "name": "Staging", "slug": "staging", "is_default": false, "created_at": created},
+                    {"id": "env_prod", "name": "Production", "slug": "production", "is_default": false, "created_at": created},
+                ],
+                "flags": [
+                    {
+                        "id": "flg_checkout", "key": "new-checkout", "name": "New checkout",
+                        "description": "Single-page checkout flow", "flag_type": "boolean",
+                        "default_value": false, "tags": ["checkout", "web"],
+                        "created_at": created, "updated_at": ts(-Duration::days(2)),
+                        "environments": [
+                            {"id": "fe_checkout_dev", "environment_id": "env_dev", "enabled": true, "value": null, "rules": [], "rollout_percentage": 100},
+                            {"id": "fe_checkout_stg", "environment_id": "env_stg", "enabled": true, "value": null,
+                             "rules": [{"description": "Internal users", "clauses": [{"attribute": "email", "operator": "ends_with", "value": "@demo.shop"}], "value": true}],
+                             "rollout_percentage": 50},
+                            {"id": "fe_checkout_prod", "environment_id": "env_prod", "enabled": true, "value": null, "rules": [], "rollout_percentage": 10},
+                        ],
+                    },
+                    {
+                        "id": "flg_search", "key": "search-algorithm", "name": "Search algorithm",
+                        "description": "Ranking used for product search", "flag_type": "string",
+                        "default_value": "classic", "tags": ["search"],
+                        "created_at": created, "updated_at": created,
+                        "environments": [
+                            {"id": "fe_search_dev", "environment_id": "env_dev", "enabled": true, "value": "semantic", "rules": [], "rollout_percentage": 100},
+                            {"id": "fe_search_stg", "environment_id": "env_stg", "enabled": true, "value": null, "rules": [], "rollout_percentage": 100},
+                            {"id": "fe_search_prod", "environment_id": "env_prod", "enabled": false, "value": null, "rules": [], "rollout_percentage": 100},
+                        ],
+                    },
+                    {
+                        "id": "flg_dark", "key": "dark-mode", "name": "Dark mode",
+                        "description": "", "flag_type": "boolean",
+                        "default_value": false, "tags": ["ui"],
+                        "created_at": created, "updated_at": created,
+                        "environments": [
+                            {"id": "fe_dark_dev", "environment_id": "env_dev", "enabled": true, "value": null, "rules": [], "rollout_percentage": 100},
+                            {"id": "fe_dark_stg", "environment_id": "env_stg", "enabled": false, "value": null, "rules": [], "rollout_percentage": 100},
+                            {"id": "fe_dark_prod", "environment_id": "env_prod", "enabled": false, "value": null, "rules": [], "rollout_percentage": 100},
+                        ],
+                    },
+                ],
+                "variations": {
+                    "search-algorithm": {
+                        "env_stg": [
+                            {"id": "var_classic", "key": "classic", "name": "Classic", "value": "classic", "weight": 50},
+                            {"id": "var_semantic", "key": "semantic", "name": "Semantic", "value": "semantic", "weight": 50},
+                        ],
+                    },
+                },
+                "schedules": {
+                    "new-checkout": {
+                        "env_prod": [
+                            {"id": "sch_checkout_5", "action": "set_rollout", "scheduled_at": ts(-Duration::days(3)),
+                             "executed_at": ts(-Duration::days(3)), "status": "executed",
+                             "payload": {"rollout_percentage": 5, "rollout_plan": true}, "created_at": ts(-Duration::days(7))},
+                            {"id": "sch_checkout_25", "action": "set_rollout", "scheduled_at": ts(Duration::days(2)),
+                             "status": "pending", "payload": {"rollout_percentage": 25, "rollout_plan": true}, "created_at": ts(-Duration::days(7))},
+                            {"id": "sch_checkout_100", "action": "set_rollout", "scheduled_at": ts(Duration::days(9)),
+                             "status": "pending", "payload": {"rollout_percentage": 100, "rollout_plan": true}, "created_at": ts(-Duration::days(7))},
+                        ],
+                    },
+                },
+                "configs": [
+                    {
+                        "id": "cfg_theme", "key": "theme", "name": "Theme", "description": "Storefront colours",
+                        "config_type": "json", "default_value": {"primary": "#3b82f6", "radius": 4},
+                        "tags": ["ui"], "created_at": created, "updated_at": created,
+                        "environments": [
+                            {"id": "ce_theme_prod", "environment_id": "env_prod", "value": {"primary": "#1d4ed8", "radius": 4}, "is_active": true},
+                        ],
+                        "metadata": {"schema": {
+                            "type": "object", "required": ["primary"],
+                            "properties": {
+                                "primary": {"type": "string", "minLength": 4, "maxLength": 7},
+                                "radius": {"type": "integer", "minimum": 0, "maximum": 16},
+                            },
+                            "additionalProperties": false,
+                        }},
+                    },
+                    {
+                        "id": "cfg_cart", "key": "max-cart-items", "name": "Max cart items", "description": "",
+                        "config_type": "number", "default_value": 50,
+                        "tags": [], "created_at": created, "updated_at": created,
+                        "environments": [],
+                        "metadata": {"schema": {"type": "integer", "minimum": 1, "maximum": 500}},
+                    },
+                    {
+                        "id": "cfg_banner", "key": "banner-text", "name": "Banner text", "description": "Shown above the header",
+                        "config_type": "string", "default_value": "",
+                        "tags": ["marketing"], "created_at": created, "updated_at": created,
+                        "environments": [
+                            {"id": "ce_banner_prod", "environment_id": "env_prod", "value": "Free shipping this week", "is_active": true},
+                        ],
+                    },
+                ],
+                "ai_configs": [
+                    {
+                        "id": "aic_review", "file_name": "code-review.md", "file_type": "skill",
+                        "content": "# Code review\n\nCheck flag keys against the manifest before merging.\n",
+                        "is_active": true, "folder": "skills", "created_at": created, "updated_at": created,
+                        "project_id": "prj_demo", "environment_id": "env_dev",
+                    },
+                    {
+                        "id": "aic_rules", "file_name": "flags.md", "file_type": "rule",
+                        "content": "# Flags\n\nRemove a flag once it has been at 100% for two weeks.\n",
+                        "is_active": true, "created_at": created, "updated_at": created,
+                        "project_id": "prj_demo", "environment_id": "env_dev",
+                    },
+                ],
+                "webhooks": [
+                    {
+                        "id": "whe_slack", "url": "https://hooks.demo.shop/flagdash", "description": "Slack relay",
+                        "environment_id": "env_prod", "event_types": ["flag.updated", "flag.toggled"],
+                        "is_active": true, "signing_secret": "whsec_demo_slack",
+                        "created_at": created, "updated_at": created,
+                    },
+                    {
+                        "id": "whe_audit", "url": "https://audit.demo.shop/events", "description": "Audit log",
+                        "environment_id": "env_prod", "event_types": ["config.updated"],
+                        "is_active": false, "consecutive_failures": 12,
+                        "disabled_at": ts(-Duration::days(1)), "disabled_reason": "Too many consecutive failures",
+                        "signing_secret": "whsec_demo_audit",
+                        "created_at": created, "updated_at": ts(-Duration::days(1)),
+                    },
+                ],
+                "deliveries": {
+                    "whe_slack": [
+                        {"id": "dlv_3", "event_type": "flag.toggled", "status": "success", "http_status": 200,
+                         "attempt_count": 1, "max_attempts": 5, "completed_at": ts(-Duration::hours(2)), "created_at": ts(-Duration::hours(2))},
+                        {"id": "dlv_2", "event_type": "flag.updated", "status": "failed", "http_status": 502,
+                         "error_message": "Bad gateway", "attempt_count": 5, "max_attempts": 5,
+                         "completed_at": ts(-Duration::days(1)), "created_at": ts(-Duration::days(1))},
+                        {"id": "dlv_1", "event_type": "flag.updated", "status": "success", "http_status": 200,
+                         "attempt_count": 2, "max_attempts": 5, "completed_at": ts(-Duration::days(2)), "created_at": ts(-Duration::days(2))},
+                    ],
+                    "whe_audit": [
+                        {"id": "dlv_4", "event_type": "config.updated", "status": "failed", "http_status": 0,
+                         "error_message": "connection refused", "attempt_count": 5, "max_attempts": 5,
+                         "completed_at": ts(-Duration::days(1)), "created_at": ts(-Duration::days(1))},
+                    ],
+                },
+            }],
+        });
+        serde_json::from_value(state).expect("demo state is valid")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_demo_state_covers_every_view() {
+        let api = MemoryBackend::demo();
+        let projects = api.list_projects().await.unwrap();
+        assert_eq!(projects[0].id, "prj_demo");
+        assert_eq!(api.list_environments("prj_demo").await.unwrap().len(), 3);
+        assert_eq!(api.list_flags("prj_demo").await.unwrap().len(), 3);
+        assert_eq!(api.list_configs("prj_demo").await.unwrap().len(), 3);
+        assert!(!api
+            .list_ai_configs("prj_demo", "env_dev")
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(!api
+            .list_variations("search-algorithm", "prj_demo", "env_stg")
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(!api
+            .list_schedules("new-checkout", "prj_demo", "env_prod")
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(api.list_webhooks("prj_demo").await.unwrap().len(), 2);
+        assert_eq!(
+            api.list_webhook_deliveries("whe_slack", 2, 1)
+                .await
+                .unwrap()
+                .iter()
+                .map(|d| d.id.as_str())
+                .collect::<Vec<_>>(),
+            ["dlv_2", "dlv_1"]
+        );
+        assert!(api.validate_key().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_flag_lifecycle() {
+        let api = MemoryBackend::demo();
+        let req = CreateFlagRequest {
+            project_id: "prj_demo".into(),
+            key: "beta".into(),
+            name: "Beta".into(),
+            description: String::new(),
+            flag_type: "boolean".into(),
+            tags: vec![],
+            default_value: None,
+        };
+        let flag = api.create_flag(&req).await.unwrap();
+        assert_eq!(flag.default_value, json!(false));
+        assert_eq!(flag.environments.len(), 3);
+        assert!(matches!(
+            api.create_flag(&req).await,
+            Err(ApiError::Validation(_))
+        ));
+
+        let toggled = api
+            .toggle_flag("beta", "prj_demo", "env_prod")
+            .await
+            .unwrap();
+        assert!(toggled.flag_environment.enabled);
+        assert!(matches!(
+            api.set_rollout("beta", "prj_demo", "env_prod", 101).await,
+            Err(ApiError::Validation(_))
+        ));
+        api.set_rollout("beta", "prj_demo", "env_prod", 40)
+            .await
+            .unwrap();
+        let flag = api.get_flag("beta", "prj_demo").await.unwrap();
+        let prod = flag
+            .environments
+            .iter()
+            .find(|e| e.environment_id == "env_prod")
+            .unwrap();
+        assert!(prod.enabled);
+        assert_eq!(prod.rollout_percentage, 40);
+
+        api.delete_flag("beta", "prj_demo").await.unwrap();
+        assert!(matches!(
+            api.get_flag("beta", "prj_demo").await,
+            Err(ApiError::NotFound(_))
+        ));
+        assert!(matches!(
+            api.list_flags("prj_missing").await,
+            Err(ApiError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_schedules_and_config_values() {
+        let api = MemoryBackend::demo();
+        let past = CreateScheduleRequest::set_rollout(Utc::now() - Duration::hours(1), 50);
+        assert!(matches!(
+            api.create_schedule("new-checkout", "prj_demo", "env_prod", &past)
+                .await,
+            Err(ApiError::Validation(_))
+        ));
+        let future = CreateScheduleRequest::set_rollout(Utc::now() + Duration::hours(1), 50);
+        let schedule = api
+            .create_schedule("new-checkout", "prj_demo", "env_prod", &future)
+            .await
+            .unwrap();
+        api.cancel_schedule("new-checkout", "prj_demo", &schedule.id)
+            .await
+            .unwrap();
+        assert!(api
+            .cancel_schedule("new-checkout", "prj_demo", &schedule.id)
+            .await
+            .is_err());
+
+        let resp = api
+            .set_config_value("max-cart-items", "prj_demo", "env_prod", json!(20))
+            .await
+            .unwrap();
+        assert_eq!(resp.config_environment.remote_config_id, "cfg_cart");
+        let config = api.get_config("max-cart-items", "prj_demo").await.unwrap();
+        assert_eq!(config.environments[0].value, json!(20));
+    }
+
+    #[test]
+    fn test_state_round_trips_through_json() {
+        let state = MemoryState::demo(Utc::now());
+        let json = serde_json::to_string(&state).unwrap();
+        let back: MemoryState = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.projects[0].flags.len(), state.projects[0].flags.len());
+        assert_eq!(back.projects[0].project.id, "prj_demo");
+    }
+}
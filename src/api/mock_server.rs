@@ -0,0 +1,668 @@
+use crate::api::backend::FlagDashApi;
+use crate::api::error::ApiError;
+use crate::api::memory::{MemoryBackend, MemoryState};
+use crate::api::types::*;
+use anyhow::{Context, Result};
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use hyper::body::Incoming;
+use hyper::header::{AUTHORIZATION, CONTENT_TYPE, HOST};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+
+/// A FlagDash server for development and demos: the `/api/v1/auth/device*`
+/// and `/api/v1/manage/*` endpoints, answered by a `MemoryBackend`.
+///
+/// Any non-empty bearer token is accepted, and device logins are approved on
+/// the first poll. With a state file, the data is loaded from it at start and
+/// written back after every change.
+#[derive(Clone)]
+pub struct MockServer {
+    backend: MemoryBackend,
+    state_file: Option<PathBuf>,
+    save_lock: Arc<Mutex<()>>,
+    log: bool,
+}
+
+/// A route's result: the status and JSON body to send.
+type Reply = Result<(StatusCode, Option<Value>), ApiError>;
+
+impl MockServer {
+    pub fn new(backend: MemoryBackend) -> Self {
+        Self {
+            backend,
+            state_file: None,
+            save_lock: Arc::new(Mutex::new(())),
+            log: false,
+        }
+    }
+
+    /// Serve the state in `path`, seeding it with the demo project first if
+    /// the file doesn't exist yet (or `reset` is set).
+    pub fn with_state_file(path: &Path, reset: bool) -> Result<Self> {
+        let state = if path.exists() && !reset {
+            let contents = std::fs::read_to_string(path)
+                .with_context(|| format!("reading {}", path.display()))?;
+            serde_json::from_str(&contents)
+                .with_context(|| format!("parsing {}", path.display()))?
+        } else {
+            MemoryState::demo(chrono::Utc::now())
+        };
+        let mut server = Self::new(MemoryBackend::new(state));
+        server.state_file = Some(path.to_path_buf());
+        server.save()?;
+        Ok(server)
+    }
+
+    /// Print one line per request to stderr.
+    pub fn logging(mut self) -> Self {
+        self.log = true;
+        self
+    }
+
+    /// Accept connections until the task is dropped.
+    pub async fn serve(self, listener: TcpListener) -> Result<()> {
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let server = self.clone();
+            tokio::spawn(async move {
+                let service = service_fn(move |req| {
+                    let server = server.clone();
+                    async move { Ok::<_, Infallible>(server.handle(req).await) }
+                });
+                // A client hanging up mid-request isn't the server's problem
+                let _ = http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await;
+            });
+        }
+    }
+
+    async fn handle(&self, req: Request<Incoming>) -> Response<Full<Bytes>> {
+        let method = req.method().clone();
+        let uri = req.uri().clone();
+        let host = header(&req, HOST).unwrap_or("localhost").to_string();
+        let authenticated = header(&req, AUTHORIZATION)
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|token| !token.trim().is_empty());
+        let body = match req.into_body().collect().await {
+            Ok(body) => body.to_bytes(),
+            Err(e) => {
+                return error_response(StatusCode::BAD_REQUEST, "bad_request", &e.to_string())
+            }
+        };
+
+        let call = Call {
+            method: &method,
+            segments: uri
+                .path()
+                .split('/')
+                .filter(|s| !s.is_empty())
+                .map(percent_decode)
+                .collect(),
+            query: parse_query(uri.query().unwrap_or("")),
+            body: &body,
+        };
+        let segments: Vec<&str> = call.segments.iter().map(String::as_str).collect();
+
+        let response = match segments.as_slice() {
+            ["device"] if method == Method::GET => html_response(
+                "<h1>Device approved</h1><p>You can close this tab and return to the terminal.</p>",
+            ),
+            ["api", "v1", "auth", rest @ ..] => {
+                into_response(self.auth_route(&call, rest, &host).await)
+            }
+            ["api", "v1", "manage", rest @ ..] if authenticated => {
+                into_response(self.manage_route(&call, rest).await)
+            }
+            ["api", "v1", "manage", ..] => error_response(
+                StatusCode::UNAUTHORIZED,
+                "unauthorized",
+                "missing or empty bearer token",
+            ),
+            _ => route_not_found(&method, uri.path()),
+        };
+
+        if method != Method::GET && response.status().is_success() {
+            if let Err(e) = self.save() {
+                eprintln!("mock: {:#}", e);
+            }
+        }
+        if self.log {
+            eprintln!("{} {} -> {}", method, uri, response.status().as_u16());
+        }
+        response
+    }
+
+    async fn auth_route(&self, call: &Call<'_>, rest: &[&str], host: &str) -> Reply {
+        let api = &self.backend;
+        match (call.method, rest) {
+            (&Method::POST, ["device"]) => {
+                let body: DeviceAuthRequest = call.json()?;
+                let mut auth = api.request_device_auth(body.device_name.as_deref()).await?;
+                auth.verification_url = format!("http://{}/device", host);
+                ok(&auth)
+            }
+            (&Method::POST, ["device", "token"]) => {
+                let body: DeviceTokenRequest = call.json()?;
+                ok(&api.poll_device_token(&body.device_code).await?)
+            }
+            _ => Err(no_route(call)),
+        }
+    }
+
+    async fn manage_route(&self, call: &Call<'_>, rest: &[&str]) -> Reply {
+        let api = &self.backend;
+        let get = Method::GET;
+        let post = Method::POST;
+        let put = Method::PUT;
+        let delete = Method::DELETE;
+        let m = call.method;
+
+        match rest {
+            // ── Projects & Environments ─────────────────────────────
+            ["projects"] if *m == get => ok(&ProjectsResponse {
+                projects: api.list_projects().await?,
+            }),
+            ["environments"] if *m == get => ok(&EnvironmentsResponse {
+                environments: api.list_environments(call.param("project_id")?).await?,
+            }),
+
+            // ── Flags ───────────────────────────────────────────────
+            ["flags"] if *m == get => ok(&ManagedFlagsResponse {
+                flags: api.list_flags(call.param("project_id")?).await?,
+            }),
+            ["flags"] if *m == post => created(&ManagedFlagResponse {
+                flag: api.create_flag(&call.json()?).await?,
+            }),
+            ["flags", key] if *m == get => ok(&ManagedFlagResponse {
+                flag: api.get_flag(key, call.param("project_id")?).await?,
+            }),
+            ["flags", key] if *m == put => ok(&ManagedFlagResponse {
+                flag: api
+                    .update_flag(key, call.param("project_id")?, &call.json()?)
+                    .await?,
+            }),
+            ["flags", key] if *m == delete => {
+                api.delete_flag(key, call.param("project_id")?).await?;
+                no_content()
+            }
+            ["flags", key, "toggle"] if *m == post => ok(&api
+                .toggle_flag(
+                    key,
+                    call.param("project_id")?,
+                    call.param("environment_id")?,
+                )
+                .await?),
+            ["flags", key, "rollout"] if *m == put => {
+                let body: UpdateRolloutRequest = call.json()?;
+                ok(&api
+                    .set_rollout(
+                        key,
+                        call.param("project_id")?,
+                        call.param("environment_id")?,
+                        body.rollout_percentage,
+                    )
+                    .await?)
+            }
+            ["flags", key, "rules"] if *m == put => {
+                let body: UpdateRulesRequest = call.json()?;
+                ok(&api
+                    .update_rules(
+                        key,
+                        call.param("project_id")?,
+                        call.param("environment_id")?,
+                        body.rules,
+                    )
+                    .await?)
+            }
+            ["flags", key, "variations"] if *m == get => ok(&VariationsResponse {
+                variations: api
+                    .list_variations(
+                        key,
+                        call.param("project_id")?,
+                        call.param("environment_id")?,
+                    )
+                    .await?,
+            }),
+            ["flags", key, "variations"] if *m == put => {
+                let body: SetVariationsRequest = call.json()?;
+                ok(&VariationsResponse {
+                    variations: api
+                        .set_variations(
+                            key,
+                            call.param("project_id")?,
+                            call.param("environment_id")?,
+                            body.variations,
+                        )
+                        .await?,
+                })
+            }
+            ["flags", key, "variations"] if *m == delete => {
+                api.delete_variations(
+                    key,
+                    call.param("project_id")?,
+                    call.param("environment_id")?,
+                )
+                .await?;
+                no_content()
+            }
+
+            // ── Schedules ───────────────────────────────────────────
+            ["flags", key, "schedules"] if *m == get => ok(&SchedulesResponse {
+                schedules: api
+                    .list_schedules(
+                        key,
+                        call.param("project_id")?,
+                        call.param("environment_id")?,
+                    )
+                    .await?,
+            }),
+            ["flags", key, "schedules"] if *m == post => created(&ScheduleResponse {
+                schedule: api
+                    .create_schedule(
+                        key,
+                        call.param("project_id")?,
+                        call.param("environment_id")?,
+                        &call.json()?,
+                    )
+                    .await?,
+            }),
+            ["flags", key, "schedules", id] if *m == delete => {
+                api.cancel_schedule(key, call.param("project_id")?, id)
+                    .await?;
+                no_content()
+            }
+
+            // ── Configs ─────────────────────────────────────────────
+            ["configs"] if *m == get => ok(&ManagedConfigsResponse {
+                configs: api.list_configs(call.param("project_id")?).await?,
+            }),
+            ["configs"] if *m == post => created(&ManagedConfigResponse {
+                config: api.create_config(&call.json()?).await?,
+            }),
+            ["configs", key] if *m == get => ok(&ManagedConfigResponse {
+                config: api.get_config(key, call.param("project_id")?).await?,
+            }),
+            ["configs", key] if *m == put => ok(&ManagedConfigResponse {
+                config: api
+                    .update_config(key, call.param("project_id")?, &call.json()?)
+                    .await?,
+            }),
+            ["configs", key] if *m == delete => {
+                api.delete_config(key, call.param("project_id")?).await?;
+                no_content()
+            }
+            ["configs", key, "value"] if *m == put => {
+                let body: UpdateConfigValueRequest = call.json()?;
+                ok(&api
+                    .set_config_value(
+                        key,
+                        call.param("project_id")?,
+                        call.param("environment_id")?,
+                        body.value,
+                    )
+                    .await?)
+            }
+
+            // ── AI Configs ──────────────────────────────────────────
+            ["ai-configs"] if *m == get => ok(&ManagedAiConfigsResponse {
+                ai_configs: api
+                    .list_ai_configs(call.param("project_id")?, call.param("environment_id")?)
+                    .await?,
+            }),
+            ["ai-configs"] if *m == post => created(&ManagedAiConfigResponse {
+                ai_config: api.create_ai_config(&call.json()?).await?,
+            }),
+            ["ai-configs", "initialize"] if *m == post => {
+                let body: InitializeAiConfigsRequest = call.json()?;
+                created(&ManagedAiConfigsResponse {
+                    ai_configs: api
+                        .initialize_ai_configs(&body.project_id, &body.environment_id)
+                        .await?,
+                })
+            }
+            ["ai-configs", file_name] if *m == get => ok(&ManagedAiConfigResponse {
+                ai_config: api
+                    .get_ai_config(
+                        file_name,
+                        call.param("project_id")?,
+                        call.param("environment_id")?,
+                    )
+                    .await?,
+            }),
+            ["ai-configs", file_name] if *m == put => ok(&ManagedAiConfigResponse {
+                ai_config: api
+                    .update_ai_config(
+                        file_name,
+                        call.param("project_id")?,
+                        call.param("environment_id")?,
+                        &call.json()?,
+                    )
+                    .await?,
+            }),
+            ["ai-configs", file_name] if *m == delete => {
+                api.delete_ai_config(
+                    file_name,
+                    call.param("project_id")?,
+                    call.param("environment_id")?,
+                )
+                .await?;
+                no_content()
+            }
+
+            // ── Webhooks ────────────────────────────────────────────
+            ["webhooks"] if *m == get => ok(&WebhookEndpointsResponse {
+                endpoints: api.list_webhooks(call.param("project_id")?).await?,
+            }),
+            ["webhooks"] if *m == post => created(&WebhookEndpointResponse {
+                endpoint: api.create_webhook(&call.json()?).await?,
+            }),
+            ["webhooks", id] if *m == get => ok(&WebhookEndpointResponse {
+                endpoint: api.get_webhook(id).await?,
+            }),
+            ["webhooks", id] if *m == put => ok(&WebhookEndpointResponse {
+                endpoint: api.update_webhook(id, &call.json()?).await?,
+            }),
+            ["webhooks", id] if *m == delete => {
+                api.delete_webhook(id).await?;
+                no_content()
+            }
+            ["webhooks", id, "regenerate-secret"] if *m == post => ok(&WebhookEndpointResponse {
+                endpoint: api.regenerate_webhook_secret(id).await?,
+            }),
+            ["webhooks", id, "reactivate"] if *m == post => ok(&WebhookEndpointResponse {
+                endpoint: api.reactivate_webhook(id).await?,
+            }),
+            ["webhooks", id, "deliveries"] if *m == get => ok(&WebhookDeliveriesResponse {
+                deliveries: api
+                    .list_webhook_deliveries(
+                        id,
+                        call.number_param("limit", 20)?,
+                        call.number_param("offset", 0)?,
+                    )
+                    .await?,
+            }),
+
+            _ => Err(no_route(call)),
+        }
+    }
+
+    /// Write the state to the state file, if there is one. Written to a
+    /// temporary file first so a crash can't leave it half-written.
+    fn save(&self) -> Result<()> {
+        let Some(path) = &self.state_file else {
+            return Ok(());
+        };
+        let _guard = self.save_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let json = serde_json::to_string_pretty(&self.backend.snapshot())?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json + "\n").with_context(|| format!("writing {}", tmp.display()))?;
+        std::fs::rename(&tmp, path).with_context(|| format!("writing {}", path.display()))
+    }
+}
+
+/// The parts of a request the routes look at.
+struct Call<'a> {
+    method: &'a Method,
+    segments: Vec<String>,
+    query: HashMap<String, String>,
+    body: &'a Bytes,
+}
+
+impl Call<'_> {
+    fn json<T: DeserializeOwned>(&self) -> Result<T, ApiError> {
+        serde_json::from_slice(self.body)
+            .map_err(|e| ApiError::Validation(format!("invalid request body: {}", e)))
+    }
+
+    fn param(&self, name: &str) -> Result<&str, ApiError> {
+        self.query
+            .get(name)
+            .map(String::as_str)
+            .filter(|v| !v.is_empty())
+            .ok_or_else(|| ApiError::Validation(format!("{} is required", name)))
+    }
+
+    fn number_param(&self, name: &str, default: i32) -> Result<i32, ApiError> {
+        match self.query.get(name) {
+            Some(v) => v
+                .parse()
+                .map_err(|_| ApiError::Validation(format!("{} must be a number", name))),
+            None => Ok(default),
+        }
+    }
+}
+
+fn ok<T: Serialize>(body: &T) -> Reply {
+    Ok((StatusCode::OK, Some(to_json(body)?)))
+}
+
+fn created<T: Serialize>(body: &T) -> Reply {
+    Ok((StatusCode::CREATED, Some(to_json(body)?)))
+}
+
+fn no_content() -> Reply {
+    Ok((StatusCode::NO_CONTENT, None))
+}
+
+fn to_json<T: Serialize>(body: &T) -> Result<Value, ApiError> {
+    serde_json::to_value(body).map_err(|e| ApiError::Parse(e.to_string()))
+}
+
+fn no_route(call: &Call<'_>) -> ApiError {
+    ApiError::Http {
+        status: 404,
+        message: format!("no route for {} /{}", call.method, call.segments.join("/")),
+    }
+}
+
+fn into_response(reply: Reply) -> Response<Full<Bytes>> {
+    match reply {
+        Ok((status, Some(body))) => json_response(status, &body),
+        Ok((status, None)) => Response::builder()
+            .status(status)
+            .body(Full::default())
+            .expect("valid response"),
+        Err(e) => {
+            let (status, code) = match &e {
+                ApiError::Unauthorized => (StatusCode::UNAUTHORIZED, "unauthorized"),
+                ApiError::Forbidden => (StatusCode::FORBIDDEN, "forbidden"),
+                ApiError::NotFound(_) => (StatusCode::NOT_FOUND, "not_found"),
+                ApiError::Validation(_) => (StatusCode::UNPROCESSABLE_ENTITY, "validation_failed"),
+                ApiError::RateLimited(_) => (StatusCode::TOO_MANY_REQUESTS, "rate_limited"),
+                ApiError::Http { status, .. } => (
+                    StatusCode::from_u16(*status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+                    "error",
+                ),
+                ApiError::Network(_) | ApiError::Parse(_) => {
+                    (StatusCode::INTERNAL_SERVER_ERROR, "internal_error")
+                }
+            };
+            let message = match e {
+                ApiError::NotFound(m) | ApiError::Validation(m) => m,
+                ApiError::Http { message, .. } => message,
+                other => other.to_string(),
+            };
+            error_response(status, code, &message)
+        }
+    }
+}
+
+fn error_response(status: StatusCode, code: &str, message: &str) -> Response<Full<Bytes>> {
+    let body = ErrorResponse {
+        error: code.to_string(),
+        message: message.to_string(),
+    };
+    json_response(status, &serde_json::to_value(body).unwrap_or_default())
+}
+
+fn route_not_found(method: &Method, path: &str) -> Response<Full<Bytes>> {
+    error_response(
+        StatusCode::NOT_FOUND,
+        "not_found",
+        &format!("no route for {} {}", method, path),
+    )
+}
+
+fn json_response(status: StatusCode, body: &Value) -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Full::new(Bytes::from(body.to_string())))
+        .expect("valid response")
+}
+
+fn html_response(body: &str) -> Response<Full<Bytes>> {
+    Response::builder()
+        .header(CONTENT_TYPE, "text/html; charset=utf-8")
+        .body(Full::new(Bytes::from(format!(
+            "<!doctype html><title>FlagDash mock</title>{}",
+            body
+        ))))
+        .expect("valid response")
+}
+
+fn header<B>(req: &Request<B>, name: hyper::header::HeaderName) -> Option<&str> {
+    req.headers().get(name)?.to_str().ok()
+}
+
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(name), percent_decode(value))
+        })
+        .collect()
+}
+
+fn percent_decode(s: &str) -> String {
+    let mut out = Vec::with_capacity(s.len());
+    let bytes = s.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| s.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                out.push(byte);
+                i += 3;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::client::ApiClient;
+    use crate::api::retry::RetryPolicy;
+    use crate::config::ConnectionConfig;
+
+    async fn start(server: MockServer) -> ConnectionConfig {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(server.serve(listener));
+        ConnectionConfig {
+            base_url: format!("http://{}", addr),
+            retry: RetryPolicy::none(),
+            ..ConnectionConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_query() {
+        let query = parse_query("project_id=prj%20acme&environment_id=env_prod&flag");
+        assert_eq!(query["project_id"], "prj acme");
+        assert_eq!(query["environment_id"], "env_prod");
+        assert_eq!(query["flag"], "");
+        assert_eq!(percent_decode("caf%C3%A9%2"), "café%2");
+    }
+
+    #[tokio::test]
+    async fn test_login_then_edit_over_http() {
+        let dir = std::env::temp_dir().join(format!("flagdash-mock-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let state_file = dir.join("state.json");
+        let connection = start(MockServer::with_state_file(&state_file, false).unwrap()).await;
+
+        let anonymous = ApiClient::new_unauthenticated(&connection).unwrap();
+        assert!(matches!(
+            anonymous.list_projects().await,
+            Err(ApiError::Unauthorized)
+        ));
+        let auth = anonymous.request_device_auth(Some("laptop")).await.unwrap();
+        assert_eq!(
+            auth.verification_url,
+            format!("{}/device", connection.base_url)
+        );
+        let token = anonymous.wait_for_device_token(&auth).await.unwrap();
+        let session = token.session_token.unwrap();
+
+        let api = ApiClient::new(&connection, &session).unwrap();
+        let projects = api.list_projects().await.unwrap();
+        let project = &projects[0].id;
+        let environments = api.list_environments(project).await.unwrap();
+        let env = &environments[0].id;
+
+        let flag = api.get_flag("new-checkout", project).await.unwrap();
+        let enabled = flag
+            .environments
+            .iter()
+            .find(|e| &e.environment_id == env)
+            .map(|e| e.enabled)
+            .unwrap_or(false);
+        let toggled = api.toggle_flag("new-checkout", project, env).await.unwrap();
+        assert_eq!(toggled.flag_environment.enabled, !enabled);
+
+        assert!(matches!(
+            api.get_flag("ghost", project).await,
+            Err(ApiError::NotFound(_))
+        ));
+        assert!(matches!(
+            api.set_rollout("new-checkout", project, env, 101).await,
+            Err(ApiError::Validation(_))
+        ));
+        let webhook = &api.list_webhooks(project).await.unwrap()[0];
+        api.delete_webhook(&webhook.id).await.unwrap();
+
+        // The changes were saved, and a new server picks them up
+        let connection = start(MockServer::with_state_file(&state_file, false).unwrap()).await;
+        let api = ApiClient::new(&connection, &session).unwrap();
+        let flag = api.get_flag("new-checkout", project).await.unwrap();
+        assert_eq!(
+            flag.environments
+                .iter()
+                .find(|e| &e.environment_id == env)
+                .unwrap()
+                .enabled,
+            !enabled
+        );
+        assert!(api
+            .list_webhooks(project)
+            .await
+            .unwrap()
+            .iter()
+            .all(|w| w.id != webhook.id));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,3 +1,9 @@
+pub mod backend;
 pub mod client;
 pub mod error;
+pub mod fixtures;
+pub mod memory;
+pub mod mock_server;
+pub mod retry;
+pub mod transport;
 pub mod types;
@@ -0,0 +1,199 @@
+use chrono::{DateTime, Utc};
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// When and how often `ApiClient` retries a failed request.
+///
+/// Only idempotent methods (GET, PUT, DELETE) are retried unless
+/// `retry_non_idempotent` is set, since a POST that timed out may already
+/// have been applied. Connection failures are retried for every method:
+/// the request never reached the server.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt; 0 disables retrying.
+    pub max_retries: u32,
+    /// Backoff before the first retry, doubled for each retry after it.
+    pub base_delay_ms: u64,
+    /// Upper bound for a single backoff.
+    pub max_delay_ms: u64,
+    /// The longest `Retry-After` the client waits out by itself. Longer waits
+    /// are returned as `ApiError::RateLimited` so the UI can count down instead.
+    pub max_retry_after_secs: u64,
+    pub retry_non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay_ms: 250,
+            max_delay_ms: 8_000,
+            max_retry_after_secs: 30,
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether requests with `method` may be retried after they were sent.
+    pub fn allows(&self, method: &Method) -> bool {
+        self.retry_non_idempotent || is_idempotent(method)
+    }
+
+    /// Backoff before retry number `attempt` (0-based): a random point in
+    /// `[0, min(max_delay, base_delay * 2^attempt)]` ("full jitter"), so
+    /// clients that failed together don't retry together.
+    pub fn backoff(&self, attempt: u32, jitter: f64) -> Duration {
+        let ceiling = self
+            .base_delay_ms
+            .saturating_mul(1u64.checked_shl(attempt).unwrap_or(u64::MAX))
+            .min(self.max_delay_ms);
+        Duration::from_millis((ceiling as f64 * jitter.clamp(0.0, 1.0)) as u64)
+    }
+
+    /// How long to wait before retrying a response with `status`, or `None`
+    /// if it shouldn't be retried (here) at all.
+    pub fn delay_for(
+        &self,
+        status: StatusCode,
+        retry_after: Option<Duration>,
+        attempt: u32,
+    ) -> Option<Duration> {
+        if !is_transient(status) {
+            return None;
+        }
+        match retry_after {
+            Some(wait) if wait > Duration::from_secs(self.max_retry_after_secs) => None,
+            Some(wait) => (attempt < self.max_retries).then_some(wait),
+            None => self.retry_delay(attempt),
+        }
+    }
+
+    /// A jittered backoff before retry number `attempt`, or `None` once the
+    /// retries are used up.
+    pub fn retry_delay(&self, attempt: u32) -> Option<Duration> {
+        (attempt < self.max_retries).then(|| self.backoff(attempt, jitter()))
+    }
+}
+
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::PUT | Method::DELETE
+    )
+}
+
+/// Statuses that usually clear up on their own.
+pub fn is_transient(status: StatusCode) -> bool {
+    matches!(status.as_u16(), 408 | 429 | 502 | 503 | 504)
+}
+
+/// Parse a `Retry-After` header value: either delay-seconds or an HTTP-date.
+pub fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = DateTime::parse_from_rfc2822(value).ok()?;
+    // A date in the past means "now"
+    Some((at.with_timezone(&Utc) - now).to_std().unwrap_or_default())
+}
+
+/// A uniformly random fraction in `[0, 1)`.
+fn jitter() -> f64 {
+    let mut bytes = [0u8; 8];
+    if getrandom::getrandom(&mut bytes).is_err() {
+        return 1.0;
+    }
+    (u64::from_le_bytes(bytes) >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_backoff_grows_and_caps() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.backoff(0, 1.0), Duration::from_millis(250));
+        assert_eq!(policy.backoff(2, 1.0), Duration::from_millis(1_000));
+        assert_eq!(policy.backoff(2, 0.5), Duration::from_millis(500));
+        assert_eq!(policy.backoff(10, 1.0), Duration::from_millis(8_000));
+        assert_eq!(policy.backoff(200, 1.0), Duration::from_millis(8_000));
+        assert_eq!(policy.backoff(3, 0.0), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_delay_for() {
+        let policy = RetryPolicy::default();
+        let unavailable = StatusCode::SERVICE_UNAVAILABLE;
+        assert!(policy.delay_for(unavailable, None, 0).unwrap() <= Duration::from_millis(250));
+        assert_eq!(policy.delay_for(unavailable, None, 3), None);
+        assert_eq!(policy.delay_for(StatusCode::NOT_FOUND, None, 0), None);
+        assert_eq!(
+            policy.delay_for(
+                StatusCode::TOO_MANY_REQUESTS,
+                Some(Duration::from_secs(2)),
+                0
+            ),
+            Some(Duration::from_secs(2))
+        );
+        // Too long to sit out; surfaced to the caller instead
+        assert_eq!(
+            policy.delay_for(
+                StatusCode::TOO_MANY_REQUESTS,
+                Some(Duration::from_secs(90)),
+                0
+            ),
+            None
+        );
+        assert_eq!(RetryPolicy::none().delay_for(unavailable, None, 0), None);
+    }
+
+    #[test]
+    fn test_retries_idempotent_methods_only() {
+        let policy = RetryPolicy::default();
+        assert!(policy.allows(&Method::GET));
+        assert!(policy.allows(&Method::PUT));
+        assert!(policy.allows(&Method::DELETE));
+        assert!(!policy.allows(&Method::POST));
+        let policy = RetryPolicy {
+            retry_non_idempotent: true,
+            ..RetryPolicy::default()
+        };
+        assert!(policy.allows(&Method::POST));
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = Utc.with_ymd_and_hms(2015, 10, 21, 7, 28, 0).unwrap();
+        assert_eq!(
+            parse_retry_after("120", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+}
@@ -0,0 +1,137 @@
+use crate::config::ConnectionConfig;
+use anyhow::{Context, Result};
+use reqwest::{Certificate, Client, Identity, NoProxy, Proxy};
+use std::path::Path;
+use std::time::Duration;
+
+pub const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 10;
+pub const DEFAULT_READ_TIMEOUT_SECS: u64 = 30;
+
+pub fn default_user_agent() -> String {
+    format!("flagdash-cli/{}", env!("CARGO_PKG_VERSION"))
+}
+
+/// Build the HTTP client for a profile's `[connection]` settings.
+pub fn build_http_client(connection: &ConnectionConfig) -> Result<Client> {
+    let mut builder = Client::builder()
+        .connect_timeout(Duration::from_secs(
+            connection
+                .connect_timeout_secs
+                .unwrap_or(DEFAULT_CONNECT_TIMEOUT_SECS),
+        ))
+        .read_timeout(Duration::from_secs(
+            connection
+                .read_timeout_secs
+                .unwrap_or(DEFAULT_READ_TIMEOUT_SECS),
+        ))
+        .user_agent(
+            connection
+                .user_agent
+                .clone()
+                .unwrap_or_else(default_user_agent),
+        );
+
+    if let Some(url) = &connection.proxy {
+        let proxy = Proxy::all(url)
+            .with_context(|| format!("invalid proxy URL '{}'", url))?
+            .no_proxy(
+                connection
+                    .no_proxy
+                    .as_deref()
+                    .and_then(NoProxy::from_string),
+            );
+        builder = builder.proxy(proxy);
+    }
+
+    for path in &connection.ca_certs {
+        for cert in read_certificates(path)
+            .with_context(|| format!("loading CA certificate {}", path.display()))?
+        {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+
+    if let Some(cert) = &connection.client_cert {
+        builder = builder.identity(
+            read_identity(cert, connection.client_key.as_deref())
+                .with_context(|| format!("loading client certificate {}", cert.display()))?,
+        );
+    } else if connection.client_key.is_some() {
+        anyhow::bail!("client_key is set but client_cert isn't");
+    }
+
+    builder.build().context("setting up the HTTP client")
+}
+
+/// Every certificate in a PEM bundle, or the single certificate in a DER file.
+fn read_certificates(path: &Path) -> Result<Vec<Certificate>> {
+    let bytes = std::fs::read(path)?;
+    if bytes.windows(10).any(|w| w == b"-----BEGIN") {
+        let certs = Certificate::from_pem_bundle(&bytes)?;
+        if certs.is_empty() {
+            anyhow::bail!("no certificates found");
+        }
+        Ok(certs)
+    } else {
+        Ok(vec![Certificate::from_der(&bytes)?])
+    }
+}
+
+fn read_identity(cert: &Path, key: Option<&Path>) -> Result<Identity> {
+    let mut pem = std::fs::read(cert)?;
+    if let Some(key) = key {
+        pem.push(b'\n');
+        pem.extend(std::fs::read(key).with_context(|| format!("reading {}", key.display()))?);
+    }
+    Ok(Identity::from_pem(&pem)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_http_client_reports_bad_settings() {
+        assert!(build_http_client(&ConnectionConfig::default()).is_ok());
+
+        let connection = ConnectionConfig {
+            proxy: Some("http://proxy.corp:3128".into()),
+            no_proxy: Some("localhost,.internal".into()),
+            connect_timeout_secs: Some(3),
+            user_agent: Some("acme-ci".into()),
+            ..ConnectionConfig::default()
+        };
+        assert!(build_http_client(&connection).is_ok());
+
+        let connection = ConnectionConfig {
+            proxy: Some("not a url".into()),
+            ..ConnectionConfig::default()
+        };
+        let err = build_http_client(&connection).unwrap_err();
+        assert!(format!("{:#}", err).contains("invalid proxy URL"));
+
+        let connection = ConnectionConfig {
+            ca_certs: vec!["/nonexistent/ca.pem".into()],
+            ..ConnectionConfig::default()
+        };
+        let err = build_http_client(&connection).unwrap_err();
+        assert!(format!("{:#}", err).contains("/nonexistent/ca.pem"));
+
+        let dir = std::env::temp_dir().join(format!("flagdash-transport-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let garbage = dir.join("ca.pem");
+        std::fs::write(&garbage, "-----BEGIN CERTIFICATE-----\nnope\n").unwrap();
+        let connection = ConnectionConfig {
+            ca_certs: vec![garbage],
+            ..ConnectionConfig::default()
+        };
+        assert!(build_http_client(&connection).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let connection = ConnectionConfig {
+            client_key: Some("key.pem".into()),
+            ..ConnectionConfig::default()
+        };
+        assert!(build_http_client(&connection).is_err());
+    }
+}
@@ -139,6 +139,14 @@ pub struct ManagedConfig {
     pub updated_at: DateTime<Utc>,
     #[serde(default, deserialize_with = "null_default")]
     pub environments: Vec<ConfigEnvironmentValue>,
+    /// Free-form data attached to the config. A JSON Schema under `schema`
+    /// is used to validate values before they're saved.
+    #[serde(
+        default,
+        deserialize_with = "null_default",
+        skip_serializing_if = "serde_json::Value::is_null"
+    )]
+    pub metadata: serde_json::Value,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -281,21 +289,21 @@ pub struct EnvironmentsResponse {
 
 // ── Request types ────────────────────────────────────────────────────
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateFlagRequest {
     pub project_id: String,
     pub key: String,
     pub name: String,
-    #[serde(skip_serializing_if = "String::is_empty")]
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub description: String,
     pub flag_type: String,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub tags: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub default_value: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateFlagRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
@@ -309,17 +317,17 @@ pub struct UpdateFlagRequest {
     pub is_archived: Option<bool>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateRulesRequest {
     pub rules: serde_json::Value,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateRolloutRequest {
     pub rollout_percentage: i32,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VariationInput {
     pub key: String,
     pub name: String,
@@ -327,12 +335,12 @@ pub struct VariationInput {
     pub weight: i32,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SetVariationsRequest {
     pub variations: Vec<VariationInput>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateScheduleRequest {
     pub action: String,
     pub scheduled_at: String,
@@ -340,21 +348,73 @@ pub struct CreateScheduleRequest {
     pub payload: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+/// What a schedule does to the flag environment when it fires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleAction {
+    Enable,
+    Disable,
+    SetRollout,
+    SetValue,
+}
+
+impl ScheduleAction {
+    pub const ALL: [ScheduleAction; 4] = [
+        ScheduleAction::Enable,
+        ScheduleAction::Disable,
+        ScheduleAction::SetRollout,
+        ScheduleAction::SetValue,
+    ];
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|a| a.as_str() == s)
+    }
+
+    /// The `action` string sent to and returned by the API.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ScheduleAction::Enable => "enable",
+            ScheduleAction::Disable => "disable",
+            ScheduleAction::SetRollout => "set_rollout",
+            ScheduleAction::SetValue => "set_value",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ScheduleAction::Enable => "Enable",
+            ScheduleAction::Disable => "Disable",
+            ScheduleAction::SetRollout => "Set rollout",
+            ScheduleAction::SetValue => "Set value",
+        }
+    }
+}
+
+impl CreateScheduleRequest {
+    /// A schedule that sets the rollout percentage, with the same payload as `UpdateRolloutRequest`.
+    pub fn set_rollout(scheduled_at: DateTime<Utc>, percentage: i32) -> Self {
+        Self {
+            action: ScheduleAction::SetRollout.as_str().to_string(),
+            scheduled_at: scheduled_at.to_rfc3339(),
+            payload: Some(serde_json::json!({ "rollout_percentage": percentage })),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateConfigRequest {
     pub project_id: String,
     pub key: String,
     pub name: String,
-    #[serde(skip_serializing_if = "String::is_empty")]
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub description: String,
     pub config_type: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub default_value: Option<serde_json::Value>,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub tags: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateConfigRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
@@ -368,19 +428,19 @@ pub struct UpdateConfigRequest {
     pub is_archived: Option<bool>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateConfigValueRequest {
     pub value: serde_json::Value,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateAiConfigRequest {
     pub project_id: String,
     pub environment_id: String,
     pub file_name: String,
     pub file_type: String,
     pub content: String,
-    #[serde(skip_serializing_if = "String::is_empty")]
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub folder: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub is_active: Option<bool>,
@@ -388,7 +448,7 @@ pub struct CreateAiConfigRequest {
     pub metadata: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateAiConfigRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub content: Option<String>,
@@ -400,23 +460,23 @@ pub struct UpdateAiConfigRequest {
     pub folder: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InitializeAiConfigsRequest {
     pub project_id: String,
     pub environment_id: String,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateWebhookRequest {
     pub project_id: String,
     pub environment_id: String,
     pub url: String,
-    #[serde(skip_serializing_if = "String::is_empty")]
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub description: String,
     pub event_types: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateWebhookRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<String>,
@@ -467,20 +527,20 @@ pub struct DeviceTokenUser {
     pub role: String,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeviceAuthRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub device_name: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeviceTokenRequest {
     pub device_code: String,
 }
 
 // ── Error response ───────────────────────────────────────────────────
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ErrorResponse {
     #[serde(default)]
     pub error: String,
@@ -2,15 +2,19 @@ use crate::action::{
     Action, ConfirmAction, DashboardData, DashboardFlag, SidebarSection, ToastLevel, ToastMessage,
     View,
 };
-use crate::api::client::ApiClient;
+use crate::api::backend::{self, Backend, SharedApi};
+use crate::api::error::ApiError;
 use crate::components::confirm_dialog::ConfirmDialog;
 use crate::components::environment_switcher::EnvironmentSwitcher;
 use crate::components::header::Header;
+use crate::components::profile_switcher::ProfileSwitcher;
 use crate::components::sidebar::Sidebar;
 use crate::components::status_bar::StatusBar;
 use crate::components::toast::Toast;
-use crate::config::AppConfig;
+use crate::config::{AppConfig, SessionExpiry};
 use crate::event::Event;
+use crate::manifest::apply::apply_operation;
+use crate::schema;
 use crate::views::ai_configs::{
     detail::AiConfigDetailView, form::AiConfigFormView, list::AiConfigListView,
 };
@@ -19,11 +23,13 @@ use crate::views::configs::{
     value_editor::ConfigValueEditorView,
 };
 use crate::views::dashboard::DashboardView;
-use crate::views::environments::list::EnvironmentListView;
+use crate::views::environments::{
+    list::EnvironmentListView, matrix::EnvironmentMatrixView, promote::PromoteView,
+};
 use crate::views::flags::{
     detail::FlagDetailView, form::FlagFormView, list::FlagListView, rollout::FlagRolloutView,
-    rules::FlagRulesView, schedules::FlagSchedulesView, toggle::FlagToggleView,
-    variations::FlagVariationsView,
+    rules::FlagRulesView, schedules::FlagSchedulesView, simulate::SimulatePanel,
+    toggle::FlagToggleView, variations::FlagVariationsView,
 };
 use crate::views::login::LoginView;
 use crate::views::project_picker::ProjectPickerView;
@@ -38,7 +44,7 @@ use tokio::sync::mpsc;
 
 pub struct App {
     pub config: AppConfig,
-    pub api: Option<ApiClient>,
+    pub api: Option<SharedApi>,
     pub running: bool,
 
     // Layout components
@@ -48,6 +54,7 @@ pub struct App {
     pub toast: Toast,
     pub confirm: ConfirmDialog,
     pub env_switcher: EnvironmentSwitcher,
+    pub profile_switcher: ProfileSwitcher,
 
     // Current view
     pub current_view: View,
@@ -76,6 +83,11 @@ pub struct App {
     pub webhook_detail: WebhookDetailView,
     pub webhook_form: Option<WebhookFormView>,
     pub env_list: EnvironmentListView,
+    pub env_matrix: EnvironmentMatrixView,
+    pub promote: Option<PromoteView>,
+
+    /// Where to return after logging in again when the session expired mid-use.
+    pub resume_view: Option<View>,
 
     // Async action channel
     pub action_tx: mpsc::UnboundedSender<Action>,
@@ -83,18 +95,20 @@ pub struct App {
 }
 
 impl App {
-    pub fn new(config: AppConfig) -> Self {
-        let key_tier = config.user_role_tier();
-        let (action_tx, action_rx) = mpsc::unbounded_channel();
-
+    pub fn new(config: AppConfig) -> anyhow::Result<Self> {
         let api = if config.has_session_token() {
-            Some(ApiClient::new(
-                &config.connection.base_url,
-                &config.auth.session_token,
-            ))
+            Some(backend::connect(&config)?)
         } else {
             None
         };
+        Ok(Self::with_api(config, api))
+    }
+
+    /// Create the app with an already connected backend, e.g. a
+    /// `MemoryBackend` with known data in tests.
+    pub fn with_api(config: AppConfig, api: Option<SharedApi>) -> Self {
+        let key_tier = config.user_role_tier();
+        let (action_tx, action_rx) = mpsc::unbounded_channel();
 
         let mut app = Self {
             status_bar: StatusBar::new(&config.connection.base_url),
@@ -106,6 +120,7 @@ impl App {
             toast: Toast::new(),
             confirm: ConfirmDialog::new(),
             env_switcher: EnvironmentSwitcher::new(),
+            profile_switcher: ProfileSwitcher::new(),
             current_view: View::Login,
             pending_confirm: None,
             project_picker: ProjectPickerView::new(),
@@ -130,10 +145,16 @@ impl App {
             webhook_detail: WebhookDetailView::new(key_tier),
             webhook_form: None,
             env_list: EnvironmentListView::new(),
+            env_matrix: EnvironmentMatrixView::new(),
+            promote: None,
+            resume_view: None,
             action_tx,
             action_rx,
         };
 
+        app.header.profile = app.config.profile.clone();
+        app.status_bar.session_expires_at = app.config.auth.expires_at();
+
         // Navigate to the correct initial view (triggers data loading)
         if app.config.has_session_token() {
             app.status_bar.connected = true;
@@ -145,7 +166,11 @@ impl App {
                 &app.config.defaults.project_id,
                 &app.config.defaults.environment_id,
             );
-            app.navigate(View::ProjectPicker);
+            if app.config.auth.expiry(chrono::Utc::now()) == SessionExpiry::Expired {
+                app.begin_reauth("Your session has expired. Log in again to continue.");
+            } else {
+                app.navigate(View::ProjectPicker);
+            }
         }
 
         app
@@ -166,6 +191,14 @@ impl App {
             return Ok(());
         }
 
+        // Profile switcher overlay
+        if self.profile_switcher.is_visible() {
+            if let Some(action) = self.profile_switcher.handle_event(event) {
+                self.process_action(action);
+            }
+            return Ok(());
+        }
+
         // Confirm dialog takes priority
         if self.confirm.is_visible() {
             if let Some(action) = self.confirm.handle_event(event) {
@@ -192,13 +225,31 @@ impl App {
                             | View::WebhookCreate
                             | View::WebhookEdit(_)
                     )
+                    && !self.is_capturing_text()
                 {
                     self.running = false;
                     return Ok(());
                 }
 
+                // Global 'C' for profile switcher, also before logging in
+                if key.code == crossterm::event::KeyCode::Char('C')
+                    && (self.is_main_view() || matches!(self.current_view, View::Login))
+                    && !self.is_searching()
+                    && !self.is_capturing_text()
+                {
+                    if self.config.backend == Backend::Memory {
+                        self.toast.show(
+                            "Profiles aren't used with --backend memory".to_string(),
+                            ToastLevel::Info,
+                        );
+                    } else {
+                        self.profile_switcher.show(&self.config.profile);
+                    }
+                    return Ok(());
+                }
+
                 // Global 'e' for environment switcher, 'p' for project picker, 'l' for logout
-                if self.is_main_view() && !self.is_searching() {
+                if self.is_main_view() && !self.is_searching() && !self.is_capturing_text() {
                     match key.code {
                         crossterm::event::KeyCode::Char('e') => {
                             self.open_environment_switcher();
@@ -305,10 +356,12 @@ impl App {
                 .webhook_form
                 .as_mut()
                 .and_then(|f| f.handle_event(event)),
-            View::EnvironmentList => {
-                self.env_list.handle_event(event);
-                self.sidebar.handle_event(event)
-            }
+            View::EnvironmentList => self
+                .env_list
+                .handle_event(event)
+                .or_else(|| self.sidebar.handle_event(event)),
+            View::EnvironmentMatrix => self.env_matrix.handle_event(event),
+            View::Promote => self.promote.as_mut().and_then(|v| v.handle_event(event)),
         };
 
         if let Some(action) = action {
@@ -345,6 +398,14 @@ impl App {
                 self.handle_device_token_poll_result(*response);
             }
             Action::LoginSuccess => {
+                if let Some(view) = self.resume_view.take() {
+                    // Views kept their state while logged out; pick up where we left off
+                    self.current_view = view;
+                    self.reload_current_view();
+                    self.toast
+                        .show("Logged in again".to_string(), ToastLevel::Success);
+                    return;
+                }
                 self.project_picker.set_saved_defaults(
                     &self.config.defaults.project_id,
                     &self.config.defaults.environment_id,
@@ -362,16 +423,19 @@ impl App {
                     v.environments = envs.clone();
                 }
                 if let Some(v) = &mut self.flag_rollout {
-                    v.environments = envs.clone();
+                    v.set_environments(envs.clone());
+                    if let Some(action) = v.load_action() {
+                        self.process_action(action);
+                    }
                 }
                 if let Some(v) = &mut self.flag_rules {
-                    v.environments = envs.clone();
-                }
-                if let Some(v) = &mut self.flag_variations {
-                    v.environments = envs.clone();
+                    v.set_environments(envs.clone());
                 }
                 if let Some(v) = &mut self.flag_schedules {
-                    v.environments = envs.clone();
+                    v.set_environments(envs.clone());
+                    if let Some(action) = v.load_action() {
+                        self.process_action(action);
+                    }
                 }
                 if let Some(v) = &mut self.config_value_editor {
                     v.environments = envs.clone();
@@ -382,7 +446,22 @@ impl App {
                 if let Some(v) = &mut self.flag_toggle {
                     v.flag = Some((*flag).clone());
                 }
+                if let Some(v) = &mut self.flag_rules {
+                    v.set_flag((*flag).clone());
+                }
+                if let Some(v) = &mut self.flag_variations {
+                    v.flag = Some((*flag).clone());
+                }
+                if let Some(v) = &mut self.flag_schedules {
+                    v.flag = Some((*flag).clone());
+                }
+                if let Some(v) = &mut self.flag_rollout {
+                    v.set_flag((*flag).clone());
+                }
                 self.flag_detail.flag = Some(*flag);
+                if let Some(panel) = &mut self.flag_detail.simulate {
+                    panel.refresh(self.flag_detail.flag.as_ref());
+                }
             }
             Action::ConfigLoaded(config) => {
                 self.config_detail.config = Some(*config);
@@ -397,6 +476,9 @@ impl App {
                 self.webhook_detail.deliveries = deliveries;
             }
             Action::SchedulesLoaded(schedules) => {
+                if let Some(v) = &mut self.flag_rollout {
+                    v.set_schedules(schedules.clone());
+                }
                 if let Some(v) = &mut self.flag_schedules {
                     v.set_schedules(schedules);
                 }
@@ -409,6 +491,38 @@ impl App {
             Action::DashboardLoaded(data) => {
                 self.dashboard_view.data = Some(data);
             }
+            Action::OpenSimulator(key) => {
+                self.flag_detail.simulate =
+                    Some(SimulatePanel::new(&self.config.defaults.environment_id));
+                self.load_simulation_data(key);
+            }
+            Action::SimulationDataLoaded {
+                environments,
+                variations,
+            } => {
+                if let Some(v) = &mut self.flag_rules {
+                    v.set_environments(environments.clone());
+                    v.set_variations(variations.clone());
+                }
+                if let Some(v) = &mut self.flag_variations {
+                    v.set_data(environments.clone(), variations.clone());
+                }
+                if let Some(panel) = &mut self.flag_detail.simulate {
+                    panel.set_data(environments, variations);
+                    panel.refresh(self.flag_detail.flag.as_ref());
+                }
+            }
+            Action::EnvironmentStateLoaded {
+                environments,
+                flags,
+                configs,
+            } => {
+                self.env_matrix
+                    .set_data(environments.clone(), flags.clone(), configs.clone());
+                if let Some(v) = &mut self.promote {
+                    v.set_data(environments, flags, configs);
+                }
+            }
             Action::SwitcherEnvironmentsLoaded(envs) => {
                 self.env_switcher.set_environments(envs);
             }
@@ -427,6 +541,20 @@ impl App {
                 self.reload_current_view();
             }
             Action::EnvironmentSwitcherDismissed => {}
+            Action::ProfileSwitched(name) => {
+                match AppConfig::switch_profile(&name).and_then(App::new) {
+                    Ok(app) => {
+                        // A profile is a different server and login: start over as
+                        // if launched with --profile. Requests still in flight report
+                        // to the old channel and are dropped.
+                        *self = app;
+                        self.toast
+                            .show(format!("Switched to profile {}", name), ToastLevel::Success);
+                    }
+                    Err(e) => self.toast.show(e.to_string(), ToastLevel::Error),
+                }
+            }
+            Action::ProfileSwitcherDismissed => {}
             Action::ProjectsLoaded(projects) => {
                 self.project_picker.set_projects(projects);
             }
@@ -458,6 +586,14 @@ impl App {
             Action::SubmitFlagToggle(key) => self.submit_flag_toggle(key),
             Action::SubmitRolloutUpdate(key) => self.submit_rollout_update(key),
             Action::SubmitRulesUpdate(key) => self.submit_rules_update(key),
+            Action::SubmitVariationsUpdate(key) => self.submit_variations_update(key),
+            Action::SubmitScheduleCreate(key) => self.submit_schedule_create(key),
+            Action::SubmitRolloutPlan(key) => self.submit_rollout_plan(key),
+            Action::LoadSchedules {
+                flag_key,
+                environment_id,
+            } => self.load_schedules(flag_key, environment_id),
+            Action::LoadEnvironmentState => self.load_environment_state(),
             Action::SubmitConfigCreate => self.submit_config_create(),
             Action::SubmitConfigUpdate(key) => self.submit_config_update(key),
             Action::SubmitConfigValueUpdate(key) => self.submit_config_value_update(key),
@@ -503,12 +639,32 @@ impl App {
             }
             Action::ScheduleCreated(_) | Action::ScheduleCancelled(_) => {
                 // Reload schedules in place
-                let key = match &self.current_view {
-                    View::FlagSchedules(k) => Some(k.clone()),
-                    _ => None,
-                };
-                if let Some(k) = key {
-                    self.load_schedules(k);
+                if let Some(v) = &mut self.flag_schedules {
+                    v.close_form();
+                    if let Some(action) = v.load_action() {
+                        self.process_action(action);
+                    }
+                }
+            }
+            Action::ScheduleCreateFailed(msg) => match &mut self.flag_schedules {
+                Some(v) if v.is_editing() => v.set_form_error(msg),
+                _ => self.toast.show(msg, ToastLevel::Error),
+            },
+            Action::RolloutPlanCreated(_) => {
+                if let Some(v) = &mut self.flag_rollout {
+                    v.plan_created();
+                    if let Some(action) = v.load_action() {
+                        self.process_action(action);
+                    }
+                }
+            }
+            Action::PromotionApplied(_) => self.load_environment_state(),
+            Action::RolloutPlanCancelled(remaining) => {
+                if let Some(v) = &mut self.flag_rollout {
+                    v.set_paused(remaining);
+                    if let Some(action) = v.load_action() {
+                        self.process_action(action);
+                    }
                 }
             }
             Action::ConfigCreated(_) | Action::ConfigUpdated(_) => {
@@ -545,12 +701,32 @@ impl App {
             Action::WebhookSecretRegenerated(webhook) | Action::WebhookReactivated(webhook) => {
                 self.webhook_detail.webhook = Some(*webhook);
             }
+            Action::Unauthorized => {
+                if !matches!(self.current_view, View::Login) {
+                    self.begin_reauth(
+                        "Your session has expired or was revoked. Log in again to continue where you left off.",
+                    );
+                } else if self.resume_view.is_none() && !self.login_view.is_waiting() {
+                    let msg = ApiError::Unauthorized.to_string();
+                    self.login_view.set_error(&msg);
+                    self.toast.show(msg, ToastLevel::Error);
+                }
+                // Otherwise another request sent with the old session failed
+                // too; the login already under way takes care of it.
+            }
             Action::ApiError(ref msg) => {
                 if matches!(self.current_view, View::Login) {
                     self.login_view.set_error(msg);
                 }
                 self.toast.show(msg.clone(), ToastLevel::Error);
             }
+            Action::RateLimited(wait) => {
+                self.status_bar.rate_limited_until = Some(std::time::Instant::now() + wait);
+                self.toast.show(
+                    ApiError::RateLimited(Some(wait)).to_string(),
+                    ToastLevel::Error,
+                );
+            }
             Action::SetLoading(loading) => {
                 self.status_bar.loading = loading;
             }
@@ -565,7 +741,10 @@ impl App {
             View::ProjectPicker => self.load_projects(),
             View::Dashboard => self.load_dashboard(),
             View::FlagList => self.load_flags(),
-            View::FlagDetail(key) => self.load_flag(key.clone()),
+            View::FlagDetail(key) => {
+                self.flag_detail.simulate = None;
+                self.load_flag(key.clone());
+            }
             View::ConfigList => self.load_configs(),
             View::ConfigDetail(key) => self.load_config(key.clone()),
             View::AiConfigList => self.load_ai_configs(),
@@ -573,6 +752,20 @@ impl App {
             View::WebhookList => self.load_webhooks(),
             View::WebhookDetail(id) => self.load_webhook(id.clone()),
             View::EnvironmentList => self.load_environments(),
+            View::EnvironmentMatrix => self.load_environment_state(),
+            View::Promote => {
+                let from = self
+                    .env_list
+                    .selected_environment()
+                    .map(|e| e.id.clone())
+                    .unwrap_or_default();
+                self.promote = Some(PromoteView::new(
+                    &from,
+                    &self.config.defaults.environment_id,
+                    self.flag_detail.key_tier.clone(),
+                ));
+                self.load_environment_state();
+            }
             View::FlagCreate => {
                 self.flag_form = Some(FlagFormView::new_create(&self.config.defaults.project_id));
             }
@@ -632,25 +825,53 @@ impl App {
                 self.load_environments();
             }
             View::FlagRollout(key) => {
-                self.flag_rollout = Some(FlagRolloutView::new(key));
+                self.flag_rollout = Some(
+                    FlagRolloutView::new(key, self.flag_detail.key_tier.clone())
+                        .with_environment(&self.config.defaults.environment_id),
+                );
+                self.load_flag(key.clone());
                 self.load_environments();
             }
             View::FlagRules(key) => {
-                self.flag_rules = Some(FlagRulesView::new(key));
-                self.load_environments();
+                self.flag_rules = Some(
+                    FlagRulesView::new(key)
+                        .with_environment(&self.config.defaults.environment_id)
+                        .with_format(self.config.value_format),
+                );
+                self.load_flag(key.clone());
+                self.load_simulation_data(key.clone());
             }
             View::FlagVariations(key) => {
-                self.flag_variations = Some(FlagVariationsView::new(key));
-                self.load_environments();
+                self.flag_variations = Some(
+                    FlagVariationsView::new(key)
+                        .with_environment(&self.config.defaults.environment_id),
+                );
+                self.load_flag(key.clone());
+                self.load_simulation_data(key.clone());
             }
             View::FlagSchedules(key) => {
-                self.flag_schedules = Some(FlagSchedulesView::new(key));
+                self.flag_schedules = Some(
+                    FlagSchedulesView::new(key, self.flag_detail.key_tier.clone())
+                        .with_environment(&self.config.defaults.environment_id),
+                );
+                self.load_flag(key.clone());
                 self.load_environments();
             }
             View::ConfigValueEditor(key) => {
-                let mut editor = ConfigValueEditorView::new(key);
+                let config = self.config_detail.config.as_ref();
+                let config_type = config.map(|c| c.config_type.as_str()).unwrap_or("json");
+                let schema = match config.map(schema::for_config).transpose() {
+                    Ok(schema) => schema.flatten(),
+                    Err(e) => {
+                        self.toast.show(format!("{:#}", e), ToastLevel::Error);
+                        None
+                    }
+                };
+                let mut editor = ConfigValueEditorView::new(key, config_type)
+                    .with_format(self.config.value_format)
+                    .with_schema(schema);
                 // Pre-fill with current config value from the first environment
-                if let Some(config) = &self.config_detail.config {
+                if let Some(config) = config {
                     if let Some(env) = config.environments.first() {
                         editor.set_value(&env.value);
                     } else {
@@ -680,6 +901,7 @@ impl App {
             View::WebhookDetail(_) | View::WebhookCreate | View::WebhookEdit(_) => {
                 View::WebhookList
             }
+            View::EnvironmentMatrix | View::Promote => View::EnvironmentList,
             _ => View::Dashboard,
         };
         self.navigate(back_view);
@@ -699,13 +921,19 @@ impl App {
 
     fn handle_browser_login_requested(&mut self) {
         let base_url = self.config.connection.base_url.clone();
+        let client = match backend::connect_unauthenticated(&self.config) {
+            Ok(client) => client,
+            Err(e) => {
+                self.login_view.set_error(&format!("{:#}", e));
+                return;
+            }
+        };
         let tx = self.action_tx.clone();
         let hostname = std::env::var("HOSTNAME")
             .or_else(|_| std::env::var("COMPUTERNAME"))
             .unwrap_or_else(|_| "FlagDash CLI".to_string());
 
         tokio::spawn(async move {
-            let client = ApiClient::new_unauthenticated(&base_url);
             match client.request_device_auth(Some(&hostname)).await {
                 Ok(resp) => {
                     let _ = tx.send(Action::DeviceAuthReceived(Box::new(resp)));
@@ -732,48 +960,24 @@ impl App {
         let _ = open::that(&device_auth.verification_url);
 
         // Start polling for the token
-        let base_url = self.config.connection.base_url.clone();
-        let device_code = device_auth.device_code.clone();
-        let interval = device_auth.interval;
-        let expires_in = device_auth.expires_in;
+        let client = match backend::connect_unauthenticated(&self.config) {
+            Ok(client) => client,
+            Err(e) => {
+                self.login_view.set_error(&format!("{:#}", e));
+                return;
+            }
+        };
         let tx = self.action_tx.clone();
 
         tokio::spawn(async move {
-            let client = ApiClient::new_unauthenticated(&base_url);
-            let max_polls = expires_in / interval.max(1);
-            let sleep_duration = std::time::Duration::from_secs(interval.max(2));
-
-            for _ in 0..max_polls {
-                tokio::time::sleep(sleep_duration).await;
-                match client.poll_device_token(&device_code).await {
-                    Ok(resp) => {
-                        let _ = tx.send(Action::DeviceTokenPollResult(Box::new(resp.clone())));
-                        // If we got a token or a terminal error, stop polling
-                        if resp.session_token.is_some() {
-                            return;
-                        }
-                        if let Some(err) = &resp.error {
-                            if err != "authorization_pending" && err != "slow_down" {
-                                return;
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        let _ = tx.send(Action::ApiError(format!("Poll error: {}", e)));
-                        return;
-                    }
+            match client.wait_for_device_token(&device_auth).await {
+                Ok(resp) => {
+                    let _ = tx.send(Action::DeviceTokenPollResult(Box::new(resp)));
+                }
+                Err(e) => {
+                    let _ = tx.send(Action::ApiError(format!("Poll error: {}", e)));
                 }
             }
-            // Expired
-            let _ = tx.send(Action::DeviceTokenPollResult(Box::new(
-                crate::api::types::DeviceTokenResponse {
-                    session_token: None,
-                    account: None,
-                    user: None,
-                    expires_at: None,
-                    error: Some("expired_token".to_string()),
-                },
-            )));
         });
     }
 
@@ -781,27 +985,26 @@ impl App {
         &mut self,
         response: crate::api::types::DeviceTokenResponse,
     ) {
-        if let Some(token) = response.session_token {
+        if let Some(token) = response.session_token.clone() {
             // Success! Store the session token and user info
-            self.config.auth.session_token = token;
+            self.config.set_session(token, &response);
 
-            if let Some(user) = &response.user {
-                self.config.auth.user_name = user.name.clone();
-                self.config.auth.user_email = user.email.clone();
-                self.config.auth.user_role = user.role.clone();
-            }
-
-            if let Some(expires_at) = &response.expires_at {
-                self.config.auth.token_expires_at = expires_at.clone();
+            if let Err(e) = self.config.save() {
+                self.toast.show(
+                    format!("Couldn't save the session: {:#}", e),
+                    ToastLevel::Error,
+                );
             }
-
-            let _ = self.config.save();
+            self.status_bar.session_expires_at = self.config.auth.expires_at();
 
             // Set up the API client with the new session token
-            self.api = Some(ApiClient::new(
-                &self.config.connection.base_url,
-                &self.config.auth.session_token,
-            ));
+            match backend::connect(&self.config) {
+                Ok(api) => self.api = Some(api),
+                Err(e) => {
+                    self.login_view.set_error(&format!("{:#}", e));
+                    return;
+                }
+            }
 
             // Update key tier for views
             let key_tier = self.config.user_role_tier();
@@ -840,16 +1043,41 @@ impl App {
         }
     }
 
+    /// Send the user through the device login again, remembering the current
+    /// view so they land back on it afterwards.
+    fn begin_reauth(&mut self, notice: &str) {
+        self.resume_view = match &self.current_view {
+            View::Login | View::ProjectPicker => None,
+            view => Some(view.clone()),
+        };
+        self.env_switcher.hide();
+        self.status_bar.connected = false;
+        self.header.connected = false;
+        self.login_view = LoginView::new().with_notice(notice);
+        self.current_view = View::Login;
+        self.handle_browser_login_requested();
+    }
+
     fn handle_logout(&mut self) {
+        self.resume_view = None;
         self.config.clear_auth();
-        let _ = self.config.save();
+        let saved = self.config.save();
         self.api = None;
         self.status_bar.connected = false;
         self.header.connected = false;
         self.header.project_name.clear();
         self.header.environment_name.clear();
         self.login_view = LoginView::new();
-        self.toast.show("Logged out".to_string(), ToastLevel::Info);
+        match saved {
+            Ok(()) => self.toast.show("Logged out".to_string(), ToastLevel::Info),
+            Err(e) => self.toast.show(
+                format!(
+                    "Logged out, but the stored session couldn't be removed: {:#}",
+                    e
+                ),
+                ToastLevel::Error,
+            ),
+        }
         self.current_view = View::Login;
     }
 
@@ -874,7 +1102,7 @@ impl App {
                             }));
                         }
                         Err(e) => {
-                            let _ = tx.send(Action::ApiError(e.to_string()));
+                            let _ = tx.send(Action::from(e));
                         }
                     }
                 });
@@ -890,7 +1118,7 @@ impl App {
                             }));
                         }
                         Err(e) => {
-                            let _ = tx.send(Action::ApiError(e.to_string()));
+                            let _ = tx.send(Action::from(e));
                         }
                     }
                 });
@@ -906,7 +1134,7 @@ impl App {
                             }));
                         }
                         Err(e) => {
-                            let _ = tx.send(Action::ApiError(e.to_string()));
+                            let _ = tx.send(Action::from(e));
                         }
                     }
                 });
@@ -922,7 +1150,7 @@ impl App {
                             }));
                         }
                         Err(e) => {
-                            let _ = tx.send(Action::ApiError(e.to_string()));
+                            let _ = tx.send(Action::from(e));
                         }
                     }
                 });
@@ -944,15 +1172,91 @@ impl App {
                             }));
                         }
                         Err(e) => {
-                            let _ = tx.send(Action::ApiError(e.to_string()));
+                            let _ = tx.send(Action::from(e));
+                        }
+                    }
+                });
+            }
+            ConfirmAction::Promote { from, to, changes } => {
+                let environments = self
+                    .promote
+                    .as_ref()
+                    .map(|v| v.environments.clone())
+                    .unwrap_or_default();
+                tokio::spawn(async move {
+                    for (i, change) in changes.iter().enumerate() {
+                        if let Err(e) = apply_operation(
+                            api.as_ref(),
+                            &project_id,
+                            &environments,
+                            &change.operation,
+                        )
+                        .await
+                        {
+                            let _ = tx.send(Action::ApiError(format!(
+                                "{} {}: {:#} ({} of {} changes applied)",
+                                change.resource,
+                                change.address,
+                                e,
+                                i,
+                                changes.len()
+                            )));
+                            let _ = tx.send(Action::PromotionApplied(i));
+                            if matches!(e.downcast_ref(), Some(ApiError::Unauthorized)) {
+                                let _ = tx.send(Action::Unauthorized);
+                            }
+                            return;
+                        }
+                    }
+                    let _ = tx.send(Action::PromotionApplied(changes.len()));
+                    let _ = tx.send(Action::Toast(ToastMessage {
+                        message: format!(
+                            "Promoted {} change(s) from {} to {}",
+                            changes.len(),
+                            from,
+                            to
+                        ),
+                        level: ToastLevel::Success,
+                    }));
+                });
+            }
+            ConfirmAction::CancelRolloutPlan {
+                flag_key,
+                schedule_ids,
+                resume_steps,
+            } => {
+                tokio::spawn(async move {
+                    for schedule_id in &schedule_ids {
+                        if let Err(e) = api
+                            .cancel_schedule(&flag_key, &project_id, schedule_id)
+                            .await
+                        {
+                            let _ = tx.send(Action::from(e));
+                            let _ = tx.send(Action::RolloutPlanCancelled(None));
+                            return;
                         }
                     }
+                    let verb = if resume_steps.is_some() {
+                        "paused"
+                    } else {
+                        "aborted"
+                    };
+                    let _ = tx.send(Action::RolloutPlanCancelled(resume_steps));
+                    let _ = tx.send(Action::Toast(ToastMessage {
+                        message: format!("Rollout {}", verb),
+                        level: ToastLevel::Success,
+                    }));
                 });
             }
-            ConfirmAction::DeleteVariations(key) => {
-                let env_id2 = env_id;
+            ConfirmAction::DeleteVariations {
+                flag_key,
+                environment_id,
+            } => {
                 tokio::spawn(async move {
-                    match api.delete_variations(&key, &project_id, &env_id2).await {
+                    match api
+                        .delete_variations(&flag_key, &project_id, &environment_id)
+                        .await
+                    {
                         Ok(()) => {
                             let _ = tx.send(Action::VariationsDeleted);
                             let _ = tx.send(Action::Toast(ToastMessage {
@@ -961,7 +1265,7 @@ impl App {
                             }));
                         }
                         Err(e) => {
-                            let _ = tx.send(Action::ApiError(e.to_string()));
+                            let _ = tx.send(Action::from(e));
                         }
                     }
                 });
@@ -993,6 +1297,22 @@ impl App {
             || self.ai_config_list.search.active
     }
 
+    /// A text panel inside an otherwise key-driven view is open and capturing
+    /// keys: the flag detail simulate panel, or the variation, schedule or
+    /// rollout plan form.
+    fn is_capturing_text(&self) -> bool {
+        match self.current_view {
+            View::FlagDetail(_) => self.flag_detail.simulate.is_some(),
+            View::FlagVariations(_) => self
+                .flag_variations
+                .as_ref()
+                .is_some_and(|v| v.is_editing()),
+            View::FlagSchedules(_) => self.flag_schedules.as_ref().is_some_and(|v| v.is_editing()),
+            View::FlagRollout(_) => self.flag_rollout.as_ref().is_some_and(|v| v.is_editing()),
+            _ => false,
+        }
+    }
+
     fn open_environment_switcher(&mut self) {
         self.env_switcher.show(&self.config.defaults.environment_id);
         // Fetch environments for the current project
@@ -1006,7 +1326,7 @@ impl App {
                     let _ = tx.send(Action::SwitcherEnvironmentsLoaded(envs));
                 }
                 Err(e) => {
-                    let _ = tx.send(Action::ApiError(e.to_string()));
+                    let _ = tx.send(Action::from(e));
                 }
             }
         });
@@ -1016,7 +1336,10 @@ impl App {
         match &self.current_view {
             View::Dashboard => self.load_dashboard(),
             View::FlagList => self.load_flags(),
-            View::FlagDetail(key) => self.load_flag(key.clone()),
+            View::FlagDetail(key) => {
+                self.flag_detail.simulate = None;
+                self.load_flag(key.clone());
+            }
             View::ConfigList => self.load_configs(),
             View::ConfigDetail(key) => self.load_config(key.clone()),
             View::AiConfigList => self.load_ai_configs(),
@@ -1024,6 +1347,20 @@ impl App {
             View::WebhookList => self.load_webhooks(),
             View::WebhookDetail(id) => self.load_webhook(id.clone()),
             View::EnvironmentList => self.load_environments(),
+            // Sub-views keep edits in progress when their data arrives again
+            View::FlagToggle(key) | View::FlagRollout(key) | View::FlagSchedules(key) => {
+                self.load_flag(key.clone());
+                self.load_environments();
+            }
+            View::FlagRules(key) | View::FlagVariations(key) => {
+                self.load_flag(key.clone());
+                self.load_simulation_data(key.clone());
+            }
+            View::ConfigValueEditor(key) => {
+                self.load_config(key.clone());
+                self.load_environments();
+            }
+            View::EnvironmentMatrix => self.load_environment_state(),
             _ => {}
         }
     }
@@ -1040,7 +1377,7 @@ impl App {
                     let _ = tx.send(Action::ProjectsLoaded(projects));
                 }
                 Err(e) => {
-                    let _ = tx.send(Action::ApiError(e.to_string()));
+                    let _ = tx.send(Action::from(e));
                 }
             }
         });
@@ -1056,7 +1393,7 @@ impl App {
                     let _ = tx.send(Action::PickerEnvironmentsLoaded(envs));
                 }
                 Err(e) => {
-                    let _ = tx.send(Action::ApiError(e.to_string()));
+                    let _ = tx.send(Action::from(e));
                 }
             }
         });
@@ -1121,7 +1458,7 @@ impl App {
 
             // Recent flags for dashboard table (up to 8, most recently updated)
             let mut sorted_flags = flags.clone();
-            sorted_flags.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+            sorted_flags.sort_by_key(|f| std::cmp::Reverse(f.updated_at));
             let recent_flags: Vec<DashboardFlag> = sorted_flags
                 .iter()
                 .take(8)
@@ -1166,7 +1503,7 @@ impl App {
                     let _ = tx.send(Action::FlagsLoaded(flags));
                 }
                 Err(e) => {
-                    let _ = tx.send(Action::ApiError(e.to_string()));
+                    let _ = tx.send(Action::from(e));
                 }
             }
         });
@@ -1183,7 +1520,7 @@ impl App {
                     let _ = tx.send(Action::FlagLoaded(Box::new(flag)));
                 }
                 Err(e) => {
-                    let _ = tx.send(Action::ApiError(e.to_string()));
+                    let _ = tx.send(Action::from(e));
                 }
             }
         });
@@ -1200,7 +1537,7 @@ impl App {
                     let _ = tx.send(Action::ConfigsLoaded(configs));
                 }
                 Err(e) => {
-                    let _ = tx.send(Action::ApiError(e.to_string()));
+                    let _ = tx.send(Action::from(e));
                 }
             }
         });
@@ -1217,7 +1554,7 @@ impl App {
                     let _ = tx.send(Action::ConfigLoaded(Box::new(config)));
                 }
                 Err(e) => {
-                    let _ = tx.send(Action::ApiError(e.to_string()));
+                    let _ = tx.send(Action::from(e));
                 }
             }
         });
@@ -1235,7 +1572,7 @@ impl App {
                     let _ = tx.send(Action::AiConfigsLoaded(configs));
                 }
                 Err(e) => {
-                    let _ = tx.send(Action::ApiError(e.to_string()));
+                    let _ = tx.send(Action::from(e));
                 }
             }
         });
@@ -1253,7 +1590,7 @@ impl App {
                     let _ = tx.send(Action::AiConfigLoaded(Box::new(config)));
                 }
                 Err(e) => {
-                    let _ = tx.send(Action::ApiError(e.to_string()));
+                    let _ = tx.send(Action::from(e));
                 }
             }
         });
@@ -1270,7 +1607,7 @@ impl App {
                     let _ = tx.send(Action::WebhooksLoaded(webhooks));
                 }
                 Err(e) => {
-                    let _ = tx.send(Action::ApiError(e.to_string()));
+                    let _ = tx.send(Action::from(e));
                 }
             }
         });
@@ -1287,7 +1624,7 @@ impl App {
                     let _ = tx.send(Action::WebhookLoaded(Box::new(webhook)));
                 }
                 Err(e) => {
-                    let _ = tx.send(Action::ApiError(e.to_string()));
+                    let _ = tx.send(Action::from(e));
                 }
             }
             if let Ok(deliveries) = api.list_webhook_deliveries(&id2, 50, 0).await {
@@ -1296,11 +1633,10 @@ impl App {
         });
     }
 
-    fn load_schedules(&self, flag_key: String) {
+    fn load_schedules(&self, flag_key: String, env_id: String) {
         let Some(api) = &self.api else { return };
         let api = api.clone();
         let project_id = self.config.defaults.project_id.clone();
-        let env_id = self.config.defaults.environment_id.clone();
         let tx = self.action_tx.clone();
         tokio::spawn(async move {
             match api.list_schedules(&flag_key, &project_id, &env_id).await {
@@ -1308,12 +1644,71 @@ impl App {
                     let _ = tx.send(Action::SchedulesLoaded(schedules));
                 }
                 Err(e) => {
-                    let _ = tx.send(Action::ApiError(e.to_string()));
+                    let _ = tx.send(Action::from(e));
                 }
             }
         });
     }
 
+    /// Environments, flags and configs for the promotion and comparison views.
+    fn load_environment_state(&self) {
+        let Some(api) = &self.api else { return };
+        let api = api.clone();
+        let project_id = self.config.defaults.project_id.clone();
+        let tx = self.action_tx.clone();
+        tokio::spawn(async move {
+            match tokio::try_join!(
+                api.list_environments(&project_id),
+                api.list_flags(&project_id),
+                api.list_configs(&project_id),
+            ) {
+                Ok((environments, flags, configs)) => {
+                    let _ = tx.send(Action::EnvironmentStateLoaded {
+                        environments,
+                        flags,
+                        configs,
+                    });
+                }
+                Err(e) => {
+                    let _ = tx.send(Action::from(e));
+                }
+            }
+        });
+    }
+
+    /// Environments plus per-environment variations, for the simulate panel and rules linting.
+    fn load_simulation_data(&self, flag_key: String) {
+        let Some(api) = &self.api else { return };
+        let api = api.clone();
+        let project_id = self.config.defaults.project_id.clone();
+        let tx = self.action_tx.clone();
+        tokio::spawn(async move {
+            let environments = match api.list_environments(&project_id).await {
+                Ok(envs) => envs,
+                Err(e) => {
+                    let _ = tx.send(Action::from(e));
+                    return;
+                }
+            };
+            let mut variations = std::collections::HashMap::new();
+            for env in &environments {
+                match api.list_variations(&flag_key, &project_id, &env.id).await {
+                    Ok(v) => {
+                        variations.insert(env.id.clone(), v);
+                    }
+                    Err(crate::api::error::ApiError::NotFound(_)) => {}
+                    Err(e) => {
+                        let _ = tx.send(Action::from(e));
+                    }
+                }
+            }
+            let _ = tx.send(Action::SimulationDataLoaded {
+                environments,
+                variations,
+            });
+        });
+    }
+
     fn load_environments(&self) {
         let Some(api) = &self.api else { return };
         let api = api.clone();
@@ -1325,7 +1720,7 @@ impl App {
                     let _ = tx.send(Action::EnvironmentsLoaded(envs));
                 }
                 Err(e) => {
-                    let _ = tx.send(Action::ApiError(e.to_string()));
+                    let _ = tx.send(Action::from(e));
                 }
             }
         });
@@ -1349,7 +1744,7 @@ impl App {
                     }));
                 }
                 Err(e) => {
-                    let _ = tx.send(Action::ApiError(e.to_string()));
+                    let _ = tx.send(Action::from(e));
                 }
             }
         });
@@ -1372,7 +1767,7 @@ impl App {
                     }));
                 }
                 Err(e) => {
-                    let _ = tx.send(Action::ApiError(e.to_string()));
+                    let _ = tx.send(Action::from(e));
                 }
             }
         });
@@ -1396,7 +1791,7 @@ impl App {
                     }));
                 }
                 Err(e) => {
-                    let _ = tx.send(Action::ApiError(e.to_string()));
+                    let _ = tx.send(Action::from(e));
                 }
             }
         });
@@ -1421,7 +1816,7 @@ impl App {
                     }));
                 }
                 Err(e) => {
-                    let _ = tx.send(Action::ApiError(e.to_string()));
+                    let _ = tx.send(Action::from(e));
                 }
             }
         });
@@ -1445,7 +1840,7 @@ impl App {
                     }));
                 }
                 Err(e) => {
-                    let _ = tx.send(Action::ApiError(e.to_string()));
+                    let _ = tx.send(Action::from(e));
                 }
             }
         });
@@ -1474,7 +1869,7 @@ impl App {
                     }));
                 }
                 Err(e) => {
-                    let _ = tx.send(Action::ApiError(e.to_string()));
+                    let _ = tx.send(Action::from(e));
                 }
             }
         });
@@ -1498,7 +1893,7 @@ impl App {
                     }));
                 }
                 Err(e) => {
-                    let _ = tx.send(Action::ApiError(e.to_string()));
+                    let _ = tx.send(Action::from(e));
                 }
             }
         });
@@ -1522,7 +1917,7 @@ impl App {
                     }));
                 }
                 Err(e) => {
-                    let _ = tx.send(Action::ApiError(e.to_string()));
+                    let _ = tx.send(Action::from(e));
                 }
             }
         });
@@ -1550,7 +1945,7 @@ impl App {
                     }));
                 }
                 Err(e) => {
-                    let _ = tx.send(Action::ApiError(e.to_string()));
+                    let _ = tx.send(Action::from(e));
                 }
             }
         });
@@ -1582,23 +1977,155 @@ impl App {
                     }));
                 }
                 Err(e) => {
-                    let _ = tx.send(Action::ApiError(e.to_string()));
+                    let _ = tx.send(Action::from(e));
                 }
             }
         });
     }
 
+    fn submit_variations_update(&mut self, key: String) {
+        let Some(view) = &self.flag_variations else {
+            return;
+        };
+        let Some(env_id) = view.selected_environment_id() else {
+            return;
+        };
+        let env_id = env_id.to_string();
+        let variations = match view.validated() {
+            Ok(v) => v,
+            Err(e) => {
+                self.toast.show(e, ToastLevel::Error);
+                return;
+            }
+        };
+        let Some(api) = &self.api else { return };
+        let api = api.clone();
+        let project_id = self.config.defaults.project_id.clone();
+        let tx = self.action_tx.clone();
+        tokio::spawn(async move {
+            match api
+                .set_variations(&key, &project_id, &env_id, variations)
+                .await
+            {
+                Ok(saved) => {
+                    let _ = tx.send(Action::VariationsUpdated(saved));
+                    let _ = tx.send(Action::Toast(ToastMessage {
+                        message: "Variations saved".to_string(),
+                        level: ToastLevel::Success,
+                    }));
+                }
+                Err(e) => {
+                    let _ = tx.send(Action::from(e));
+                }
+            }
+        });
+    }
+
+    fn submit_schedule_create(&mut self, key: String) {
+        let Some(view) = &self.flag_schedules else {
+            return;
+        };
+        let Some(env_id) = view.selected_environment_id() else {
+            return;
+        };
+        let env_id = env_id.to_string();
+        let req = match view.create_request() {
+            Ok(r) => r,
+            Err(e) => {
+                self.process_action(Action::ScheduleCreateFailed(e));
+                return;
+            }
+        };
+        let Some(api) = &self.api else { return };
+        let api = api.clone();
+        let project_id = self.config.defaults.project_id.clone();
+        let tx = self.action_tx.clone();
+        tokio::spawn(async move {
+            match api.create_schedule(&key, &project_id, &env_id, &req).await {
+                Ok(schedule) => {
+                    let _ = tx.send(Action::ScheduleCreated(Box::new(schedule)));
+                    let _ = tx.send(Action::Toast(ToastMessage {
+                        message: "Schedule created".to_string(),
+                        level: ToastLevel::Success,
+                    }));
+                }
+                Err(e) => {
+                    let _ = tx.send(match Action::from(e) {
+                        Action::ApiError(msg) => Action::ScheduleCreateFailed(msg),
+                        action => action,
+                    });
+                }
+            }
+        });
+    }
+
+    /// Create one `set_rollout` schedule per plan step, in order. If a step
+    /// fails, the ones already created are kept and shown in the timeline.
+    fn submit_rollout_plan(&mut self, key: String) {
+        let Some(view) = &mut self.flag_rollout else {
+            return;
+        };
+        let Some(env_id) = view.selected_environment_id() else {
+            return;
+        };
+        let env_id = env_id.to_string();
+        let requests = match view.plan_requests() {
+            Ok(r) => r,
+            Err(e) => {
+                view.set_form_error(e);
+                return;
+            }
+        };
+        let Some(api) = &self.api else { return };
+        let api = api.clone();
+        let project_id = self.config.defaults.project_id.clone();
+        let tx = self.action_tx.clone();
+        tokio::spawn(async move {
+            let mut created = Vec::with_capacity(requests.len());
+            for req in &requests {
+                match api.create_schedule(&key, &project_id, &env_id, req).await {
+                    Ok(schedule) => created.push(schedule),
+                    Err(e) => {
+                        let _ = tx.send(Action::ApiError(format!(
+                            "{} (scheduled {} of {} steps)",
+                            e,
+                            created.len(),
+                            requests.len()
+                        )));
+                        let _ = tx.send(Action::RolloutPlanCreated(created));
+                        if matches!(e, ApiError::Unauthorized) {
+                            let _ = tx.send(Action::Unauthorized);
+                        }
+                        return;
+                    }
+                }
+            }
+            let _ = tx.send(Action::Toast(ToastMessage {
+                message: format!("Scheduled {} rollout steps", created.len()),
+                level: ToastLevel::Success,
+            }));
+            let _ = tx.send(Action::RolloutPlanCreated(created));
+        });
+    }
+
     fn submit_rules_update(&mut self, key: String) {
         let Some(view) = &self.flag_rules else { return };
         let Some(env_id) = view.selected_environment_id() else {
             return;
         };
         let env_id = env_id.to_string();
+        let errors = view.diagnostics().iter().filter(|d| d.is_error()).count();
+        if errors > 0 {
+            self.toast.show(
+                format!("Fix {} rule error(s) before saving", errors),
+                ToastLevel::Error,
+            );
+            return;
+        }
         let rules = match view.parse_rules() {
             Ok(r) => r,
             Err(e) => {
-                self.toast
-                    .show(format!("Invalid JSON: {}", e), ToastLevel::Error);
+                self.toast.show(e, ToastLevel::Error);
                 return;
             }
         };
@@ -1616,7 +2143,7 @@ impl App {
                     }));
                 }
                 Err(e) => {
-                    let _ = tx.send(Action::ApiError(e.to_string()));
+                    let _ = tx.send(Action::from(e));
                 }
             }
         });
@@ -1630,11 +2157,10 @@ impl App {
             return;
         };
         let env_id = env_id.to_string();
-        let value = match view.parse_value() {
+        let value = match view.value() {
             Ok(v) => v,
             Err(e) => {
-                self.toast
-                    .show(format!("Invalid JSON: {}", e), ToastLevel::Error);
+                self.toast.show(e, ToastLevel::Error);
                 return;
             }
         };
@@ -1655,7 +2181,7 @@ impl App {
                     }));
                 }
                 Err(e) => {
-                    let _ = tx.send(Action::ApiError(e.to_string()));
+                    let _ = tx.send(Action::from(e));
                 }
             }
         });
@@ -1669,6 +2195,7 @@ impl App {
         if matches!(self.current_view, View::Login) {
             self.login_view.render(frame, area);
             self.toast.render(frame, area);
+            self.profile_switcher.render(frame, area);
             return;
         }
 
@@ -1714,6 +2241,7 @@ impl App {
         self.toast.render(frame, area);
         self.confirm.render(frame, area);
         self.env_switcher.render(frame, area);
+        self.profile_switcher.render(frame, area);
     }
 
     fn render_view(&mut self, frame: &mut Frame, area: Rect) {
@@ -1778,6 +2306,12 @@ impl App {
                 }
             }
             View::EnvironmentList => self.env_list.render(frame, area),
+            View::EnvironmentMatrix => self.env_matrix.render(frame, area),
+            View::Promote => {
+                if let Some(v) = &mut self.promote {
+                    v.render(frame, area);
+                }
+            }
             View::Login | View::ProjectPicker => {} // handled above
         }
     }
@@ -0,0 +1,44 @@
+use super::CommandContext;
+use anyhow::Result;
+use clap::Subcommand;
+
+#[derive(Subcommand, Debug)]
+pub enum AiConfigsCommand {
+    /// List AI configs in an environment
+    List {
+        /// Environment ID, slug or name (defaults to the configured environment)
+        #[arg(long)]
+        env: Option<String>,
+    },
+    /// Show a single AI config
+    Get {
+        /// File name
+        file_name: String,
+        /// Environment ID, slug or name (defaults to the configured environment)
+        #[arg(long)]
+        env: Option<String>,
+    },
+}
+
+pub async fn run(cmd: AiConfigsCommand, ctx: &mut CommandContext) -> Result<()> {
+    let project_id = ctx.project_id()?.to_string();
+    match cmd {
+        AiConfigsCommand::List { env } => {
+            let environment = ctx.resolve_environment(env.as_deref()).await?;
+            let configs = ctx
+                .api
+                .list_ai_configs(&project_id, &environment.id)
+                .await?;
+            ctx.output.list(&configs)?;
+        }
+        AiConfigsCommand::Get { file_name, env } => {
+            let environment = ctx.resolve_environment(env.as_deref()).await?;
+            let config = ctx
+                .api
+                .get_ai_config(&file_name, &project_id, &environment.id)
+                .await?;
+            ctx.output.item(&config)?;
+        }
+    }
+    Ok(())
+}
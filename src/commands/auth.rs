@@ -0,0 +1,172 @@
+use crate::api::backend;
+use crate::api::error::ApiError;
+use crate::api::types::DeviceAuthResponse;
+use crate::config::{AppConfig, SessionExpiry};
+use crate::output::Output;
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use qrcode::render::unicode::Dense1x2;
+use qrcode::QrCode;
+use serde::Serialize;
+use std::io::Write;
+
+#[derive(Args, Debug)]
+pub struct LoginArgs {
+    /// Don't try to open a browser; print the URL, code and a QR code instead
+    #[arg(long)]
+    pub no_browser: bool,
+}
+
+/// Who the current profile is logged in as. Never includes the token.
+#[derive(Debug, Serialize)]
+struct Identity {
+    profile: String,
+    base_url: String,
+    name: String,
+    email: String,
+    role: String,
+    expires_at: String,
+    /// Whether the server accepted the session (`whoami` only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    valid: Option<bool>,
+}
+
+impl Identity {
+    fn new(config: &AppConfig) -> Self {
+        Self {
+            profile: config.profile.clone(),
+            base_url: config.connection.base_url.clone(),
+            name: config.auth.user_name.clone(),
+            email: config.auth.user_email.clone(),
+            role: config.auth.user_role.clone(),
+            expires_at: config.auth.token_expires_at.clone(),
+            valid: None,
+        }
+    }
+
+    fn describe(&self) -> String {
+        let who = match (self.name.is_empty(), self.email.is_empty()) {
+            (false, false) => format!("{} <{}>", self.name, self.email),
+            (true, false) => self.email.clone(),
+            (false, true) => self.name.clone(),
+            (true, true) => "an unknown user".to_string(),
+        };
+        let mut out = format!("{} on {} (profile {})", who, self.base_url, self.profile);
+        if !self.role.is_empty() {
+            out.push_str(&format!(", role {}", self.role));
+        }
+        out
+    }
+}
+
+/// Log in with the device flow, without the TUI. Instructions go to stderr so
+/// `--output json` stays parseable.
+pub async fn login(args: LoginArgs, mut config: AppConfig, output: Output) -> Result<()> {
+    let client = backend::connect_unauthenticated(&config)?;
+    let device_name = std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .unwrap_or_else(|_| "FlagDash CLI".to_string());
+    let auth = client
+        .request_device_auth(Some(&device_name))
+        .await
+        .with_context(|| format!("starting login at {}", config.connection.base_url))?;
+
+    let opened = !args.no_browser && open::that(&auth.verification_url).is_ok();
+    print_instructions(&auth, opened)?;
+
+    let response = client
+        .wait_for_device_token(&auth)
+        .await
+        .context("waiting for authorization")?;
+    let Some(token) = response.session_token.clone() else {
+        match response.error.as_deref() {
+            Some("expired_token") => bail!("the login code expired; run `flagdash login` again"),
+            Some("access_denied") => bail!("login was denied"),
+            Some(other) => bail!("login failed: {}", other),
+            None => bail!("login failed: the server returned no session"),
+        }
+    };
+    config.set_session(token, &response);
+    config.save().context("saving the session")?;
+
+    let identity = Identity::new(&config);
+    output.done(&identity, &format!("Logged in as {}", identity.describe()))
+}
+
+fn print_instructions(auth: &DeviceAuthResponse, opened: bool) -> Result<()> {
+    let mut err = std::io::stderr();
+    if opened {
+        writeln!(
+            err,
+            "A browser window should have opened for you to log in."
+        )?;
+        writeln!(err, "If not, go to: {}", auth.verification_url)?;
+    } else {
+        writeln!(err, "To log in, open this URL on any device:")?;
+        writeln!(err)?;
+        writeln!(err, "  {}", auth.verification_url)?;
+        if let Ok(code) = QrCode::new(auth.verification_url.as_bytes()) {
+            let qr = code
+                .render::<Dense1x2>()
+                .dark_color(Dense1x2::Light)
+                .light_color(Dense1x2::Dark)
+                .quiet_zone(true)
+                .build();
+            writeln!(err)?;
+            writeln!(err, "{}", qr)?;
+        }
+    }
+    writeln!(err)?;
+    writeln!(err, "and enter the code: {}", auth.user_code)?;
+    writeln!(err)?;
+    writeln!(err, "Waiting for authorization (Ctrl+C to cancel)...")?;
+    err.flush()?;
+    Ok(())
+}
+
+/// Forget the current profile's session. There is no server-side revocation
+/// endpoint, so this only removes the local copy.
+pub fn logout(mut config: AppConfig, output: Output) -> Result<()> {
+    let was_logged_in = config.has_session_token();
+    let identity = Identity::new(&config);
+    config.clear_auth();
+    config.save().context("removing the session")?;
+    let message = if was_logged_in {
+        format!("Logged out {}", identity.describe())
+    } else {
+        format!("Not logged in (profile {})", identity.profile)
+    };
+    output.done(&identity, &message)
+}
+
+/// Show who the session belongs to and check that the server still accepts it.
+pub async fn whoami(config: AppConfig, output: Output) -> Result<()> {
+    if !config.has_session_token() {
+        bail!(
+            "not logged in (profile {}): run `flagdash login`",
+            config.profile
+        );
+    }
+    let mut identity = Identity::new(&config);
+    let mut message = identity.describe();
+    let valid = match config.auth.expiry(chrono::Utc::now()) {
+        SessionExpiry::Expired => false,
+        SessionExpiry::Valid | SessionExpiry::ExpiresSoon(_) => {
+            let api = backend::connect(&config)?;
+            match api.validate_key().await {
+                Ok(()) => true,
+                Err(ApiError::Unauthorized) => false,
+                Err(e) => return Err(e).context("checking the session"),
+            }
+        }
+    };
+    identity.valid = Some(valid);
+    if valid && !identity.expires_at.is_empty() {
+        message.push_str(&format!("\nSession expires at {}", identity.expires_at));
+    }
+    output.done(&identity, &message)?;
+    if !valid {
+        bail!("session is no longer valid: run `flagdash login`");
+    }
+    Ok(())
+}
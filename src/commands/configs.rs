@@ -0,0 +1,145 @@
+use super::CommandContext;
+use crate::api::types::ManagedConfig;
+use crate::eval::{describe_value, fits_type};
+use crate::schema;
+use crate::value_format::ValueFormat;
+use anyhow::{anyhow, bail, Context, Result};
+use clap::Subcommand;
+use serde_json::Value;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigsCommand {
+    /// List all remote configs in the project
+    List {
+        /// Environment for the enabled/value columns
+        #[arg(long)]
+        env: Option<String>,
+    },
+    /// Show a single remote config
+    Get {
+        /// Config key
+        key: String,
+        /// Environment for the enabled/value columns
+        #[arg(long)]
+        env: Option<String>,
+    },
+    /// Set a remote config's value in an environment
+    Set {
+        /// Config key
+        key: String,
+        /// The value, or "-" for stdin. String configs take it as-is unless --format is given
+        #[arg(conflicts_with = "file", required_unless_present = "file")]
+        value: Option<String>,
+        /// Read the value from a file; a .json, .yaml/.yml or .toml extension picks the format
+        #[arg(long)]
+        file: Option<PathBuf>,
+        /// Format the value is written in (default: the file extension, then the
+        /// `[editor] value_format` setting). TOML puts it under `value`, e.g. `value = 42`
+        #[arg(long, value_enum)]
+        format: Option<ValueFormat>,
+        /// Environment ID, slug or name (defaults to the configured environment)
+        #[arg(long)]
+        env: Option<String>,
+    },
+}
+
+pub async fn run(cmd: ConfigsCommand, ctx: &mut CommandContext) -> Result<()> {
+    let project_id = ctx.project_id()?.to_string();
+    match cmd {
+        ConfigsCommand::List { env } => {
+            ctx.select_output_environment(env.as_deref()).await?;
+            let configs = ctx.api.list_configs(&project_id).await?;
+            ctx.output.list(&configs)?;
+        }
+        ConfigsCommand::Get { key, env } => {
+            ctx.select_output_environment(env.as_deref()).await?;
+            let config = ctx.api.get_config(&key, &project_id).await?;
+            ctx.output.item(&config)?;
+        }
+        ConfigsCommand::Set {
+            key,
+            value,
+            file,
+            format,
+            env,
+        } => {
+            let environment = ctx.resolve_environment(env.as_deref()).await?;
+            let config = ctx.api.get_config(&key, &project_id).await?;
+            let value = read_value(
+                &config,
+                value.as_deref(),
+                file.as_deref(),
+                format,
+                ctx.config.value_format,
+            )?;
+            let resp = ctx
+                .api
+                .set_config_value(&key, &project_id, &environment.id, value)
+                .await
+                .with_context(|| format!("setting the value of '{}'", key))?;
+            ctx.output.done(
+                &resp.config_environment,
+                &format!("Config '{}' set in {}", key, environment.slug),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// The value to set, read from the argument, stdin or `file` and checked
+/// against the config's type and schema. Without `--format`, a string
+/// config's argument or stdin is the string itself.
+fn read_value(
+    config: &ManagedConfig,
+    arg: Option<&str>,
+    file: Option<&Path>,
+    format: Option<ValueFormat>,
+    default_format: ValueFormat,
+) -> Result<Value> {
+    let text = match (file, arg) {
+        (Some(path), _) => {
+            std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?
+        }
+        (None, Some("-")) => {
+            let mut buf = String::new();
+            std::io::stdin()
+                .read_to_string(&mut buf)
+                .context("reading stdin")?;
+            buf.strip_suffix('\n').unwrap_or(&buf).to_string()
+        }
+        (None, Some(arg)) => arg.to_string(),
+        (None, None) => bail!("pass a value, \"-\" for stdin or --file"),
+    };
+    let value = if config.config_type == "string" && file.is_none() && format.is_none() {
+        Value::String(text)
+    } else {
+        let format = format
+            .or_else(|| file.and_then(ValueFormat::from_path))
+            .unwrap_or(default_format);
+        format
+            .read(&text, "value")
+            .map_err(|e| anyhow!("invalid {} value: {}", format.label(), e))?
+    };
+    if !fits_type(&value, &config.config_type) {
+        bail!(
+            "config '{}' holds {} values, but got {}",
+            config.key,
+            config.config_type,
+            describe_value(&value)
+        );
+    }
+    if let Some(schema) = schema::for_config(config)? {
+        let errors = schema::validate(&value, &schema);
+        if !errors.is_empty() {
+            let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            bail!(
+                "the value doesn't match the schema for '{}':\n  {}",
+                config.key,
+                errors.join("\n  ")
+            );
+        }
+    }
+    Ok(value)
+}
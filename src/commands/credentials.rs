@@ -0,0 +1,113 @@
+use crate::config::ConfigFile;
+use crate::credentials::{self, StoreKind};
+use crate::output::Output;
+use anyhow::Result;
+use clap::Subcommand;
+use serde::Serialize;
+
+#[derive(Subcommand, Debug)]
+pub enum CredentialsCommand {
+    /// Show where session tokens are stored and which profiles have one
+    Show,
+    /// Move every profile's session token to another store
+    Use {
+        /// keyring, encrypted-file or plaintext (tokens in config.toml)
+        #[arg(value_enum)]
+        store: StoreKind,
+    },
+}
+
+#[derive(Serialize)]
+struct CredentialsStatus {
+    store: StoreKind,
+    logged_in: Vec<String>,
+}
+
+/// Credential commands only touch the config file and the stores; none of them log in.
+pub fn run(command: CredentialsCommand, output: Output) -> Result<()> {
+    let mut file = ConfigFile::load()?;
+    match command {
+        CredentialsCommand::Show => {
+            let store = file.credential_store()?;
+            let mut logged_in = Vec::new();
+            for name in file.names() {
+                if file.resolve(&name, store.as_deref())?.has_session_token() {
+                    logged_in.push(name);
+                }
+            }
+            let location = match file.credentials.store {
+                StoreKind::Keyring => "the OS keyring".to_string(),
+                StoreKind::EncryptedFile => {
+                    credentials::credentials_file_path()?.display().to_string()
+                }
+                StoreKind::Plaintext => crate::config::config_file_path()?.display().to_string(),
+            };
+            let message = format!(
+                "Store: {} ({})\nLogged in: {}",
+                file.credentials.store.label(),
+                location,
+                if logged_in.is_empty() {
+                    "none".to_string()
+                } else {
+                    logged_in.join(", ")
+                }
+            );
+            output.done(
+                &CredentialsStatus {
+                    store: file.credentials.store,
+                    logged_in,
+                },
+                &message,
+            )
+        }
+        CredentialsCommand::Use { store: kind } => {
+            let from = file.credential_store()?;
+            let to = credentials::open(kind)?;
+            let mut moved = Vec::new();
+            // Profiles whose token was read from the old store, not the file
+            let mut from_store = Vec::new();
+            if kind != file.credentials.store {
+                let names: Vec<String> = file.profiles.keys().cloned().collect();
+                for name in names {
+                    let in_file = file.profile(&name)?.has_session_token();
+                    let token = file.resolve(&name, from.as_deref())?.auth.session_token;
+                    if token.is_empty() {
+                        continue;
+                    }
+                    let auth = &mut file.profiles.get_mut(&name).expect("listed above").auth;
+                    match &to {
+                        Some(to) => {
+                            to.set(&name, &token)?;
+                            auth.session_token.clear();
+                            auth.api_key.clear();
+                        }
+                        None => auth.session_token = token,
+                    }
+                    if !in_file {
+                        from_store.push(name.clone());
+                    }
+                    moved.push(name);
+                }
+                file.credentials.store = kind;
+                file.save()?;
+                // Only forget the old copies once the new store is in use
+                if let Some(from) = &from {
+                    for name in &from_store {
+                        from.delete(name)?;
+                    }
+                }
+            }
+            output.done(
+                &CredentialsStatus {
+                    store: kind,
+                    logged_in: moved.clone(),
+                },
+                &format!(
+                    "Storing session tokens in {} ({} moved)",
+                    kind.label(),
+                    moved.len()
+                ),
+            )
+        }
+    }
+}
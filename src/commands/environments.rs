@@ -0,0 +1,20 @@
+use super::CommandContext;
+use anyhow::Result;
+use clap::Subcommand;
+
+#[derive(Subcommand, Debug)]
+pub enum EnvironmentsCommand {
+    /// List environments in the project
+    List,
+}
+
+pub async fn run(cmd: EnvironmentsCommand, ctx: &mut CommandContext) -> Result<()> {
+    match cmd {
+        EnvironmentsCommand::List => {
+            let project_id = ctx.project_id()?;
+            let environments = ctx.api.list_environments(project_id).await?;
+            ctx.output.list(&environments)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,248 @@
+use super::CommandContext;
+use crate::api::error::ApiError;
+use crate::eval::{evaluate, Evaluation};
+use crate::output::{cell_value, NONE};
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use serde::Serialize;
+use serde_json::Value;
+use std::io::Read;
+
+#[derive(Args, Debug)]
+pub struct EvalArgs {
+    /// Flag key
+    pub key: String,
+    /// Environment ID, slug or name (defaults to the configured environment)
+    #[arg(long)]
+    pub env: Option<String>,
+    /// User context: inline JSON, a file containing a JSON object, or "-" for stdin
+    #[arg(long, conflicts_with = "batch", required_unless_present = "batch")]
+    pub context: Option<String>,
+    /// Evaluate every context in a JSONL file ("-" for stdin) and summarize the values served
+    #[arg(long)]
+    pub batch: Option<String>,
+}
+
+#[derive(Serialize)]
+struct BatchSummary {
+    flag: String,
+    environment: String,
+    total: usize,
+    distribution: Vec<ValueCount>,
+    results: Vec<Evaluation>,
+}
+
+#[derive(Serialize)]
+struct ValueCount {
+    value: Value,
+    count: usize,
+    percent: f64,
+}
+
+pub async fn run(args: EvalArgs, ctx: &mut CommandContext) -> Result<()> {
+    let project_id = ctx.project_id()?.to_string();
+    let environment = ctx.resolve_environment(args.env.as_deref()).await?;
+    let flag = ctx.api.get_flag(&args.key, &project_id).await?;
+    let state = flag
+        .environments
+        .iter()
+        .find(|e| e.environment_id == environment.id)
+        .with_context(|| {
+            format!(
+                "flag '{}' has no state in environment '{}'",
+                flag.key, environment.slug
+            )
+        })?;
+    let variations = match ctx
+        .api
+        .list_variations(&flag.key, &project_id, &environment.id)
+        .await
+    {
+        Ok(v) => v,
+        Err(ApiError::NotFound(_)) => Vec::new(),
+        Err(e) => return Err(e).context("fetching variations"),
+    };
+
+    if let Some(batch) = args.batch {
+        let contexts = parse_batch(&read_input(&batch)?, &batch)?;
+        let mut results = Vec::with_capacity(contexts.len());
+        for context in &contexts {
+            results.push(
+                evaluate(&flag, state, &variations, context)
+                    .context("flag has invalid targeting rules")?,
+            );
+        }
+        let summary = BatchSummary {
+            flag: flag.key.clone(),
+            environment: environment.slug.clone(),
+            total: results.len(),
+            distribution: distribution(&results),
+            results,
+        };
+        let mut message = format!(
+            "Evaluated {} contexts for '{}' in {}\n",
+            summary.total, summary.flag, summary.environment
+        );
+        let width = summary
+            .distribution
+            .iter()
+            .map(|d| cell_value(&d.value).len())
+            .max()
+            .unwrap_or(0)
+            .max("VALUE".len());
+        message.push_str(&format!(
+            "\n{:<width$}  {:>7}  {:>7}",
+            "VALUE", "COUNT", "PERCENT"
+        ));
+        for d in &summary.distribution {
+            message.push_str(&format!(
+                "\n{:<width$}  {:>7}  {:>6.1}%",
+                cell_value(&d.value),
+                d.count,
+                d.percent
+            ));
+        }
+        return ctx.output.done(&summary, &message);
+    }
+
+    let raw = read_input(args.context.as_deref().unwrap_or("-"))?;
+    let context: Value = serde_json::from_str(&raw).context("invalid JSON context")?;
+    if !context.is_object() {
+        bail!("the user context must be a JSON object");
+    }
+    let result = evaluate(&flag, state, &variations, &context)
+        .context("flag has invalid targeting rules")?;
+    let rollout = match (result.bucket, result.in_rollout) {
+        (Some(bucket), Some(inside)) => format!(
+            "bucket {:.2} is {} the {}% rollout",
+            bucket,
+            if inside { "inside" } else { "outside" },
+            state.rollout_percentage
+        ),
+        _ => NONE.to_string(),
+    };
+    let message = format!(
+        "Value:      {}\nReason:     {}\nVariation:  {}\nRollout:    {}",
+        result.value,
+        result.reason,
+        result.variation.as_deref().unwrap_or(NONE),
+        rollout
+    );
+    ctx.output.done(&result, &message)
+}
+
+/// Parse a JSONL batch of user contexts, skipping blank lines. Errors name
+/// the `source` and line number.
+fn parse_batch(input: &str, source: &str) -> Result<Vec<Value>> {
+    let mut contexts = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let context: Value = serde_json::from_str(line)
+            .with_context(|| format!("{}:{}: invalid JSON context", source, i + 1))?;
+        if !context.is_object() {
+            bail!(
+                "{}:{}: the user context must be a JSON object",
+                source,
+                i + 1
+            );
+        }
+        contexts.push(context);
+    }
+    Ok(contexts)
+}
+
+/// Count how often each value was served, most common first.
+fn distribution(results: &[Evaluation]) -> Vec<ValueCount> {
+    let mut counts: Vec<ValueCount> = Vec::new();
+    for r in results {
+        match counts.iter_mut().find(|c| c.value == r.value) {
+            Some(c) => c.count += 1,
+            None => counts.push(ValueCount {
+                value: r.value.clone(),
+                count: 1,
+                percent: 0.0,
+            }),
+        }
+    }
+    for c in &mut counts {
+        c.percent = c.count as f64 * 100.0 / results.len() as f64;
+    }
+    counts.sort_by_key(|c| std::cmp::Reverse(c.count));
+    counts
+}
+
+/// Read an argument that may be inline JSON, a file path, or "-" for stdin.
+fn read_input(arg: &str) -> Result<String> {
+    if arg == "-" {
+        let mut buf = String::new();
+        std::io::stdin()
+            .read_to_string(&mut buf)
+            .context("reading stdin")?;
+        return Ok(buf);
+    }
+    if arg.trim_start().starts_with('{') {
+        return Ok(arg.to_string());
+    }
+    std::fs::read_to_string(arg).with_context(|| format!("reading {}", arg))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eval::Reason;
+    use serde_json::json;
+
+    fn served(value: Value) -> Evaluation {
+        Evaluation {
+            value,
+            reason: Reason::Fallthrough,
+            variation: None,
+            bucket: None,
+            in_rollout: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_batch() {
+        let input = "{\"key\": \"u1\"}\n\n  \n{\"key\": \"u2\", \"plan\": \"pro\"}\n";
+        let contexts = parse_batch(input, "users.jsonl").unwrap();
+        assert_eq!(
+            contexts,
+            vec![json!({"key": "u1"}), json!({"key": "u2", "plan": "pro"})]
+        );
+        assert!(parse_batch("", "users.jsonl").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_batch_reports_bad_line() {
+        let err = parse_batch("{\"key\": \"u1\"}\n{\"key\": \n", "users.jsonl").unwrap_err();
+        assert!(err.to_string().starts_with("users.jsonl:2:"), "{}", err);
+
+        let err = parse_batch("{}\n\n[1, 2]\n", "-").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "-:3: the user context must be a JSON object"
+        );
+    }
+
+    #[test]
+    fn test_distribution_most_common_first() {
+        let results = vec![
+            served(json!(false)),
+            served(json!(true)),
+            served(json!(true)),
+            served(json!(true)),
+        ];
+        let counts = distribution(&results);
+        assert_eq!(
+            counts
+                .iter()
+                .map(|c| (c.value.clone(), c.count, c.percent))
+                .collect::<Vec<_>>(),
+            vec![(json!(true), 3, 75.0), (json!(false), 1, 25.0)]
+        );
+        assert!(distribution(&[]).is_empty());
+    }
+}
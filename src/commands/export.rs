@@ -0,0 +1,92 @@
+use super::CommandContext;
+use crate::api::error::ApiError;
+use crate::manifest::{Manifest, ManifestFormat, ProjectSnapshot};
+use anyhow::{Context, Result};
+use clap::Args;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+#[derive(Args, Debug)]
+pub struct ExportArgs {
+    /// Write the manifest to this file instead of stdout
+    #[arg(long, short = 'f')]
+    pub file: Option<PathBuf>,
+    /// Manifest format (defaults to the file extension, or TOML)
+    #[arg(long, value_enum)]
+    pub format: Option<ManifestFormat>,
+}
+
+pub async fn run(args: ExportArgs, ctx: &mut CommandContext) -> Result<()> {
+    let manifest = Manifest::from_snapshot(&fetch_snapshot(ctx).await?);
+    let format = args
+        .format
+        .or_else(|| args.file.as_deref().map(ManifestFormat::from_path))
+        .unwrap_or(ManifestFormat::Toml);
+    let text = manifest.to_string(format)?;
+    match args.file {
+        Some(path) => {
+            std::fs::write(&path, text).with_context(|| format!("writing {}", path.display()))?;
+            eprintln!(
+                "Exported {} flags, {} configs and {} webhooks to {}",
+                manifest.flags.len(),
+                manifest.configs.len(),
+                manifest.webhooks.len(),
+                path.display()
+            );
+        }
+        None => print!("{}", text),
+    }
+    Ok(())
+}
+
+/// Fetch the current state of the selected project, including variations.
+pub async fn fetch_snapshot(ctx: &CommandContext) -> Result<ProjectSnapshot> {
+    let project_id = ctx.project_id()?;
+    let api = &ctx.api;
+    let (environments, flags, configs, webhooks, projects) = tokio::try_join!(
+        api.list_environments(project_id),
+        api.list_flags(project_id),
+        api.list_configs(project_id),
+        api.list_webhooks(project_id),
+        api.list_projects(),
+    )
+    .context("fetching project state")?;
+
+    let mut tasks = tokio::task::JoinSet::new();
+    for flag in &flags {
+        for env in &flag.environments {
+            let api = api.clone();
+            let project_id = project_id.to_string();
+            let key = flag.key.clone();
+            let environment_id = env.environment_id.clone();
+            tasks.spawn(async move {
+                let result = api
+                    .list_variations(&key, &project_id, &environment_id)
+                    .await;
+                (key, environment_id, result)
+            });
+        }
+    }
+    let mut variations = HashMap::new();
+    while let Some(joined) = tasks.join_next().await {
+        let (key, environment_id, result) = joined?;
+        match result {
+            Ok(vs) if !vs.is_empty() => {
+                variations.insert((key, environment_id), vs);
+            }
+            Ok(_) | Err(ApiError::NotFound(_)) => {}
+            Err(e) => {
+                return Err(e).with_context(|| format!("fetching variations for '{}'", key));
+            }
+        }
+    }
+
+    Ok(ProjectSnapshot {
+        project: projects.into_iter().find(|p| p.id == project_id),
+        environments,
+        flags,
+        configs,
+        webhooks,
+        variations,
+    })
+}
@@ -0,0 +1,511 @@
+use super::{parse_json_arg, parse_list_arg, CommandContext};
+use crate::api::types::{CreateFlagRequest, Schedule, UpdateFlagRequest};
+use crate::rollout;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, Utc};
+use clap::Subcommand;
+
+#[derive(Subcommand, Debug)]
+pub enum FlagsCommand {
+    /// List all flags in the project
+    List {
+        /// Environment for the enabled/rollout/value columns
+        #[arg(long)]
+        env: Option<String>,
+    },
+    /// Show a single flag
+    Get {
+        /// Flag key
+        key: String,
+        /// Environment for the enabled/rollout/value columns
+        #[arg(long)]
+        env: Option<String>,
+    },
+    /// Create a new flag
+    Create {
+        /// Flag key
+        key: String,
+        /// Display name (defaults to the key)
+        #[arg(long)]
+        name: Option<String>,
+        #[arg(long, default_value = "")]
+        description: String,
+        /// Flag type: boolean, string, number or json
+        #[arg(long = "type", default_value = "boolean")]
+        flag_type: String,
+        /// Comma-separated tags
+        #[arg(long)]
+        tags: Option<String>,
+        /// Default value as JSON (bare words are treated as strings)
+        #[arg(long)]
+        default_value: Option<String>,
+    },
+    /// Update a flag's metadata
+    Update {
+        /// Flag key
+        key: String,
+        #[arg(long)]
+        name: Option<String>,
+        #[arg(long)]
+        description: Option<String>,
+        /// Comma-separated tags (replaces the existing list)
+        #[arg(long)]
+        tags: Option<String>,
+        /// Default value as JSON (bare words are treated as strings)
+        #[arg(long)]
+        default_value: Option<String>,
+        /// Archive or unarchive the flag
+        #[arg(long)]
+        archived: Option<bool>,
+    },
+    /// Delete a flag
+    Delete {
+        /// Flag key
+        key: String,
+    },
+    /// Toggle a flag in an environment
+    Toggle {
+        /// Flag key
+        key: String,
+        /// Environment ID, slug or name (defaults to the configured environment)
+        #[arg(long)]
+        env: Option<String>,
+        /// Only toggle if the flag is currently off
+        #[arg(long, conflicts_with = "off")]
+        on: bool,
+        /// Only toggle if the flag is currently on
+        #[arg(long)]
+        off: bool,
+    },
+    /// Set the rollout percentage in an environment
+    Rollout {
+        /// Flag key
+        key: String,
+        /// Percentage (0-100)
+        #[arg(value_parser = clap::value_parser!(i32).range(0..=100))]
+        percentage: i32,
+        /// Environment ID, slug or name (defaults to the configured environment)
+        #[arg(long)]
+        env: Option<String>,
+    },
+    /// Schedule a progressive rollout, one set_rollout schedule per step
+    RolloutPlan {
+        /// Flag key
+        key: String,
+        /// Steps as PERCENT[@DELAY], each delay relative to the previous step
+        #[arg(long, default_value = rollout::DEFAULT_STEPS)]
+        steps: String,
+        /// Delay for steps that don't give one (e.g. 30m, 12h, 1d, 1w)
+        #[arg(long, default_value = rollout::DEFAULT_INTERVAL)]
+        interval: String,
+        /// When the plan starts: now, +DURATION or an RFC 3339 timestamp
+        #[arg(long, default_value = "now")]
+        start: String,
+        /// Print the timeline without creating schedules
+        #[arg(long)]
+        dry_run: bool,
+        /// Environment ID, slug or name (defaults to the configured environment)
+        #[arg(long)]
+        env: Option<String>,
+    },
+    /// Show a flag's rollout timeline in an environment
+    RolloutStatus {
+        /// Flag key
+        key: String,
+        /// Environment ID, slug or name (defaults to the configured environment)
+        #[arg(long)]
+        env: Option<String>,
+    },
+    /// Pause a rollout plan by cancelling its remaining steps, printing how to resume it
+    RolloutPause {
+        /// Flag key
+        key: String,
+        /// Environment ID, slug or name (defaults to the configured environment)
+        #[arg(long)]
+        env: Option<String>,
+    },
+    /// Abort a rollout plan by cancelling its remaining steps
+    RolloutAbort {
+        /// Flag key
+        key: String,
+        /// Environment ID, slug or name (defaults to the configured environment)
+        #[arg(long)]
+        env: Option<String>,
+    },
+}
+
+pub async fn run(cmd: FlagsCommand, ctx: &mut CommandContext) -> Result<()> {
+    let project_id = ctx.project_id()?.to_string();
+    let project_id = project_id.as_str();
+    match cmd {
+        FlagsCommand::List { env } => {
+            ctx.select_output_environment(env.as_deref()).await?;
+            let flags = ctx.api.list_flags(project_id).await?;
+            ctx.output.list(&flags)?;
+        }
+        FlagsCommand::Get { key, env } => {
+            ctx.select_output_environment(env.as_deref()).await?;
+            let flag = ctx.api.get_flag(&key, project_id).await?;
+            ctx.output.item(&flag)?;
+        }
+        FlagsCommand::Create {
+            key,
+            name,
+            description,
+            flag_type,
+            tags,
+            default_value,
+        } => {
+            let req = CreateFlagRequest {
+                project_id: project_id.to_string(),
+                name: name.unwrap_or_else(|| key.clone()),
+                key,
+                description,
+                flag_type,
+                tags: tags.as_deref().map(parse_list_arg).unwrap_or_default(),
+                default_value: default_value.as_deref().map(parse_json_arg),
+            };
+            let flag = ctx.api.create_flag(&req).await?;
+            ctx.output
+                .done(&flag, &format!("Created flag '{}'", flag.key))?;
+        }
+        FlagsCommand::Update {
+            key,
+            name,
+            description,
+            tags,
+            default_value,
+            archived,
+        } => {
+            let req = UpdateFlagRequest {
+                name,
+                description,
+                tags: tags.as_deref().map(parse_list_arg),
+                default_value: default_value.as_deref().map(parse_json_arg),
+                is_archived: archived,
+            };
+            let flag = ctx.api.update_flag(&key, project_id, &req).await?;
+            ctx.output
+                .done(&flag, &format!("Updated flag '{}'", flag.key))?;
+        }
+        FlagsCommand::Delete { key } => {
+            ctx.api.delete_flag(&key, project_id).await?;
+            ctx.output.done(
+                &serde_json::json!({ "deleted": key }),
+                &format!("Deleted flag '{}'", key),
+            )?;
+        }
+        FlagsCommand::Toggle { key, env, on, off } => {
+            let environment = ctx.resolve_environment(env.as_deref()).await?;
+            if on || off {
+                let flag = ctx.api.get_flag(&key, project_id).await?;
+                let state = flag
+                    .environments
+                    .into_iter()
+                    .find(|e| e.environment_id == environment.id);
+                let enabled = state.as_ref().map(|e| e.enabled).unwrap_or(false);
+                if enabled == on {
+                    return ctx.output.done(
+                        &state,
+                        &format!(
+                            "Flag '{}' is already {} in {}",
+                            key,
+                            if enabled { "ON" } else { "OFF" },
+                            environment.slug
+                        ),
+                    );
+                }
+            }
+            let resp = ctx
+                .api
+                .toggle_flag(&key, project_id, &environment.id)
+                .await?;
+            let enabled = resp.flag_environment.enabled;
+            ctx.output.done(
+                &resp.flag_environment,
+                &format!(
+                    "Flag '{}' is now {} in {}",
+                    key,
+                    if enabled { "ON" } else { "OFF" },
+                    environment.slug
+                ),
+            )?;
+        }
+        FlagsCommand::Rollout {
+            key,
+            percentage,
+            env,
+        } => {
+            let environment = ctx.resolve_environment(env.as_deref()).await?;
+            let resp = ctx
+                .api
+                .set_rollout(&key, project_id, &environment.id, percentage)
+                .await
+                .with_context(|| format!("setting rollout for '{}'", key))?;
+            ctx.output.done(
+                &resp.flag_environment,
+                &format!(
+                    "Rollout for '{}' set to {}% in {}",
+                    key, percentage, environment.slug
+                ),
+            )?;
+        }
+        FlagsCommand::RolloutPlan {
+            key,
+            steps,
+            interval,
+            start,
+            dry_run,
+            env,
+        } => {
+            let interval = rollout::parse_duration(&interval).context("invalid --interval")?;
+            let steps = rollout::parse_steps(&steps, interval).context("invalid --steps")?;
+            let now = Utc::now();
+            let start = rollout::parse_start(&start, now)?;
+            let environment = ctx.resolve_environment(env.as_deref()).await?;
+            let requests = rollout::requests(&steps, start, now)?;
+            if dry_run {
+                let timeline = rollout::timeline(&steps, start, now)?;
+                let message = timeline
+                    .iter()
+                    .map(|(pct, at)| format!("{:>4}%  {}", pct, format_time(*at)))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                return ctx.output.done(&requests, &message);
+            }
+            let mut created = Vec::with_capacity(requests.len());
+            for req in &requests {
+                match ctx
+                    .api
+                    .create_schedule(&key, project_id, &environment.id, req)
+                    .await
+                {
+                    Ok(schedule) => created.push(schedule),
+                    Err(e) => {
+                        return Err(e).with_context(|| {
+                            format!(
+                                "creating step {} of {} for '{}' ({} already scheduled; `flagdash flags rollout-abort {}` removes them)",
+                                created.len() + 1,
+                                requests.len(),
+                                key,
+                                created.len(),
+                                key
+                            )
+                        })
+                    }
+                }
+            }
+            let message = format!(
+                "Scheduled {} rollout step(s) for '{}' in {}\n{}",
+                created.len(),
+                key,
+                environment.slug,
+                timeline_lines(&created)
+            );
+            ctx.output.done(&created, &message)?;
+        }
+        FlagsCommand::RolloutStatus { key, env } => {
+            let environment = ctx.resolve_environment(env.as_deref()).await?;
+            let schedules = ctx
+                .api
+                .list_schedules(&key, project_id, &environment.id)
+                .await?;
+            let plan: Vec<Schedule> = rollout::plan_schedules(&schedules)
+                .into_iter()
+                .cloned()
+                .collect();
+            let message = if plan.is_empty() {
+                format!(
+                    "No rollout steps scheduled for '{}' in {}",
+                    key, environment.slug
+                )
+            } else {
+                timeline_lines(&plan)
+            };
+            ctx.output.done(&plan, &message)?;
+        }
+        FlagsCommand::RolloutPause { key, env } => {
+            stop_rollout_plan(ctx, &key, env.as_deref(), true).await?;
+        }
+        FlagsCommand::RolloutAbort { key, env } => {
+            stop_rollout_plan(ctx, &key, env.as_deref(), false).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Cancel every pending step of a rollout plan. Pausing also prints the
+/// `rollout-plan` invocation that schedules the same steps again.
+async fn stop_rollout_plan(
+    ctx: &CommandContext,
+    key: &str,
+    env: Option<&str>,
+    pause: bool,
+) -> Result<()> {
+    let project_id = ctx.project_id()?;
+    let environment = ctx.resolve_environment(env).await?;
+    let schedules = ctx
+        .api
+        .list_schedules(key, project_id, &environment.id)
+        .await?;
+    let pending = rollout::pending(&schedules);
+    if pending.is_empty() {
+        return ctx.output.done(
+            &serde_json::json!({ "cancelled": [] }),
+            &format!(
+                "No pending rollout steps for '{}' in {}",
+                key, environment.slug
+            ),
+        );
+    }
+    let remaining = rollout::format_steps(&rollout::remaining_steps(&pending, Utc::now()));
+    for schedule in &pending {
+        ctx.api
+            .cancel_schedule(key, project_id, &schedule.id)
+            .await
+            .with_context(|| format!("cancelling schedule {}", schedule.id))?;
+    }
+    let cancelled: Vec<&str> = pending.iter().map(|s| s.id.as_str()).collect();
+    let mut message = format!(
+        "Cancelled {} rollout step(s) for '{}' in {}",
+        cancelled.len(),
+        key,
+        environment.slug
+    );
+    if pause {
+        message.push_str(&format!(
+            "\nResume with: flagdash flags rollout-plan {} --env {} --steps {}",
+            key, environment.slug, remaining
+        ));
+    }
+    ctx.output.done(
+        &serde_json::json!({ "cancelled": cancelled, "remaining_steps": remaining }),
+        &message,
+    )
+}
+
+/// One line per step: percentage, local time and status.
+fn timeline_lines(schedules: &[Schedule]) -> String {
+    schedules
+        .iter()
+        .filter_map(|s| {
+            let pct = rollout::step_percentage(s)?;
+            Some(format!(
+                "{:>4}%  {}  {}",
+                pct,
+                format_time(s.scheduled_at),
+                s.status
+            ))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn format_time(at: DateTime<Utc>) -> String {
+    at.with_timezone(&Local)
+        .format("%Y-%m-%d %H:%M %:z")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::memory::MemoryBackend;
+    use crate::config::AppConfig;
+    use crate::output::Output;
+    use chrono::Duration;
+    use std::sync::Arc;
+
+    fn context() -> CommandContext {
+        CommandContext {
+            config: AppConfig::in_memory(None, Some("env_stg")),
+            api: Arc::new(MemoryBackend::demo()),
+            output: Output::default(),
+        }
+    }
+
+    async fn enabled(ctx: &CommandContext) -> bool {
+        ctx.api
+            .get_flag("dark-mode", "prj_demo")
+            .await
+            .unwrap()
+            .environments
+            .iter()
+            .find(|e| e.environment_id == "env_stg")
+            .map(|e| e.enabled)
+            .unwrap_or(false)
+    }
+
+    fn toggle(on: bool, off: bool) -> FlagsCommand {
+        FlagsCommand::Toggle {
+            key: "dark-mode".into(),
+            env: None,
+            on,
+            off,
+        }
+    }
+
+    async fn plan(ctx: &CommandContext) -> Vec<Schedule> {
+        let schedules = ctx
+            .api
+            .list_schedules("dark-mode", "prj_demo", "env_stg")
+            .await
+            .unwrap();
+        rollout::pending(&schedules).into_iter().cloned().collect()
+    }
+
+    #[tokio::test]
+    async fn test_toggle_on_and_off_are_idempotent() {
+        let mut ctx = context();
+        assert!(!enabled(&ctx).await);
+        for _ in 0..2 {
+            run(toggle(true, false), &mut ctx).await.unwrap();
+            assert!(enabled(&ctx).await);
+        }
+        for _ in 0..2 {
+            run(toggle(false, true), &mut ctx).await.unwrap();
+            assert!(!enabled(&ctx).await);
+        }
+        // Without --on/--off it flips.
+        run(toggle(false, false), &mut ctx).await.unwrap();
+        assert!(enabled(&ctx).await);
+    }
+
+    #[tokio::test]
+    async fn test_rollout_plan_schedules_steps() {
+        let mut ctx = context();
+        let rollout_plan = |dry_run| FlagsCommand::RolloutPlan {
+            key: "dark-mode".into(),
+            steps: "10,50@1h,100@1d".into(),
+            interval: "1d".into(),
+            start: "+2h".into(),
+            dry_run,
+            env: Some("staging".into()),
+        };
+
+        run(rollout_plan(true), &mut ctx).await.unwrap();
+        assert!(plan(&ctx).await.is_empty());
+
+        let before = Utc::now();
+        run(rollout_plan(false), &mut ctx).await.unwrap();
+        let steps = plan(&ctx).await;
+        let percentages: Vec<i32> = steps.iter().filter_map(rollout::step_percentage).collect();
+        assert_eq!(percentages, vec![10, 50, 100]);
+        assert!(steps[0].scheduled_at >= before + Duration::hours(2));
+        assert_eq!(
+            steps[1].scheduled_at - steps[0].scheduled_at,
+            Duration::hours(1)
+        );
+        assert_eq!(
+            steps[2].scheduled_at - steps[1].scheduled_at,
+            Duration::days(1)
+        );
+
+        let abort = FlagsCommand::RolloutAbort {
+            key: "dark-mode".into(),
+            env: None,
+        };
+        run(abort, &mut ctx).await.unwrap();
+        assert!(plan(&ctx).await.is_empty());
+    }
+}
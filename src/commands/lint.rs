@@ -0,0 +1,117 @@
+use super::CommandContext;
+use crate::api::error::ApiError;
+use crate::config::AppConfig;
+use crate::eval::lint::{lint_rules, Diagnostic, LintContext};
+use crate::output::Output;
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use serde::Serialize;
+use std::io::{Read, Write};
+
+#[derive(Subcommand, Debug)]
+pub enum LintCommand {
+    /// Check a targeting rules file (a JSON array of rules)
+    Rules(LintRulesArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct LintRulesArgs {
+    /// Rules file, or "-" for stdin
+    pub file: String,
+    /// Check served values against this flag type: boolean, string, number or json
+    #[arg(long = "type")]
+    pub flag_type: Option<String>,
+    /// Comma-separated variation keys that rules may serve
+    #[arg(long, value_delimiter = ',')]
+    pub variations: Option<Vec<String>>,
+    /// Take the flag type and variations from this flag instead (requires login)
+    #[arg(long, conflicts_with_all = ["flag_type", "variations"])]
+    pub flag: Option<String>,
+    /// Environment ID, slug or name for --flag's variations (defaults to the configured environment)
+    #[arg(long, requires = "flag")]
+    pub env: Option<String>,
+}
+
+#[derive(Serialize)]
+struct LintReport {
+    file: String,
+    errors: usize,
+    warnings: usize,
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// Linting a file on its own works offline; only `--flag` talks to the API.
+pub async fn run(command: LintCommand, config: AppConfig, output: Output) -> Result<()> {
+    match command {
+        LintCommand::Rules(args) => {
+            let source = if args.file == "-" {
+                let mut buf = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut buf)
+                    .context("reading stdin")?;
+                buf
+            } else {
+                std::fs::read_to_string(&args.file)
+                    .with_context(|| format!("reading {}", args.file))?
+            };
+
+            let (flag_type, variations, output) = match &args.flag {
+                Some(key) => {
+                    let ctx = CommandContext::new(config, output)?;
+                    let (flag_type, variations) = fetch_flag_context(&ctx, key, args.env).await?;
+                    (Some(flag_type), Some(variations), ctx.output)
+                }
+                None => (args.flag_type, args.variations, output),
+            };
+            let diagnostics = lint_rules(
+                &source,
+                &LintContext {
+                    flag_type: flag_type.as_deref(),
+                    variations: variations.as_deref(),
+                },
+            );
+
+            let report = LintReport {
+                errors: diagnostics.iter().filter(|d| d.is_error()).count(),
+                warnings: diagnostics.iter().filter(|d| !d.is_error()).count(),
+                file: args.file,
+                diagnostics,
+            };
+            let mut message: String = report
+                .diagnostics
+                .iter()
+                .map(|d| format!("{}:{}\n", report.file, d))
+                .collect();
+            message.push_str(&format!(
+                "{}: {} error(s), {} warning(s)",
+                report.file, report.errors, report.warnings
+            ));
+            output.done(&report, &message)?;
+            if report.errors > 0 {
+                std::io::stdout().flush()?;
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+    }
+}
+
+async fn fetch_flag_context(
+    ctx: &CommandContext,
+    key: &str,
+    env: Option<String>,
+) -> Result<(String, Vec<String>)> {
+    let project_id = ctx.project_id()?;
+    let environment = ctx.resolve_environment(env.as_deref()).await?;
+    let flag = ctx.api.get_flag(key, project_id).await?;
+    let variations = match ctx
+        .api
+        .list_variations(key, project_id, &environment.id)
+        .await
+    {
+        Ok(v) => v.into_iter().map(|v| v.key).collect(),
+        Err(ApiError::NotFound(_)) => Vec::new(),
+        Err(e) => return Err(e).context("fetching variations"),
+    };
+    Ok((flag.flag_type, variations))
+}
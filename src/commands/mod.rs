@@ -0,0 +1,209 @@
+pub mod ai_configs;
+pub mod auth;
+pub mod configs;
+pub mod credentials;
+pub mod environments;
+pub mod eval;
+pub mod export;
+pub mod flags;
+pub mod lint;
+pub mod plan;
+pub mod profile;
+pub mod promote;
+pub mod serve_mock;
+pub mod webhooks;
+
+use crate::api::backend::{self, SharedApi};
+use crate::api::types::Environment;
+use crate::config::{AppConfig, SessionExpiry};
+use crate::output::Output;
+use anyhow::{bail, Context, Result};
+use clap::Subcommand;
+
+/// Headless subcommands. These talk to the API directly and never touch the terminal UI.
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Manage feature flags
+    #[command(subcommand)]
+    Flags(flags::FlagsCommand),
+    /// Manage remote configs
+    #[command(subcommand)]
+    Configs(configs::ConfigsCommand),
+    /// Inspect AI configs
+    #[command(subcommand, name = "ai-configs")]
+    AiConfigs(ai_configs::AiConfigsCommand),
+    /// Inspect webhook endpoints
+    #[command(subcommand)]
+    Webhooks(webhooks::WebhooksCommand),
+    /// Inspect environments
+    #[command(subcommand)]
+    Environments(environments::EnvironmentsCommand),
+    /// Evaluate a flag locally for a user context
+    Eval(eval::EvalArgs),
+    /// Export flags, configs and webhooks as a declarative manifest
+    Export(export::ExportArgs),
+    /// Show the changes needed to make the project match a manifest
+    Plan(plan::PlanArgs),
+    /// Apply a manifest to the project
+    Apply(plan::ApplyArgs),
+    /// Copy flag and config state from one environment to another
+    Promote(promote::PromoteArgs),
+    /// Check files for mistakes before they are applied
+    #[command(subcommand)]
+    Lint(lint::LintCommand),
+    /// Log in with a device code, without the TUI
+    Login(auth::LoginArgs),
+    /// Forget the current profile's session
+    Logout,
+    /// Show who the current profile is logged in as
+    Whoami,
+    /// Manage named connection profiles
+    #[command(subcommand)]
+    Profile(profile::ProfileCommand),
+    /// Choose where session tokens are stored
+    #[command(subcommand)]
+    Credentials(credentials::CredentialsCommand),
+    /// Run a local mock FlagDash server for development and demos
+    #[command(name = "serve-mock")]
+    ServeMock(serve_mock::ServeMockArgs),
+}
+
+/// Run a headless subcommand to completion.
+pub async fn run(command: Command, config: AppConfig, output: Output) -> Result<()> {
+    // These work without a valid session
+    match command {
+        Command::Lint(cmd) => return lint::run(cmd, config, output).await,
+        Command::Login(args) => return auth::login(args, config, output).await,
+        Command::Logout => return auth::logout(config, output),
+        Command::Whoami => return auth::whoami(config, output).await,
+        _ => {}
+    }
+    let mut ctx = CommandContext::new(config, output)?;
+    match command {
+        Command::Flags(cmd) => flags::run(cmd, &mut ctx).await,
+        Command::Configs(cmd) => configs::run(cmd, &mut ctx).await,
+        Command::AiConfigs(cmd) => ai_configs::run(cmd, &mut ctx).await,
+        Command::Webhooks(cmd) => webhooks::run(cmd, &mut ctx).await,
+        Command::Environments(cmd) => environments::run(cmd, &mut ctx).await,
+        Command::Eval(args) => eval::run(args, &mut ctx).await,
+        Command::Export(args) => export::run(args, &mut ctx).await,
+        Command::Plan(args) => plan::run_plan(args, &mut ctx).await,
+        Command::Apply(args) => plan::run_apply(args, &mut ctx).await,
+        Command::Promote(args) => promote::run(args, &mut ctx).await,
+        Command::Lint(_) | Command::Login(_) | Command::Logout | Command::Whoami => {
+            unreachable!("handled before logging in")
+        }
+        Command::Profile(_) | Command::Credentials(_) | Command::ServeMock(_) => {
+            unreachable!("handled before loading a profile")
+        }
+    }
+}
+
+/// Shared state for headless commands: the resolved config, an authenticated
+/// client and the output settings.
+pub struct CommandContext {
+    pub config: AppConfig,
+    pub api: SharedApi,
+    pub output: Output,
+}
+
+impl CommandContext {
+    pub fn new(config: AppConfig, mut output: Output) -> Result<Self> {
+        if !config.has_session_token() {
+            bail!(
+                "not logged in: run `flagdash login`, or pass --session-token / set FLAGDASH_SESSION_TOKEN"
+            );
+        }
+        if config.auth.expiry(chrono::Utc::now()) == SessionExpiry::Expired {
+            bail!(
+                "session expired at {}: run `flagdash login` to log in again",
+                config.auth.token_expires_at
+            );
+        }
+        let api = backend::connect(&config)?;
+        if !config.defaults.environment_id.is_empty() {
+            output.environment_id = Some(config.defaults.environment_id.clone());
+        }
+        Ok(Self {
+            config,
+            api,
+            output,
+        })
+    }
+
+    /// The project to operate on (from --project-id, env var or config file).
+    pub fn project_id(&self) -> Result<&str> {
+        let pid = self.config.defaults.project_id.as_str();
+        if pid.is_empty() {
+            bail!("no project selected: pass --project-id or set FLAGDASH_PROJECT_ID");
+        }
+        Ok(pid)
+    }
+
+    /// Point per-environment output columns at `env` when one was given on the
+    /// command line; otherwise the configured default environment is used.
+    pub async fn select_output_environment(&mut self, env: Option<&str>) -> Result<()> {
+        if env.is_some() {
+            let environment = self.resolve_environment(env).await?;
+            self.output.environment_id = Some(environment.id);
+        }
+        Ok(())
+    }
+
+    /// Resolve an environment given by ID, slug or name. Falls back to the
+    /// configured default environment when `env` is `None`.
+    pub async fn resolve_environment(&self, env: Option<&str>) -> Result<Environment> {
+        let project_id = self.project_id()?;
+        let wanted = match env {
+            Some(e) => e,
+            None if !self.config.defaults.environment_id.is_empty() => {
+                self.config.defaults.environment_id.as_str()
+            }
+            None => bail!("no environment selected: pass --env or set FLAGDASH_ENVIRONMENT_ID"),
+        };
+        let environments = self
+            .api
+            .list_environments(project_id)
+            .await
+            .context("listing environments")?;
+        find_environment(&environments, wanted)
+            .cloned()
+            .with_context(|| {
+                let known: Vec<&str> = environments.iter().map(|e| e.slug.as_str()).collect();
+                format!(
+                    "unknown environment '{}' (available: {})",
+                    wanted,
+                    known.join(", ")
+                )
+            })
+    }
+}
+
+/// Find an environment by ID, slug or (case-insensitive) name.
+pub fn find_environment<'a>(
+    environments: &'a [Environment],
+    wanted: &str,
+) -> Option<&'a Environment> {
+    environments
+        .iter()
+        .find(|e| e.id == wanted || e.slug == wanted)
+        .or_else(|| {
+            environments
+                .iter()
+                .find(|e| e.name.eq_ignore_ascii_case(wanted))
+        })
+}
+
+/// Parse a JSON literal from the command line. Bare words that aren't valid
+/// JSON are treated as strings, so `--default-value blue` works without quoting.
+pub fn parse_json_arg(raw: &str) -> serde_json::Value {
+    serde_json::from_str(raw).unwrap_or_else(|_| serde_json::Value::String(raw.to_string()))
+}
+
+/// Split a comma-separated list, dropping empty entries.
+pub fn parse_list_arg(raw: &str) -> Vec<String> {
+    raw.split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
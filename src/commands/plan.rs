@@ -0,0 +1,207 @@
+use super::export::fetch_snapshot;
+use super::CommandContext;
+use crate::api::backend::FlagDashApi;
+use crate::api::types::{
+    CreateConfigRequest, CreateFlagRequest, CreateWebhookRequest, Environment, UpdateConfigRequest,
+    UpdateFlagRequest, UpdateWebhookRequest,
+};
+use crate::manifest::apply::{apply_operation, environment_id};
+use crate::manifest::plan::{flag_environment_changes, Change, ChangeKind, Operation, Plan};
+use crate::manifest::{FlagEnvironmentSpec, Manifest};
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+
+#[derive(Args, Debug)]
+pub struct PlanArgs {
+    /// Manifest file (TOML or YAML, by extension)
+    pub file: PathBuf,
+    /// Also plan deletion of flags, configs and webhooks missing from the manifest
+    #[arg(long)]
+    pub prune: bool,
+    /// Exit with 0 when there are no changes, 2 when there are changes and 1 on error
+    #[arg(long)]
+    pub detailed_exitcode: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct ApplyArgs {
+    /// Manifest file (TOML or YAML, by extension)
+    pub file: PathBuf,
+    /// Delete flags, configs and webhooks missing from the manifest
+    #[arg(long)]
+    pub prune: bool,
+    /// Apply without asking for confirmation
+    #[arg(long, short = 'y')]
+    pub yes: bool,
+}
+
+pub async fn run_plan(args: PlanArgs, ctx: &mut CommandContext) -> Result<()> {
+    let manifest = Manifest::load(&args.file)?;
+    let snapshot = fetch_snapshot(ctx).await?;
+    let plan = Plan::compute(&manifest, &snapshot, args.prune)?;
+    ctx.output.done(&plan, &plan.render())?;
+    if args.detailed_exitcode && !plan.is_empty() {
+        std::io::stdout().flush()?;
+        std::process::exit(2);
+    }
+    Ok(())
+}
+
+pub async fn run_apply(args: ApplyArgs, ctx: &mut CommandContext) -> Result<()> {
+    let manifest = Manifest::load(&args.file)?;
+    let snapshot = fetch_snapshot(ctx).await?;
+    let plan = Plan::compute(&manifest, &snapshot, args.prune)?;
+    if plan.is_empty() {
+        return ctx.output.done(&plan, &plan.render());
+    }
+
+    if !args.yes {
+        confirm(&plan.render())?;
+    }
+
+    let project_id = ctx.project_id()?;
+    for (i, change) in plan.changes.iter().enumerate() {
+        eprintln!(
+            "  {} {} {}",
+            change.kind.symbol(),
+            change.resource,
+            change.address
+        );
+        apply_change(ctx.api.as_ref(), project_id, &snapshot.environments, change)
+            .await
+            .with_context(|| {
+                format!(
+                    "applying {} {} ({} of {} changes applied)",
+                    change.resource,
+                    change.address,
+                    i,
+                    plan.changes.len()
+                )
+            })?;
+    }
+    ctx.output.done(
+        &plan,
+        &format!(
+            "Apply complete: {} created, {} updated, {} deleted.",
+            plan.count(ChangeKind::Create),
+            plan.count(ChangeKind::Update),
+            plan.count(ChangeKind::Delete)
+        ),
+    )
+}
+
+/// Show the pending changes on stderr and require a literal `yes` on stdin.
+pub(super) fn confirm(changes: &str) -> Result<()> {
+    if !std::io::stdin().is_terminal() {
+        bail!("refusing to apply without confirmation: pass --yes to apply non-interactively");
+    }
+    eprintln!("{}\n", changes);
+    eprint!("Apply these changes? Only 'yes' will be accepted: ");
+    std::io::stderr().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    if answer.trim() != "yes" {
+        bail!("apply cancelled");
+    }
+    Ok(())
+}
+
+/// Apply one planned change. Creating a flag or config also brings its
+/// per-environment state in line with the manifest.
+async fn apply_change(
+    api: &dyn FlagDashApi,
+    project_id: &str,
+    environments: &[Environment],
+    change: &Change,
+) -> Result<()> {
+    match &change.operation {
+        Operation::CreateFlag { key, spec } => {
+            let req = CreateFlagRequest {
+                project_id: project_id.to_string(),
+                key: key.clone(),
+                name: spec.name.clone(),
+                description: spec.description.clone(),
+                flag_type: spec.flag_type.clone(),
+                tags: spec.tags.clone(),
+                default_value: Some(spec.default_value.clone()).filter(|v| !v.is_null()),
+            };
+            let flag = api.create_flag(&req).await?;
+            if spec.archived {
+                let req = UpdateFlagRequest {
+                    name: None,
+                    description: None,
+                    tags: None,
+                    default_value: None,
+                    is_archived: Some(true),
+                };
+                api.update_flag(key, project_id, &req).await?;
+            }
+            for (env, desired) in &spec.environments {
+                let environment_id = environment_id(environments, env)?;
+                let current = flag
+                    .environments
+                    .iter()
+                    .find(|e| e.environment_id == environment_id)
+                    .map(|e| FlagEnvironmentSpec::from_live(e, &[]));
+                for change in flag_environment_changes(key, env, desired, current.as_ref()) {
+                    apply_operation(api, project_id, environments, &change.operation).await?;
+                }
+            }
+            Ok(())
+        }
+        Operation::CreateConfig { key, spec } => {
+            let req = CreateConfigRequest {
+                project_id: project_id.to_string(),
+                key: key.clone(),
+                name: spec.name.clone(),
+                description: spec.description.clone(),
+                config_type: spec.config_type.clone(),
+                default_value: Some(spec.default_value.clone()).filter(|v| !v.is_null()),
+                tags: spec.tags.clone(),
+            };
+            api.create_config(&req).await?;
+            if spec.archived {
+                let req = UpdateConfigRequest {
+                    name: None,
+                    description: None,
+                    tags: None,
+                    default_value: None,
+                    is_archived: Some(true),
+                };
+                api.update_config(key, project_id, &req).await?;
+            }
+            for (env, value) in &spec.environments {
+                if value.value.is_null() {
+                    continue;
+                }
+                let environment_id = environment_id(environments, env)?;
+                api.set_config_value(key, project_id, environment_id, value.value.clone())
+                    .await?;
+            }
+            Ok(())
+        }
+        Operation::CreateWebhook { spec } => {
+            let req = CreateWebhookRequest {
+                project_id: project_id.to_string(),
+                environment_id: environment_id(environments, &spec.environment)?.to_string(),
+                url: spec.url.clone(),
+                description: spec.description.clone(),
+                event_types: spec.events.clone(),
+            };
+            let endpoint = api.create_webhook(&req).await?;
+            if !spec.active {
+                let req = UpdateWebhookRequest {
+                    url: None,
+                    description: None,
+                    event_types: None,
+                    is_active: Some(false),
+                };
+                api.update_webhook(&endpoint.id, &req).await?;
+            }
+            Ok(())
+        }
+        op => apply_operation(api, project_id, environments, op).await,
+    }
+}
@@ -0,0 +1,193 @@
+use crate::config::{AppConfig, ConfigFile, DEFAULT_PROFILE};
+use crate::output::{yes_no, Output, Tabular, NONE};
+use anyhow::{bail, Result};
+use clap::{Args, Subcommand};
+use serde::Serialize;
+
+#[derive(Subcommand, Debug)]
+pub enum ProfileCommand {
+    /// List profiles
+    List,
+    /// Make a profile the one used by default
+    Use {
+        /// Profile name
+        name: String,
+    },
+    /// Create a profile from --base-url, --project-id and --environment-id
+    Add(ProfileAddArgs),
+    /// Delete a profile and its stored session token
+    Remove {
+        /// Profile name
+        name: String,
+    },
+}
+
+#[derive(Args, Debug)]
+pub struct ProfileAddArgs {
+    /// Profile name
+    pub name: String,
+    /// Also make it the one used by default
+    #[arg(long = "use")]
+    pub make_current: bool,
+}
+
+/// The global options `profile` commands read: the selected profile and the
+/// connection settings a new profile starts with.
+#[derive(Debug, Default)]
+pub struct ProfileOptions<'a> {
+    pub profile: Option<&'a str>,
+    pub base_url: Option<&'a str>,
+    pub project_id: Option<&'a str>,
+    pub environment_id: Option<&'a str>,
+}
+
+/// A profile as listed by `flagdash profile list`. Credentials are never shown.
+#[derive(Debug, Serialize)]
+struct ProfileSummary {
+    name: String,
+    current: bool,
+    base_url: String,
+    logged_in: bool,
+    user: String,
+    project: String,
+    environment: String,
+}
+
+impl ProfileSummary {
+    fn new(name: &str, config: &AppConfig, current: &str) -> Self {
+        let or_id = |name: &str, id: &str| if name.is_empty() { id } else { name }.to_string();
+        Self {
+            name: name.to_string(),
+            current: name == current,
+            base_url: config.connection.base_url.clone(),
+            logged_in: config.has_session_token(),
+            user: config.auth.user_email.clone(),
+            project: or_id(&config.defaults.project_name, &config.defaults.project_id),
+            environment: or_id(
+                &config.defaults.environment_name,
+                &config.defaults.environment_id,
+            ),
+        }
+    }
+}
+
+impl Tabular for ProfileSummary {
+    const COLUMNS: &'static [&'static str] = &[
+        "key",
+        "name",
+        "current",
+        "base_url",
+        "logged_in",
+        "user",
+        "project",
+        "environment",
+    ];
+    const DEFAULT_COLUMNS: &'static [&'static str] =
+        &["name", "current", "base_url", "user", "project"];
+
+    fn cell(&self, column: &str, _environment_id: Option<&str>) -> String {
+        let or_none = |s: &str| if s.is_empty() { NONE } else { s }.to_string();
+        match column {
+            "key" | "name" => self.name.clone(),
+            "current" => if self.current { "*" } else { "" }.to_string(),
+            "base_url" => self.base_url.clone(),
+            "logged_in" => yes_no(self.logged_in),
+            "user" => or_none(&self.user),
+            "project" => or_none(&self.project),
+            "environment" => or_none(&self.environment),
+            _ => String::new(),
+        }
+    }
+}
+
+/// Profile commands only edit the config file; none of them log in.
+pub fn run(command: ProfileCommand, options: ProfileOptions, output: Output) -> Result<()> {
+    let mut file = ConfigFile::load()?;
+    match command {
+        ProfileCommand::List => {
+            let current = options.profile.unwrap_or(file.current()).to_string();
+            let store = file.credential_store()?;
+            let profiles = file
+                .names()
+                .iter()
+                .map(|name| {
+                    let config = file.resolve(name, store.as_deref())?;
+                    Ok(ProfileSummary::new(name, &config, &current))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            output.list(&profiles)
+        }
+        ProfileCommand::Use { name } => {
+            let config = file.profile(&name)?;
+            file.current_profile = name.clone();
+            file.save()?;
+            let summary = ProfileSummary::new(&name, &config, &name);
+            output.done(
+                &summary,
+                &format!("Using profile '{}' ({})", name, summary.base_url),
+            )
+        }
+        ProfileCommand::Add(args) => {
+            if file.profiles.contains_key(&args.name) {
+                bail!("profile '{}' already exists", args.name);
+            }
+            validate_name(&args.name)?;
+            let mut config = AppConfig {
+                profile: args.name.clone(),
+                ..AppConfig::default()
+            };
+            if let Some(url) = options.base_url {
+                config.connection.base_url = url.to_string();
+            }
+            if let Some(pid) = options.project_id {
+                config.defaults.project_id = pid.to_string();
+            }
+            if let Some(eid) = options.environment_id {
+                config.defaults.environment_id = eid.to_string();
+            }
+            file.profiles.insert(args.name.clone(), config.clone());
+            if args.make_current {
+                file.current_profile = args.name.clone();
+            }
+            file.save()?;
+            let summary = ProfileSummary::new(&args.name, &config, file.current());
+            output.done(
+                &summary,
+                &format!(
+                    "Added profile '{}' ({}). Log in with: flagdash --profile {}",
+                    args.name, config.connection.base_url, args.name
+                ),
+            )
+        }
+        ProfileCommand::Remove { name } => {
+            let Some(config) = file.profiles.remove(&name) else {
+                bail!("unknown profile '{}'", name);
+            };
+            if let Some(store) = file.credential_store()? {
+                store.delete(&name)?;
+            }
+            let mut message = format!("Removed profile '{}'", name);
+            if file.current() == name && name != DEFAULT_PROFILE {
+                file.current_profile.clear();
+                message.push_str(&format!("; now using '{}'", DEFAULT_PROFILE));
+            }
+            file.save()?;
+            output.done(&ProfileSummary::new(&name, &config, ""), &message)
+        }
+    }
+}
+
+/// Profile names become TOML table keys and appear in commands, so keep them simple.
+fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        bail!(
+            "invalid profile name '{}': use letters, digits, '-' and '_'",
+            name
+        );
+    }
+    Ok(())
+}
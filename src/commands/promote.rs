@@ -0,0 +1,95 @@
+use super::plan::confirm;
+use super::{find_environment, CommandContext};
+use crate::manifest::apply::apply_operation;
+use crate::manifest::promote::{PromoteField, PromoteFilter, Promotion};
+use anyhow::{Context, Result};
+use clap::Args;
+
+#[derive(Args, Debug)]
+pub struct PromoteArgs {
+    /// Source environment ID, slug or name
+    #[arg(long)]
+    pub from: String,
+    /// Target environment ID, slug or name
+    #[arg(long)]
+    pub to: String,
+    /// Comma-separated flag keys to promote (default: all flags, unless --configs is given)
+    #[arg(long, value_delimiter = ',')]
+    pub flags: Option<Vec<String>>,
+    /// Comma-separated config keys to promote (default: all configs, unless --flags is given)
+    #[arg(long, value_delimiter = ',')]
+    pub configs: Option<Vec<String>>,
+    /// Comma-separated fields to copy: enabled, rollout, rules, values (default: all)
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub fields: Option<Vec<PromoteField>>,
+    /// Show the changes without applying them
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Apply without asking for confirmation
+    #[arg(long, short = 'y')]
+    pub yes: bool,
+}
+
+pub async fn run(args: PromoteArgs, ctx: &mut CommandContext) -> Result<()> {
+    let project_id = ctx.project_id()?;
+    let api = &ctx.api;
+    let (environments, flags, configs) = tokio::try_join!(
+        api.list_environments(project_id),
+        api.list_flags(project_id),
+        api.list_configs(project_id),
+    )
+    .context("fetching project state")?;
+    let resolve = |wanted: &str| {
+        find_environment(&environments, wanted).with_context(|| {
+            let known: Vec<&str> = environments.iter().map(|e| e.slug.as_str()).collect();
+            format!(
+                "unknown environment '{}' (available: {})",
+                wanted,
+                known.join(", ")
+            )
+        })
+    };
+    let (from, to) = (resolve(&args.from)?, resolve(&args.to)?);
+    let filter = PromoteFilter {
+        flags: args.flags,
+        configs: args.configs,
+        fields: args.fields.unwrap_or_else(|| PromoteField::ALL.to_vec()),
+    };
+    let promotion = Promotion::compute(&flags, &configs, from, to, &filter)?;
+    if promotion.is_empty() || args.dry_run {
+        return ctx.output.done(&promotion, &promotion.render());
+    }
+
+    if !args.yes {
+        confirm(&promotion.render())?;
+    }
+
+    for (i, change) in promotion.changes.iter().enumerate() {
+        eprintln!(
+            "  {} {} {}",
+            change.kind.symbol(),
+            change.resource,
+            change.address
+        );
+        apply_operation(api.as_ref(), project_id, &environments, &change.operation)
+            .await
+            .with_context(|| {
+                format!(
+                    "promoting {} {} ({} of {} changes applied)",
+                    change.resource,
+                    change.address,
+                    i,
+                    promotion.changes.len()
+                )
+            })?;
+    }
+    ctx.output.done(
+        &promotion,
+        &format!(
+            "Promotion complete: {} change(s) applied from {} to {}.",
+            promotion.changes.len(),
+            promotion.from,
+            promotion.to
+        ),
+    )
+}
@@ -0,0 +1,47 @@
+use crate::api::memory::{MemoryBackend, SESSION_TOKEN};
+use crate::api::mock_server::MockServer;
+use anyhow::{Context, Result};
+use clap::Args;
+use std::path::PathBuf;
+
+#[derive(Args, Debug)]
+pub struct ServeMockArgs {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:8787")]
+    pub listen: String,
+    /// JSON file to load the data from and save changes to. Seeded with the
+    /// demo project if it doesn't exist; without it, changes are lost on exit
+    #[arg(long)]
+    pub state: Option<PathBuf>,
+    /// Replace the state file's contents with the demo project
+    #[arg(long, requires = "state")]
+    pub reset: bool,
+}
+
+pub async fn run(args: ServeMockArgs) -> Result<()> {
+    let server = match &args.state {
+        Some(path) => MockServer::with_state_file(path, args.reset)?,
+        None => MockServer::new(MemoryBackend::demo()),
+    };
+    let listener = tokio::net::TcpListener::bind(&args.listen)
+        .await
+        .with_context(|| format!("listening on {}", args.listen))?;
+    let base_url = format!("http://{}", listener.local_addr()?);
+
+    eprintln!("Mock FlagDash server listening on {}", base_url);
+    if let Some(path) = &args.state {
+        eprintln!("State: {}", path.display());
+    }
+    eprintln!();
+    eprintln!("  FLAGDASH_BASE_URL={} flagdash login", base_url);
+    eprintln!(
+        "  FLAGDASH_BASE_URL={} FLAGDASH_SESSION_TOKEN={} flagdash",
+        base_url, SESSION_TOKEN
+    );
+    eprintln!();
+
+    tokio::select! {
+        result = server.logging().serve(listener) => result,
+        _ = tokio::signal::ctrl_c() => Ok(()),
+    }
+}
@@ -0,0 +1,29 @@
+use super::CommandContext;
+use anyhow::Result;
+use clap::Subcommand;
+
+#[derive(Subcommand, Debug)]
+pub enum WebhooksCommand {
+    /// List webhook endpoints in the project
+    List,
+    /// Show a single webhook endpoint
+    Get {
+        /// Endpoint ID
+        id: String,
+    },
+}
+
+pub async fn run(cmd: WebhooksCommand, ctx: &mut CommandContext) -> Result<()> {
+    match cmd {
+        WebhooksCommand::List => {
+            let project_id = ctx.project_id()?;
+            let webhooks = ctx.api.list_webhooks(project_id).await?;
+            ctx.output.list(&webhooks)?;
+        }
+        WebhooksCommand::Get { id } => {
+            let webhook = ctx.api.get_webhook(&id).await?;
+            ctx.output.item(&webhook)?;
+        }
+    }
+    Ok(())
+}
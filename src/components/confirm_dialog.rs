@@ -77,9 +77,29 @@ impl ConfirmDialog {
             ConfirmAction::CancelSchedule { schedule_id, .. } => {
                 format!("Cancel schedule '{}'?", schedule_id)
             }
-            ConfirmAction::DeleteVariations(key) => {
-                format!("Delete all variations for '{}'?", key)
+            ConfirmAction::DeleteVariations { flag_key, .. } => {
+                format!("Delete all variations for '{}'?", flag_key)
             }
+            ConfirmAction::CancelRolloutPlan {
+                flag_key,
+                schedule_ids,
+                resume_steps,
+            } => format!(
+                "{} rollout of '{}'? {} pending step(s) will be cancelled.",
+                if resume_steps.is_some() {
+                    "Pause"
+                } else {
+                    "Abort"
+                },
+                flag_key,
+                schedule_ids.len()
+            ),
+            ConfirmAction::Promote { from, to, changes } => format!(
+                "Promote {} change(s) from {} to {}?",
+                changes.len(),
+                from,
+                to
+            ),
         };
 
         // Center dialog
@@ -31,6 +31,10 @@ impl EnvironmentSwitcher {
         self.visible
     }
 
+    pub fn hide(&mut self) {
+        self.visible = false;
+    }
+
     pub fn show(&mut self, current_env_id: &str) {
         self.visible = true;
         self.loading = true;
@@ -5,6 +5,8 @@ use ratatui::widgets::{Block, Borders, Paragraph};
 use ratatui::Frame;
 
 pub struct Header {
+    /// Active profile; only shown when it isn't the default one.
+    pub profile: String,
     pub project_name: String,
     pub environment_name: String,
     pub connected: bool,
@@ -13,6 +15,7 @@ pub struct Header {
 impl Header {
     pub fn new() -> Self {
         Self {
+            profile: String::new(),
             project_name: String::new(),
             environment_name: String::new(),
             connected: false,
@@ -29,11 +32,17 @@ impl Header {
 
         let chunks = Layout::horizontal([Constraint::Min(0), Constraint::Length(26)]).split(inner);
 
-        // Left: ◆ FlagDash  │  project > environment
+        // Left: ◆ FlagDash [profile]  │  project > environment
         let mut left_spans = vec![
             Span::styled(" ◆ ", theme::title()),
             Span::styled("FlagDash", theme::title()),
         ];
+        if !self.profile.is_empty() && self.profile != crate::config::DEFAULT_PROFILE {
+            left_spans.push(Span::styled(
+                format!(" [{}]", self.profile),
+                theme::warning(),
+            ));
+        }
         if !self.project_name.is_empty() {
             left_spans.push(Span::styled("  |  ", theme::dim()));
             left_spans.push(Span::styled(self.project_name.clone(), theme::normal()));
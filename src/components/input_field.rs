@@ -58,30 +58,22 @@ impl InputField {
                     self.cursor += 1;
                     return true;
                 }
-                KeyCode::Backspace => {
-                    if self.cursor > 0 {
-                        self.cursor -= 1;
-                        self.value.remove(self.cursor);
-                        return true;
-                    }
+                KeyCode::Backspace if self.cursor > 0 => {
+                    self.cursor -= 1;
+                    self.value.remove(self.cursor);
+                    return true;
                 }
-                KeyCode::Delete => {
-                    if self.cursor < self.value.len() {
-                        self.value.remove(self.cursor);
-                        return true;
-                    }
+                KeyCode::Delete if self.cursor < self.value.len() => {
+                    self.value.remove(self.cursor);
+                    return true;
                 }
-                KeyCode::Left => {
-                    if self.cursor > 0 {
-                        self.cursor -= 1;
-                        return true;
-                    }
+                KeyCode::Left if self.cursor > 0 => {
+                    self.cursor -= 1;
+                    return true;
                 }
-                KeyCode::Right => {
-                    if self.cursor < self.value.len() {
-                        self.cursor += 1;
-                        return true;
-                    }
+                KeyCode::Right if self.cursor < self.value.len() => {
+                    self.cursor += 1;
+                    return true;
                 }
                 KeyCode::Home => {
                     self.cursor = 0;
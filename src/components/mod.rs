@@ -2,6 +2,7 @@ pub mod confirm_dialog;
 pub mod environment_switcher;
 pub mod header;
 pub mod input_field;
+pub mod profile_switcher;
 pub mod search_bar;
 pub mod sidebar;
 pub mod status_bar;
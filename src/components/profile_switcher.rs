@@ -0,0 +1,212 @@
+use crate::action::Action;
+use crate::config::ConfigFile;
+use crate::event::Event;
+use crate::theme;
+use crossterm::event::{KeyCode, KeyEventKind};
+use ratatui::layout::{Alignment, Constraint, Layout, Rect};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+
+/// A profile as shown in the switcher.
+#[derive(Debug, Clone)]
+pub struct ProfileEntry {
+    pub name: String,
+    pub base_url: String,
+    pub user_email: String,
+}
+
+pub struct ProfileSwitcher {
+    visible: bool,
+    profiles: Vec<ProfileEntry>,
+    selected_idx: usize,
+    current_profile: String,
+    error: Option<String>,
+}
+
+impl ProfileSwitcher {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            profiles: Vec::new(),
+            selected_idx: 0,
+            current_profile: String::new(),
+            error: None,
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Open the switcher with the profiles in the config file. Profiles are
+    /// read synchronously, so there is no loading state.
+    pub fn show(&mut self, current_profile: &str) {
+        self.visible = true;
+        self.current_profile = current_profile.to_string();
+        self.profiles.clear();
+        self.error = None;
+        match ConfigFile::load() {
+            Ok(file) => {
+                for name in file.names() {
+                    if let Ok(config) = file.profile(&name) {
+                        self.profiles.push(ProfileEntry {
+                            name,
+                            base_url: config.connection.base_url,
+                            user_email: config.auth.user_email,
+                        });
+                    }
+                }
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+        self.selected_idx = self
+            .profiles
+            .iter()
+            .position(|p| p.name == self.current_profile)
+            .unwrap_or(0);
+    }
+
+    pub fn handle_event(&mut self, event: &Event) -> Option<Action> {
+        if !self.visible {
+            return None;
+        }
+
+        if let Event::Key(key) = event {
+            if key.kind != KeyEventKind::Press {
+                return None;
+            }
+
+            match key.code {
+                KeyCode::Up | KeyCode::Char('k') => {
+                    if self.selected_idx > 0 {
+                        self.selected_idx -= 1;
+                    }
+                    None
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    if !self.profiles.is_empty() && self.selected_idx < self.profiles.len() - 1 {
+                        self.selected_idx += 1;
+                    }
+                    None
+                }
+                KeyCode::Enter => {
+                    let profile = self.profiles.get(self.selected_idx)?;
+                    self.visible = false;
+                    if profile.name == self.current_profile {
+                        Some(Action::ProfileSwitcherDismissed)
+                    } else {
+                        Some(Action::ProfileSwitched(profile.name.clone()))
+                    }
+                }
+                KeyCode::Esc => {
+                    self.visible = false;
+                    Some(Action::ProfileSwitcherDismissed)
+                }
+                _ => None,
+            }
+        } else {
+            None
+        }
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        if !self.visible {
+            return;
+        }
+
+        // border(2) + rows + instructions(1) + padding(1)
+        let height = (self.profiles.len().max(1) as u16 + 4).min(area.height.saturating_sub(4));
+        let width = 64u16.min(area.width.saturating_sub(4));
+        let dialog_area = centered_rect(width, height, area);
+
+        let block = Block::default()
+            .title(" Switch Profile ")
+            .title_style(theme::heading())
+            .borders(Borders::ALL)
+            .border_style(theme::active_border());
+
+        frame.render_widget(Clear, dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let inner = Rect {
+            x: dialog_area.x + 2,
+            y: dialog_area.y + 1,
+            width: dialog_area.width.saturating_sub(4),
+            height: dialog_area.height.saturating_sub(2),
+        };
+
+        if let Some(error) = &self.error {
+            let error = Paragraph::new(Line::from(Span::styled(error.clone(), theme::warning())))
+                .alignment(Alignment::Center);
+            frame.render_widget(error, inner);
+            return;
+        }
+
+        let chunks = Layout::vertical([
+            Constraint::Min(1),    // profile list
+            Constraint::Length(1), // instructions
+        ])
+        .split(inner);
+
+        let name_width = self
+            .profiles
+            .iter()
+            .map(|p| p.name.len())
+            .max()
+            .unwrap_or(0);
+        let rows: Vec<Line> = self
+            .profiles
+            .iter()
+            .enumerate()
+            .map(|(i, profile)| {
+                let is_current = profile.name == self.current_profile;
+                let is_selected = i == self.selected_idx;
+
+                let marker = if is_selected { ">" } else { " " };
+                let current_badge = if is_current { " ●" } else { "" };
+
+                let style = if is_selected {
+                    theme::title()
+                } else if is_current {
+                    theme::highlight()
+                } else {
+                    theme::normal()
+                };
+
+                let mut detail = profile.base_url.clone();
+                if !profile.user_email.is_empty() {
+                    detail.push_str(&format!("  {}", profile.user_email));
+                }
+                Line::from(vec![
+                    Span::styled(format!(" {} ", marker), style),
+                    Span::styled(
+                        format!("{:<width$}", profile.name, width = name_width),
+                        style,
+                    ),
+                    Span::styled(format!("  {}", detail), theme::dim()),
+                    Span::styled(current_badge, theme::status_on()),
+                ])
+            })
+            .collect();
+
+        frame.render_widget(Paragraph::new(rows), chunks[0]);
+
+        let instructions = Paragraph::new(Line::from(vec![
+            Span::styled("j/k", theme::title()),
+            Span::styled(" navigate  ", theme::dim()),
+            Span::styled("Enter", theme::title()),
+            Span::styled(" switch  ", theme::dim()),
+            Span::styled("Esc", theme::title()),
+            Span::styled(" cancel", theme::dim()),
+        ]))
+        .alignment(Alignment::Center);
+        frame.render_widget(instructions, chunks[1]);
+    }
+}
+
+fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let x = area.x + (area.width.saturating_sub(width)) / 2;
+    let y = area.y + (area.height.saturating_sub(height)) / 2;
+    Rect::new(x, y, width.min(area.width), height.min(area.height))
+}
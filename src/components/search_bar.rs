@@ -46,12 +46,10 @@ impl SearchBar {
                     self.cursor += 1;
                     return true;
                 }
-                KeyCode::Backspace => {
-                    if self.cursor > 0 {
-                        self.cursor -= 1;
-                        self.query.remove(self.cursor);
-                        return true;
-                    }
+                KeyCode::Backspace if self.cursor > 0 => {
+                    self.cursor -= 1;
+                    self.query.remove(self.cursor);
+                    return true;
                 }
                 KeyCode::Esc => {
                     self.deactivate();
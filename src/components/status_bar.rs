@@ -1,13 +1,21 @@
+use crate::config::SessionExpiry;
+use crate::rollout::format_duration;
 use crate::theme;
+use chrono::{DateTime, Utc};
 use ratatui::layout::{Constraint, Layout, Rect};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph};
 use ratatui::Frame;
+use std::time::Instant;
 
 pub struct StatusBar {
     pub connected: bool,
     pub base_url: String,
     pub loading: bool,
+    /// Shown as a warning once the session is close to expiring.
+    pub session_expires_at: Option<DateTime<Utc>>,
+    /// Set when the server rate limits us; counted down until it passes.
+    pub rate_limited_until: Option<Instant>,
 }
 
 impl StatusBar {
@@ -16,6 +24,8 @@ impl StatusBar {
             connected: false,
             base_url: base_url.to_string(),
             loading: false,
+            session_expires_at: None,
+            rate_limited_until: None,
         }
     }
 
@@ -27,7 +37,27 @@ impl StatusBar {
         let inner = block.inner(area);
         frame.render_widget(block, area);
 
-        let chunks = Layout::horizontal([Constraint::Min(0), Constraint::Length(8)]).split(inner);
+        let expiry = match SessionExpiry::of(self.session_expires_at, Utc::now()) {
+            SessionExpiry::Valid => None,
+            SessionExpiry::ExpiresSoon(left) => Some(format!(
+                "⚠ session expires in {}  ",
+                // Whole minutes are plenty for a warning
+                format_duration(chrono::Duration::minutes(left.num_minutes().max(1)))
+            )),
+            SessionExpiry::Expired => Some("⚠ session expired  ".to_string()),
+        };
+        let rate_limit = self
+            .rate_limited_until
+            .map(|until| until.saturating_duration_since(Instant::now()))
+            .filter(|left| !left.is_zero())
+            .map(|left| format!("⏳ rate limited, retry in {}s  ", left.as_secs() + 1));
+        let right_width = 8
+            + expiry.as_deref().map_or(0, |e| e.chars().count() as u16)
+            + rate_limit
+                .as_deref()
+                .map_or(0, |r| r.chars().count() as u16);
+        let chunks =
+            Layout::horizontal([Constraint::Min(0), Constraint::Length(right_width)]).split(inner);
 
         // Left: key shortcut hints
         let left = if self.loading {
@@ -56,6 +86,8 @@ impl StatusBar {
                 Span::styled(" env  ", theme::dim()),
                 Span::styled("p", theme::title()),
                 Span::styled(" project  ", theme::dim()),
+                Span::styled("C", theme::title()),
+                Span::styled(" profile  ", theme::dim()),
                 Span::styled("l", theme::title()),
                 Span::styled(" logout  ", theme::dim()),
                 Span::styled("1-6", theme::title()),
@@ -64,12 +96,18 @@ impl StatusBar {
         };
         frame.render_widget(left, chunks[0]);
 
-        // Right: q quit
-        let right = Paragraph::new(Line::from(vec![
-            Span::styled("q", theme::title()),
-            Span::styled(" quit", theme::dim()),
-        ]))
-        .alignment(ratatui::layout::Alignment::Right);
+        // Right: rate limit countdown, session expiry warning, q quit
+        let mut right_spans = Vec::new();
+        if let Some(rate_limit) = rate_limit {
+            right_spans.push(Span::styled(rate_limit, theme::warning()));
+        }
+        if let Some(expiry) = expiry {
+            right_spans.push(Span::styled(expiry, theme::warning()));
+        }
+        right_spans.push(Span::styled("q", theme::title()));
+        right_spans.push(Span::styled(" quit", theme::dim()));
+        let right =
+            Paragraph::new(Line::from(right_spans)).alignment(ratatui::layout::Alignment::Right);
         frame.render_widget(right, chunks[1]);
     }
 }
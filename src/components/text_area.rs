@@ -13,6 +13,8 @@ pub struct TextArea {
     pub cursor_col: usize,
     pub focused: bool,
     pub scroll_offset: usize,
+    /// Zero-based line numbers to highlight as errors.
+    pub marked_lines: Vec<usize>,
 }
 
 impl TextArea {
@@ -24,6 +26,7 @@ impl TextArea {
             cursor_col: 0,
             focused: false,
             scroll_offset: 0,
+            marked_lines: Vec::new(),
         }
     }
 
@@ -166,9 +169,17 @@ impl TextArea {
         let visible_lines: Vec<Line> = self
             .lines
             .iter()
+            .enumerate()
             .skip(scroll)
             .take(visible_height)
-            .map(|l| Line::from(Span::styled(l.as_str(), theme::normal())))
+            .map(|(i, l)| {
+                let style = if self.marked_lines.contains(&i) {
+                    theme::status_off()
+                } else {
+                    theme::normal()
+                };
+                Line::from(Span::styled(l.as_str(), style))
+            })
             .collect();
 
         let paragraph = Paragraph::new(visible_lines).block(block);
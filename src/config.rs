@@ -1,9 +1,36 @@
-use anyhow::{Context, Result};
+use crate::api::backend::Backend;
+use crate::api::memory;
+use crate::api::retry::RetryPolicy;
+use crate::api::types::DeviceTokenResponse;
+use crate::credentials::{self, CredentialStore, StoreKind};
+use crate::value_format::ValueFormat;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
+/// Profile used when none is selected and the config file doesn't name one.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// The settings of one profile. This is what the rest of the app reads; the
+/// config file keeps one of these per named profile.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AppConfig {
+    /// Name of the profile these settings were loaded from and are saved to.
+    #[serde(skip)]
+    pub profile: String,
+    /// Where API calls go; chosen per run with `--backend`.
+    #[serde(skip)]
+    pub backend: Backend,
+    /// Fixture file to append every HTTP exchange to; set with
+    /// `--record-fixtures`.
+    #[serde(skip)]
+    pub record_fixtures: Option<PathBuf>,
+    /// Format for writing JSON values in the editors and `configs set`; from
+    /// the config file's `[editor]` section, shared by every profile.
+    #[serde(skip)]
+    pub value_format: ValueFormat,
     #[serde(default)]
     pub auth: AuthConfig,
     #[serde(default)]
@@ -14,7 +41,9 @@ pub struct AppConfig {
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AuthConfig {
-    #[serde(default)]
+    /// Only written to the file with the plaintext credential store; otherwise
+    /// the token lives in the store and this is filled in on load.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub session_token: String,
     #[serde(default)]
     pub user_name: String,
@@ -30,16 +59,90 @@ pub struct AuthConfig {
     pub api_key: String,
 }
 
+/// How long before a session expires the status bar starts warning about it.
+pub const EXPIRY_WARNING_HOURS: i64 = 24;
+
+/// Where a session stands relative to `token_expires_at`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SessionExpiry {
+    /// No expiry recorded, or it is far off.
+    Valid,
+    /// Expires within [`EXPIRY_WARNING_HOURS`]; holds the time left.
+    ExpiresSoon(Duration),
+    Expired,
+}
+
+impl AuthConfig {
+    /// `token_expires_at` as a timestamp, if it is set and parses as RFC 3339.
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(&self.token_expires_at)
+            .ok()
+            .map(|t| t.with_timezone(&Utc))
+    }
+
+    pub fn expiry(&self, now: DateTime<Utc>) -> SessionExpiry {
+        SessionExpiry::of(self.expires_at(), now)
+    }
+}
+
+impl SessionExpiry {
+    pub fn of(expires_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> Self {
+        match expires_at {
+            Some(at) if at <= now => SessionExpiry::Expired,
+            Some(at) if at - now <= Duration::hours(EXPIRY_WARNING_HOURS) => {
+                SessionExpiry::ExpiresSoon(at - now)
+            }
+            _ => SessionExpiry::Valid,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConnectionConfig {
     #[serde(default = "default_base_url")]
     pub base_url: String,
+    /// Proxy for all requests, e.g. `http://proxy.corp:3128`. When unset the
+    /// `HTTPS_PROXY`/`HTTP_PROXY`/`NO_PROXY` environment variables apply.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// Comma-separated hosts that bypass `proxy`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub no_proxy: Option<String>,
+    /// Extra CA certificates (PEM bundles or DER) to trust besides the
+    /// built-in roots.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ca_certs: Vec<PathBuf>,
+    /// PEM client certificate for mutual TLS. May also hold the private key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_cert: Option<PathBuf>,
+    /// PEM private key for `client_cert`, if it's kept in a separate file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_key: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect_timeout_secs: Option<u64>,
+    /// Longest wait for the next chunk of a response.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_timeout_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    /// Retrying of failed requests; written out only once customised.
+    #[serde(default, skip_serializing_if = "RetryPolicy::is_default")]
+    pub retry: RetryPolicy,
 }
 
 impl Default for ConnectionConfig {
     fn default() -> Self {
         Self {
             base_url: default_base_url(),
+            proxy: None,
+            no_proxy: None,
+            ca_certs: Vec::new(),
+            client_cert: None,
+            client_key: None,
+            connect_timeout_secs: None,
+            read_timeout_secs: None,
+            user_agent: None,
+            retry: RetryPolicy::default(),
         }
     }
 }
@@ -48,6 +151,27 @@ fn default_base_url() -> String {
     "https://flagdash.io".to_string()
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CredentialsConfig {
+    /// Where session tokens are kept: `keyring`, `encrypted-file` or `plaintext`.
+    #[serde(default)]
+    pub store: StoreKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct EditorConfig {
+    /// How config values and targeting rules are shown for editing: `json`,
+    /// `yaml` or `toml`.
+    #[serde(default)]
+    pub value_format: ValueFormat,
+}
+
+impl EditorConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct DefaultsConfig {
     #[serde(default)]
@@ -60,21 +184,203 @@ pub struct DefaultsConfig {
     pub environment_name: String,
 }
 
+/// The config file: every named profile plus the one in use.
+///
+/// Files written before profiles existed have a single top-level
+/// `auth`/`connection`/`defaults` block; it is read as the `default` profile
+/// and written back in the new layout on the next save.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ConfigFile {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub current_profile: String,
+    #[serde(default)]
+    pub credentials: CredentialsConfig,
+    #[serde(default, skip_serializing_if = "EditorConfig::is_default")]
+    pub editor: EditorConfig,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, AppConfig>,
+    #[serde(default, skip_serializing)]
+    auth: Option<AuthConfig>,
+    #[serde(default, skip_serializing)]
+    connection: Option<ConnectionConfig>,
+    #[serde(default, skip_serializing)]
+    defaults: Option<DefaultsConfig>,
+}
+
+impl ConfigFile {
+    /// Read the config file, or an empty one if it doesn't exist yet.
+    pub fn load() -> Result<Self> {
+        let path = config_file_path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("reading {}", path.display()))?;
+        if let Err(e) = credentials::restrict_permissions(&path) {
+            tracing::warn!("{:#}", e);
+        }
+        Self::parse(&content).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    /// Parse a config file, migrating a single-block config into `default`.
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut file: ConfigFile = toml::from_str(content)?;
+        if file.auth.is_some() || file.connection.is_some() || file.defaults.is_some() {
+            let legacy = AppConfig {
+                auth: file.auth.take().unwrap_or_default(),
+                connection: file.connection.take().unwrap_or_default(),
+                defaults: file.defaults.take().unwrap_or_default(),
+                ..AppConfig::default()
+            };
+            file.profiles
+                .entry(DEFAULT_PROFILE.to_string())
+                .or_insert(legacy);
+        }
+        Ok(file)
+    }
+
+    /// Write the config file, readable by its owner only.
+    pub fn save(&self) -> Result<()> {
+        let path = config_file_path()?;
+        let content = toml::to_string_pretty(self).context("serializing config")?;
+        credentials::write_private(&path, &content)
+    }
+
+    /// The configured credential store, or `None` for plaintext.
+    pub fn credential_store(&self) -> Result<Option<Box<dyn CredentialStore>>> {
+        credentials::open(self.credentials.store)
+    }
+
+    /// Ask for the encrypted file's passphrase up front, if that's the store
+    /// in use, so it isn't needed in the middle of the TUI.
+    pub fn unlock_credentials(&self) -> Result<()> {
+        if let Some(store) = self.credential_store()? {
+            store.unlock()?;
+        }
+        Ok(())
+    }
+
+    /// Move tokens still written in the file into `store`. Returns whether
+    /// any moved, in which case the file should be saved.
+    pub fn move_tokens_to(&mut self, store: &dyn CredentialStore) -> Result<bool> {
+        let mut moved = false;
+        for (name, config) in self.profiles.iter_mut() {
+            let auth = &mut config.auth;
+            let token = if auth.session_token.is_empty() {
+                &auth.api_key
+            } else {
+                &auth.session_token
+            };
+            if token.is_empty() {
+                continue;
+            }
+            store.set(name, token)?;
+            auth.session_token.clear();
+            auth.api_key.clear();
+            moved = true;
+        }
+        Ok(moved)
+    }
+
+    /// Profile `name` with its session token, wherever that is stored.
+    pub fn resolve(&self, name: &str, store: Option<&dyn CredentialStore>) -> Result<AppConfig> {
+        let mut config = self.profile(name)?;
+        if let (true, Some(store)) = (config.auth.session_token.is_empty(), store) {
+            config.auth.session_token = store.get(name)?.unwrap_or_default();
+        }
+        Ok(config)
+    }
+
+    /// The profile used when neither `--profile` nor `FLAGDASH_PROFILE` is given.
+    pub fn current(&self) -> &str {
+        if self.current_profile.is_empty() {
+            DEFAULT_PROFILE
+        } else {
+            &self.current_profile
+        }
+    }
+
+    /// The settings of profile `name`. `default` always exists, even before
+    /// anything has been saved.
+    pub fn profile(&self, name: &str) -> Result<AppConfig> {
+        let mut config = match self.profiles.get(name) {
+            Some(config) => config.clone(),
+            None if name == DEFAULT_PROFILE => AppConfig::default(),
+            None => bail!(
+                "unknown profile '{}': create it with `flagdash profile add {}`",
+                name,
+                name
+            ),
+        };
+        config.profile = name.to_string();
+
+        // Migrate legacy api_key field to session_token
+        if config.auth.session_token.is_empty() && !config.auth.api_key.is_empty() {
+            config.auth.session_token = std::mem::take(&mut config.auth.api_key);
+        }
+        Ok(config)
+    }
+
+    /// Every profile name, including the implicit `default`, sorted.
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.profiles.keys().cloned().collect();
+        if !self.profiles.contains_key(DEFAULT_PROFILE) {
+            names.push(DEFAULT_PROFILE.to_string());
+            names.sort();
+        }
+        names
+    }
+}
+
 impl AppConfig {
-    /// Load config with priority: CLI args > env vars > config file
+    /// Load config with priority: CLI args > env vars > config file.
+    ///
+    /// `cli_profile` (from `--profile` or `FLAGDASH_PROFILE`) picks the profile;
+    /// otherwise the config file's `current_profile` is used.
     pub fn load(
+        cli_profile: Option<&str>,
         cli_session_token: Option<&str>,
         cli_base_url: Option<&str>,
         cli_project_id: Option<&str>,
         cli_environment_id: Option<&str>,
     ) -> Result<Self> {
         // Start with config file
-        let mut config = Self::load_from_file().unwrap_or_default();
-
-        // Migrate legacy api_key field to session_token
-        if config.auth.session_token.is_empty() && !config.auth.api_key.is_empty() {
-            config.auth.session_token = std::mem::take(&mut config.auth.api_key);
-        }
+        let mut file = ConfigFile::load().unwrap_or_default();
+        let name = cli_profile.unwrap_or(file.current()).to_string();
+        let token_override = cli_session_token.is_some()
+            || std::env::var("FLAGDASH_SESSION_TOKEN").is_ok()
+            || std::env::var("FLAGDASH_API_KEY").is_ok();
+        let mut config = if token_override {
+            // The token is given explicitly, so the credential store isn't needed
+            file.profile(&name)?
+        } else {
+            // An unavailable store (no Secret Service in CI, containers or over
+            // SSH) must not stop a token already in the file from working.
+            let store = file.credential_store()?;
+            if let Some(store) = store.as_deref() {
+                let mut migrated = file.clone();
+                match migrated.move_tokens_to(store) {
+                    Ok(moved) => {
+                        if moved {
+                            migrated.save()?;
+                        }
+                        file = migrated;
+                    }
+                    Err(e) => eprintln!(
+                        "warning: leaving session tokens in the config file: {:#}",
+                        e
+                    ),
+                }
+            }
+            match file.resolve(&name, store.as_deref()) {
+                Ok(config) => config,
+                Err(e) => {
+                    eprintln!("warning: reading the session token: {:#}", e);
+                    file.profile(&name)?
+                }
+            }
+        };
+        config.value_format = file.editor.value_format;
 
         // Override with env vars (FLAGDASH_SESSION_TOKEN takes priority, FLAGDASH_API_KEY as fallback)
         if let Ok(token) = std::env::var("FLAGDASH_SESSION_TOKEN") {
@@ -91,6 +397,9 @@ impl AppConfig {
         if let Ok(eid) = std::env::var("FLAGDASH_ENVIRONMENT_ID") {
             config.defaults.environment_id = eid;
         }
+        if let Some(format) = value_format_from_env()? {
+            config.value_format = format;
+        }
 
         // Override with CLI args
         if let Some(token) = cli_session_token {
@@ -109,29 +418,64 @@ impl AppConfig {
         Ok(config)
     }
 
-    fn load_from_file() -> Result<Self> {
-        let path = config_file_path()?;
-        if !path.exists() {
-            return Ok(Self::default());
+    /// Settings for `--backend memory`: logged in to the simulated server as
+    /// its demo user, with nothing read from or written to the config file.
+    pub fn in_memory(project_id: Option<&str>, environment_id: Option<&str>) -> Self {
+        let mut config = Self {
+            profile: "memory".to_string(),
+            backend: Backend::Memory,
+            ..Self::default()
+        };
+        config.auth.session_token = memory::SESSION_TOKEN.to_string();
+        config.auth.user_name = "Demo User".to_string();
+        config.auth.user_email = "demo@flagdash.local".to_string();
+        config.auth.user_role = "owner".to_string();
+        config.connection.base_url = "memory://demo".to_string();
+        config.defaults.project_id = project_id.unwrap_or("prj_demo").to_string();
+        config.defaults.environment_id = environment_id.unwrap_or("env_dev").to_string();
+        config
+    }
+
+    /// Load profile `name` from the config file, without any overrides, and
+    /// make it the current profile. The value format preference isn't tied to
+    /// a profile, so it is kept.
+    pub fn switch_profile(name: &str) -> Result<Self> {
+        let mut file = ConfigFile::load()?;
+        let mut config = file.resolve(name, file.credential_store()?.as_deref())?;
+        config.value_format = file.editor.value_format;
+        if let Some(format) = value_format_from_env()? {
+            config.value_format = format;
         }
-        let content = std::fs::read_to_string(&path)
-            .with_context(|| format!("reading {}", path.display()))?;
-        let config: AppConfig =
-            toml::from_str(&content).with_context(|| format!("parsing {}", path.display()))?;
+        file.current_profile = name.to_string();
+        file.save()?;
         Ok(config)
     }
 
-    /// Save the current config to the config file.
+    /// Save the current config to its profile in the config file, leaving
+    /// other profiles untouched. The session token goes to the credential
+    /// store unless that is plaintext.
     pub fn save(&self) -> Result<()> {
-        let path = config_file_path()?;
-        if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent)
-                .with_context(|| format!("creating config dir {}", parent.display()))?;
+        if self.backend == Backend::Memory {
+            return Ok(());
         }
-        let content = toml::to_string_pretty(self).context("serializing config")?;
-        std::fs::write(&path, content)
-            .with_context(|| format!("writing config to {}", path.display()))?;
-        Ok(())
+        let mut file = ConfigFile::load()?;
+        let name = if self.profile.is_empty() {
+            DEFAULT_PROFILE
+        } else {
+            &self.profile
+        };
+        let mut stored = self.clone();
+        if let Some(store) = file.credential_store()? {
+            let token = &self.auth.session_token;
+            if token.is_empty() {
+                store.delete(name)?;
+            } else if store.get(name)?.as_ref() != Some(token) {
+                store.set(name, token)?;
+            }
+            stored.auth.session_token.clear();
+        }
+        file.profiles.insert(name.to_string(), stored);
+        file.save()
     }
 
     /// Returns true if we have a session token configured.
@@ -153,6 +497,19 @@ impl AppConfig {
         }
     }
 
+    /// Record a completed device login: the token and who it belongs to.
+    pub fn set_session(&mut self, token: String, response: &DeviceTokenResponse) {
+        self.auth.session_token = token;
+        self.auth.api_key.clear();
+        if let Some(user) = &response.user {
+            self.auth.user_name = user.name.clone();
+            self.auth.user_email = user.email.clone();
+            self.auth.user_role = user.role.clone();
+        }
+        // A new session without an expiry mustn't inherit the old one's
+        self.auth.token_expires_at = response.expires_at.clone().unwrap_or_default();
+    }
+
     /// Clear all auth fields (logout).
     pub fn clear_auth(&mut self) {
         self.auth.session_token.clear();
@@ -220,6 +577,29 @@ pub fn config_file_path() -> Result<PathBuf> {
     Ok(config_dir.join("config.toml"))
 }
 
+/// Directory of local JSON Schemas for config values, `<key>.json` each.
+pub fn schema_dir() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir()
+        .context("could not determine config directory")?
+        .join("flagdash");
+    Ok(config_dir.join("schemas"))
+}
+
+/// The value format from `FLAGDASH_VALUE_FORMAT`, if it's set.
+pub fn value_format_from_env() -> Result<Option<ValueFormat>> {
+    let Ok(format) = std::env::var("FLAGDASH_VALUE_FORMAT") else {
+        return Ok(None);
+    };
+    clap::ValueEnum::from_str(&format, true)
+        .map(Some)
+        .map_err(|_| {
+            anyhow::anyhow!(
+                "FLAGDASH_VALUE_FORMAT must be json, yaml or toml, not '{}'",
+                format
+            )
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -260,6 +640,125 @@ mod tests {
         assert!(!config.has_session_token());
     }
 
+    #[test]
+    fn test_single_block_config_migrates_to_default_profile() {
+        let file = ConfigFile::parse(
+            r#"
+[auth]
+api_key = "management_old"
+
+[connection]
+base_url = "http://localhost:4000"
+"#,
+        )
+        .unwrap();
+        assert_eq!(file.current(), DEFAULT_PROFILE);
+        let config = file.profile(DEFAULT_PROFILE).unwrap();
+        assert_eq!(config.profile, "default");
+        assert_eq!(config.connection.base_url, "http://localhost:4000");
+        assert_eq!(config.auth.session_token, "management_old");
+
+        let saved = toml::to_string_pretty(&file).unwrap();
+        assert!(saved.contains("[profiles.default.connection]"));
+        assert!(!saved.contains("\n[auth]"));
+        assert!(!saved.contains("api_key"));
+    }
+
+    #[test]
+    fn test_profiles() {
+        let file = ConfigFile::parse(
+            r#"
+current_profile = "prod"
+
+[profiles.local.connection]
+base_url = "http://localhost:4000"
+
+[profiles.prod.auth]
+session_token = "session_prod"
+"#,
+        )
+        .unwrap();
+        assert_eq!(file.current(), "prod");
+        assert_eq!(file.names(), vec!["default", "local", "prod"]);
+        let prod = file.profile("prod").unwrap();
+        assert!(prod.has_session_token());
+        assert_eq!(prod.connection.base_url, "https://flagdash.io");
+        assert!(!file.profile("local").unwrap().has_session_token());
+        assert!(file.profile("default").is_ok());
+        assert!(file.profile("staging").is_err());
+    }
+
+    #[test]
+    fn test_editor_settings() {
+        let file = ConfigFile::parse("[editor]\nvalue_format = \"yaml\"\n").unwrap();
+        assert_eq!(file.editor.value_format, ValueFormat::Yaml);
+        assert!(toml::to_string(&file).unwrap().contains("[editor]"));
+
+        let file = ConfigFile::parse("").unwrap();
+        assert_eq!(file.editor.value_format, ValueFormat::Json);
+        assert!(!toml::to_string(&file).unwrap().contains("[editor]"));
+    }
+
+    #[test]
+    fn test_plaintext_tokens_move_to_store() {
+        let path =
+            std::env::temp_dir().join(format!("flagdash-test-{}-migrate.enc", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let store = credentials::EncryptedFileStore::new(
+            path.clone(),
+            credentials::Passphrase::Fixed("secret".into()),
+        );
+        let mut file = ConfigFile::parse(
+            r#"
+[profiles.default.auth]
+session_token = "session_a"
+
+[profiles.legacy.auth]
+api_key = "management_b"
+
+[profiles.empty.connection]
+base_url = "http://localhost:4000"
+"#,
+        )
+        .unwrap();
+
+        assert!(file.move_tokens_to(&store).unwrap());
+        assert!(
+            !file.move_tokens_to(&store).unwrap(),
+            "nothing left to move"
+        );
+        let saved = toml::to_string_pretty(&file).unwrap();
+        assert!(!saved.contains("session_a"));
+        assert!(!saved.contains("session_token"));
+
+        let resolve = |name: &str| file.resolve(name, Some(&store)).unwrap().auth.session_token;
+        assert_eq!(resolve("default"), "session_a");
+        assert_eq!(resolve("legacy"), "management_b");
+        assert_eq!(resolve("empty"), "");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_session_expiry() {
+        let now = DateTime::parse_from_rfc3339("2026-03-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let mut auth = AuthConfig::default();
+        assert_eq!(auth.expiry(now), SessionExpiry::Valid);
+
+        auth.token_expires_at = "2026-03-01T18:00:00+00:00".to_string();
+        assert_eq!(
+            auth.expiry(now),
+            SessionExpiry::ExpiresSoon(Duration::hours(6))
+        );
+        auth.token_expires_at = "2026-03-05T00:00:00Z".to_string();
+        assert_eq!(auth.expiry(now), SessionExpiry::Valid);
+        auth.token_expires_at = "2026-03-01T12:00:00Z".to_string();
+        assert_eq!(auth.expiry(now), SessionExpiry::Expired);
+        auth.token_expires_at = "next tuesday".to_string();
+        assert_eq!(auth.expiry(now), SessionExpiry::Valid);
+    }
+
     #[test]
     fn test_clear_auth() {
         let mut config = AppConfig::default();
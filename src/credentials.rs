@@ -0,0 +1,399 @@
+//! Where session tokens are kept.
+//!
+//! By default tokens live in the OS secret store (Keychain, Credential
+//! Manager, Secret Service) under the service `flagdash`, one entry per
+//! profile. Machines without one can use an encrypted file next to the config
+//! file, whose key is derived from a passphrase. Keeping tokens in
+//! `config.toml` itself is still possible, but only when asked for.
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Service name used for keyring entries.
+const KEYRING_SERVICE: &str = "flagdash";
+/// Environment variable holding the encrypted file's passphrase.
+pub const PASSPHRASE_ENV: &str = "FLAGDASH_CREDENTIALS_PASSPHRASE";
+
+/// The configured credential backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum StoreKind {
+    /// The OS secret store
+    #[default]
+    Keyring,
+    /// A file encrypted with a key derived from a passphrase
+    EncryptedFile,
+    /// Plaintext in config.toml
+    Plaintext,
+}
+
+impl StoreKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            StoreKind::Keyring => "keyring",
+            StoreKind::EncryptedFile => "encrypted-file",
+            StoreKind::Plaintext => "plaintext",
+        }
+    }
+}
+
+/// A place to keep one session token per profile.
+pub trait CredentialStore {
+    fn get(&self, profile: &str) -> Result<Option<String>>;
+    fn set(&self, profile: &str, token: &str) -> Result<()>;
+    /// Forget a profile's token. Deleting a token that isn't there is not an error.
+    fn delete(&self, profile: &str) -> Result<()>;
+    /// Get anything interactive, like a passphrase, out of the way.
+    fn unlock(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Open the store for `kind`, or `None` for plaintext, where the token stays
+/// in the config file itself.
+pub fn open(kind: StoreKind) -> Result<Option<Box<dyn CredentialStore>>> {
+    Ok(match kind {
+        StoreKind::Keyring => Some(Box::new(KeyringStore)),
+        StoreKind::EncryptedFile => Some(Box::new(EncryptedFileStore::new(
+            credentials_file_path()?,
+            Passphrase::Prompt,
+        ))),
+        StoreKind::Plaintext => None,
+    })
+}
+
+pub struct KeyringStore;
+
+impl KeyringStore {
+    /// Run a keyring call on its own thread. The Secret Service backend drives
+    /// its own async runtime, which can't be started from inside ours.
+    fn call<T: Send>(
+        profile: &str,
+        f: impl FnOnce(keyring::Entry) -> keyring::Result<T> + Send,
+    ) -> Result<keyring::Result<T>> {
+        let entry = keyring::Entry::new(KEYRING_SERVICE, profile).map_err(keyring_error)?;
+        std::thread::scope(|scope| scope.spawn(|| f(entry)).join())
+            .map_err(|_| anyhow!("the OS keyring call panicked"))
+    }
+}
+
+impl CredentialStore for KeyringStore {
+    fn get(&self, profile: &str) -> Result<Option<String>> {
+        match Self::call(profile, |entry| entry.get_password())? {
+            Ok(token) => Ok(Some(token)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(keyring_error(e)),
+        }
+    }
+
+    fn set(&self, profile: &str, token: &str) -> Result<()> {
+        Self::call(profile, |entry| entry.set_password(token))?.map_err(keyring_error)
+    }
+
+    fn delete(&self, profile: &str) -> Result<()> {
+        match Self::call(profile, |entry| entry.delete_credential())? {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(keyring_error(e)),
+        }
+    }
+}
+
+fn keyring_error(e: keyring::Error) -> anyhow::Error {
+    anyhow!(
+        "OS keyring unavailable ({}); set `store = \"encrypted-file\"` under [credentials] in the config file, or run `flagdash credentials use encrypted-file`",
+        e
+    )
+}
+
+/// Where the encrypted file's passphrase comes from.
+pub enum Passphrase {
+    /// `FLAGDASH_CREDENTIALS_PASSPHRASE`, or a prompt on the terminal. Asked
+    /// once per process.
+    Prompt,
+    Fixed(String),
+}
+
+impl Passphrase {
+    fn resolve(&self, creating: bool) -> Result<String> {
+        static CACHED: OnceLock<String> = OnceLock::new();
+        match self {
+            Passphrase::Fixed(p) => Ok(p.clone()),
+            Passphrase::Prompt => {
+                if let Some(p) = CACHED.get() {
+                    return Ok(p.clone());
+                }
+                let passphrase = match std::env::var(PASSPHRASE_ENV) {
+                    Ok(p) => p,
+                    Err(_) => prompt_passphrase(if creating {
+                        "Choose a passphrase for the FlagDash credentials file: "
+                    } else {
+                        "FlagDash credentials passphrase: "
+                    })?,
+                };
+                if passphrase.is_empty() {
+                    bail!("the credentials passphrase can't be empty");
+                }
+                Ok(CACHED.get_or_init(|| passphrase).clone())
+            }
+        }
+    }
+}
+
+/// Tokens encrypted with XChaCha20-Poly1305 under a key derived from a
+/// passphrase with Argon2id. Each write uses a fresh salt and nonce.
+pub struct EncryptedFileStore {
+    path: PathBuf,
+    passphrase: Passphrase,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EncryptedFile {
+    version: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+impl EncryptedFileStore {
+    pub fn new(path: PathBuf, passphrase: Passphrase) -> Self {
+        Self { path, passphrase }
+    }
+
+    fn read(&self) -> Result<BTreeMap<String, String>> {
+        if !self.path.exists() {
+            self.passphrase.resolve(true)?;
+            return Ok(BTreeMap::new());
+        }
+        let content = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("reading {}", self.path.display()))?;
+        let file: EncryptedFile = serde_json::from_str(&content)
+            .with_context(|| format!("parsing {}", self.path.display()))?;
+        if file.version != 1 {
+            bail!("unsupported credentials file version {}", file.version);
+        }
+        let salt = STANDARD.decode(&file.salt).context("decoding salt")?;
+        let nonce = STANDARD.decode(&file.nonce).context("decoding nonce")?;
+        let ciphertext = STANDARD
+            .decode(&file.ciphertext)
+            .context("decoding ciphertext")?;
+        if nonce.len() != 24 {
+            bail!("corrupt credentials file {}", self.path.display());
+        }
+        let cipher = cipher(&self.passphrase.resolve(false)?, &salt)?;
+        let plaintext = cipher
+            .decrypt(XNonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| {
+                anyhow!(
+                    "couldn't decrypt {}: wrong passphrase?",
+                    self.path.display()
+                )
+            })?;
+        serde_json::from_slice(&plaintext).context("parsing decrypted credentials")
+    }
+
+    fn write(&self, tokens: &BTreeMap<String, String>) -> Result<()> {
+        let mut salt = [0u8; 16];
+        let mut nonce = [0u8; 24];
+        getrandom::getrandom(&mut salt).map_err(|e| anyhow!("generating salt: {}", e))?;
+        getrandom::getrandom(&mut nonce).map_err(|e| anyhow!("generating nonce: {}", e))?;
+        let cipher = cipher(&self.passphrase.resolve(true)?, &salt)?;
+        let plaintext = serde_json::to_vec(tokens).context("serializing credentials")?;
+        let ciphertext = cipher
+            .encrypt(XNonce::from_slice(&nonce), plaintext.as_slice())
+            .map_err(|_| anyhow!("encrypting credentials"))?;
+        let file = EncryptedFile {
+            version: 1,
+            salt: STANDARD.encode(salt),
+            nonce: STANDARD.encode(nonce),
+            ciphertext: STANDARD.encode(ciphertext),
+        };
+        let content = serde_json::to_string_pretty(&file).context("serializing credentials")?;
+        write_private(&self.path, &content)
+    }
+}
+
+impl CredentialStore for EncryptedFileStore {
+    /// Resolve the passphrase now, so a prompt doesn't land in the middle of
+    /// the TUI when a token is first saved.
+    fn unlock(&self) -> Result<()> {
+        self.read().map(|_| ())
+    }
+
+    fn get(&self, profile: &str) -> Result<Option<String>> {
+        Ok(self.read()?.remove(profile))
+    }
+
+    fn set(&self, profile: &str, token: &str) -> Result<()> {
+        let mut tokens = self.read()?;
+        tokens.insert(profile.to_string(), token.to_string());
+        self.write(&tokens)
+    }
+
+    fn delete(&self, profile: &str) -> Result<()> {
+        if !self.path.exists() {
+            return Ok(());
+        }
+        let mut tokens = self.read()?;
+        if tokens.remove(profile).is_some() {
+            self.write(&tokens)?;
+        }
+        Ok(())
+    }
+}
+
+fn cipher(passphrase: &str, salt: &[u8]) -> Result<XChaCha20Poly1305> {
+    let mut key = [0u8; 32];
+    argon2::Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow!("deriving key: {}", e))?;
+    XChaCha20Poly1305::new_from_slice(&key).map_err(|_| anyhow!("invalid key length"))
+}
+
+/// Read a passphrase from the terminal without echoing it.
+fn prompt_passphrase(label: &str) -> Result<String> {
+    use crossterm::event::{read, Event, KeyCode, KeyEventKind, KeyModifiers};
+
+    if !std::io::stdin().is_terminal() {
+        bail!(
+            "the credentials file is encrypted: set {} or run interactively",
+            PASSPHRASE_ENV
+        );
+    }
+    eprint!("{}", label);
+    std::io::stderr().flush()?;
+    crossterm::terminal::enable_raw_mode()?;
+    let mut passphrase = String::new();
+    let result = loop {
+        match read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Enter => break Ok(()),
+                KeyCode::Esc => break Err(anyhow!("cancelled")),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    break Err(anyhow!("cancelled"))
+                }
+                KeyCode::Backspace => {
+                    passphrase.pop();
+                }
+                KeyCode::Char(c) => passphrase.push(c),
+                _ => {}
+            },
+            Ok(_) => {}
+            Err(e) => break Err(e.into()),
+        }
+    };
+    crossterm::terminal::disable_raw_mode()?;
+    eprintln!();
+    result.map(|_| passphrase)
+}
+
+/// Path of the encrypted credentials file, next to the config file.
+pub fn credentials_file_path() -> Result<PathBuf> {
+    Ok(crate::config::config_file_path()?.with_file_name("credentials.enc"))
+}
+
+/// Write a file only its owner can read (0600 on Unix). The content goes to a
+/// temporary file in the same directory first and is renamed over `path`, so
+/// a crash or a full disk can't leave it half-written.
+pub fn write_private(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("creating config dir {}", parent.display()))?;
+    }
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp = path.with_file_name(tmp_name);
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options
+        .open(&tmp)
+        .with_context(|| format!("writing {}", tmp.display()))?;
+    restrict_permissions(&tmp)?;
+    file.write_all(content.as_bytes())
+        .and_then(|_| file.sync_all())
+        .with_context(|| format!("writing {}", tmp.display()))?;
+    std::fs::rename(&tmp, path).with_context(|| format!("writing {}", path.display()))
+}
+
+/// Make an existing file readable by its owner only. A no-op on platforms
+/// without Unix permissions.
+pub fn restrict_permissions(path: &Path) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(path)
+            .with_context(|| format!("reading {}", path.display()))?
+            .permissions()
+            .mode();
+        if mode & 0o077 != 0 {
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+                .with_context(|| format!("restricting permissions of {}", path.display()))?;
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("flagdash-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join(name)
+    }
+
+    #[test]
+    fn test_encrypted_file_store_roundtrip() {
+        let path = temp_path("credentials-roundtrip.enc");
+        let _ = std::fs::remove_file(&path);
+        let store = EncryptedFileStore::new(path.clone(), Passphrase::Fixed("hunter2".into()));
+
+        assert_eq!(store.get("default").unwrap(), None);
+        store.set("default", "session_a").unwrap();
+        store.set("prod", "session_b").unwrap();
+        assert_eq!(store.get("default").unwrap().as_deref(), Some("session_a"));
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("session_a"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        store.delete("default").unwrap();
+        assert_eq!(store.get("default").unwrap(), None);
+        assert_eq!(store.get("prod").unwrap().as_deref(), Some("session_b"));
+
+        let wrong = EncryptedFileStore::new(path.clone(), Passphrase::Fixed("nope".into()));
+        assert!(wrong.get("prod").is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_private_tightens_existing_file() {
+        use std::os::unix::fs::PermissionsExt;
+        let path = temp_path("config-perms.toml");
+        std::fs::write(&path, "x").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        write_private(&path, "y").unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "y");
+        assert!(!path.with_file_name("config-perms.toml.tmp").exists());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
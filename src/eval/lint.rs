@@ -0,0 +1,488 @@
+use super::rules::{Operator, Rule};
+use super::{describe_value, fits_type};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A problem found in a rules document, located by 1-based line and column
+/// and by JSON pointer (e.g. `/0/clauses/1/operator`).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub line: usize,
+    pub column: usize,
+    pub path: String,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    /// Index of the rule the diagnostic points into, if any.
+    pub fn rule(&self) -> Option<usize> {
+        self.path.split('/').nth(1)?.parse().ok()
+    }
+
+    /// Index of the clause the diagnostic points into, if any.
+    pub fn clause(&self) -> Option<usize> {
+        let mut parts = self.path.split('/').skip(2);
+        match parts.next()? {
+            "clauses" | "conditions" => parts.next()?.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}: {}",
+            self.line, self.column, self.severity, self.message
+        )
+    }
+}
+
+/// What the rules are checked against. Checks whose input is `None` are skipped.
+#[derive(Debug, Clone, Default)]
+pub struct LintContext<'a> {
+    /// The flag's type: `boolean`, `number`, `string` or `json`.
+    pub flag_type: Option<&'a str>,
+    /// Variation keys defined for the flag in the environment.
+    pub variations: Option<&'a [String]>,
+}
+
+/// Check a rules document (JSON text) for syntax and schema errors, unknown
+/// operators, values that don't fit the operator or the flag type, unknown
+/// variations, and rules that can never match. Diagnostics are sorted by position.
+pub fn lint_rules(source: &str, ctx: &LintContext) -> Vec<Diagnostic> {
+    let value: Value = match serde_json::from_str(source) {
+        Ok(v) => v,
+        Err(e) => {
+            return vec![Diagnostic {
+                severity: Severity::Error,
+                line: e.line(),
+                column: e.column(),
+                path: String::new(),
+                message: error_message(&e),
+            }]
+        }
+    };
+    let mut linter = Linter {
+        positions: locate(source),
+        diagnostics: Vec::new(),
+    };
+    linter.rules(&value, ctx);
+    let mut diagnostics = linter.diagnostics;
+    diagnostics.sort_by_key(|d| (d.line, d.column, d.severity));
+    diagnostics
+}
+
+struct Linter {
+    positions: HashMap<String, (usize, usize)>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Linter {
+    fn push(&mut self, severity: Severity, path: String, message: String) {
+        // Missing fields are reported at the nearest enclosing value.
+        let mut at = path.as_str();
+        let (line, column) = loop {
+            if let Some(pos) = self.positions.get(at) {
+                break *pos;
+            }
+            match at.rfind('/') {
+                Some(i) => at = &at[..i],
+                None => break (1, 1),
+            }
+        };
+        self.diagnostics.push(Diagnostic {
+            severity,
+            line,
+            column,
+            path,
+            message,
+        });
+    }
+
+    fn error(&mut self, path: String, message: String) {
+        self.push(Severity::Error, path, message);
+    }
+
+    fn warning(&mut self, path: String, message: String) {
+        self.push(Severity::Warning, path, message);
+    }
+
+    fn rules(&mut self, value: &Value, ctx: &LintContext) {
+        let items = match value {
+            Value::Null => return,
+            Value::Array(items) => items,
+            _ => {
+                return self.error(String::new(), "rules must be a JSON array".to_string());
+            }
+        };
+        let mut catch_all: Option<usize> = None;
+        for (i, item) in items.iter().enumerate() {
+            let path = format!("/{}", i);
+            let Some(object) = item.as_object() else {
+                self.error(path, "a rule must be a JSON object".to_string());
+                continue;
+            };
+            let rule: Rule = match serde_json::from_value(item.clone()) {
+                Ok(r) => r,
+                Err(e) => {
+                    self.error(path, format!("invalid rule: {}", e));
+                    continue;
+                }
+            };
+            let clauses_path = format!("{}/{}", path, clauses_key(object));
+
+            if let Some(first) = catch_all {
+                self.warning(
+                    path.clone(),
+                    format!(
+                        "rule {} is unreachable: rule {} matches every user",
+                        i + 1,
+                        first + 1
+                    ),
+                );
+            }
+            if rule.clauses.is_empty() {
+                self.warning(
+                    clauses_path.clone(),
+                    format!("rule {} has no clauses and matches every user", i + 1),
+                );
+                catch_all.get_or_insert(i);
+            }
+            for (j, clause) in rule.clauses.iter().enumerate() {
+                let path = format!("{}/{}", clauses_path, j);
+                if clause.attribute.trim().is_empty() {
+                    self.error(
+                        format!("{}/attribute", path),
+                        "clause has no attribute".to_string(),
+                    );
+                }
+                let Some(op) = clause.op() else {
+                    self.error(
+                        format!("{}/operator", path),
+                        format!(
+                            "unknown operator '{}', expected one of: {}",
+                            clause.operator,
+                            Operator::ALL.map(Operator::as_str).join(", ")
+                        ),
+                    );
+                    continue;
+                };
+                if let Some(problem) = operand_problem(op, &clause.value) {
+                    let severity = if op.takes_value() {
+                        Severity::Error
+                    } else {
+                        Severity::Warning
+                    };
+                    self.push(severity, format!("{}/value", path), problem);
+                }
+            }
+
+            match (&rule.value, &rule.variation) {
+                (Some(_), Some(_)) => self.warning(
+                    format!("{}/variation", path),
+                    "rule sets both a value and a variation; the variation is served".to_string(),
+                ),
+                (None, None) => self.warning(
+                    path.clone(),
+                    "rule serves neither a value nor a variation; the environment value is served"
+                        .to_string(),
+                ),
+                _ => {}
+            }
+            if let Some(key) = &rule.variation {
+                if let Some(known) = ctx.variations {
+                    if !known.contains(key) {
+                        let expected = if known.is_empty() {
+                            "the flag has no variations in this environment".to_string()
+                        } else {
+                            format!("expected one of: {}", known.join(", "))
+                        };
+                        self.error(
+                            format!("{}/variation", path),
+                            format!("unknown variation '{}', {}", key, expected),
+                        );
+                    }
+                }
+            } else if let (Some(value), Some(flag_type)) = (&rule.value, ctx.flag_type) {
+                if !fits_type(value, flag_type) {
+                    self.error(
+                        format!("{}/value", path),
+                        format!(
+                            "rule serves {} but the flag type is {}",
+                            describe_value(value),
+                            flag_type
+                        ),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Rules read from the API may use `conditions` instead of `clauses`.
+fn clauses_key(rule: &Map<String, Value>) -> &'static str {
+    if rule.contains_key("conditions") && !rule.contains_key("clauses") {
+        "conditions"
+    } else {
+        "clauses"
+    }
+}
+
+/// Why `value` is the wrong shape for `op`, if it is.
+fn operand_problem(op: Operator, value: &Value) -> Option<String> {
+    let name = op.as_str();
+    if !op.takes_value() {
+        return (!value.is_null()).then(|| format!("'{}' ignores the clause value", name));
+    }
+    if value.is_null() {
+        return Some(format!("'{}' needs a value", name));
+    }
+    if op.takes_list() {
+        return (!value.is_array()).then(|| format!("'{}' needs a list of values", name));
+    }
+    if value.is_array() || value.is_object() {
+        let hint = match op {
+            Operator::Eq => "; use 'in' to match any of a list",
+            Operator::Neq => "; use 'not_in' to exclude a list",
+            _ => "",
+        };
+        return Some(format!("'{}' needs a single value{}", name, hint));
+    }
+    if op.is_string()
+        && !value.is_string()
+        && !matches!(op, Operator::Contains | Operator::NotContains)
+    {
+        return Some(format!("'{}' needs a string value", name));
+    }
+    if op.is_ordering() && !value.is_number() && !value.is_string() {
+        return Some(format!("'{}' needs a number or a string", name));
+    }
+    None
+}
+
+/// serde_json messages end in " at line L column C", which diagnostics carry separately.
+fn error_message(e: &serde_json::Error) -> String {
+    let message = e.to_string();
+    match message.rfind(" at line ") {
+        Some(i) => message[..i].to_string(),
+        None => message,
+    }
+}
+
+/// Map the JSON pointer of every value in a valid JSON document to the
+/// 1-based line and column where it starts.
+fn locate(source: &str) -> HashMap<String, (usize, usize)> {
+    let mut scanner = Scanner {
+        chars: source.chars().collect(),
+        pos: 0,
+        line: 1,
+        column: 1,
+        positions: HashMap::new(),
+    };
+    scanner.value(String::new());
+    scanner.positions
+}
+
+struct Scanner {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+    column: usize,
+    positions: HashMap<String, (usize, usize)>,
+}
+
+impl Scanner {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    fn value(&mut self, path: String) {
+        self.skip_whitespace();
+        self.positions
+            .insert(path.clone(), (self.line, self.column));
+        match self.peek() {
+            Some('{') => {
+                self.bump();
+                loop {
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some('"') => {
+                            let key = self.string();
+                            self.skip_whitespace();
+                            self.bump(); // ':'
+                            let escaped = key.replace('~', "~0").replace('/', "~1");
+                            self.value(format!("{}/{}", path, escaped));
+                        }
+                        Some(',') => {
+                            self.bump();
+                        }
+                        _ => {
+                            self.bump(); // '}'
+                            break;
+                        }
+                    }
+                }
+            }
+            Some('[') => {
+                self.bump();
+                let mut index = 0;
+                loop {
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(']') | None => {
+                            self.bump();
+                            break;
+                        }
+                        Some(',') => {
+                            self.bump();
+                        }
+                        _ => {
+                            self.value(format!("{}/{}", path, index));
+                            index += 1;
+                        }
+                    }
+                }
+            }
+            Some('"') => {
+                self.string();
+            }
+            _ => {
+                while self
+                    .peek()
+                    .is_some_and(|c| !matches!(c, ',' | ']' | '}') && !c.is_whitespace())
+                {
+                    self.bump();
+                }
+            }
+        }
+    }
+
+    fn string(&mut self) -> String {
+        self.bump(); // opening quote
+        let mut out = String::new();
+        while let Some(c) = self.bump() {
+            match c {
+                '"' => break,
+                '\\' => {
+                    if let Some(escaped) = self.bump() {
+                        out.push(escaped);
+                    }
+                }
+                c => out.push(c),
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lint(source: &str) -> Vec<Diagnostic> {
+        let variations = vec!["control".to_string(), "treatment".to_string()];
+        lint_rules(
+            source,
+            &LintContext {
+                flag_type: Some("boolean"),
+                variations: Some(&variations),
+            },
+        )
+    }
+
+    #[test]
+    fn test_valid_rules_have_no_diagnostics() {
+        let source = r#"[
+  {"clauses": [{"attribute": "country", "operator": "in", "value": ["NZ", "AU"]}], "value": true},
+  {"clauses": [{"attribute": "beta", "operator": "exists"}], "variation": "treatment"}
+]"#;
+        assert_eq!(lint(source), vec![]);
+    }
+
+    #[test]
+    fn test_syntax_error_position() {
+        let diagnostics = lint("[\n  {\"clauses\": [}\n]");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (2, 16));
+        assert!(!diagnostics[0].message.contains("at line"));
+    }
+
+    #[test]
+    fn test_semantic_errors_are_located() {
+        let source = r#"[
+  {"clauses": [], "value": true},
+  {"clauses": [{"attribute": "country", "operator": "equals", "value": "NZ"}],
+   "value": "yes"},
+  {"conditions": [{"attribute": "plan", "operator": "in", "value": "pro"}],
+   "variation": "missing"}
+]"#;
+        let diagnostics = lint(source);
+        let found: Vec<(usize, usize, Severity, &str)> = diagnostics
+            .iter()
+            .map(|d| (d.line, d.column, d.severity, d.path.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (2, 15, Severity::Warning, "/0/clauses"),
+                (3, 3, Severity::Warning, "/1"),
+                (3, 53, Severity::Error, "/1/clauses/0/operator"),
+                (4, 13, Severity::Error, "/1/value"),
+                (5, 3, Severity::Warning, "/2"),
+                (5, 68, Severity::Error, "/2/conditions/0/value"),
+                (6, 17, Severity::Error, "/2/variation"),
+            ]
+        );
+        assert_eq!(diagnostics[2].rule(), Some(1));
+        assert_eq!(diagnostics[2].clause(), Some(0));
+        assert_eq!(diagnostics[1].clause(), None);
+    }
+
+    #[test]
+    fn test_checks_without_context_are_skipped() {
+        let source = r#"[{"clauses": [{"attribute": "a", "operator": "eq", "value": 1}], "variation": "x"}]"#;
+        assert_eq!(lint_rules(source, &LintContext::default()), vec![]);
+    }
+}
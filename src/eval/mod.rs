@@ -0,0 +1,437 @@
+pub mod lint;
+pub mod rules;
+
+use crate::api::types::{FlagEnvironmentData, ManagedFlag, Variation};
+use rules::{parse_rules, Clause, Operator, Rule};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Why an evaluation produced its value.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Reason {
+    /// The flag is off in this environment; the default value is served.
+    Off,
+    /// Targeting rule `index` (zero-based) matched.
+    RuleMatch { index: usize },
+    /// No rule matched and the user's bucket is outside the rollout percentage.
+    OutOfRollout,
+    /// No rule matched, the user is in the rollout and a variation was picked by weight.
+    Variation,
+    /// No rule matched and the user is in the rollout.
+    Fallthrough,
+}
+
+impl std::fmt::Display for Reason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Reason::Off => write!(f, "flag is off"),
+            Reason::RuleMatch { index } => write!(f, "rule {} matched", index + 1),
+            Reason::OutOfRollout => write!(f, "outside rollout"),
+            Reason::Variation => write!(f, "in rollout, variation picked by weight"),
+            Reason::Fallthrough => write!(f, "in rollout, no rule matched"),
+        }
+    }
+}
+
+/// The outcome of evaluating a flag for one user context.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Evaluation {
+    pub value: Value,
+    pub reason: Reason,
+    /// Key of the variation served, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variation: Option<String>,
+    /// The user's rollout bucket in `[0, 100)`, when rollout was consulted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bucket: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub in_rollout: Option<bool>,
+}
+
+/// Evaluate `flag` in one environment for a user `context` (a JSON object of attributes).
+///
+/// Order: an off flag serves the default value; otherwise the first rule whose
+/// clauses all match wins; otherwise the user is bucketed by `key` against the
+/// rollout percentage, and users inside it get a weighted variation (if any)
+/// or the environment value.
+pub fn evaluate(
+    flag: &ManagedFlag,
+    environment: &FlagEnvironmentData,
+    variations: &[Variation],
+    context: &Value,
+) -> Result<Evaluation, serde_json::Error> {
+    if !environment.enabled {
+        return Ok(Evaluation {
+            value: flag.default_value.clone(),
+            reason: Reason::Off,
+            variation: None,
+            bucket: None,
+            in_rollout: None,
+        });
+    }
+
+    let rules = parse_rules(&environment.rules)?;
+    if let Some((index, rule)) = rules
+        .iter()
+        .enumerate()
+        .find(|(_, r)| rule_matches(r, context))
+    {
+        let variation = rule
+            .variation
+            .as_deref()
+            .and_then(|key| variations.iter().find(|v| v.key == key));
+        let value = match (variation, &rule.value) {
+            (Some(v), _) => v.value.clone(),
+            (None, Some(value)) => value.clone(),
+            (None, None) => enabled_value(flag, environment),
+        };
+        return Ok(Evaluation {
+            value,
+            reason: Reason::RuleMatch { index },
+            variation: variation.map(|v| v.key.clone()),
+            bucket: None,
+            in_rollout: None,
+        });
+    }
+
+    let user_key = bucketing_key(context);
+    let bucket = bucket(&flag.key, "rollout", &user_key);
+    let in_rollout = bucket < environment.rollout_percentage as f64;
+    if !in_rollout {
+        return Ok(Evaluation {
+            value: flag.default_value.clone(),
+            reason: Reason::OutOfRollout,
+            variation: None,
+            bucket: Some(bucket),
+            in_rollout: Some(false),
+        });
+    }
+
+    if let Some(variation) = pick_variation(&flag.key, &user_key, variations) {
+        return Ok(Evaluation {
+            value: variation.value.clone(),
+            reason: Reason::Variation,
+            variation: Some(variation.key.clone()),
+            bucket: Some(bucket),
+            in_rollout: Some(true),
+        });
+    }
+
+    Ok(Evaluation {
+        value: enabled_value(flag, environment),
+        reason: Reason::Fallthrough,
+        variation: None,
+        bucket: Some(bucket),
+        in_rollout: Some(true),
+    })
+}
+
+/// The value served to users in an enabled environment: the environment
+/// value, or `true` for boolean flags without one, or the flag default.
+fn enabled_value(flag: &ManagedFlag, environment: &FlagEnvironmentData) -> Value {
+    if !environment.value.is_null() {
+        environment.value.clone()
+    } else if flag.flag_type == "boolean" {
+        Value::Bool(true)
+    } else {
+        flag.default_value.clone()
+    }
+}
+
+pub fn rule_matches(rule: &Rule, context: &Value) -> bool {
+    rule.clauses.iter().all(|c| clause_matches(c, context))
+}
+
+/// Test a single clause. A missing attribute only ever matches `not_exists`,
+/// and unknown operators never match.
+pub fn clause_matches(clause: &Clause, context: &Value) -> bool {
+    let Some(op) = clause.op() else {
+        return false;
+    };
+    let actual = lookup(context, &clause.attribute).filter(|v| !v.is_null());
+    let Some(actual) = actual else {
+        return op == Operator::NotExists;
+    };
+    let expected = &clause.value;
+    match op {
+        Operator::Exists => true,
+        Operator::NotExists => false,
+        Operator::Eq => loose_eq(actual, expected),
+        Operator::Neq => !loose_eq(actual, expected),
+        Operator::In => list(expected).iter().any(|e| loose_eq(actual, e)),
+        Operator::NotIn => !list(expected).iter().any(|e| loose_eq(actual, e)),
+        Operator::Contains => contains(actual, expected),
+        Operator::NotContains => !contains(actual, expected),
+        Operator::StartsWith => match (actual.as_str(), expected.as_str()) {
+            (Some(a), Some(e)) => a.starts_with(e),
+            _ => false,
+        },
+        Operator::EndsWith => match (actual.as_str(), expected.as_str()) {
+            (Some(a), Some(e)) => a.ends_with(e),
+            _ => false,
+        },
+        Operator::Gt | Operator::Gte | Operator::Lt | Operator::Lte => {
+            match compare(actual, expected) {
+                Some(ord) => match op {
+                    Operator::Gt => ord.is_gt(),
+                    Operator::Gte => ord.is_ge(),
+                    Operator::Lt => ord.is_lt(),
+                    _ => ord.is_le(),
+                },
+                None => false,
+            }
+        }
+    }
+}
+
+/// Look up a dotted attribute path such as `user.country` in the context.
+fn lookup<'a>(context: &'a Value, path: &str) -> Option<&'a Value> {
+    if let Some(v) = context.get(path) {
+        return Some(v);
+    }
+    path.split('.').try_fold(context, |v, part| v.get(part))
+}
+
+/// The identifier used for bucketing: the context's `key`, falling back to `id`.
+/// Contexts without either all land in the same bucket.
+pub fn bucketing_key(context: &Value) -> String {
+    ["key", "id"]
+        .iter()
+        .find_map(|k| context.get(*k).filter(|v| !v.is_null()))
+        .map(|v| match v {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        })
+        .unwrap_or_default()
+}
+
+/// Deterministically map a user to a bucket in `[0, 100)` with two decimal places.
+/// The salt keeps rollout and variation assignment independent of each other.
+pub fn bucket(flag_key: &str, salt: &str, user_key: &str) -> f64 {
+    let hash = fnv1a(format!("{}.{}.{}", flag_key, salt, user_key).as_bytes());
+    (hash % 10_000) as f64 / 100.0
+}
+
+/// Pick a variation for the user, proportionally to the variation weights.
+pub fn pick_variation<'a>(
+    flag_key: &str,
+    user_key: &str,
+    variations: &'a [Variation],
+) -> Option<&'a Variation> {
+    let total: i64 = variations.iter().map(|v| v.weight.max(0) as i64).sum();
+    if total == 0 {
+        return None;
+    }
+    let point = bucket(flag_key, "variation", user_key) / 100.0 * total as f64;
+    let mut cumulative = 0.0;
+    for v in variations {
+        cumulative += v.weight.max(0) as f64;
+        if point < cumulative {
+            return Some(v);
+        }
+    }
+    variations.iter().rev().find(|v| v.weight > 0)
+}
+
+/// Whether `value` can be served by a flag of `flag_type`. Unknown types and
+/// `json` accept anything.
+pub fn fits_type(value: &Value, flag_type: &str) -> bool {
+    match flag_type {
+        "boolean" => value.is_boolean(),
+        "number" => value.is_number(),
+        "string" => value.is_string(),
+        _ => true,
+    }
+}
+
+/// Describe a value's JSON type along with the value, e.g. `a string ("yes")`.
+pub fn describe_value(value: &Value) -> String {
+    let kind = match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    };
+    format!("{} ({})", kind, value)
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for b in bytes {
+        hash ^= *b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+fn list(value: &Value) -> Vec<Value> {
+    match value {
+        Value::Array(items) => items.clone(),
+        other => vec![other.clone()],
+    }
+}
+
+/// Equality that treats numbers by value (so `1` equals `1.0`) and numeric
+/// strings as numbers when compared with a number.
+fn loose_eq(a: &Value, b: &Value) -> bool {
+    match (as_number(a), as_number(b)) {
+        (Some(x), Some(y)) if a.is_number() || b.is_number() => x == y,
+        _ => a == b,
+    }
+}
+
+fn contains(actual: &Value, expected: &Value) -> bool {
+    match actual {
+        Value::String(s) => expected.as_str().is_some_and(|e| s.contains(e)),
+        Value::Array(items) => items.iter().any(|i| loose_eq(i, expected)),
+        _ => false,
+    }
+}
+
+/// Order two values numerically when both are numbers (or numeric strings),
+/// otherwise lexically when both are strings, which works for ISO-8601 dates.
+fn compare(a: &Value, b: &Value) -> Option<std::cmp::Ordering> {
+    match (as_number(a), as_number(b)) {
+        (Some(x), Some(y)) => x.partial_cmp(&y),
+        _ => Some(a.as_str()?.cmp(b.as_str()?)),
+    }
+}
+
+fn as_number(v: &Value) -> Option<f64> {
+    match v {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn flag(rules: Value, rollout: i32, enabled: bool) -> (ManagedFlag, FlagEnvironmentData) {
+        let ts = "2026-01-01T00:00:00Z".parse().unwrap();
+        let env = FlagEnvironmentData {
+            id: "fe_1".into(),
+            environment_id: "env_1".into(),
+            enabled,
+            value: Value::Null,
+            rules,
+            rollout_percentage: rollout,
+        };
+        let flag = ManagedFlag {
+            id: "flg_1".into(),
+            key: "checkout".into(),
+            name: "Checkout".into(),
+            description: String::new(),
+            flag_type: "boolean".into(),
+            default_value: json!(false),
+            tags: vec![],
+            is_archived: false,
+            created_at: ts,
+            updated_at: ts,
+            environments: vec![env.clone()],
+        };
+        (flag, env)
+    }
+
+    #[test]
+    fn test_off_serves_default() {
+        let (f, e) = flag(json!([]), 100, false);
+        let result = evaluate(&f, &e, &[], &json!({"key": "u1"})).unwrap();
+        assert_eq!(result.value, json!(false));
+        assert_eq!(result.reason, Reason::Off);
+    }
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let rules = json!([
+            {"clauses": [{"attribute": "country", "operator": "in", "value": ["NZ", "AU"]},
+                         {"attribute": "plan.tier", "operator": "eq", "value": "pro"}],
+             "value": true},
+            {"clauses": [{"attribute": "country", "operator": "eq", "value": "NZ"}],
+             "value": false}
+        ]);
+        let (f, e) = flag(rules, 0, true);
+        let ctx = json!({"key": "u1", "country": "NZ", "plan": {"tier": "pro"}});
+        let result = evaluate(&f, &e, &[], &ctx).unwrap();
+        assert_eq!(result.reason, Reason::RuleMatch { index: 0 });
+        assert_eq!(result.value, json!(true));
+
+        let ctx = json!({"key": "u1", "country": "NZ"});
+        let result = evaluate(&f, &e, &[], &ctx).unwrap();
+        assert_eq!(result.reason, Reason::RuleMatch { index: 1 });
+    }
+
+    #[test]
+    fn test_operators() {
+        let ctx = json!({"age": 30, "email": "a@example.com", "tags": ["beta"], "v": "12"});
+        let check = |attribute: &str, operator: &str, value: Value| {
+            let clause = Clause {
+                attribute: attribute.into(),
+                operator: operator.into(),
+                value,
+                extra: Default::default(),
+                explicit_null: false,
+            };
+            clause_matches(&clause, &ctx)
+        };
+        assert!(check("age", "gte", json!(30)));
+        assert!(!check("age", "lt", json!(18)));
+        assert!(check("v", "gt", json!(9)));
+        assert!(check("email", "ends_with", json!("@example.com")));
+        assert!(check("tags", "contains", json!("beta")));
+        assert!(check("missing", "not_exists", Value::Null));
+        assert!(!check("missing", "neq", json!("x")));
+        assert!(!check("age", "between", json!(1)));
+    }
+
+    #[test]
+    fn test_bucketing_is_deterministic() {
+        let a = bucket("checkout", "rollout", "user-1");
+        assert_eq!(a, bucket("checkout", "rollout", "user-1"));
+        assert!((0.0..100.0).contains(&a));
+        let in_rollout = (0..1000)
+            .filter(|i| bucket("checkout", "rollout", &format!("user-{}", i)) < 25.0)
+            .count();
+        assert!((180..320).contains(&in_rollout), "{}", in_rollout);
+    }
+
+    #[test]
+    fn test_variation_weights() {
+        let variation = |key: &str, weight| Variation {
+            id: key.into(),
+            key: key.into(),
+            name: key.into(),
+            value: json!(key),
+            weight,
+        };
+        let variations = vec![variation("a", 0), variation("b", 100)];
+        for i in 0..50 {
+            let picked = pick_variation("checkout", &format!("u{}", i), &variations).unwrap();
+            assert_eq!(picked.key, "b");
+        }
+        let (f, e) = flag(json!([]), 100, true);
+        let result = evaluate(&f, &e, &variations, &json!({"key": "u1"})).unwrap();
+        assert_eq!(result.reason, Reason::Variation);
+        assert_eq!(result.variation.as_deref(), Some("b"));
+        assert_eq!(result.in_rollout, Some(true));
+    }
+
+    #[test]
+    fn test_rules_round_trip() {
+        let raw = json!([{
+            "description": "beta users",
+            "clauses": [{"attribute": "tags", "operator": "contains", "value": "beta", "negate": false}],
+            "value": null,
+            "id": "rule_1"
+        }]);
+        let parsed = parse_rules(&raw).unwrap();
+        assert_eq!(parsed[0].value, Some(Value::Null));
+        assert_eq!(rules::rules_to_value(&parsed), raw);
+    }
+}
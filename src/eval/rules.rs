@@ -0,0 +1,266 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+
+/// A targeting rule. A rule matches when all of its clauses match, and then
+/// serves either a fixed `value` or the value of a named `variation`.
+///
+/// Fields this schema doesn't know about are kept in `extra`, and the key the
+/// clauses were read from is remembered, so rules read from the API
+/// serialize back unchanged.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "RawRule", into = "RawRule")]
+pub struct Rule {
+    pub description: String,
+    pub clauses: Vec<Clause>,
+    pub value: Option<Value>,
+    pub variation: Option<String>,
+    pub extra: Map<String, Value>,
+    pub clauses_key: ClausesKey,
+}
+
+/// Where a rule's clauses are stored: the API accepts `conditions` as well
+/// as `clauses`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClausesKey {
+    #[default]
+    Clauses,
+    Conditions,
+    /// Neither key was present; one is only written once there are clauses.
+    Absent,
+}
+
+/// A rule exactly as it appears in JSON.
+#[derive(Serialize, Deserialize)]
+struct RawRule {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    clauses: Option<Vec<Clause>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    conditions: Option<Vec<Clause>>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    value: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    variation: Option<String>,
+    #[serde(flatten)]
+    extra: Map<String, Value>,
+}
+
+impl From<RawRule> for Rule {
+    fn from(raw: RawRule) -> Self {
+        let mut extra = raw.extra;
+        let (clauses, clauses_key) = match (raw.clauses, raw.conditions) {
+            (Some(clauses), conditions) => {
+                // Both keys: `clauses` is the one evaluated, the other is kept as is
+                if let Some(conditions) = conditions {
+                    extra.insert(
+                        "conditions".to_string(),
+                        serde_json::to_value(conditions).unwrap_or(Value::Null),
+                    );
+                }
+                (clauses, ClausesKey::Clauses)
+            }
+            (None, Some(conditions)) => (conditions, ClausesKey::Conditions),
+            (None, None) => (Vec::new(), ClausesKey::Absent),
+        };
+        Rule {
+            description: raw.description,
+            clauses,
+            value: raw.value,
+            variation: raw.variation,
+            extra,
+            clauses_key,
+        }
+    }
+}
+
+impl From<Rule> for RawRule {
+    fn from(rule: Rule) -> Self {
+        let (clauses, conditions) = match rule.clauses_key {
+            ClausesKey::Conditions => (None, Some(rule.clauses)),
+            ClausesKey::Absent if rule.clauses.is_empty() => (None, None),
+            ClausesKey::Clauses | ClausesKey::Absent => (Some(rule.clauses), None),
+        };
+        RawRule {
+            description: rule.description,
+            clauses,
+            conditions,
+            value: rule.value,
+            variation: rule.variation,
+            extra: rule.extra,
+        }
+    }
+}
+
+/// One `attribute operator value` test against the user context.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "RawClause", into = "RawClause")]
+pub struct Clause {
+    pub attribute: String,
+    pub operator: String,
+    pub value: Value,
+    pub extra: Map<String, Value>,
+    /// The clause was read with an explicit `"value": null`, which is written
+    /// back; otherwise a null value is left out.
+    pub explicit_null: bool,
+}
+
+/// A clause exactly as it appears in JSON.
+#[derive(Serialize, Deserialize)]
+struct RawClause {
+    attribute: String,
+    operator: String,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    value: Option<Value>,
+    #[serde(flatten)]
+    extra: Map<String, Value>,
+}
+
+impl From<RawClause> for Clause {
+    fn from(raw: RawClause) -> Self {
+        Clause {
+            attribute: raw.attribute,
+            operator: raw.operator,
+            explicit_null: raw.value == Some(Value::Null),
+            value: raw.value.unwrap_or(Value::Null),
+            extra: raw.extra,
+        }
+    }
+}
+
+impl From<Clause> for RawClause {
+    fn from(clause: Clause) -> Self {
+        let value = match clause.value {
+            Value::Null if !clause.explicit_null => None,
+            value => Some(value),
+        };
+        RawClause {
+            attribute: clause.attribute,
+            operator: clause.operator,
+            value,
+            extra: clause.extra,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Eq,
+    Neq,
+    In,
+    NotIn,
+    Contains,
+    NotContains,
+    StartsWith,
+    EndsWith,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Exists,
+    NotExists,
+}
+
+impl Operator {
+    pub const ALL: [Operator; 14] = [
+        Operator::Eq,
+        Operator::Neq,
+        Operator::In,
+        Operator::NotIn,
+        Operator::Contains,
+        Operator::NotContains,
+        Operator::StartsWith,
+        Operator::EndsWith,
+        Operator::Gt,
+        Operator::Gte,
+        Operator::Lt,
+        Operator::Lte,
+        Operator::Exists,
+        Operator::NotExists,
+    ];
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|op| op.as_str() == s)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Operator::Eq => "eq",
+            Operator::Neq => "neq",
+            Operator::In => "in",
+            Operator::NotIn => "not_in",
+            Operator::Contains => "contains",
+            Operator::NotContains => "not_contains",
+            Operator::StartsWith => "starts_with",
+            Operator::EndsWith => "ends_with",
+            Operator::Gt => "gt",
+            Operator::Gte => "gte",
+            Operator::Lt => "lt",
+            Operator::Lte => "lte",
+            Operator::Exists => "exists",
+            Operator::NotExists => "not_exists",
+        }
+    }
+
+    /// `in` / `not_in` compare against a list of values.
+    pub fn takes_list(self) -> bool {
+        matches!(self, Operator::In | Operator::NotIn)
+    }
+
+    /// `exists` / `not_exists` ignore the clause value.
+    pub fn takes_value(self) -> bool {
+        !matches!(self, Operator::Exists | Operator::NotExists)
+    }
+
+    /// String operators only make sense against string values.
+    pub fn is_string(self) -> bool {
+        matches!(
+            self,
+            Operator::Contains | Operator::NotContains | Operator::StartsWith | Operator::EndsWith
+        )
+    }
+
+    pub fn is_ordering(self) -> bool {
+        matches!(
+            self,
+            Operator::Gt | Operator::Gte | Operator::Lt | Operator::Lte
+        )
+    }
+}
+
+impl Clause {
+    /// The parsed operator, or `None` if it isn't one this CLI knows.
+    pub fn op(&self) -> Option<Operator> {
+        Operator::parse(&self.operator)
+    }
+}
+
+/// Parse a rules value as stored on `FlagEnvironmentData`. `null` means no rules.
+pub fn parse_rules(value: &Value) -> Result<Vec<Rule>, serde_json::Error> {
+    if value.is_null() {
+        return Ok(Vec::new());
+    }
+    Vec::<Rule>::deserialize(value)
+}
+
+/// Serialize rules back into the JSON shape `UpdateRulesRequest` expects.
+pub fn rules_to_value(rules: &[Rule]) -> Value {
+    serde_json::to_value(rules).unwrap_or_else(|_| Value::Array(vec![]))
+}
+
+/// Like `Option::deserialize`, but an explicit `null` becomes `Some(Value::Null)`
+/// so that a rule serving `null` survives a round trip.
+fn present<'de, D>(deserializer: D) -> Result<Option<Value>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Value::deserialize(deserializer).map(Some)
+}
@@ -28,18 +28,13 @@ impl EventHandler {
         tokio::spawn(async move {
             loop {
                 if event::poll(tick_rate).unwrap_or(false) {
-                    match event::read() {
-                        Ok(CrosstermEvent::Key(key)) => {
-                            if event_tx.send(Event::Key(key)).is_err() {
-                                break;
-                            }
-                        }
-                        Ok(CrosstermEvent::Resize(w, h)) => {
-                            if event_tx.send(Event::Resize(w, h)).is_err() {
-                                break;
-                            }
-                        }
-                        _ => {}
+                    let event = match event::read() {
+                        Ok(CrosstermEvent::Key(key)) => Event::Key(key),
+                        Ok(CrosstermEvent::Resize(w, h)) => Event::Resize(w, h),
+                        _ => continue,
+                    };
+                    if event_tx.send(event).is_err() {
+                        break;
                     }
                 } else if event_tx.send(Event::Tick).is_err() {
                     break;
@@ -4,11 +4,21 @@
 mod action;
 mod api;
 mod app;
+mod commands;
 mod components;
 mod config;
+mod credentials;
+mod eval;
 mod event;
+mod manifest;
+mod output;
+mod rollout;
+mod schema;
 mod theme;
 mod tui;
+#[cfg(test)]
+mod tui_snapshots;
+mod value_format;
 mod views;
 
 use anyhow::Result;
@@ -22,25 +32,61 @@ use clap::Parser;
     author = "FlagDash <team@flagdash.io>"
 )]
 struct Cli {
+    /// Named profile to use (defaults to the config file's current profile)
+    #[arg(long, env = "FLAGDASH_PROFILE", global = true)]
+    profile: Option<String>,
+
     /// Session token (overrides config file and env var)
-    #[arg(long, env = "FLAGDASH_SESSION_TOKEN")]
+    #[arg(long, env = "FLAGDASH_SESSION_TOKEN", global = true)]
     session_token: Option<String>,
 
     /// Management API key (legacy alias for --session-token)
-    #[arg(long, env = "FLAGDASH_API_KEY", hide = true)]
+    #[arg(long, env = "FLAGDASH_API_KEY", hide = true, global = true)]
     api_key: Option<String>,
 
     /// Base URL for FlagDash API
-    #[arg(long, env = "FLAGDASH_BASE_URL")]
+    #[arg(long, env = "FLAGDASH_BASE_URL", global = true)]
     base_url: Option<String>,
 
     /// Default project ID
-    #[arg(long, env = "FLAGDASH_PROJECT_ID")]
+    #[arg(long, env = "FLAGDASH_PROJECT_ID", global = true)]
     project_id: Option<String>,
 
     /// Default environment ID
-    #[arg(long, env = "FLAGDASH_ENVIRONMENT_ID")]
+    #[arg(long, env = "FLAGDASH_ENVIRONMENT_ID", global = true)]
     environment_id: Option<String>,
+
+    /// Where API calls go: the FlagDash server, or a simulated one with demo
+    /// data that lives only for this run
+    #[arg(
+        long,
+        env = "FLAGDASH_BACKEND",
+        value_enum,
+        default_value_t,
+        global = true
+    )]
+    backend: api::backend::Backend,
+
+    /// Append every HTTP request and response to this fixture file, with
+    /// bearer tokens redacted, for replaying in tests
+    #[arg(long, env = "FLAGDASH_RECORD_FIXTURES", hide = true, global = true)]
+    record_fixtures: Option<std::path::PathBuf>,
+
+    /// Output format for headless commands
+    #[arg(long, short = 'o', value_enum, default_value_t, global = true)]
+    output: output::OutputFormat,
+
+    /// Comma-separated columns for table/tsv output (e.g. key,enabled,rollout)
+    #[arg(long, global = true)]
+    columns: Option<String>,
+
+    /// Omit the header row in table/tsv output
+    #[arg(long, global = true)]
+    no_headers: bool,
+
+    /// Run a command without launching the TUI
+    #[command(subcommand)]
+    command: Option<commands::Command>,
 }
 
 #[tokio::main]
@@ -48,13 +94,6 @@ async fn main() -> Result<()> {
     // Parse CLI args
     let cli = Cli::parse();
 
-    // Install panic hook to restore terminal on crash
-    let original_hook = std::panic::take_hook();
-    std::panic::set_hook(Box::new(move |panic_info| {
-        let _ = tui::restore();
-        original_hook(panic_info);
-    }));
-
     // Initialize tracing (logs to file, not stdout)
     let log_dir = dirs::data_dir()
         .unwrap_or_else(|| std::path::PathBuf::from("."))
@@ -69,22 +108,80 @@ async fn main() -> Result<()> {
             .init();
     }
 
+    let output = output::Output::new(cli.output, cli.columns.as_deref(), cli.no_headers);
+
+    // Profile and credential management edit the config file itself, so they
+    // run before a profile is loaded (the one named by --profile may not exist
+    // yet). The mock server doesn't use a profile at all.
+    let command = match cli.command {
+        Some(commands::Command::Profile(cmd)) => {
+            let options = commands::profile::ProfileOptions {
+                profile: cli.profile.as_deref(),
+                base_url: cli.base_url.as_deref(),
+                project_id: cli.project_id.as_deref(),
+                environment_id: cli.environment_id.as_deref(),
+            };
+            return commands::profile::run(cmd, options, output);
+        }
+        Some(commands::Command::Credentials(cmd)) => {
+            return commands::credentials::run(cmd, output);
+        }
+        Some(commands::Command::ServeMock(args)) => {
+            return commands::serve_mock::run(args).await;
+        }
+        command => command,
+    };
+
     // --session-token takes priority, --api-key is a fallback
     let token = cli.session_token.or(cli.api_key);
 
-    // Load config with priority: CLI args > env vars > config file
-    let app_config = config::AppConfig::load(
-        token.as_deref(),
-        cli.base_url.as_deref(),
-        cli.project_id.as_deref(),
-        cli.environment_id.as_deref(),
-    )?;
+    // Load config with priority: CLI args > env vars > config file. The
+    // memory backend doesn't use the config file at all.
+    let mut app_config = match cli.backend {
+        api::backend::Backend::Http => config::AppConfig::load(
+            cli.profile.as_deref(),
+            token.as_deref(),
+            cli.base_url.as_deref(),
+            cli.project_id.as_deref(),
+            cli.environment_id.as_deref(),
+        )?,
+        api::backend::Backend::Memory => {
+            let mut config = config::AppConfig::in_memory(
+                cli.project_id.as_deref(),
+                cli.environment_id.as_deref(),
+            );
+            config.value_format = config::value_format_from_env()?.unwrap_or_default();
+            config
+        }
+    };
+    app_config.record_fixtures = cli.record_fixtures;
+
+    // Headless commands run to completion without touching the terminal
+    if let Some(command) = command {
+        return commands::run(command, app_config, output).await;
+    }
+
+    // Ask for the credentials passphrase now rather than in the middle of the TUI
+    if app_config.backend == api::backend::Backend::Http {
+        config::ConfigFile::load()
+            .unwrap_or_default()
+            .unlock_credentials()?;
+    }
+
+    // Create app before taking over the terminal, so bad connection settings
+    // are reported plainly
+    let mut app = app::App::new(app_config)?;
+
+    // Install panic hook to restore terminal on crash
+    let original_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |panic_info| {
+        let _ = tui::restore();
+        original_hook(panic_info);
+    }));
 
     // Initialize terminal
     let mut terminal = tui::init()?;
 
-    // Create app
-    let mut app = app::App::new(app_config);
     let mut events = event::EventHandler::new(250); // 4 ticks/sec
 
     // Main event loop
@@ -0,0 +1,160 @@
+use super::plan::Operation;
+use crate::api::backend::FlagDashApi;
+use crate::api::types::{Environment, VariationInput};
+use anyhow::{bail, Context, Result};
+
+/// Apply a single-call operation.
+pub async fn apply_operation(
+    api: &dyn FlagDashApi,
+    project_id: &str,
+    environments: &[Environment],
+    op: &Operation,
+) -> Result<()> {
+    match op {
+        Operation::UpdateFlag { key, request } => {
+            api.update_flag(key, project_id, request).await?;
+        }
+        Operation::DeleteFlag { key } => api.delete_flag(key, project_id).await?,
+        Operation::Toggle {
+            key,
+            environment,
+            enabled,
+        } => {
+            let environment_id = environment_id(environments, environment)?;
+            // The toggle endpoint flips the state, so check it first to keep a
+            // stale or repeated plan from disabling what it meant to enable.
+            let flag = api.get_flag(key, project_id).await?;
+            let current = flag
+                .environments
+                .iter()
+                .find(|e| e.environment_id == environment_id)
+                .map(|e| e.enabled)
+                .unwrap_or(false);
+            if current != *enabled {
+                let resp = api.toggle_flag(key, project_id, environment_id).await?;
+                if resp.flag_environment.enabled != *enabled {
+                    bail!(
+                        "toggling '{}' in {} left it {}",
+                        key,
+                        environment,
+                        if resp.flag_environment.enabled {
+                            "enabled"
+                        } else {
+                            "disabled"
+                        }
+                    );
+                }
+            }
+        }
+        Operation::SetRollout {
+            key,
+            environment,
+            percentage,
+        } => {
+            let environment_id = environment_id(environments, environment)?;
+            api.set_rollout(key, project_id, environment_id, *percentage)
+                .await?;
+        }
+        Operation::SetRules {
+            key,
+            environment,
+            rules,
+        } => {
+            let environment_id = environment_id(environments, environment)?;
+            api.update_rules(key, project_id, environment_id, rules.clone())
+                .await?;
+        }
+        Operation::SetVariations {
+            key,
+            environment,
+            variations,
+        } => {
+            let environment_id = environment_id(environments, environment)?;
+            let inputs = variations
+                .iter()
+                .map(|v| VariationInput {
+                    key: v.key.clone(),
+                    name: v.name.clone(),
+                    value: v.value.clone(),
+                    weight: v.weight,
+                })
+                .collect();
+            api.set_variations(key, project_id, environment_id, inputs)
+                .await?;
+        }
+        Operation::DeleteVariations { key, environment } => {
+            let environment_id = environment_id(environments, environment)?;
+            api.delete_variations(key, project_id, environment_id)
+                .await?;
+        }
+        Operation::UpdateConfig { key, request } => {
+            api.update_config(key, project_id, request).await?;
+        }
+        Operation::DeleteConfig { key } => api.delete_config(key, project_id).await?,
+        Operation::SetConfigValue {
+            key,
+            environment,
+            value,
+        } => {
+            let environment_id = environment_id(environments, environment)?;
+            api.set_config_value(key, project_id, environment_id, value.clone())
+                .await?;
+        }
+        Operation::UpdateWebhook { id, request } => {
+            api.update_webhook(id, request).await?;
+        }
+        Operation::DeleteWebhook { id } => api.delete_webhook(id).await?,
+        Operation::CreateFlag { .. }
+        | Operation::CreateConfig { .. }
+        | Operation::CreateWebhook { .. } => {
+            unreachable!("creates are applied by commands::plan::apply_change")
+        }
+    }
+    Ok(())
+}
+
+pub fn environment_id<'a>(environments: &'a [Environment], slug: &str) -> Result<&'a str> {
+    environments
+        .iter()
+        .find(|e| e.slug == slug)
+        .map(|e| e.id.as_str())
+        .with_context(|| format!("unknown environment '{}'", slug))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::memory::MemoryBackend;
+
+    async fn enabled(api: &MemoryBackend, environment_id: &str) -> bool {
+        api.get_flag("new-checkout", "prj_demo")
+            .await
+            .unwrap()
+            .environments
+            .iter()
+            .find(|e| e.environment_id == environment_id)
+            .map(|e| e.enabled)
+            .unwrap_or(false)
+    }
+
+    #[tokio::test]
+    async fn test_toggle_is_idempotent() {
+        let api = MemoryBackend::demo();
+        let environments = api.list_environments("prj_demo").await.unwrap();
+        let env = &environments[0];
+        let desired = !enabled(&api, &env.id).await;
+        let op = Operation::Toggle {
+            key: "new-checkout".into(),
+            environment: env.slug.clone(),
+            enabled: desired,
+        };
+
+        // Applying the same plan twice must not flip the flag back.
+        for _ in 0..2 {
+            apply_operation(&api, "prj_demo", &environments, &op)
+                .await
+                .unwrap();
+            assert_eq!(enabled(&api, &env.id).await, desired);
+        }
+    }
+}
@@ -0,0 +1,442 @@
+pub mod apply;
+pub mod plan;
+pub mod promote;
+
+use crate::api::types::{
+    Environment, FlagEnvironmentData, ManagedConfig, ManagedFlag, Project, Variation,
+    WebhookEndpoint,
+};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// A declarative, versionable snapshot of a project's flags, configs and
+/// webhooks. Everything is keyed by flag/config key and environment slug so
+/// the file stays stable across accounts and diffs cleanly in review.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub version: u32,
+    /// Project slug, informational only.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub project: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub flags: BTreeMap<String, FlagSpec>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub configs: BTreeMap<String, ConfigSpec>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub webhooks: Vec<WebhookSpec>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlagSpec {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    #[serde(rename = "type")]
+    pub flag_type: String,
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub default_value: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub archived: bool,
+    /// Per-environment state, keyed by environment slug.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub environments: BTreeMap<String, FlagEnvironmentSpec>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlagEnvironmentSpec {
+    pub enabled: bool,
+    #[serde(default)]
+    pub rollout: i32,
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub value: serde_json::Value,
+    #[serde(default = "empty_rules", skip_serializing_if = "is_empty_rules")]
+    pub rules: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variations: Vec<VariationSpec>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VariationSpec {
+    pub key: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub value: serde_json::Value,
+    pub weight: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigSpec {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    #[serde(rename = "type")]
+    pub config_type: String,
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub default_value: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub archived: bool,
+    /// Per-environment values, keyed by environment slug.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub environments: BTreeMap<String, ConfigEnvironmentSpec>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigEnvironmentSpec {
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub value: serde_json::Value,
+    #[serde(default = "default_true")]
+    pub active: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookSpec {
+    pub url: String,
+    /// Environment slug.
+    pub environment: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    pub events: Vec<String>,
+    #[serde(default = "default_true")]
+    pub active: bool,
+}
+
+fn is_false(b: &bool) -> bool {
+    !*b
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn empty_rules() -> serde_json::Value {
+    serde_json::Value::Array(vec![])
+}
+
+fn is_empty_rules(rules: &serde_json::Value) -> bool {
+    match rules {
+        serde_json::Value::Null => true,
+        serde_json::Value::Array(a) => a.is_empty(),
+        _ => false,
+    }
+}
+
+/// Live project state needed to build a manifest.
+pub struct ProjectSnapshot {
+    pub project: Option<Project>,
+    pub environments: Vec<Environment>,
+    pub flags: Vec<ManagedFlag>,
+    pub configs: Vec<ManagedConfig>,
+    pub webhooks: Vec<WebhookEndpoint>,
+    /// Variations keyed by (flag key, environment ID).
+    pub variations: HashMap<(String, String), Vec<Variation>>,
+}
+
+impl Manifest {
+    /// Build a manifest from live project state, translating environment IDs to slugs.
+    pub fn from_snapshot(snapshot: &ProjectSnapshot) -> Self {
+        let slug_of = |id: &str| -> String {
+            snapshot
+                .environments
+                .iter()
+                .find(|e| e.id == id)
+                .map(|e| e.slug.clone())
+                .unwrap_or_else(|| id.to_string())
+        };
+
+        let flags = snapshot
+            .flags
+            .iter()
+            .map(|f| {
+                let environments = f
+                    .environments
+                    .iter()
+                    .map(|e| {
+                        let variations = snapshot
+                            .variations
+                            .get(&(f.key.clone(), e.environment_id.clone()))
+                            .map(Vec::as_slice)
+                            .unwrap_or_default();
+                        (
+                            slug_of(&e.environment_id),
+                            FlagEnvironmentSpec::from_live(e, variations),
+                        )
+                    })
+                    .collect();
+                (
+                    f.key.clone(),
+                    FlagSpec {
+                        name: f.name.clone(),
+                        description: f.description.clone(),
+                        flag_type: f.flag_type.clone(),
+                        default_value: f.default_value.clone(),
+                        tags: f.tags.clone(),
+                        archived: f.is_archived,
+                        environments,
+                    },
+                )
+            })
+            .collect();
+
+        let configs = snapshot
+            .configs
+            .iter()
+            .map(|c| {
+                let environments = c
+                    .environments
+                    .iter()
+                    .map(|e| {
+                        (
+                            slug_of(&e.environment_id),
+                            ConfigEnvironmentSpec {
+                                value: e.value.clone(),
+                                active: e.is_active,
+                            },
+                        )
+                    })
+                    .collect();
+                (
+                    c.key.clone(),
+                    ConfigSpec {
+                        name: c.name.clone(),
+                        description: c.description.clone(),
+                        config_type: c.config_type.clone(),
+                        default_value: c.default_value.clone(),
+                        tags: c.tags.clone(),
+                        archived: c.is_archived,
+                        environments,
+                    },
+                )
+            })
+            .collect();
+
+        let mut webhooks: Vec<WebhookSpec> = snapshot
+            .webhooks
+            .iter()
+            .map(|w| WebhookSpec {
+                url: w.url.clone(),
+                environment: slug_of(&w.environment_id),
+                description: w.description.clone(),
+                events: w.event_types.clone(),
+                active: w.is_active,
+            })
+            .collect();
+        webhooks.sort_by(|a, b| (&a.environment, &a.url).cmp(&(&b.environment, &b.url)));
+
+        Self {
+            version: MANIFEST_VERSION,
+            project: snapshot
+                .project
+                .as_ref()
+                .map(|p| p.slug.clone())
+                .unwrap_or_default(),
+            flags,
+            configs,
+            webhooks,
+        }
+    }
+
+    pub fn to_string(&self, format: ManifestFormat) -> Result<String> {
+        match format {
+            ManifestFormat::Toml => toml::to_string_pretty(self).context(
+                "serializing manifest as TOML (TOML cannot represent null; try --format yaml)",
+            ),
+            ManifestFormat::Yaml => {
+                serde_yaml::to_string(self).context("serializing manifest as YAML")
+            }
+        }
+    }
+
+    pub fn parse(content: &str, format: ManifestFormat) -> Result<Self> {
+        let manifest: Manifest = match format {
+            ManifestFormat::Toml => toml::from_str(content).context("parsing TOML manifest")?,
+            ManifestFormat::Yaml => {
+                serde_yaml::from_str(content).context("parsing YAML manifest")?
+            }
+        };
+        if manifest.version != MANIFEST_VERSION {
+            bail!(
+                "unsupported manifest version {} (expected {})",
+                manifest.version,
+                MANIFEST_VERSION
+            );
+        }
+        Ok(manifest)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content =
+            std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        Self::parse(&content, ManifestFormat::from_path(path))
+            .with_context(|| format!("loading manifest {}", path.display()))
+    }
+}
+
+impl FlagEnvironmentSpec {
+    pub fn from_live(data: &FlagEnvironmentData, variations: &[Variation]) -> Self {
+        Self {
+            enabled: data.enabled,
+            rollout: data.rollout_percentage,
+            value: data.value.clone(),
+            rules: if data.rules.is_null() {
+                empty_rules()
+            } else {
+                data.rules.clone()
+            },
+            variations: variations.iter().map(VariationSpec::from).collect(),
+        }
+    }
+}
+
+impl From<&Variation> for VariationSpec {
+    fn from(v: &Variation) -> Self {
+        Self {
+            key: v.key.clone(),
+            name: v.name.clone(),
+            value: v.value.clone(),
+            weight: v.weight,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum ManifestFormat {
+    Toml,
+    Yaml,
+}
+
+impl ManifestFormat {
+    /// Pick a format from a file extension, defaulting to TOML.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("yaml") | Some("yml") => ManifestFormat::Yaml,
+            _ => ManifestFormat::Toml,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::types::ConfigEnvironmentValue;
+    use serde_json::json;
+
+    pub(super) fn snapshot() -> ProjectSnapshot {
+        let ts = "2026-01-01T00:00:00Z".parse().unwrap();
+        let env = |id: &str, slug: &str| Environment {
+            id: id.into(),
+            name: slug.to_uppercase(),
+            slug: slug.into(),
+            is_default: slug == "development",
+            created_at: ts,
+        };
+        let mut variations = HashMap::new();
+        variations.insert(
+            ("checkout".to_string(), "env_2".to_string()),
+            vec![Variation {
+                id: "var_1".into(),
+                key: "a".into(),
+                name: "A".into(),
+                value: json!("blue"),
+                weight: 100,
+            }],
+        );
+        ProjectSnapshot {
+            project: None,
+            environments: vec![env("env_1", "development"), env("env_2", "production")],
+            flags: vec![ManagedFlag {
+                id: "flg_1".into(),
+                key: "checkout".into(),
+                name: "Checkout".into(),
+                description: String::new(),
+                flag_type: "boolean".into(),
+                default_value: json!(false),
+                tags: vec![],
+                is_archived: false,
+                created_at: ts,
+                updated_at: ts,
+                environments: vec![
+                    FlagEnvironmentData {
+                        id: "fe_1".into(),
+                        environment_id: "env_1".into(),
+                        enabled: true,
+                        value: serde_json::Value::Null,
+                        rules: json!([]),
+                        rollout_percentage: 100,
+                    },
+                    FlagEnvironmentData {
+                        id: "fe_2".into(),
+                        environment_id: "env_2".into(),
+                        enabled: false,
+                        value: json!(true),
+                        rules: json!([{"attribute": "country", "operator": "eq", "value": "NZ"}]),
+                        rollout_percentage: 25,
+                    },
+                ],
+            }],
+            configs: vec![ManagedConfig {
+                id: "cfg_1".into(),
+                key: "theme".into(),
+                name: "Theme".into(),
+                description: "UI theme".into(),
+                config_type: "json".into(),
+                default_value: json!({"color": "blue"}),
+                tags: vec!["ui".into()],
+                is_archived: false,
+                created_at: ts,
+                updated_at: ts,
+                environments: vec![ConfigEnvironmentValue {
+                    id: "ce_1".into(),
+                    environment_id: "env_2".into(),
+                    value: json!({"color": "red"}),
+                    is_active: true,
+                }],
+                metadata: serde_json::Value::Null,
+            }],
+            webhooks: vec![],
+            variations,
+        }
+    }
+
+    #[test]
+    fn test_manifest_keyed_by_slug() {
+        let manifest = Manifest::from_snapshot(&snapshot());
+        let flag = &manifest.flags["checkout"];
+        assert!(flag.environments["development"].enabled);
+        assert_eq!(flag.environments["production"].rollout, 25);
+        assert_eq!(flag.environments["production"].variations[0].key, "a");
+        assert_eq!(
+            manifest.configs["theme"].environments["production"].value,
+            json!({"color": "red"})
+        );
+    }
+
+    #[test]
+    fn test_manifest_round_trip() {
+        let manifest = Manifest::from_snapshot(&snapshot());
+        for format in [ManifestFormat::Toml, ManifestFormat::Yaml] {
+            let text = manifest.to_string(format).unwrap();
+            assert_eq!(Manifest::parse(&text, format).unwrap(), manifest);
+        }
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(
+            ManifestFormat::from_path(Path::new("flags.yml")),
+            ManifestFormat::Yaml
+        );
+        assert_eq!(
+            ManifestFormat::from_path(Path::new("flags.toml")),
+            ManifestFormat::Toml
+        );
+    }
+}
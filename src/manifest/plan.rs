@@ -0,0 +1,852 @@
+use super::{
+    ConfigSpec, FlagEnvironmentSpec, FlagSpec, Manifest, ProjectSnapshot, VariationSpec,
+    WebhookSpec,
+};
+use crate::api::types::{UpdateConfigRequest, UpdateFlagRequest, UpdateWebhookRequest};
+use anyhow::{bail, Result};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Create,
+    Update,
+    Delete,
+}
+
+impl ChangeKind {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            ChangeKind::Create => "+",
+            ChangeKind::Update => "~",
+            ChangeKind::Delete => "-",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: Value,
+    pub after: Value,
+}
+
+/// A single resource change in a plan, together with the API operation that applies it.
+#[derive(Debug, Clone, Serialize)]
+pub struct Change {
+    pub kind: ChangeKind,
+    /// Resource type: flag, toggle, rollout, rules, variations, config, config_value or webhook.
+    pub resource: &'static str,
+    /// Human-readable address, e.g. `checkout[production]`.
+    pub address: String,
+    pub fields: Vec<FieldChange>,
+    #[serde(skip)]
+    pub operation: Operation,
+}
+
+/// The API call behind a [`Change`]. Environments are referenced by slug.
+#[derive(Debug, Clone)]
+pub enum Operation {
+    CreateFlag {
+        key: String,
+        spec: FlagSpec,
+    },
+    UpdateFlag {
+        key: String,
+        request: UpdateFlagRequest,
+    },
+    DeleteFlag {
+        key: String,
+    },
+    /// Toggle a flag so it ends up `enabled`. Applying is a no-op when the
+    /// live state already matches.
+    Toggle {
+        key: String,
+        environment: String,
+        enabled: bool,
+    },
+    SetRollout {
+        key: String,
+        environment: String,
+        percentage: i32,
+    },
+    SetRules {
+        key: String,
+        environment: String,
+        rules: Value,
+    },
+    SetVariations {
+        key: String,
+        environment: String,
+        variations: Vec<VariationSpec>,
+    },
+    DeleteVariations {
+        key: String,
+        environment: String,
+    },
+    CreateConfig {
+        key: String,
+        spec: ConfigSpec,
+    },
+    UpdateConfig {
+        key: String,
+        request: UpdateConfigRequest,
+    },
+    DeleteConfig {
+        key: String,
+    },
+    SetConfigValue {
+        key: String,
+        environment: String,
+        value: Value,
+    },
+    CreateWebhook {
+        spec: WebhookSpec,
+    },
+    UpdateWebhook {
+        id: String,
+        request: UpdateWebhookRequest,
+    },
+    DeleteWebhook {
+        id: String,
+    },
+}
+
+/// The set of changes needed to make the live project match a manifest.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Plan {
+    pub changes: Vec<Change>,
+    /// Live resources missing from the manifest that are left alone because
+    /// pruning was not requested.
+    pub unmanaged: Vec<String>,
+    /// Config values that are set in the project but null in the manifest.
+    /// The API can't clear a value, so these are kept.
+    pub kept_values: Vec<String>,
+}
+
+impl Plan {
+    /// Diff `desired` against the live project. With `prune`, flags, configs and
+    /// webhooks that are not in the manifest are deleted.
+    pub fn compute(desired: &Manifest, live: &ProjectSnapshot, prune: bool) -> Result<Self> {
+        let current = Manifest::from_snapshot(live);
+        validate_environments(desired, live)?;
+
+        let mut plan = Plan::default();
+
+        for (key, spec) in &desired.flags {
+            match current.flags.get(key) {
+                None => plan.changes.push(create_flag(key, spec)),
+                Some(existing) => plan.diff_flag(key, spec, existing)?,
+            }
+        }
+        for (key, spec) in &desired.configs {
+            check_config_active(key, spec, current.configs.get(key))?;
+            match current.configs.get(key) {
+                None => plan.changes.push(create_config(key, spec)),
+                Some(existing) => plan.diff_config(key, spec, existing)?,
+            }
+        }
+        plan.diff_webhooks(desired, live, prune)?;
+
+        for key in current.flags.keys() {
+            if !desired.flags.contains_key(key) {
+                plan.remove(
+                    prune,
+                    "flag",
+                    key.clone(),
+                    Operation::DeleteFlag { key: key.clone() },
+                );
+            }
+        }
+        for key in current.configs.keys() {
+            if !desired.configs.contains_key(key) {
+                plan.remove(
+                    prune,
+                    "config",
+                    key.clone(),
+                    Operation::DeleteConfig { key: key.clone() },
+                );
+            }
+        }
+        Ok(plan)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn count(&self, kind: ChangeKind) -> usize {
+        self.changes.iter().filter(|c| c.kind == kind).count()
+    }
+
+    /// One-line totals, e.g. `2 to create, 1 to update, 0 to delete`.
+    pub fn summary(&self) -> String {
+        format!(
+            "{} to create, {} to update, {} to delete",
+            self.count(ChangeKind::Create),
+            self.count(ChangeKind::Update),
+            self.count(ChangeKind::Delete)
+        )
+    }
+
+    /// Render the plan as Terraform-style text.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for change in &self.changes {
+            out.push_str(&format!(
+                "  {} {} {}\n",
+                change.kind.symbol(),
+                change.resource,
+                change.address
+            ));
+            for field in &change.fields {
+                let line = match change.kind {
+                    ChangeKind::Create => format!("{}: {}", field.field, field.after),
+                    _ => format!("{}: {} -> {}", field.field, field.before, field.after),
+                };
+                out.push_str(&format!("      {}\n", line));
+            }
+        }
+        if !self.changes.is_empty() {
+            out.push('\n');
+        }
+        if !self.unmanaged.is_empty() {
+            out.push_str(&format!(
+                "{} resource(s) exist in the project but not in the manifest (pass --prune to delete): {}\n\n",
+                self.unmanaged.len(),
+                self.unmanaged.join(", ")
+            ));
+        }
+        if !self.kept_values.is_empty() {
+            out.push_str(&format!(
+                "{} config value(s) are null in the manifest but set in the project and are kept, since values can't be cleared: {}\n\n",
+                self.kept_values.len(),
+                self.kept_values.join(", ")
+            ));
+        }
+        if self.changes.is_empty() {
+            out.push_str("No changes. The project matches the manifest.");
+        } else {
+            out.push_str(&format!("Plan: {}.", self.summary()));
+        }
+        out
+    }
+
+    fn remove(&mut self, prune: bool, resource: &'static str, address: String, op: Operation) {
+        if prune {
+            self.changes.push(Change {
+                kind: ChangeKind::Delete,
+                resource,
+                address,
+                fields: vec![],
+                operation: op,
+            });
+        } else {
+            self.unmanaged.push(format!("{} {}", resource, address));
+        }
+    }
+
+    fn diff_flag(&mut self, key: &str, desired: &FlagSpec, current: &FlagSpec) -> Result<()> {
+        if desired.flag_type != current.flag_type {
+            bail!(
+                "flag '{}' cannot change type ({} -> {}); delete and recreate it instead",
+                key,
+                current.flag_type,
+                desired.flag_type
+            );
+        }
+        let mut fields = Vec::new();
+        let mut request = UpdateFlagRequest {
+            name: None,
+            description: None,
+            tags: None,
+            default_value: None,
+            is_archived: None,
+        };
+        if desired.name != current.name {
+            fields.push(field("name", &current.name, &desired.name));
+            request.name = Some(desired.name.clone());
+        }
+        if desired.description != current.description {
+            fields.push(field(
+                "description",
+                &current.description,
+                &desired.description,
+            ));
+            request.description = Some(desired.description.clone());
+        }
+        if desired.tags != current.tags {
+            fields.push(field("tags", &current.tags, &desired.tags));
+            request.tags = Some(desired.tags.clone());
+        }
+        if desired.default_value != current.default_value {
+            fields.push(field(
+                "default_value",
+                &current.default_value,
+                &desired.default_value,
+            ));
+            request.default_value = Some(desired.default_value.clone());
+        }
+        if desired.archived != current.archived {
+            fields.push(field("archived", &current.archived, &desired.archived));
+            request.is_archived = Some(desired.archived);
+        }
+        if !fields.is_empty() {
+            self.changes.push(Change {
+                kind: ChangeKind::Update,
+                resource: "flag",
+                address: key.to_string(),
+                fields,
+                operation: Operation::UpdateFlag {
+                    key: key.to_string(),
+                    request,
+                },
+            });
+        }
+        for (env, spec) in &desired.environments {
+            self.changes.extend(flag_environment_changes(
+                key,
+                env,
+                spec,
+                current.environments.get(env),
+            ));
+        }
+        Ok(())
+    }
+
+    fn diff_config(&mut self, key: &str, desired: &ConfigSpec, current: &ConfigSpec) -> Result<()> {
+        if desired.config_type != current.config_type {
+            bail!(
+                "config '{}' cannot change type ({} -> {}); delete and recreate it instead",
+                key,
+                current.config_type,
+                desired.config_type
+            );
+        }
+        let mut fields = Vec::new();
+        let mut request = UpdateConfigRequest {
+            name: None,
+            description: None,
+            tags: None,
+            default_value: None,
+            is_archived: None,
+        };
+        if desired.name != current.name {
+            fields.push(field("name", &current.name, &desired.name));
+            request.name = Some(desired.name.clone());
+        }
+        if desired.description != current.description {
+            fields.push(field(
+                "description",
+                &current.description,
+                &desired.description,
+            ));
+            request.description = Some(desired.description.clone());
+        }
+        if desired.tags != current.tags {
+            fields.push(field("tags", &current.tags, &desired.tags));
+            request.tags = Some(desired.tags.clone());
+        }
+        if desired.default_value != current.default_value {
+            fields.push(field(
+                "default_value",
+                &current.default_value,
+                &desired.default_value,
+            ));
+            request.default_value = Some(desired.default_value.clone());
+        }
+        if desired.archived != current.archived {
+            fields.push(field("archived", &current.archived, &desired.archived));
+            request.is_archived = Some(desired.archived);
+        }
+        if !fields.is_empty() {
+            self.changes.push(Change {
+                kind: ChangeKind::Update,
+                resource: "config",
+                address: key.to_string(),
+                fields,
+                operation: Operation::UpdateConfig {
+                    key: key.to_string(),
+                    request,
+                },
+            });
+        }
+        for (env, spec) in &desired.environments {
+            let before = current
+                .environments
+                .get(env)
+                .map(|e| e.value.clone())
+                .unwrap_or(Value::Null);
+            if spec.value.is_null() {
+                if !before.is_null() {
+                    self.kept_values.push(format!("{}[{}]", key, env));
+                }
+                continue;
+            }
+            if spec.value == before {
+                continue;
+            }
+            self.changes.push(Change {
+                kind: if before.is_null() {
+                    ChangeKind::Create
+                } else {
+                    ChangeKind::Update
+                },
+                resource: "config_value",
+                address: format!("{}[{}]", key, env),
+                fields: vec![field("value", &before, &spec.value)],
+                operation: Operation::SetConfigValue {
+                    key: key.to_string(),
+                    environment: env.clone(),
+                    value: spec.value.clone(),
+                },
+            });
+        }
+        Ok(())
+    }
+
+    fn diff_webhooks(
+        &mut self,
+        desired: &Manifest,
+        live: &ProjectSnapshot,
+        prune: bool,
+    ) -> Result<()> {
+        // Webhooks have no key; they are identified by environment and URL.
+        let mut current: HashMap<(String, String), (&str, WebhookSpec)> = HashMap::new();
+        let slugs: HashMap<&str, &str> = live
+            .environments
+            .iter()
+            .map(|e| (e.id.as_str(), e.slug.as_str()))
+            .collect();
+        for w in &live.webhooks {
+            let env = slugs
+                .get(w.environment_id.as_str())
+                .copied()
+                .unwrap_or(w.environment_id.as_str());
+            let spec = WebhookSpec {
+                url: w.url.clone(),
+                environment: env.to_string(),
+                description: w.description.clone(),
+                events: w.event_types.clone(),
+                active: w.is_active,
+            };
+            current.insert((env.to_string(), w.url.clone()), (w.id.as_str(), spec));
+        }
+
+        let mut seen = BTreeMap::new();
+        for spec in &desired.webhooks {
+            let id = (spec.environment.clone(), spec.url.clone());
+            if seen.insert(id.clone(), ()).is_some() {
+                bail!(
+                    "webhook {} is listed twice for environment '{}'",
+                    spec.url,
+                    spec.environment
+                );
+            }
+            let address = format!("{}[{}]", spec.url, spec.environment);
+            match current.get(&id) {
+                None => {
+                    let mut fields = vec![field("events", &Value::Null, &spec.events)];
+                    if !spec.description.is_empty() {
+                        fields.insert(0, field("description", &Value::Null, &spec.description));
+                    }
+                    if !spec.active {
+                        fields.push(field("active", &Value::Null, &false));
+                    }
+                    self.changes.push(Change {
+                        kind: ChangeKind::Create,
+                        resource: "webhook",
+                        address,
+                        fields,
+                        operation: Operation::CreateWebhook { spec: spec.clone() },
+                    });
+                }
+                Some((webhook_id, existing)) => {
+                    let mut fields = Vec::new();
+                    let mut request = UpdateWebhookRequest {
+                        url: None,
+                        description: None,
+                        event_types: None,
+                        is_active: None,
+                    };
+                    if spec.description != existing.description {
+                        fields.push(field(
+                            "description",
+                            &existing.description,
+                            &spec.description,
+                        ));
+                        request.description = Some(spec.description.clone());
+                    }
+                    if spec.events != existing.events {
+                        fields.push(field("events", &existing.events, &spec.events));
+                        request.event_types = Some(spec.events.clone());
+                    }
+                    if spec.active != existing.active {
+                        fields.push(field("active", &existing.active, &spec.active));
+                        request.is_active = Some(spec.active);
+                    }
+                    if !fields.is_empty() {
+                        self.changes.push(Change {
+                            kind: ChangeKind::Update,
+                            resource: "webhook",
+                            address,
+                            fields,
+                            operation: Operation::UpdateWebhook {
+                                id: webhook_id.to_string(),
+                                request,
+                            },
+                        });
+                    }
+                }
+            }
+        }
+
+        let mut stale: Vec<_> = current
+            .into_iter()
+            .filter(|(id, _)| !seen.contains_key(id))
+            .collect();
+        stale.sort_by(|a, b| a.0.cmp(&b.0));
+        for ((env, url), (webhook_id, _)) in stale {
+            self.remove(
+                prune,
+                "webhook",
+                format!("{}[{}]", url, env),
+                Operation::DeleteWebhook {
+                    id: webhook_id.to_string(),
+                },
+            );
+        }
+        Ok(())
+    }
+}
+
+/// A config value's `active` state can't be set through the API, so a
+/// manifest that asks for a different one fails the plan instead of being
+/// silently ignored. Values the project doesn't have yet start active.
+fn check_config_active(
+    key: &str,
+    desired: &ConfigSpec,
+    current: Option<&ConfigSpec>,
+) -> Result<()> {
+    for (env, spec) in &desired.environments {
+        let active = current
+            .and_then(|c| c.environments.get(env))
+            .map(|e| e.active)
+            .unwrap_or(true);
+        if spec.active != active {
+            bail!(
+                "config '{}' in {}: active is {} in the project but {} in the manifest, and apply can't change it; update the manifest or change it in FlagDash",
+                key,
+                env,
+                active,
+                spec.active
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Changes needed to bring one environment of a flag in line with `desired`.
+/// `current` is `None` when the flag has no state in that environment yet.
+pub fn flag_environment_changes(
+    key: &str,
+    environment: &str,
+    desired: &FlagEnvironmentSpec,
+    current: Option<&FlagEnvironmentSpec>,
+) -> Vec<Change> {
+    let address = format!("{}[{}]", key, environment);
+    let mut changes = Vec::new();
+
+    let enabled = current.map(|c| c.enabled).unwrap_or(false);
+    if desired.enabled != enabled {
+        changes.push(Change {
+            kind: ChangeKind::Update,
+            resource: "toggle",
+            address: address.clone(),
+            fields: vec![field("enabled", &enabled, &desired.enabled)],
+            operation: Operation::Toggle {
+                key: key.to_string(),
+                environment: environment.to_string(),
+                enabled: desired.enabled,
+            },
+        });
+    }
+
+    if current.map(|c| c.rollout) != Some(desired.rollout) {
+        let before = current
+            .map(|c| Value::from(c.rollout))
+            .unwrap_or(Value::Null);
+        changes.push(Change {
+            kind: ChangeKind::Update,
+            resource: "rollout",
+            address: address.clone(),
+            fields: vec![field("rollout", &before, &desired.rollout)],
+            operation: Operation::SetRollout {
+                key: key.to_string(),
+                environment: environment.to_string(),
+                percentage: desired.rollout,
+            },
+        });
+    }
+
+    let empty = Value::Array(vec![]);
+    let rules = current.map(|c| &c.rules).unwrap_or(&empty);
+    if &desired.rules != rules {
+        changes.push(Change {
+            kind: set_kind(is_empty(rules), is_empty(&desired.rules)),
+            resource: "rules",
+            address: address.clone(),
+            fields: vec![field("rules", rules, &desired.rules)],
+            operation: Operation::SetRules {
+                key: key.to_string(),
+                environment: environment.to_string(),
+                rules: desired.rules.clone(),
+            },
+        });
+    }
+
+    let variations = current.map(|c| c.variations.as_slice()).unwrap_or(&[]);
+    if desired.variations != variations {
+        let operation = if desired.variations.is_empty() {
+            Operation::DeleteVariations {
+                key: key.to_string(),
+                environment: environment.to_string(),
+            }
+        } else {
+            Operation::SetVariations {
+                key: key.to_string(),
+                environment: environment.to_string(),
+                variations: desired.variations.clone(),
+            }
+        };
+        changes.push(Change {
+            kind: set_kind(variations.is_empty(), desired.variations.is_empty()),
+            resource: "variations",
+            address,
+            fields: vec![field("variations", &variations, &desired.variations)],
+            operation,
+        });
+    }
+
+    changes
+}
+
+fn create_flag(key: &str, spec: &FlagSpec) -> Change {
+    let mut fields = vec![
+        field("name", &Value::Null, &spec.name),
+        field("type", &Value::Null, &spec.flag_type),
+    ];
+    if !spec.default_value.is_null() {
+        fields.push(field("default_value", &Value::Null, &spec.default_value));
+    }
+    if !spec.tags.is_empty() {
+        fields.push(field("tags", &Value::Null, &spec.tags));
+    }
+    for (env, e) in &spec.environments {
+        fields.push(field(&format!("{}.enabled", env), &Value::Null, &e.enabled));
+        fields.push(field(&format!("{}.rollout", env), &Value::Null, &e.rollout));
+        if !is_empty(&e.rules) {
+            fields.push(field(&format!("{}.rules", env), &Value::Null, &e.rules));
+        }
+        if !e.variations.is_empty() {
+            fields.push(field(
+                &format!("{}.variations", env),
+                &Value::Null,
+                &e.variations,
+            ));
+        }
+    }
+    Change {
+        kind: ChangeKind::Create,
+        resource: "flag",
+        address: key.to_string(),
+        fields,
+        operation: Operation::CreateFlag {
+            key: key.to_string(),
+            spec: spec.clone(),
+        },
+    }
+}
+
+fn create_config(key: &str, spec: &ConfigSpec) -> Change {
+    let mut fields = vec![
+        field("name", &Value::Null, &spec.name),
+        field("type", &Value::Null, &spec.config_type),
+    ];
+    if !spec.default_value.is_null() {
+        fields.push(field("default_value", &Value::Null, &spec.default_value));
+    }
+    if !spec.tags.is_empty() {
+        fields.push(field("tags", &Value::Null, &spec.tags));
+    }
+    for (env, e) in &spec.environments {
+        if !e.value.is_null() {
+            fields.push(field(&format!("{}.value", env), &Value::Null, &e.value));
+        }
+    }
+    Change {
+        kind: ChangeKind::Create,
+        resource: "config",
+        address: key.to_string(),
+        fields,
+        operation: Operation::CreateConfig {
+            key: key.to_string(),
+            spec: spec.clone(),
+        },
+    }
+}
+
+/// Every environment slug in the manifest must exist in the live project.
+fn validate_environments(desired: &Manifest, live: &ProjectSnapshot) -> Result<()> {
+    let known: Vec<&str> = live.environments.iter().map(|e| e.slug.as_str()).collect();
+    let check = |env: &str, what: String| -> Result<()> {
+        if !known.contains(&env) {
+            bail!(
+                "unknown environment '{}' in {} (available: {})",
+                env,
+                what,
+                known.join(", ")
+            );
+        }
+        Ok(())
+    };
+    for (key, flag) in &desired.flags {
+        for env in flag.environments.keys() {
+            check(env, format!("flag '{}'", key))?;
+        }
+    }
+    for (key, config) in &desired.configs {
+        for env in config.environments.keys() {
+            check(env, format!("config '{}'", key))?;
+        }
+    }
+    for webhook in &desired.webhooks {
+        check(&webhook.environment, format!("webhook {}", webhook.url))?;
+    }
+    Ok(())
+}
+
+/// Kind of a change to a collection (rule set, variation set) that may go from or to empty.
+fn set_kind(was_empty: bool, now_empty: bool) -> ChangeKind {
+    match (was_empty, now_empty) {
+        (true, _) => ChangeKind::Create,
+        (false, true) => ChangeKind::Delete,
+        (false, false) => ChangeKind::Update,
+    }
+}
+
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Array(a) => a.is_empty(),
+        _ => false,
+    }
+}
+
+fn field<B: Serialize + ?Sized, A: Serialize + ?Sized>(
+    name: &str,
+    before: &B,
+    after: &A,
+) -> FieldChange {
+    FieldChange {
+        field: name.to_string(),
+        before: serde_json::to_value(before).unwrap_or(Value::Null),
+        after: serde_json::to_value(after).unwrap_or(Value::Null),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::tests::snapshot;
+    use serde_json::json;
+
+    #[test]
+    fn test_plan_no_changes() {
+        let live = snapshot();
+        let manifest = Manifest::from_snapshot(&live);
+        let plan = Plan::compute(&manifest, &live, true).unwrap();
+        assert!(plan.is_empty());
+        assert!(plan.unmanaged.is_empty());
+    }
+
+    #[test]
+    fn test_plan_environment_changes() {
+        let live = snapshot();
+        let mut manifest = Manifest::from_snapshot(&live);
+        let flag = manifest.flags.get_mut("checkout").unwrap();
+        let prod = flag.environments.get_mut("production").unwrap();
+        prod.enabled = true;
+        prod.rollout = 50;
+        prod.rules = json!([]);
+        prod.variations.clear();
+        flag.name = "Checkout v2".into();
+
+        let plan = Plan::compute(&manifest, &live, false).unwrap();
+        let summary: Vec<(ChangeKind, &str)> =
+            plan.changes.iter().map(|c| (c.kind, c.resource)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (ChangeKind::Update, "flag"),
+                (ChangeKind::Update, "toggle"),
+                (ChangeKind::Update, "rollout"),
+                (ChangeKind::Delete, "rules"),
+                (ChangeKind::Delete, "variations"),
+            ]
+        );
+        assert!(matches!(
+            plan.changes[4].operation,
+            Operation::DeleteVariations { .. }
+        ));
+    }
+
+    #[test]
+    fn test_plan_prune() {
+        let live = snapshot();
+        let mut manifest = Manifest::from_snapshot(&live);
+        manifest.configs.clear();
+
+        let plan = Plan::compute(&manifest, &live, false).unwrap();
+        assert!(plan.is_empty());
+        assert_eq!(plan.unmanaged, vec!["config theme"]);
+
+        let plan = Plan::compute(&manifest, &live, true).unwrap();
+        assert_eq!(plan.count(ChangeKind::Delete), 1);
+        assert!(plan.unmanaged.is_empty());
+    }
+
+    #[test]
+    fn test_plan_config_value_limits() {
+        let live = snapshot();
+        let mut manifest = Manifest::from_snapshot(&live);
+        let theme = manifest.configs.get_mut("theme").unwrap();
+        let prod = theme.environments.get_mut("production").unwrap();
+        prod.value = Value::Null;
+
+        let plan = Plan::compute(&manifest, &live, false).unwrap();
+        assert!(plan.is_empty());
+        assert_eq!(plan.kept_values, vec!["theme[production]"]);
+        assert!(plan
+            .render()
+            .contains("can't be cleared: theme[production]"));
+
+        let theme = manifest.configs.get_mut("theme").unwrap();
+        theme.environments.get_mut("production").unwrap().active = false;
+        let err = Plan::compute(&manifest, &live, false).unwrap_err();
+        assert!(err.to_string().contains("apply can't change it"), "{}", err);
+    }
+
+    #[test]
+    fn test_plan_rejects_unknown_environment() {
+        let live = snapshot();
+        let mut manifest = Manifest::from_snapshot(&live);
+        let flag = manifest.flags.get_mut("checkout").unwrap();
+        let prod = flag.environments["production"].clone();
+        flag.environments.insert("qa".into(), prod);
+        let err = Plan::compute(&manifest, &live, false).unwrap_err();
+        assert!(err.to_string().contains("unknown environment 'qa'"));
+    }
+}
@@ -0,0 +1,339 @@
+use super::plan::{flag_environment_changes, Change, ChangeKind, FieldChange, Operation};
+use super::FlagEnvironmentSpec;
+use crate::api::types::{Environment, ManagedConfig, ManagedFlag};
+use anyhow::{bail, Result};
+use serde::Serialize;
+use serde_json::Value;
+
+/// The per-environment fields a promotion can copy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum PromoteField {
+    Enabled,
+    Rollout,
+    Rules,
+    /// Remote config values.
+    Values,
+}
+
+impl PromoteField {
+    pub const ALL: [PromoteField; 4] = [
+        PromoteField::Enabled,
+        PromoteField::Rollout,
+        PromoteField::Rules,
+        PromoteField::Values,
+    ];
+
+    /// The field a change belongs to, by its resource type.
+    pub fn of(change: &Change) -> Option<Self> {
+        match change.resource {
+            "toggle" => Some(PromoteField::Enabled),
+            "rollout" => Some(PromoteField::Rollout),
+            "rules" => Some(PromoteField::Rules),
+            "config_value" => Some(PromoteField::Values),
+            _ => None,
+        }
+    }
+}
+
+/// What to promote. Naming flags or configs limits the promotion to exactly
+/// those resources; naming neither promotes everything.
+#[derive(Debug, Clone)]
+pub struct PromoteFilter {
+    pub flags: Option<Vec<String>>,
+    pub configs: Option<Vec<String>>,
+    pub fields: Vec<PromoteField>,
+}
+
+impl Default for PromoteFilter {
+    fn default() -> Self {
+        Self {
+            flags: None,
+            configs: None,
+            fields: PromoteField::ALL.to_vec(),
+        }
+    }
+}
+
+impl PromoteFilter {
+    fn includes_flag(&self, key: &str) -> bool {
+        match (&self.flags, &self.configs) {
+            (Some(keys), _) => keys.iter().any(|k| k == key),
+            (None, Some(_)) => false,
+            (None, None) => true,
+        }
+    }
+
+    fn includes_config(&self, key: &str) -> bool {
+        match (&self.configs, &self.flags) {
+            (Some(keys), _) => keys.iter().any(|k| k == key),
+            (None, Some(_)) => false,
+            (None, None) => true,
+        }
+    }
+}
+
+/// The changes that make one environment's flag and config state match another's.
+#[derive(Debug, Clone, Serialize)]
+pub struct Promotion {
+    /// Source environment slug.
+    pub from: String,
+    /// Target environment slug.
+    pub to: String,
+    pub changes: Vec<Change>,
+    /// Differences that are reported but can't be promoted.
+    pub notes: Vec<String>,
+}
+
+impl Promotion {
+    /// Diff `from` against `to` for every flag and config the filter selects.
+    pub fn compute(
+        flags: &[ManagedFlag],
+        configs: &[ManagedConfig],
+        from: &Environment,
+        to: &Environment,
+        filter: &PromoteFilter,
+    ) -> Result<Self> {
+        if from.id == to.id {
+            bail!("cannot promote '{}' to itself", from.slug);
+        }
+        for key in filter.flags.iter().flatten() {
+            if !flags.iter().any(|f| &f.key == key) {
+                bail!("unknown flag '{}'", key);
+            }
+        }
+        for key in filter.configs.iter().flatten() {
+            if !configs.iter().any(|c| &c.key == key) {
+                bail!("unknown config '{}'", key);
+            }
+        }
+
+        let mut promotion = Promotion {
+            from: from.slug.clone(),
+            to: to.slug.clone(),
+            changes: Vec::new(),
+            notes: Vec::new(),
+        };
+
+        let mut flags: Vec<&ManagedFlag> = flags
+            .iter()
+            .filter(|f| filter.includes_flag(&f.key))
+            .collect();
+        flags.sort_by(|a, b| a.key.cmp(&b.key));
+        for flag in flags {
+            let state = |env: &Environment| {
+                flag.environments
+                    .iter()
+                    .find(|e| e.environment_id == env.id)
+                    .map(|e| FlagEnvironmentSpec::from_live(e, &[]))
+            };
+            let Some(source) = state(from) else {
+                promotion.notes.push(format!(
+                    "flag '{}' has no state in {}; skipped",
+                    flag.key, from.slug
+                ));
+                continue;
+            };
+            let target = state(to);
+            promotion.changes.extend(
+                flag_environment_changes(&flag.key, &to.slug, &source, target.as_ref())
+                    .into_iter()
+                    .filter(|c| PromoteField::of(c).is_some_and(|f| filter.fields.contains(&f))),
+            );
+            let before = target.map(|t| t.value).unwrap_or(Value::Null);
+            if !source.value.is_null() && source.value != before {
+                promotion.notes.push(format!(
+                    "flag '{}' value differs ({} -> {}); per-environment flag values can't be promoted",
+                    flag.key, before, source.value
+                ));
+            }
+        }
+
+        if filter.fields.contains(&PromoteField::Values) {
+            let mut configs: Vec<&ManagedConfig> = configs
+                .iter()
+                .filter(|c| filter.includes_config(&c.key))
+                .collect();
+            configs.sort_by(|a, b| a.key.cmp(&b.key));
+            for config in configs {
+                let value = |env: &Environment| {
+                    config
+                        .environments
+                        .iter()
+                        .find(|e| e.environment_id == env.id)
+                        .map(|e| e.value.clone())
+                        .unwrap_or(Value::Null)
+                };
+                let (after, before) = (value(from), value(to));
+                if after.is_null() || after == before {
+                    continue;
+                }
+                promotion.changes.push(Change {
+                    kind: if before.is_null() {
+                        ChangeKind::Create
+                    } else {
+                        ChangeKind::Update
+                    },
+                    resource: "config_value",
+                    address: format!("{}[{}]", config.key, to.slug),
+                    fields: vec![FieldChange {
+                        field: "value".to_string(),
+                        before,
+                        after: after.clone(),
+                    }],
+                    operation: Operation::SetConfigValue {
+                        key: config.key.clone(),
+                        environment: to.slug.clone(),
+                        value: after,
+                    },
+                });
+            }
+        }
+        Ok(promotion)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Render the promotion as plan-style text.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for change in &self.changes {
+            out.push_str(&format!(
+                "  {} {} {}\n",
+                change.kind.symbol(),
+                change.resource,
+                change.address
+            ));
+            for field in &change.fields {
+                out.push_str(&format!(
+                    "      {}: {} -> {}\n",
+                    field.field, field.before, field.after
+                ));
+            }
+        }
+        if !self.changes.is_empty() {
+            out.push('\n');
+        }
+        for note in &self.notes {
+            out.push_str(&format!("Note: {}\n", note));
+        }
+        if !self.notes.is_empty() {
+            out.push('\n');
+        }
+        if self.changes.is_empty() {
+            out.push_str(&format!(
+                "No changes. {} already matches {}.",
+                self.to, self.from
+            ));
+        } else {
+            out.push_str(&format!(
+                "Promote {} -> {}: {} change(s).",
+                self.from,
+                self.to,
+                self.changes.len()
+            ));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::tests::snapshot;
+    use serde_json::json;
+
+    #[test]
+    fn test_promote_flag_and_config_state() {
+        let live = snapshot();
+        let (dev, prod) = (&live.environments[0], &live.environments[1]);
+
+        let promotion = Promotion::compute(
+            &live.flags,
+            &live.configs,
+            prod,
+            dev,
+            &PromoteFilter::default(),
+        )
+        .unwrap();
+        let summary: Vec<(ChangeKind, &str, &str)> = promotion
+            .changes
+            .iter()
+            .map(|c| (c.kind, c.resource, c.address.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (ChangeKind::Update, "toggle", "checkout[development]"),
+                (ChangeKind::Update, "rollout", "checkout[development]"),
+                (ChangeKind::Create, "rules", "checkout[development]"),
+                (ChangeKind::Create, "config_value", "theme[development]"),
+            ]
+        );
+        assert!(matches!(
+            &promotion.changes[3].operation,
+            Operation::SetConfigValue { environment, value, .. }
+                if environment == "development" && value == &json!({"color": "red"})
+        ));
+        assert_eq!(
+            promotion.notes.len(),
+            1,
+            "flag value is reported, not promoted"
+        );
+
+        // Nothing to copy from development for the config, which has no value there.
+        let promotion = Promotion::compute(
+            &live.flags,
+            &live.configs,
+            dev,
+            prod,
+            &PromoteFilter::default(),
+        )
+        .unwrap();
+        assert!(promotion
+            .changes
+            .iter()
+            .all(|c| c.resource != "config_value"));
+        assert!(matches!(promotion.changes[2].kind, ChangeKind::Delete));
+    }
+
+    #[test]
+    fn test_promote_filter() {
+        let live = snapshot();
+        let (dev, prod) = (&live.environments[0], &live.environments[1]);
+
+        let filter = PromoteFilter {
+            flags: Some(vec!["checkout".into()]),
+            configs: None,
+            fields: vec![PromoteField::Rollout],
+        };
+        let promotion = Promotion::compute(&live.flags, &live.configs, prod, dev, &filter).unwrap();
+        assert_eq!(promotion.changes.len(), 1);
+        assert_eq!(promotion.changes[0].resource, "rollout");
+
+        let filter = PromoteFilter {
+            flags: None,
+            configs: Some(vec!["theme".into()]),
+            ..PromoteFilter::default()
+        };
+        let promotion = Promotion::compute(&live.flags, &live.configs, prod, dev, &filter).unwrap();
+        assert_eq!(promotion.changes.len(), 1);
+        assert!(promotion.notes.is_empty());
+
+        let filter = PromoteFilter {
+            flags: Some(vec!["nope".into()]),
+            ..PromoteFilter::default()
+        };
+        assert!(Promotion::compute(&live.flags, &live.configs, prod, dev, &filter).is_err());
+        assert!(Promotion::compute(
+            &live.flags,
+            &live.configs,
+            dev,
+            dev,
+            &PromoteFilter::default()
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,498 @@
+use crate::api::types::{
+    Environment, ManagedAiConfig, ManagedConfig, ManagedFlag, WebhookEndpoint,
+};
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use serde::Serialize;
+use unicode_width::UnicodeWidthStr;
+
+/// Output format for headless commands.
+#[derive(Debug, Clone, Copy, PartialEq, Default, ValueEnum)]
+pub enum OutputFormat {
+    /// Aligned, human-readable columns
+    #[default]
+    Table,
+    /// The raw API objects as pretty-printed JSON
+    Json,
+    /// The raw API objects as YAML
+    Yaml,
+    /// Tab-separated columns, one row per line
+    Tsv,
+}
+
+/// A resource that can be printed as rows of named columns.
+///
+/// Column names are shared across resource types where they make sense:
+/// `key` is the natural identifier (flag key, config key, AI config file
+/// name, environment slug, webhook ID) and `enabled` is the active state,
+/// so `--columns key,enabled` works for every resource.
+pub trait Tabular: Serialize {
+    /// Every column this resource understands, in display order.
+    const COLUMNS: &'static [&'static str];
+    /// Columns shown when `--columns` isn't given.
+    const DEFAULT_COLUMNS: &'static [&'static str];
+
+    /// Render a single cell. `environment_id` selects which environment the
+    /// per-environment columns (`enabled`, `rollout`, `value`) describe.
+    fn cell(&self, column: &str, environment_id: Option<&str>) -> String;
+}
+
+/// Renders command results according to `--output`, `--columns` and `--no-headers`.
+#[derive(Debug, Clone, Default)]
+pub struct Output {
+    pub format: OutputFormat,
+    pub columns: Option<Vec<String>>,
+    pub no_headers: bool,
+    /// Environment used for per-environment columns.
+    pub environment_id: Option<String>,
+}
+
+impl Output {
+    pub fn new(format: OutputFormat, columns: Option<&str>, no_headers: bool) -> Self {
+        Self {
+            format,
+            columns: columns.map(|c| {
+                c.split(',')
+                    .map(|s| s.trim().to_lowercase())
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            }),
+            no_headers,
+            environment_id: None,
+        }
+    }
+
+    /// Print a list of resources.
+    pub fn list<T: Tabular>(&self, items: &[T]) -> Result<()> {
+        print!("{}", self.render_list(items)?);
+        Ok(())
+    }
+
+    /// Print a single resource. Table output is shown vertically as `column  value` pairs.
+    pub fn item<T: Tabular>(&self, item: &T) -> Result<()> {
+        print!("{}", self.render_item(item)?);
+        Ok(())
+    }
+
+    /// Print the result of a mutation: the returned object for JSON/YAML,
+    /// a short confirmation message otherwise.
+    pub fn done<T: Serialize>(&self, value: &T, message: &str) -> Result<()> {
+        match self.format {
+            OutputFormat::Json | OutputFormat::Yaml => print!("{}", self.serialize(value)?),
+            OutputFormat::Table | OutputFormat::Tsv => println!("{}", message),
+        }
+        Ok(())
+    }
+
+    pub fn render_list<T: Tabular>(&self, items: &[T]) -> Result<String> {
+        match self.format {
+            OutputFormat::Json | OutputFormat::Yaml => self.serialize(&items),
+            OutputFormat::Table | OutputFormat::Tsv => {
+                let columns = self.resolve_columns::<T>()?;
+                let rows: Vec<Vec<String>> = items
+                    .iter()
+                    .map(|item| {
+                        columns
+                            .iter()
+                            .map(|c| item.cell(c, self.environment_id.as_deref()))
+                            .collect()
+                    })
+                    .collect();
+                let header: Vec<String> = columns.iter().map(|c| c.to_uppercase()).collect();
+                Ok(if self.format == OutputFormat::Tsv {
+                    self.render_tsv(&header, &rows)
+                } else {
+                    self.render_table(&header, &rows)
+                })
+            }
+        }
+    }
+
+    pub fn render_item<T: Tabular>(&self, item: &T) -> Result<String> {
+        match self.format {
+            OutputFormat::Json | OutputFormat::Yaml => self.serialize(item),
+            OutputFormat::Tsv => self.render_list(std::slice::from_ref(item)),
+            OutputFormat::Table => {
+                let columns = match &self.columns {
+                    Some(_) => self.resolve_columns::<T>()?,
+                    None => T::COLUMNS.iter().map(|c| c.to_string()).collect(),
+                };
+                let width = columns.iter().map(|c| c.width()).max().unwrap_or(0);
+                let mut out = String::new();
+                for c in &columns {
+                    let value = item.cell(c, self.environment_id.as_deref());
+                    out.push_str(&format!("{}{}  {}\n", c, pad(c, width), value));
+                }
+                Ok(out)
+            }
+        }
+    }
+
+    fn serialize<T: Serialize + ?Sized>(&self, value: &T) -> Result<String> {
+        match self.format {
+            OutputFormat::Yaml => serde_yaml::to_string(value).context("serializing YAML"),
+            _ => serde_json::to_string_pretty(value)
+                .map(|s| s + "\n")
+                .context("serializing JSON"),
+        }
+    }
+
+    fn resolve_columns<T: Tabular>(&self) -> Result<Vec<String>> {
+        let Some(columns) = &self.columns else {
+            return Ok(T::DEFAULT_COLUMNS.iter().map(|c| c.to_string()).collect());
+        };
+        for c in columns {
+            if !T::COLUMNS.contains(&c.as_str()) {
+                bail!(
+                    "unknown column '{}' (available: {})",
+                    c,
+                    T::COLUMNS.join(", ")
+                );
+            }
+        }
+        Ok(columns.clone())
+    }
+
+    fn render_table(&self, header: &[String], rows: &[Vec<String>]) -> String {
+        let mut widths: Vec<usize> = if self.no_headers {
+            vec![0; header.len()]
+        } else {
+            header.iter().map(|h| h.width()).collect()
+        };
+        for row in rows {
+            for (w, cell) in widths.iter_mut().zip(row) {
+                *w = (*w).max(cell.width());
+            }
+        }
+
+        let mut out = String::new();
+        let mut push_row = |cells: &[String]| {
+            let last = cells.len().saturating_sub(1);
+            let line: String = cells
+                .iter()
+                .enumerate()
+                .map(|(i, c)| {
+                    if i == last {
+                        c.clone()
+                    } else {
+                        format!("{}{}  ", c, pad(c, widths[i]))
+                    }
+                })
+                .collect();
+            out.push_str(line.trim_end());
+            out.push('\n');
+        };
+        if !self.no_headers {
+            push_row(header);
+        }
+        for row in rows {
+            push_row(row);
+        }
+        out
+    }
+
+    fn render_tsv(&self, header: &[String], rows: &[Vec<String>]) -> String {
+        let mut out = String::new();
+        if !self.no_headers {
+            out.push_str(&header.join("\t"));
+            out.push('\n');
+        }
+        for row in rows {
+            let cells: Vec<String> = row.iter().map(|c| escape_tsv(c)).collect();
+            out.push_str(&cells.join("\t"));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+fn pad(s: &str, width: usize) -> String {
+    " ".repeat(width.saturating_sub(s.width()))
+}
+
+fn escape_tsv(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+/// Format a JSON value for a table cell: strings are shown bare, everything
+/// else as compact JSON.
+pub fn cell_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn on_off(enabled: bool) -> String {
+    if enabled { "ON" } else { "OFF" }.to_string()
+}
+
+pub fn yes_no(b: bool) -> String {
+    if b { "yes" } else { "no" }.to_string()
+}
+
+pub const NONE: &str = "-";
+
+impl Tabular for ManagedFlag {
+    const COLUMNS: &'static [&'static str] = &[
+        "id",
+        "key",
+        "name",
+        "type",
+        "status",
+        "enabled",
+        "rollout",
+        "value",
+        "default",
+        "tags",
+        "archived",
+        "description",
+        "created_at",
+        "updated_at",
+    ];
+    const DEFAULT_COLUMNS: &'static [&'static str] = &["key", "type", "status", "name"];
+
+    fn cell(&self, column: &str, environment_id: Option<&str>) -> String {
+        let env =
+            environment_id.and_then(|id| self.environments.iter().find(|e| e.environment_id == id));
+        match column {
+            "id" => self.id.clone(),
+            "key" => self.key.clone(),
+            "name" => self.name.clone(),
+            "type" => self.flag_type.clone(),
+            "status" => {
+                let enabled = self.environments.iter().filter(|e| e.enabled).count();
+                let total = self.environments.len();
+                if total == 0 {
+                    NONE.to_string()
+                } else if enabled == total {
+                    "ON".to_string()
+                } else if enabled == 0 {
+                    "OFF".to_string()
+                } else {
+                    format!("{}/{}", enabled, total)
+                }
+            }
+            "enabled" => env
+                .map(|e| on_off(e.enabled))
+                .unwrap_or_else(|| NONE.into()),
+            "rollout" => env
+                .map(|e| format!("{}%", e.rollout_percentage))
+                .unwrap_or_else(|| NONE.into()),
+            "value" => env
+                .map(|e| cell_value(&e.value))
+                .unwrap_or_else(|| NONE.into()),
+            "default" => cell_value(&self.default_value),
+            "tags" => self.tags.join(","),
+            "archived" => yes_no(self.is_archived),
+            "description" => self.description.clone(),
+            "created_at" => self.created_at.to_rfc3339(),
+            "updated_at" => self.updated_at.to_rfc3339(),
+            _ => String::new(),
+        }
+    }
+}
+
+impl Tabular for ManagedConfig {
+    const COLUMNS: &'static [&'static str] = &[
+        "id",
+        "key",
+        "name",
+        "type",
+        "enabled",
+        "value",
+        "default",
+        "tags",
+        "archived",
+        "description",
+        "created_at",
+        "updated_at",
+    ];
+    const DEFAULT_COLUMNS: &'static [&'static str] = &["key", "type", "default", "name"];
+
+    fn cell(&self, column: &str, environment_id: Option<&str>) -> String {
+        let env =
+            environment_id.and_then(|id| self.environments.iter().find(|e| e.environment_id == id));
+        match column {
+            "id" => self.id.clone(),
+            "key" => self.key.clone(),
+            "name" => self.name.clone(),
+            "type" => self.config_type.clone(),
+            "enabled" => env
+                .map(|e| on_off(e.is_active))
+                .unwrap_or_else(|| NONE.into()),
+            "value" => env
+                .map(|e| cell_value(&e.value))
+                .unwrap_or_else(|| NONE.into()),
+            "default" => cell_value(&self.default_value),
+            "tags" => self.tags.join(","),
+            "archived" => yes_no(self.is_archived),
+            "description" => self.description.clone(),
+            "created_at" => self.created_at.to_rfc3339(),
+            "updated_at" => self.updated_at.to_rfc3339(),
+            _ => String::new(),
+        }
+    }
+}
+
+impl Tabular for ManagedAiConfig {
+    const COLUMNS: &'static [&'static str] = &[
+        "id",
+        "key",
+        "name",
+        "type",
+        "folder",
+        "enabled",
+        "environment_id",
+        "created_at",
+        "updated_at",
+    ];
+    const DEFAULT_COLUMNS: &'static [&'static str] = &["key", "type", "folder", "enabled"];
+
+    fn cell(&self, column: &str, _environment_id: Option<&str>) -> String {
+        match column {
+            "id" => self.id.clone(),
+            "key" | "name" => self.file_name.clone(),
+            "type" => self.file_type.clone(),
+            "folder" => self.folder.clone(),
+            "enabled" => on_off(self.is_active),
+            "environment_id" => self.environment_id.clone(),
+            "created_at" => self.created_at.to_rfc3339(),
+            "updated_at" => self.updated_at.to_rfc3339(),
+            _ => String::new(),
+        }
+    }
+}
+
+impl Tabular for WebhookEndpoint {
+    const COLUMNS: &'static [&'static str] = &[
+        "id",
+        "key",
+        "name",
+        "url",
+        "description",
+        "events",
+        "enabled",
+        "failures",
+        "disabled_reason",
+        "environment_id",
+        "created_at",
+        "updated_at",
+    ];
+    const DEFAULT_COLUMNS: &'static [&'static str] = &["id", "url", "events", "enabled"];
+
+    fn cell(&self, column: &str, _environment_id: Option<&str>) -> String {
+        match column {
+            "id" | "key" => self.id.clone(),
+            "name" | "description" => self.description.clone(),
+            "url" => self.url.clone(),
+            "events" => self.event_types.join(","),
+            "enabled" => on_off(self.is_active),
+            "failures" => self.consecutive_failures.to_string(),
+            "disabled_reason" => self.disabled_reason.clone(),
+            "environment_id" => self.environment_id.clone(),
+            "created_at" => self.created_at.to_rfc3339(),
+            "updated_at" => self.updated_at.to_rfc3339(),
+            _ => String::new(),
+        }
+    }
+}
+
+impl Tabular for Environment {
+    const COLUMNS: &'static [&'static str] =
+        &["id", "key", "name", "slug", "default", "created_at"];
+    const DEFAULT_COLUMNS: &'static [&'static str] = &["name", "slug", "id", "default"];
+
+    fn cell(&self, column: &str, _environment_id: Option<&str>) -> String {
+        match column {
+            "id" => self.id.clone(),
+            "key" | "slug" => self.slug.clone(),
+            "name" => self.name.clone(),
+            "default" => yes_no(self.is_default),
+            "created_at" => self.created_at.to_rfc3339(),
+            _ => String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::types::FlagEnvironmentData;
+
+    fn flag(key: &str, enabled: bool, rollout: i32) -> ManagedFlag {
+        ManagedFlag {
+            id: format!("id_{}", key),
+            key: key.to_string(),
+            name: key.to_uppercase(),
+            description: String::new(),
+            flag_type: "boolean".to_string(),
+            default_value: serde_json::json!(false),
+            tags: vec!["a".into(), "b".into()],
+            is_archived: false,
+            created_at: "2026-01-01T00:00:00Z".parse().unwrap(),
+            updated_at: "2026-01-02T00:00:00Z".parse().unwrap(),
+            environments: vec![FlagEnvironmentData {
+                id: "fe_1".into(),
+                environment_id: "env_prod".into(),
+                enabled,
+                value: serde_json::json!(true),
+                rules: serde_json::json!([]),
+                rollout_percentage: rollout,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_table_alignment() {
+        let mut out = Output::new(OutputFormat::Table, Some("key,enabled,rollout"), false);
+        out.environment_id = Some("env_prod".into());
+        let rendered = out
+            .render_list(&[flag("a", true, 100), flag("longer-key", false, 5)])
+            .unwrap();
+        assert_eq!(
+            rendered,
+            "KEY         ENABLED  ROLLOUT\n\
+             a           ON       100%\n\
+             longer-key  OFF      5%\n"
+        );
+    }
+
+    #[test]
+    fn test_tsv_without_headers() {
+        let out = Output::new(OutputFormat::Tsv, Some("key,tags"), true);
+        let rendered = out.render_list(&[flag("a", true, 0)]).unwrap();
+        assert_eq!(rendered, "a\ta,b\n");
+    }
+
+    #[test]
+    fn test_per_environment_columns_without_environment() {
+        let out = Output::new(OutputFormat::Tsv, Some("key,enabled"), true);
+        let rendered = out.render_list(&[flag("a", true, 0)]).unwrap();
+        assert_eq!(rendered, "a\t-\n");
+    }
+
+    #[test]
+    fn test_unknown_column() {
+        let out = Output::new(OutputFormat::Table, Some("key,bogus"), false);
+        let err = out.render_list(&[flag("a", true, 0)]).unwrap_err();
+        assert!(err.to_string().contains("unknown column 'bogus'"));
+    }
+
+    #[test]
+    fn test_json_emits_raw_objects() {
+        let out = Output::new(OutputFormat::Json, Some("key"), false);
+        let rendered = out.render_list(&[flag("a", true, 0)]).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+        assert_eq!(parsed[0]["key"], "a");
+        assert_eq!(parsed[0]["environments"][0]["environment_id"], "env_prod");
+    }
+
+    #[test]
+    fn test_tsv_escaping() {
+        assert_eq!(escape_tsv("a\tb\nc"), "a\\tb\\nc");
+    }
+}
@@ -0,0 +1,351 @@
+//! Progressive rollout plans.
+//!
+//! A plan is a list of steps like `1,5@1d,25@2d,50,100`: each step raises the
+//! rollout percentage after a delay relative to the previous step. The plan is
+//! materialised as one `set_rollout` schedule per step, tagged with
+//! [`PLAN_MARKER`], so the server runs it and pausing or aborting is just
+//! cancelling the pending tagged schedules.
+
+use crate::api::types::{CreateScheduleRequest, Schedule, ScheduleAction};
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde_json::Value;
+
+/// Steps used when a plan is created without `--steps`.
+pub const DEFAULT_STEPS: &str = "1,5,25,50,100";
+/// Delay between steps that don't give their own.
+pub const DEFAULT_INTERVAL: &str = "1d";
+/// Payload field set on the schedules a plan creates, so `set_rollout`
+/// schedules made by hand aren't taken for plan steps.
+pub const PLAN_MARKER: &str = "rollout_plan";
+
+/// One step of a plan: wait `delay` after the previous step, then set `percentage`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RolloutStep {
+    pub percentage: i32,
+    pub delay: Duration,
+}
+
+/// Parse a step list such as `1,5@1d,25@2d,50,100`.
+///
+/// The first step runs at the start time unless it has an explicit delay;
+/// every later step without one waits `interval`.
+pub fn parse_steps(spec: &str, interval: Duration) -> Result<Vec<RolloutStep>> {
+    let mut steps = Vec::new();
+    for (i, part) in spec.split(',').map(str::trim).enumerate() {
+        if part.is_empty() {
+            bail!("empty step in '{}'", spec);
+        }
+        let (pct, delay) = match part.split_once('@') {
+            Some((pct, delay)) => (pct.trim(), Some(parse_duration(delay.trim())?)),
+            None => (part, None),
+        };
+        let percentage: i32 = pct
+            .trim_end_matches('%')
+            .parse()
+            .with_context(|| format!("invalid percentage '{}'", pct))?;
+        if !(0..=100).contains(&percentage) {
+            bail!("percentage {} is outside 0-100", percentage);
+        }
+        let delay = delay.unwrap_or(if i == 0 { Duration::zero() } else { interval });
+        steps.push(RolloutStep { percentage, delay });
+    }
+    if steps.is_empty() {
+        bail!("a rollout plan needs at least one step");
+    }
+    Ok(steps)
+}
+
+/// The inverse of [`parse_steps`], always writing every delay out.
+pub fn format_steps(steps: &[RolloutStep]) -> String {
+    steps
+        .iter()
+        .map(|s| format!("{}@{}", s.percentage, format_duration(s.delay)))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Parse durations like `30m`, `2h`, `1d`, `1w` or `1d12h`.
+pub fn parse_duration(s: &str) -> Result<Duration> {
+    let s = s.trim();
+    if s.is_empty() {
+        bail!("empty duration");
+    }
+    let mut total = Duration::zero();
+    let mut digits = String::new();
+    for c in s.chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let n: i64 = digits
+            .parse()
+            .with_context(|| format!("invalid duration '{}'", s))?;
+        let part = match c {
+            's' => Duration::try_seconds(n),
+            'm' => Duration::try_minutes(n),
+            'h' => Duration::try_hours(n),
+            'd' => Duration::try_days(n),
+            'w' => Duration::try_weeks(n),
+            _ => bail!("invalid duration '{}': unknown unit '{}'", s, c),
+        };
+        total = part
+            .and_then(|part| total.checked_add(&part))
+            .with_context(|| format!("invalid duration '{}': too long", s))?;
+        digits.clear();
+    }
+    if !digits.is_empty() {
+        bail!("invalid duration '{}': missing unit (s, m, h, d or w)", s);
+    }
+    Ok(total)
+}
+
+/// Render a duration with the largest units that fit, e.g. `1d12h`.
+pub fn format_duration(d: Duration) -> String {
+    let mut secs = d.num_seconds().max(0);
+    if secs == 0 {
+        return "0m".to_string();
+    }
+    let mut out = String::new();
+    for (unit, size) in [("d", 86_400), ("h", 3_600), ("m", 60), ("s", 1)] {
+        if secs >= size {
+            out.push_str(&format!("{}{}", secs / size, unit));
+            secs %= size;
+        }
+    }
+    out
+}
+
+/// Parse a start time: `now`, a delay from now such as `+2h`, or an RFC 3339 timestamp.
+pub fn parse_start(s: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let s = s.trim();
+    if s.is_empty() || s == "now" {
+        return Ok(now);
+    }
+    if let Some(delay) = s.strip_prefix('+') {
+        return now
+            .checked_add_signed(parse_duration(delay)?)
+            .with_context(|| format!("invalid start '{}': too far in the future", s));
+    }
+    DateTime::parse_from_rfc3339(s)
+        .map(|t| t.with_timezone(&Utc))
+        .with_context(|| format!("invalid start '{}': use now, +DURATION or RFC 3339", s))
+}
+
+/// When each step runs. A plan whose first step would land in the past is
+/// shifted forward as a whole, so it starts a minute from now (for the server
+/// to accept it) and every step keeps its spacing and order. Fails when a
+/// step would land beyond the representable date range.
+pub fn timeline(
+    steps: &[RolloutStep],
+    start: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Result<Vec<(i32, DateTime<Utc>)>> {
+    let too_late = || anyhow!("the plan runs too far into the future");
+    let earliest = now + Duration::minutes(1);
+    let mut at = start;
+    let mut times = Vec::with_capacity(steps.len());
+    for step in steps {
+        at = at.checked_add_signed(step.delay).ok_or_else(too_late)?;
+        times.push((step.percentage, at));
+    }
+    if let Some(&(_, first)) = times.first() {
+        let shift = (earliest - first).max(Duration::zero());
+        for (_, at) in &mut times {
+            *at = at.checked_add_signed(shift).ok_or_else(too_late)?;
+        }
+    }
+    Ok(times)
+}
+
+/// The schedules that materialise a plan, in the order they run.
+pub fn requests(
+    steps: &[RolloutStep],
+    start: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Result<Vec<CreateScheduleRequest>> {
+    Ok(timeline(steps, start, now)?
+        .into_iter()
+        .map(|(pct, at)| step_request(at, pct))
+        .collect())
+}
+
+/// The schedule for one plan step: a `set_rollout` tagged with [`PLAN_MARKER`].
+pub fn step_request(at: DateTime<Utc>, percentage: i32) -> CreateScheduleRequest {
+    let mut req = CreateScheduleRequest::set_rollout(at, percentage);
+    if let Some(Value::Object(payload)) = &mut req.payload {
+        payload.insert(PLAN_MARKER.to_string(), Value::Bool(true));
+    }
+    req
+}
+
+/// Whether a schedule was created as a step of a rollout plan.
+pub fn is_plan_step(schedule: &Schedule) -> bool {
+    step_percentage(schedule).is_some()
+        && schedule.payload.get(PLAN_MARKER) == Some(&Value::Bool(true))
+}
+
+/// The percentage a `set_rollout` schedule sets, if it is one.
+pub fn step_percentage(schedule: &Schedule) -> Option<i32> {
+    if ScheduleAction::parse(&schedule.action) != Some(ScheduleAction::SetRollout) {
+        return None;
+    }
+    schedule
+        .payload
+        .get("rollout_percentage")
+        .and_then(|v| v.as_i64())
+        .map(|v| v as i32)
+}
+
+/// Every schedule a plan created, oldest first — the plan as the server sees it.
+pub fn plan_schedules(schedules: &[Schedule]) -> Vec<&Schedule> {
+    let mut plan: Vec<&Schedule> = schedules.iter().filter(|s| is_plan_step(s)).collect();
+    plan.sort_by_key(|s| s.scheduled_at);
+    plan
+}
+
+/// The plan's steps that haven't run yet; cancelling these pauses the plan.
+pub fn pending(schedules: &[Schedule]) -> Vec<&Schedule> {
+    plan_schedules(schedules)
+        .into_iter()
+        .filter(|s| s.status == "pending")
+        .collect()
+}
+
+/// Pending schedules turned back into steps, so a paused plan can be resumed
+/// with the same spacing.
+pub fn remaining_steps(pending: &[&Schedule], now: DateTime<Utc>) -> Vec<RolloutStep> {
+    let mut previous = now;
+    pending
+        .iter()
+        .filter_map(|s| {
+            let percentage = step_percentage(s)?;
+            let delay = (s.scheduled_at - previous).max(Duration::zero());
+            previous = previous.max(s.scheduled_at);
+            Some(RolloutStep { percentage, delay })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn schedule(id: &str, pct: i32, at: DateTime<Utc>, status: &str) -> Schedule {
+        Schedule {
+            id: id.to_string(),
+            action: "set_rollout".to_string(),
+            scheduled_at: at,
+            executed_at: None,
+            status: status.to_string(),
+            payload: serde_json::json!({ "rollout_percentage": pct, PLAN_MARKER: true }),
+            error_message: String::new(),
+            created_at: at,
+        }
+    }
+
+    #[test]
+    fn test_parse_steps_and_timeline() {
+        let steps = parse_steps("1, 5@2h, 25%, 100@1w", Duration::days(1)).unwrap();
+        assert_eq!(
+            steps.iter().map(|s| s.percentage).collect::<Vec<_>>(),
+            vec![1, 5, 25, 100]
+        );
+        assert_eq!(steps[0].delay, Duration::zero());
+        assert_eq!(steps[2].delay, Duration::days(1));
+        assert_eq!(format_steps(&steps), "1@0m,5@2h,25@1d,100@7d");
+
+        let now = Utc.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap();
+        let times = timeline(&steps, now, now).unwrap();
+        let first = now + Duration::minutes(1);
+        assert_eq!(times[0], (1, first));
+        assert_eq!(times[1], (5, first + Duration::hours(2)));
+        assert_eq!(
+            times[3],
+            (100, first + Duration::hours(2) + Duration::days(8))
+        );
+
+        // A future start is left alone
+        let later = now + Duration::hours(1);
+        assert_eq!(timeline(&steps, later, now).unwrap()[0], (1, later));
+
+        assert!(parse_steps("1,,5", Duration::days(1)).is_err());
+        assert!(parse_steps("150", Duration::days(1)).is_err());
+        assert!(parse_steps("5@soon", Duration::days(1)).is_err());
+    }
+
+    #[test]
+    fn test_timeline_starting_in_the_past_keeps_order() {
+        let now = Utc.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap();
+        let steps = parse_steps("1,5,25,50@3d", Duration::days(1)).unwrap();
+        let times = timeline(&steps, now - Duration::days(2), now).unwrap();
+        let first = now + Duration::minutes(1);
+        assert_eq!(
+            times,
+            vec![
+                (1, first),
+                (5, first + Duration::days(1)),
+                (25, first + Duration::days(2)),
+                (50, first + Duration::days(5)),
+            ]
+        );
+        assert!(times.windows(2).all(|w| w[0].1 < w[1].1));
+    }
+
+    #[test]
+    fn test_step_requests_are_tagged() {
+        let now = Utc.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap();
+        let req = step_request(now, 25);
+        assert_eq!(req.action, "set_rollout");
+        assert_eq!(
+            req.payload,
+            Some(serde_json::json!({ "rollout_percentage": 25, PLAN_MARKER: true }))
+        );
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("1d12h").unwrap(), Duration::hours(36));
+        assert_eq!(parse_duration("90m").unwrap(), Duration::minutes(90));
+        assert_eq!(format_duration(Duration::minutes(90)), "1h30m");
+        assert!(parse_duration("12").is_err());
+        assert!(parse_duration("3y").is_err());
+    }
+
+    #[test]
+    fn test_huge_durations_are_errors() {
+        let now = Utc.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap();
+        assert!(parse_duration("99999999999999w").is_err());
+        assert!(parse_duration("99999999999999999999s").is_err());
+        assert!(parse_start("+1000000000w", now).is_err());
+
+        let interval = parse_duration("10000000w").unwrap();
+        let steps = parse_steps("5,25,50", interval).unwrap();
+        assert!(timeline(&steps, now, now).is_err());
+        assert!(requests(&steps, now, now).is_err());
+    }
+
+    #[test]
+    fn test_remaining_steps_keep_spacing() {
+        let now = Utc.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap();
+        let mut disable = schedule("x", 0, now, "pending");
+        disable.action = "disable".to_string();
+        // A set_rollout made in the schedules view isn't part of the plan
+        let mut manual = schedule("m", 75, now + Duration::days(2), "pending");
+        manual.payload = serde_json::json!({ "rollout_percentage": 75 });
+        let schedules = vec![
+            schedule("c", 50, now + Duration::days(3), "pending"),
+            manual,
+            schedule("a", 5, now - Duration::days(1), "executed"),
+            disable,
+            schedule("b", 25, now + Duration::days(1), "pending"),
+        ];
+        let pending = pending(&schedules);
+        assert_eq!(
+            pending.iter().map(|s| s.id.as_str()).collect::<Vec<_>>(),
+            vec!["b", "c"]
+        );
+        assert_eq!(format_steps(&remaining_steps(&pending, now)), "25@1d,50@2d");
+    }
+}
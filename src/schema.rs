@@ -0,0 +1,402 @@
+//! JSON Schema validation for remote config values.
+//!
+//! A config's schema comes from `schemas/<key>.json` in the flagdash config
+//! directory if that file exists, otherwise from `schema` in the config's
+//! metadata. The keywords that describe a value's shape are checked: `type`,
+//! `enum`, `const`, the numeric, length and item-count bounds, `properties`,
+//! `required`, `additionalProperties`, `items`, `uniqueItems`, `allOf` and
+//! `anyOf`. Others, such as `pattern`, `format` and `$ref`, are ignored.
+
+use crate::api::types::ManagedConfig;
+use crate::config;
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::path::Path;
+use thiserror::Error;
+
+/// A value that doesn't match its schema, located by JSON pointer (empty for
+/// the value itself).
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("{}", located(.path, .message))]
+pub struct SchemaError {
+    pub path: String,
+    pub message: String,
+}
+
+fn located(path: &str, message: &str) -> String {
+    if path.is_empty() {
+        message.to_string()
+    } else {
+        format!("{}: {}", path, message)
+    }
+}
+
+/// The schema for a config, if it has one.
+pub fn for_config(config: &ManagedConfig) -> Result<Option<Value>> {
+    load(config, &config::schema_dir()?)
+}
+
+fn load(config: &ManagedConfig, dir: &Path) -> Result<Option<Value>> {
+    let path = dir.join(format!("{}.json", config.key));
+    match std::fs::read_to_string(&path) {
+        Ok(content) => {
+            let schema = serde_json::from_str(&content)
+                .with_context(|| format!("parsing schema {}", path.display()))?;
+            Ok(Some(schema))
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Ok(config.metadata.get("schema").cloned())
+        }
+        Err(e) => Err(e).with_context(|| format!("reading schema {}", path.display())),
+    }
+}
+
+/// Check `value` against `schema`, returning every mismatch found.
+pub fn validate(value: &Value, schema: &Value) -> Vec<SchemaError> {
+    let mut errors = Vec::new();
+    check(value, schema, "", &mut errors);
+    errors
+}
+
+fn check(value: &Value, schema: &Value, path: &str, errors: &mut Vec<SchemaError>) {
+    match schema {
+        Value::Bool(true) => return,
+        Value::Bool(false) => {
+            return errors.push(error(path, "no value is allowed here".to_string()));
+        }
+        Value::Object(_) => {}
+        _ => return,
+    }
+
+    if let Some(types) = schema.get("type") {
+        let names: Vec<&str> = match types {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !names.is_empty() && !names.iter().any(|t| has_type(value, t)) {
+            return errors.push(error(path, format!("must be {}", names.join(" or "))));
+        }
+    }
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            let list: Vec<String> = allowed.iter().map(Value::to_string).collect();
+            errors.push(error(path, format!("must be one of {}", list.join(", "))));
+        }
+    }
+    if let Some(expected) = schema.get("const") {
+        if value != expected {
+            errors.push(error(path, format!("must be {}", expected)));
+        }
+    }
+
+    match value {
+        Value::Number(n) => {
+            if let Err(message) = NumberRange::of(schema).check(n.as_f64().unwrap_or_default()) {
+                errors.push(error(path, message));
+            }
+        }
+        Value::String(s) => {
+            let len = s.chars().count();
+            if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+                if (len as u64) < min {
+                    errors.push(error(path, format!("must be at least {} characters", min)));
+                }
+            }
+            if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+                if len as u64 > max {
+                    errors.push(error(path, format!("must be at most {} characters", max)));
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+                if (items.len() as u64) < min {
+                    errors.push(error(path, format!("must have at least {} items", min)));
+                }
+            }
+            if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+                if items.len() as u64 > max {
+                    errors.push(error(path, format!("must have at most {} items", max)));
+                }
+            }
+            if schema.get("uniqueItems") == Some(&Value::Bool(true)) {
+                let duplicate = items
+                    .iter()
+                    .enumerate()
+                    .any(|(i, item)| items[..i].contains(item));
+                if duplicate {
+                    errors.push(error(path, "must not contain duplicate items".to_string()));
+                }
+            }
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    check(item, item_schema, &format!("{}/{}", path, i), errors);
+                }
+            }
+        }
+        Value::Object(fields) => {
+            if let Some(required) = schema.get("required").and_then(Value::as_array) {
+                for name in required.iter().filter_map(Value::as_str) {
+                    if !fields.contains_key(name) {
+                        errors.push(error(
+                            path,
+                            format!("missing required property \"{}\"", name),
+                        ));
+                    }
+                }
+            }
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for (name, field) in fields {
+                let field_path = format!("{}/{}", path, escape_pointer(name));
+                match properties.and_then(|p| p.get(name)) {
+                    Some(field_schema) => check(field, field_schema, &field_path, errors),
+                    None => match schema.get("additionalProperties") {
+                        Some(Value::Bool(false)) => errors
+                            .push(error(&field_path, "is not an allowed property".to_string())),
+                        Some(extra) => check(field, extra, &field_path, errors),
+                        None => {}
+                    },
+                }
+            }
+        }
+        _ => {}
+    }
+
+    if let Some(all) = schema.get("allOf").and_then(Value::as_array) {
+        for sub in all {
+            check(value, sub, path, errors);
+        }
+    }
+    if let Some(any) = schema.get("anyOf").and_then(Value::as_array) {
+        if !any.iter().any(|sub| validate(value, sub).is_empty()) {
+            errors.push(error(
+                path,
+                "doesn't match any of the allowed schemas".to_string(),
+            ));
+        }
+    }
+}
+
+fn error(path: &str, message: String) -> SchemaError {
+    SchemaError {
+        path: path.to_string(),
+        message,
+    }
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    match name {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => match value {
+            Value::Number(n) => {
+                n.is_i64() || n.is_u64() || n.as_f64().is_some_and(|f| f.fract() == 0.0)
+            }
+            _ => false,
+        },
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => true,
+    }
+}
+
+/// `~` and `/` escaped for use in a JSON pointer segment.
+fn escape_pointer(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+/// The bounds a schema puts on a number: `minimum`/`maximum` and their
+/// exclusive forms. The number editor shows and enforces these as you type.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct NumberRange {
+    /// Lower bound, and whether it is exclusive.
+    pub min: Option<(f64, bool)>,
+    /// Upper bound, and whether it is exclusive.
+    pub max: Option<(f64, bool)>,
+}
+
+impl NumberRange {
+    pub fn of(schema: &Value) -> Self {
+        let bound = |inclusive: &str, exclusive: &str| {
+            match schema.get(exclusive) {
+                Some(Value::Number(n)) => return n.as_f64().map(|n| (n, true)),
+                // Draft 4 spelling: a flag that makes `minimum`/`maximum` exclusive
+                Some(Value::Bool(flag)) => {
+                    return schema
+                        .get(inclusive)
+                        .and_then(Value::as_f64)
+                        .map(|n| (n, *flag));
+                }
+                _ => {}
+            }
+            schema
+                .get(inclusive)
+                .and_then(Value::as_f64)
+                .map(|n| (n, false))
+        };
+        Self {
+            min: bound("minimum", "exclusiveMinimum"),
+            max: bound("maximum", "exclusiveMaximum"),
+        }
+    }
+
+    pub fn is_unbounded(&self) -> bool {
+        self.min.is_none() && self.max.is_none()
+    }
+
+    pub fn check(&self, n: f64) -> Result<(), String> {
+        match self.min {
+            Some((min, true)) if n <= min => return Err(format!("must be greater than {}", min)),
+            Some((min, false)) if n < min => return Err(format!("must be at least {}", min)),
+            _ => {}
+        }
+        match self.max {
+            Some((max, true)) if n >= max => Err(format!("must be less than {}", max)),
+            Some((max, false)) if n > max => Err(format!("must be at most {}", max)),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl std::fmt::Display for NumberRange {
+    /// E.g. `1 to 500`, `≥ 0`, `> 0 and < 1`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.min, self.max) {
+            (Some((min, false)), Some((max, false))) => write!(f, "{} to {}", min, max),
+            (min, max) => {
+                let parts: Vec<String> = [
+                    min.map(|(n, ex)| format!("{} {}", if ex { ">" } else { "≥" }, n)),
+                    max.map(|(n, ex)| format!("{} {}", if ex { "<" } else { "≤" }, n)),
+                ]
+                .into_iter()
+                .flatten()
+                .collect();
+                write!(f, "{}", parts.join(" and "))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn messages(value: Value, schema: Value) -> Vec<String> {
+        validate(&value, &schema)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_object() {
+        let schema = json!({
+            "type": "object",
+            "required": ["primary", "radius"],
+            "properties": {
+                "primary": {"type": "string", "minLength": 4},
+                "radius": {"type": "integer", "minimum": 0, "maximum": 16},
+                "mode": {"enum": ["light", "dark"]},
+            },
+            "additionalProperties": false,
+        });
+        assert!(messages(json!({"primary": "#fff", "radius": 4}), schema.clone()).is_empty());
+        assert_eq!(
+            messages(
+                json!({"primary": "red", "radius": 20.5, "mode": "dim", "extra": 1}),
+                schema.clone()
+            ),
+            vec![
+                "/extra: is not an allowed property",
+                r#"/mode: must be one of "light", "dark""#,
+                "/primary: must be at least 4 characters",
+                "/radius: must be integer",
+            ]
+        );
+        assert_eq!(
+            messages(json!({"radius": 17}), schema.clone()),
+            vec![
+                "missing required property \"primary\"",
+                "/radius: must be at most 16"
+            ]
+        );
+        assert_eq!(messages(json!([1]), schema), vec!["must be object"]);
+    }
+
+    #[test]
+    fn test_validate_arrays_and_combinators() {
+        let schema = json!({
+            "type": "array",
+            "items": {"type": ["string", "null"]},
+            "maxItems": 2,
+            "uniqueItems": true,
+        });
+        assert!(messages(json!(["a", null]), schema.clone()).is_empty());
+        assert_eq!(
+            messages(json!(["a", "a", 3]), schema),
+            vec![
+                "must have at most 2 items",
+                "must not contain duplicate items",
+                "/2: must be string or null",
+            ]
+        );
+
+        let schema = json!({"anyOf": [{"type": "string"}, {"type": "number", "minimum": 0}]});
+        assert!(messages(json!(3), schema.clone()).is_empty());
+        assert_eq!(
+            messages(json!(-1), schema),
+            vec!["doesn't match any of the allowed schemas"]
+        );
+        assert!(messages(json!({"a/b": 1}), json!(true)).is_empty());
+        assert_eq!(
+            messages(json!({"a/b": 1}), json!({"properties": {"a/b": false}})),
+            vec!["/a~1b: no value is allowed here"]
+        );
+    }
+
+    #[test]
+    fn test_number_range() {
+        let range = NumberRange::of(&json!({"minimum": 1, "maximum": 500}));
+        assert_eq!(range.to_string(), "1 to 500");
+        assert_eq!(range.check(0.0), Err("must be at least 1".to_string()));
+        assert!(range.check(500.0).is_ok());
+
+        let range = NumberRange::of(&json!({"exclusiveMinimum": 0, "maximum": 1}));
+        assert_eq!(range.to_string(), "> 0 and ≤ 1");
+        assert_eq!(range.check(0.0), Err("must be greater than 0".to_string()));
+
+        let draft4 = NumberRange::of(&json!({"maximum": 10, "exclusiveMaximum": true}));
+        assert_eq!(draft4.check(10.0), Err("must be less than 10".to_string()));
+        assert!(NumberRange::of(&json!({})).is_unbounded());
+    }
+
+    #[test]
+    fn test_local_schema_overrides_metadata() {
+        let dir = std::env::temp_dir().join(format!("flagdash-schema-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut config: ManagedConfig = serde_json::from_value(json!({
+            "id": "cfg_1", "key": "theme", "name": "Theme", "config_type": "json",
+            "default_value": {}, "created_at": "2026-01-01T00:00:00Z",
+            "updated_at": "2026-01-01T00:00:00Z",
+        }))
+        .unwrap();
+        assert_eq!(load(&config, &dir).unwrap(), None);
+
+        config.metadata = json!({"schema": {"type": "object"}});
+        assert_eq!(
+            load(&config, &dir).unwrap(),
+            Some(json!({"type": "object"}))
+        );
+
+        std::fs::write(dir.join("theme.json"), r#"{"type": "array"}"#).unwrap();
+        assert_eq!(load(&config, &dir).unwrap(), Some(json!({"type": "array"})));
+
+        std::fs::write(dir.join("theme.json"), "{").unwrap();
+        assert!(load(&config, &dir).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -47,6 +47,10 @@ pub fn status_off() -> Style {
     Style::default().fg(ERROR)
 }
 
+pub fn warning() -> Style {
+    Style::default().fg(WARNING)
+}
+
 pub fn badge_management() -> Style {
     Style::default().fg(ACCENT).add_modifier(Modifier::BOLD)
 }
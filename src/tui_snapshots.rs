@@ -0,0 +1,327 @@
+//! Rendering tests for the TUI. Each test drives an `App` backed by a
+//! `MemoryBackend` with fixed demo data through a script of key presses,
+//! renders it into a `TestBackend` and compares the screen with a text
+//! snapshot in `tests/snapshots/`.
+//!
+//! Run with `UPDATE_SNAPSHOTS=1` to write new or changed snapshots instead of
+//! failing, then review the diff.
+
+use crate::action::{Action, View};
+use crate::api::memory::{MemoryBackend, MemoryState};
+use crate::app::App;
+use crate::config::AppConfig;
+use crate::event::Event;
+use chrono::{TimeZone, Utc};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+const WIDTH: u16 = 120;
+const HEIGHT: u16 = 32;
+
+struct Harness {
+    app: App,
+    terminal: Terminal<TestBackend>,
+}
+
+impl Harness {
+    /// Logged in to the demo project (seeded at a fixed time so dates don't
+    /// drift), on the project picker.
+    async fn logged_in() -> Self {
+        let now = Utc.with_ymd_and_hms(2026, 3, 2, 12, 0, 0).unwrap();
+        let backend = MemoryBackend::new(MemoryState::demo(now));
+        let config = AppConfig::in_memory(None, None);
+        Self::start(App::with_api(config, Some(Arc::new(backend)))).await
+    }
+
+    /// On the login screen.
+    async fn logged_out() -> Self {
+        let mut config = AppConfig::in_memory(None, None);
+        config.auth = Default::default();
+        Self::start(App::with_api(config, None)).await
+    }
+
+    async fn start(app: App) -> Self {
+        let mut harness = Self {
+            app,
+            terminal: Terminal::new(TestBackend::new(WIDTH, HEIGHT)).unwrap(),
+        };
+        harness.settle().await;
+        harness
+    }
+
+    /// Logged in, past the picker with the demo project's first environment.
+    async fn in_project() -> Self {
+        let mut harness = Self::logged_in().await;
+        harness.press("<Enter><Enter>").await;
+        harness
+    }
+
+    /// Let spawned API calls finish and process the actions they send, until
+    /// nothing more happens.
+    async fn settle(&mut self) {
+        let mut idle = 0;
+        while idle < 20 {
+            tokio::task::yield_now().await;
+            let mut progressed = false;
+            while let Ok(action) = self.app.action_rx.try_recv() {
+                self.app.process_action(action);
+                progressed = true;
+            }
+            idle = if progressed { 0 } else { idle + 1 };
+        }
+    }
+
+    /// Press keys in order, settling after each one. Plain characters are
+    /// typed as-is; special keys are written `<Enter>`, `<Esc>`, `<Tab>`,
+    /// `<BackTab>`, `<Backspace>`, `<Up>`, `<Down>`, `<Left>`, `<Right>`,
+    /// `<Space>` and `<C-x>` for Ctrl+x.
+    async fn press(&mut self, script: &str) {
+        for key in parse_keys(script) {
+            self.app.handle_event(&Event::Key(key)).unwrap();
+            self.settle().await;
+        }
+    }
+
+    /// Process an action as if a view had returned it. Used for the edit
+    /// forms, whose `e` key the global environment switcher takes first.
+    async fn dispatch(&mut self, action: Action) {
+        self.app.process_action(action);
+        self.settle().await;
+    }
+
+    async fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            self.app
+                .handle_event(&Event::Key(KeyEvent::new(KeyCode::Char(c), modifiers(c))))
+                .unwrap();
+        }
+        self.settle().await;
+    }
+
+    /// The screen as text, one line per row with trailing spaces trimmed.
+    fn screen(&mut self) -> String {
+        let app = &mut self.app;
+        self.terminal.draw(|frame| app.render(frame)).unwrap();
+        let buffer = self.terminal.backend().buffer();
+        let mut lines = Vec::with_capacity(HEIGHT as usize);
+        for y in 0..buffer.area.height {
+            let line: String = (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect();
+            lines.push(line.trim_end().to_string());
+        }
+        lines.join("\n") + "\n"
+    }
+
+    #[track_caller]
+    fn assert_snapshot(&mut self, name: &str) {
+        let actual = self.screen();
+        let path = snapshot_path(name);
+        let expected = std::fs::read_to_string(&path).ok();
+        if expected.as_deref() == Some(actual.as_str()) {
+            return;
+        }
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, &actual).unwrap();
+            return;
+        }
+        match expected {
+            None => panic!(
+                "no snapshot at {}; run with UPDATE_SNAPSHOTS=1 to create it. Screen:\n{}",
+                path.display(),
+                actual
+            ),
+            Some(expected) => panic!(
+                "screen differs from {} (run with UPDATE_SNAPSHOTS=1 to accept):\n{}",
+                path.display(),
+                line_diff(&expected, &actual)
+            ),
+        }
+    }
+}
+
+fn snapshot_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{name}.txt"))
+}
+
+fn modifiers(c: char) -> KeyModifiers {
+    if c.is_ascii_uppercase() {
+        KeyModifiers::SHIFT
+    } else {
+        KeyModifiers::NONE
+    }
+}
+
+fn parse_keys(script: &str) -> Vec<KeyEvent> {
+    let mut keys = Vec::new();
+    let mut chars = script.chars();
+    while let Some(c) = chars.next() {
+        if c != '<' {
+            keys.push(KeyEvent::new(KeyCode::Char(c), modifiers(c)));
+            continue;
+        }
+        let name: String = chars.by_ref().take_while(|&c| c != '>').collect();
+        let key = match name.as_str() {
+            "Enter" => KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE),
+            "Esc" => KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE),
+            "Tab" => KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE),
+            "BackTab" => KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT),
+            "Backspace" => KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE),
+            "Up" => KeyEvent::new(KeyCode::Up, KeyModifiers::NONE),
+            "Down" => KeyEvent::new(KeyCode::Down, KeyModifiers::NONE),
+            "Left" => KeyEvent::new(KeyCode::Left, KeyModifiers::NONE),
+            "Right" => KeyEvent::new(KeyCode::Right, KeyModifiers::NONE),
+            "Space" => KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE),
+            ctrl if ctrl.starts_with("C-") && ctrl.len() == 3 => KeyEvent::new(
+                KeyCode::Char(ctrl.chars().last().unwrap()),
+                KeyModifiers::CONTROL,
+            ),
+            other => panic!("unknown key <{other}> in {script:?}"),
+        };
+        keys.push(key);
+    }
+    keys
+}
+
+/// Changed rows, as `-expected` / `+actual` pairs with their line numbers.
+fn line_diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<_> = expected.lines().collect();
+    let actual: Vec<_> = actual.lines().collect();
+    let mut out = String::new();
+    for i in 0..expected.len().max(actual.len()) {
+        let (e, a) = (expected.get(i), actual.get(i));
+        if e != a {
+            out.push_str(&format!("{:>3} -{}\n", i + 1, e.unwrap_or(&"")));
+            out.push_str(&format!("{:>3} +{}\n", i + 1, a.unwrap_or(&"")));
+        }
+    }
+    out
+}
+
+#[tokio::test]
+async fn test_login_screen() {
+    let mut h = Harness::logged_out().await;
+    h.assert_snapshot("login");
+}
+
+#[tokio::test]
+async fn test_project_picker() {
+    let mut h = Harness::logged_in().await;
+    h.assert_snapshot("picker_projects");
+    h.press("<Enter>").await;
+    h.assert_snapshot("picker_environments");
+    h.press("jj").await;
+    h.assert_snapshot("picker_environments_production");
+}
+
+#[tokio::test]
+async fn test_flag_views() {
+    let mut h = Harness::in_project().await;
+    h.press("2").await;
+    h.assert_snapshot("flags_list");
+
+    h.press("/").await;
+    h.type_text("dark").await;
+    h.assert_snapshot("flags_list_search");
+    h.press("<Esc>").await;
+
+    h.press("<Enter>").await;
+    h.assert_snapshot("flags_detail");
+    h.press("u").await;
+    h.assert_snapshot("flags_rules_editor");
+    h.press("<Esc>r").await;
+    h.assert_snapshot("flags_rollout");
+    h.press("<Esc>").await;
+    h.dispatch(Action::Navigate(View::FlagEdit("new-checkout".into())))
+        .await;
+    h.assert_snapshot("flags_edit_form");
+    h.press("<Esc>").await;
+
+    h.press("c<Tab>").await;
+    h.type_text("Beta banner").await;
+    h.assert_snapshot("flags_create_form");
+    h.press("<Esc>").await;
+
+    h.press("jjd").await;
+    h.assert_snapshot("flags_delete_confirm");
+    h.press("y").await;
+    h.assert_snapshot("flags_list_after_delete");
+}
+
+#[tokio::test]
+async fn test_config_views() {
+    let mut h = Harness::in_project().await;
+    h.press("3").await;
+    h.assert_snapshot("configs_list");
+    h.press("<Enter>").await;
+    h.assert_snapshot("configs_detail");
+    h.press("v").await;
+    h.assert_snapshot("configs_value_editor");
+    h.press("j<Enter><C-u>").await;
+    h.type_text("red").await;
+    h.press("<Enter>").await;
+    h.assert_snapshot("configs_value_editor_schema_error");
+    h.press("<C-r>").await;
+    h.assert_snapshot("configs_value_editor_raw");
+    h.press("<C-f>").await;
+    h.assert_snapshot("configs_value_editor_yaml");
+    h.press("<Esc>").await;
+    h.dispatch(Action::Navigate(View::ConfigEdit("theme".into())))
+        .await;
+    h.assert_snapshot("configs_edit_form");
+    h.press("<Esc><Esc>c").await;
+    h.assert_snapshot("configs_create_form");
+    h.press("<Esc>").await;
+
+    h.press("j<Enter>v").await;
+    h.type_text("00").await;
+    h.assert_snapshot("configs_value_editor_number");
+    h.press("<Esc><Esc>j<Enter>v").await;
+    h.assert_snapshot("configs_value_editor_string");
+}
+
+#[tokio::test]
+async fn test_ai_config_views() {
+    let mut h = Harness::in_project().await;
+    h.press("4").await;
+    h.assert_snapshot("ai_configs_list");
+    h.press("<Enter>").await;
+    h.assert_snapshot("ai_configs_detail");
+    h.dispatch(Action::Navigate(View::AiConfigEdit(
+        "code-review.md".into(),
+    )))
+    .await;
+    h.assert_snapshot("ai_configs_edit_form");
+    h.press("<Esc><Esc>c").await;
+    h.assert_snapshot("ai_configs_create_form");
+}
+
+#[tokio::test]
+async fn test_webhook_views() {
+    let mut h = Harness::in_project().await;
+    h.press("5").await;
+    h.assert_snapshot("webhooks_list");
+    h.press("<Enter>").await;
+    h.assert_snapshot("webhooks_detail");
+    h.dispatch(Action::Navigate(View::WebhookEdit("whe_slack".into())))
+        .await;
+    h.assert_snapshot("webhooks_edit_form");
+    h.press("<Esc><Esc>c").await;
+    h.assert_snapshot("webhooks_create_form");
+}
+
+#[test]
+fn test_parse_keys() {
+    let keys = parse_keys("j<Enter><C-s>A");
+    assert_eq!(keys[0].code, KeyCode::Char('j'));
+    assert_eq!(keys[1].code, KeyCode::Enter);
+    assert_eq!(keys[2].code, KeyCode::Char('s'));
+    assert_eq!(keys[2].modifiers, KeyModifiers::CONTROL);
+    assert_eq!(keys[3].modifiers, KeyModifiers::SHIFT);
+}
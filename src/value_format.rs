@@ -0,0 +1,501 @@
+//! JSON values written as JSON, YAML or TOML, for authoring config values and
+//! targeting rules in whichever reads best. Conversions are lossless: a value
+//! a format can't hold exactly, such as a null in TOML, is an error rather
+//! than a guess.
+//!
+//! A TOML document is always a table, so the value goes under a single key
+//! (`value = 42`, or `[[rules]]` for a rules array).
+
+use crate::components::text_area::TextArea;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueFormat {
+    #[default]
+    Json,
+    Yaml,
+    Toml,
+}
+
+/// Why text couldn't be read as a value, or a value written as text. Parse
+/// errors carry the 1-based position they were found at.
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("{}{message}", location_prefix(.line, .column))]
+pub struct FormatError {
+    pub message: String,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+impl FormatError {
+    fn new(message: String) -> Self {
+        Self {
+            message,
+            line: None,
+            column: None,
+        }
+    }
+
+    fn at(message: String, line: usize, column: usize) -> Self {
+        Self {
+            message,
+            line: Some(line),
+            column: Some(column),
+        }
+    }
+}
+
+fn location_prefix(line: &Option<usize>, column: &Option<usize>) -> String {
+    match (line, column) {
+        (Some(line), Some(column)) => format!("line {}, column {}: ", line, column),
+        _ => String::new(),
+    }
+}
+
+impl ValueFormat {
+    pub fn label(self) -> &'static str {
+        match self {
+            ValueFormat::Json => "JSON",
+            ValueFormat::Yaml => "YAML",
+            ValueFormat::Toml => "TOML",
+        }
+    }
+
+    /// The next format, for cycling through them in the editors.
+    pub fn next(self) -> Self {
+        match self {
+            ValueFormat::Json => ValueFormat::Yaml,
+            ValueFormat::Yaml => ValueFormat::Toml,
+            ValueFormat::Toml => ValueFormat::Json,
+        }
+    }
+
+    /// Pick a format from a file extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Some(ValueFormat::Json),
+            Some("yaml") | Some("yml") => Some(ValueFormat::Yaml),
+            Some("toml") => Some(ValueFormat::Toml),
+            _ => None,
+        }
+    }
+
+    /// Write `value` in this format; in TOML it goes under `key`.
+    pub fn write(self, value: &Value, key: &str) -> Result<String, FormatError> {
+        match self {
+            ValueFormat::Json => {
+                Ok(serde_json::to_string_pretty(value).expect("JSON values always serialize"))
+            }
+            ValueFormat::Yaml => serde_yaml::to_string(value)
+                .map(|text| text.trim_end().to_string())
+                .map_err(|e| FormatError::new(e.to_string())),
+            ValueFormat::Toml => {
+                let mut table = toml::Table::new();
+                table.insert(key.to_string(), to_toml(value, "")?);
+                toml::to_string_pretty(&table)
+                    .map(|text| text.trim_end().to_string())
+                    .map_err(|e| FormatError::new(e.to_string()))
+            }
+        }
+    }
+
+    /// Write `value` in this format, or in JSON if this format can't hold it.
+    /// Also returns the format used and, on fallback, why.
+    pub fn write_or_json(self, value: &Value, key: &str) -> (Self, String, Option<String>) {
+        match self.write(value, key) {
+            Ok(text) => (self, text, None),
+            Err(e) => (
+                ValueFormat::Json,
+                ValueFormat::Json.write(value, key).unwrap_or_default(),
+                Some(format!("{}; showing JSON", e)),
+            ),
+        }
+    }
+
+    /// Read a value written in this format; in TOML it must be the only entry,
+    /// under `key`.
+    pub fn read(self, text: &str, key: &str) -> Result<Value, FormatError> {
+        match self {
+            ValueFormat::Json => serde_json::from_str(text)
+                .map_err(|e| FormatError::at(json_message(&e), e.line(), e.column())),
+            ValueFormat::Yaml => {
+                let mut value: serde_yaml::Value = serde_yaml::from_str(text).map_err(|e| {
+                    let message = e.to_string();
+                    match e.location() {
+                        Some(at) => {
+                            FormatError::at(strip_location(&message), at.line(), at.column())
+                        }
+                        None => FormatError::new(message),
+                    }
+                })?;
+                value
+                    .apply_merge()
+                    .map_err(|e| FormatError::new(e.to_string()))?;
+                from_yaml(value, "")
+            }
+            ValueFormat::Toml => {
+                let mut table: toml::Table = toml::from_str(text).map_err(|e| {
+                    let message = e.message().to_string();
+                    match e.span() {
+                        Some(span) => {
+                            let (line, column) = position(text, span.start);
+                            FormatError::at(message, line, column)
+                        }
+                        None => FormatError::new(message),
+                    }
+                })?;
+                match table.remove(key) {
+                    Some(value) if table.is_empty() => from_toml(value, ""),
+                    _ => Err(FormatError::new(format!(
+                        "expected a single `{}` entry, e.g. `{} = ...`",
+                        key, key
+                    ))),
+                }
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ValueFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.label())
+    }
+}
+
+/// Label for a text editor holding the `key` value in `format`, e.g.
+/// "Rules (YAML)".
+pub fn editor_label(key: &str, format: ValueFormat) -> String {
+    let mut chars = key.chars();
+    let title: String = match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    };
+    format!("{} ({})", title, format.label())
+}
+
+/// Write `value` into `editor` in `format`, falling back to JSON (and saying
+/// why) if `format` can't hold it. Returns the format used and that note.
+pub fn write_editor(
+    editor: &mut TextArea,
+    value: &Value,
+    format: ValueFormat,
+    key: &str,
+) -> (ValueFormat, Option<String>) {
+    let (format, text, note) = format.write_or_json(value, key);
+    editor.label = editor_label(key, format);
+    editor.set_content(&text);
+    (format, note)
+}
+
+/// Read `editor`'s text in `format`; the error is a message to show.
+pub fn read_editor(editor: &TextArea, format: ValueFormat, key: &str) -> Result<Value, String> {
+    format
+        .read(&editor.content(), key)
+        .map_err(|e| format!("Invalid {}: {}", format.label(), e))
+}
+
+/// Rewrite `editor`'s text in the format after `format`. The text must parse
+/// first. Returns the value, the format used and any fallback note.
+pub fn next_editor_format(
+    editor: &mut TextArea,
+    format: ValueFormat,
+    key: &str,
+) -> Result<(Value, ValueFormat, Option<String>), String> {
+    let value = read_editor(editor, format, key)?;
+    let (format, note) = write_editor(editor, &value, format.next(), key);
+    Ok((value, format, note))
+}
+
+/// serde_json's message without its trailing "at line L column C".
+fn json_message(e: &serde_json::Error) -> String {
+    strip_location(&e.to_string())
+}
+
+fn strip_location(message: &str) -> String {
+    match message.rfind(" at line ") {
+        Some(i) => message[..i].to_string(),
+        None => message.to_string(),
+    }
+}
+
+/// 1-based line and column of byte `offset` in `text`.
+fn position(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset.min(text.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+    (line, column)
+}
+
+/// "the value" for the root, otherwise the JSON pointer.
+fn describe(path: &str) -> String {
+    if path.is_empty() {
+        "the value".to_string()
+    } else {
+        path.to_string()
+    }
+}
+
+fn child(path: &str, key: &str) -> String {
+    format!("{}/{}", path, key.replace('~', "~0").replace('/', "~1"))
+}
+
+fn float(f: f64, path: &str) -> Result<Value, FormatError> {
+    Number::from_f64(f).map(Value::Number).ok_or_else(|| {
+        FormatError::new(format!(
+            "{} is {}, which JSON can't represent",
+            describe(path),
+            f
+        ))
+    })
+}
+
+fn from_yaml(value: serde_yaml::Value, path: &str) -> Result<Value, FormatError> {
+    use serde_yaml::Value as Yaml;
+    Ok(match value {
+        Yaml::Null => Value::Null,
+        Yaml::Bool(b) => Value::Bool(b),
+        Yaml::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Value::from(i)
+            } else if let Some(u) = n.as_u64() {
+                Value::from(u)
+            } else {
+                float(n.as_f64().unwrap_or(f64::NAN), path)?
+            }
+        }
+        Yaml::String(s) => Value::String(s),
+        Yaml::Sequence(items) => Value::Array(
+            items
+                .into_iter()
+                .enumerate()
+                .map(|(i, item)| from_yaml(item, &child(path, &i.to_string())))
+                .collect::<Result<_, _>>()?,
+        ),
+        Yaml::Mapping(mapping) => {
+            let mut fields = Map::new();
+            for (k, v) in mapping {
+                let Yaml::String(k) = k else {
+                    return Err(FormatError::new(format!(
+                        "{} has a key that isn't a string ({}); JSON object keys must be strings",
+                        describe(path),
+                        serde_yaml::to_string(&k).unwrap_or_default().trim_end()
+                    )));
+                };
+                let v = from_yaml(v, &child(path, &k))?;
+                fields.insert(k, v);
+            }
+            Value::Object(fields)
+        }
+        Yaml::Tagged(tagged) => {
+            return Err(FormatError::new(format!(
+                "{} has the YAML tag {}, which JSON can't represent",
+                describe(path),
+                tagged.tag
+            )))
+        }
+    })
+}
+
+fn to_toml(value: &Value, path: &str) -> Result<toml::Value, FormatError> {
+    Ok(match value {
+        Value::Null => {
+            return Err(FormatError::new(format!(
+                "TOML has no null, so {} can't be written as TOML",
+                if path.is_empty() {
+                    "a null value".to_string()
+                } else {
+                    format!("the null at {}", path)
+                }
+            )))
+        }
+        Value::Bool(b) => toml::Value::Boolean(*b),
+        Value::Number(n) => match (n.as_i64(), n.as_f64()) {
+            (Some(i), _) => toml::Value::Integer(i),
+            (None, Some(f)) if !n.is_u64() => toml::Value::Float(f),
+            _ => {
+                return Err(FormatError::new(format!(
+                    "{} is {}, larger than TOML integers can hold",
+                    describe(path),
+                    n
+                )))
+            }
+        },
+        Value::String(s) => toml::Value::String(s.clone()),
+        Value::Array(items) => toml::Value::Array(
+            items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| to_toml(item, &child(path, &i.to_string())))
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Object(fields) => {
+            let mut table = toml::Table::new();
+            for (k, v) in fields {
+                table.insert(k.clone(), to_toml(v, &child(path, k))?);
+            }
+            toml::Value::Table(table)
+        }
+    })
+}
+
+fn from_toml(value: toml::Value, path: &str) -> Result<Value, FormatError> {
+    Ok(match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => Value::from(i),
+        toml::Value::Float(f) => float(f, path)?,
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(d) => {
+            return Err(FormatError::new(format!(
+            "{} is the TOML date-time {}, which JSON can't represent; quote it to make it a string",
+            describe(path),
+            d
+        )))
+        }
+        toml::Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .enumerate()
+                .map(|(i, item)| from_toml(item, &child(path, &i.to_string())))
+                .collect::<Result<_, _>>()?,
+        ),
+        toml::Value::Table(table) => {
+            let mut fields = Map::new();
+            for (k, v) in table {
+                let v = from_toml(v, &child(path, &k))?;
+                fields.insert(k, v);
+            }
+            Value::Object(fields)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_round_trips() {
+        let value = json!({
+            "name": "checkout",
+            "tricky": ["yes", "no", "~", "null", "1.0", "0x10", "", "a: b", "#x", "true"],
+            "numbers": [0, -3, 2.5, 1.0, 1e300, 9007199254740993i64],
+            "nested": {"a/b": {"list": [{"x": 1}, {"x": 2}]}, "empty": {}},
+            "flag": false,
+        });
+        for format in [ValueFormat::Json, ValueFormat::Yaml, ValueFormat::Toml] {
+            let text = format.write(&value, "value").unwrap();
+            assert_eq!(
+                format.read(&text, "value").unwrap(),
+                value,
+                "{}:\n{}",
+                format,
+                text
+            );
+        }
+
+        let rules = json!([{"clauses": [{"attribute": "country", "operator": "in", "value": ["NZ"]}], "value": true}]);
+        let toml = ValueFormat::Toml.write(&rules, "rules").unwrap();
+        assert!(toml.starts_with("[[rules]]"), "{}", toml);
+        assert_eq!(ValueFormat::Toml.read(&toml, "rules").unwrap(), rules);
+        assert_eq!(
+            ValueFormat::Toml.read("value = 42", "value").unwrap(),
+            json!(42)
+        );
+    }
+
+    #[test]
+    fn test_unrepresentable_values() {
+        let toml = ValueFormat::Toml;
+        assert_eq!(
+            toml.write(&json!({"a": [1, null]}), "value")
+                .unwrap_err()
+                .message,
+            "TOML has no null, so the null at /a/1 can't be written as TOML"
+        );
+        assert_eq!(
+            toml.write(&Value::Null, "value").unwrap_err().message,
+            "TOML has no null, so a null value can't be written as TOML"
+        );
+        let (format, text, note) = toml.write_or_json(&json!([null]), "value");
+        assert_eq!((format, text.as_str()), (ValueFormat::Json, "[\n  null\n]"));
+        assert!(note.unwrap().ends_with("; showing JSON"));
+        assert!(toml
+            .write(&json!(u64::MAX), "value")
+            .unwrap_err()
+            .message
+            .contains("larger than TOML integers can hold"));
+
+        assert!(toml
+            .read("value = 1979-05-27", "value")
+            .unwrap_err()
+            .message
+            .starts_with("the value is the TOML date-time 1979-05-27"));
+        assert_eq!(
+            toml.read("value = [nan]", "value").unwrap_err().message,
+            "/0 is NaN, which JSON can't represent"
+        );
+        assert_eq!(
+            toml.read("other = 1", "value").unwrap_err().message,
+            "expected a single `value` entry, e.g. `value = ...`"
+        );
+
+        let yaml = ValueFormat::Yaml;
+        assert_eq!(
+            yaml.read("a:\n  1: x", "value").unwrap_err().message,
+            "/a has a key that isn't a string (1); JSON object keys must be strings"
+        );
+        assert!(yaml
+            .read("a: !thing 1", "value")
+            .unwrap_err()
+            .message
+            .starts_with("/a has the YAML tag !thing"));
+        assert_eq!(
+            yaml.read("x: .inf", "value").unwrap_err().message,
+            "/x is inf, which JSON can't represent"
+        );
+    }
+
+    #[test]
+    fn test_yaml_anchors_and_merges() {
+        let text = "base: &base {color: blue, size: 2}\nlarge:\n  <<: *base\n  size: 4\n";
+        assert_eq!(
+            ValueFormat::Yaml.read(text, "value").unwrap(),
+            json!({"base": {"color": "blue", "size": 2}, "large": {"color": "blue", "size": 4}})
+        );
+    }
+
+    #[test]
+    fn test_parse_error_positions() {
+        let e = ValueFormat::Json
+            .read("{\n  \"a\": }", "value")
+            .unwrap_err();
+        assert_eq!((e.line, e.column), (Some(2), Some(8)));
+        assert!(!e.message.contains("at line"));
+
+        let e = ValueFormat::Yaml
+            .read("a: [1, 2\nb: 3", "value")
+            .unwrap_err();
+        assert!(e.line.is_some(), "{:?}", e);
+
+        let e = ValueFormat::Toml
+            .read("value = [1,\n  2,,]", "value")
+            .unwrap_err();
+        assert_eq!(e.line, Some(2));
+        assert!(e.to_string().starts_with("line 2, column "));
+    }
+
+    #[test]
+    fn test_from_path() {
+        assert_eq!(
+            ValueFormat::from_path(Path::new("theme.yml")),
+            Some(ValueFormat::Yaml)
+        );
+        assert_eq!(ValueFormat::from_path(Path::new("theme.txt")), None);
+    }
+}
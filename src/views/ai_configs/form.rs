@@ -122,15 +122,11 @@ impl AiConfigFormView {
             // Type selector arrows
             if self.focused_field == 2 {
                 match key.code {
-                    KeyCode::Left => {
-                        if self.file_type_index > 0 {
-                            self.file_type_index -= 1;
-                        }
+                    KeyCode::Left if self.file_type_index > 0 => {
+                        self.file_type_index -= 1;
                     }
-                    KeyCode::Right => {
-                        if self.file_type_index < FILE_TYPES.len() - 1 {
-                            self.file_type_index += 1;
-                        }
+                    KeyCode::Right if self.file_type_index < FILE_TYPES.len() - 1 => {
+                        self.file_type_index += 1;
                     }
                     KeyCode::Tab => {
                         self.focused_field = 3;
@@ -1,36 +1,145 @@
 use crate::action::{Action, View};
 use crate::api::types::Environment;
+use crate::components::input_field::InputField;
 use crate::components::text_area::TextArea;
 use crate::event::Event;
+use crate::schema::{self, NumberRange, SchemaError};
 use crate::theme;
+use crate::value_format::{
+    editor_label, next_editor_format, read_editor, write_editor, ValueFormat,
+};
 use crossterm::event::{KeyCode, KeyEventKind, KeyModifiers};
 use ratatui::layout::{Constraint, Layout, Rect};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::Paragraph;
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
 use ratatui::Frame;
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// Errors shown under the editor; the rest are summarized.
+const MAX_ERRORS: usize = 4;
+/// Key the value goes under when written as TOML.
+const VALUE_KEY: &str = "value";
+
+/// The editor for a config's type.
+enum Editor {
+    /// Typed as-is, without quotes, and saved as a JSON string.
+    Text(InputField),
+    Number(InputField),
+    Boolean(bool),
+    Json(JsonEditor),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    Tree,
+    Raw,
+}
+
+/// A JSON value browsed as a tree, with its leaves edited in place, or as text
+/// in `format`.
+struct JsonEditor {
+    mode: Mode,
+    value: Value,
+    raw: TextArea,
+    format: ValueFormat,
+    /// JSON pointers of the collapsed objects and arrays.
+    collapsed: HashSet<String>,
+    cursor: usize,
+    input: Option<InputField>,
+}
+
+/// A line in the tree: the value at `pointer`, indented by `depth`.
+struct Row {
+    pointer: String,
+    depth: usize,
+    key: Option<String>,
+}
 
 pub struct ConfigValueEditorView {
     pub config_key: String,
+    pub config_type: String,
     pub environments: Vec<Environment>,
     pub selected_env: usize,
-    pub editor: TextArea,
+    schema: Option<Value>,
+    editor: Editor,
+    /// Why the value can't be saved as it stands: it doesn't parse, or
+    /// doesn't match the schema.
+    errors: Vec<SchemaError>,
+    message: Option<String>,
 }
 
 impl ConfigValueEditorView {
-    pub fn new(config_key: &str) -> Self {
-        let mut editor = TextArea::new("Value (JSON)");
-        editor.focused = true;
+    pub fn new(config_key: &str, config_type: &str) -> Self {
+        let editor = match config_type {
+            "string" => Editor::Text(focused_input("Value (string)")),
+            "number" => Editor::Number(focused_input("Value (number)")),
+            "boolean" => Editor::Boolean(false),
+            _ => Editor::Json(JsonEditor::new(ValueFormat::Json)),
+        };
         Self {
             config_key: config_key.to_string(),
+            config_type: config_type.to_string(),
             environments: Vec::new(),
             selected_env: 0,
+            schema: None,
             editor,
+            errors: Vec::new(),
+            message: None,
+        }
+    }
+
+    /// Check values against `schema` as they're edited and before saving.
+    pub fn with_schema(mut self, schema: Option<Value>) -> Self {
+        if let (Editor::Number(input), Some(schema)) = (&mut self.editor, &schema) {
+            let range = NumberRange::of(schema);
+            if !range.is_unbounded() {
+                input.label = format!("Value (number, {})", range);
+            }
+        }
+        self.schema = schema;
+        self.check();
+        self
+    }
+
+    /// Show and read JSON values as text in `format` rather than JSON.
+    pub fn with_format(mut self, format: ValueFormat) -> Self {
+        if let Editor::Json(json) = &mut self.editor {
+            *json = JsonEditor::new(format);
         }
+        self
     }
 
-    pub fn set_value(&mut self, value: &serde_json::Value) {
-        let formatted = serde_json::to_string_pretty(value).unwrap_or_default();
-        self.editor.set_content(&formatted);
+    pub fn has_schema(&self) -> bool {
+        self.schema.is_some()
+    }
+
+    /// Load `value` into the editor. A string config whose value isn't a
+    /// string starts empty, since whatever is typed is saved as a string.
+    pub fn set_value(&mut self, value: &Value) {
+        match &mut self.editor {
+            Editor::Text(input) => {
+                input.set_value(value.as_str().unwrap_or(""));
+                self.message = match value {
+                    Value::String(_) | Value::Null => None,
+                    other => Some(format!(
+                        "The current value {} isn't a string; saving replaces it with the text typed here",
+                        other
+                    )),
+                };
+                self.check();
+                return;
+            }
+            Editor::Number(input) => input.set_value(&value_text(value)),
+            Editor::Boolean(on) => *on = value.as_bool().unwrap_or(false),
+            Editor::Json(json) => {
+                self.message = json.set_value(value.clone());
+                self.check();
+                return;
+            }
+        }
+        self.message = None;
+        self.check();
     }
 
     pub fn selected_environment_id(&self) -> Option<&str> {
@@ -39,8 +148,47 @@ impl ConfigValueEditorView {
             .map(|e| e.id.as_str())
     }
 
-    pub fn parse_value(&self) -> Result<serde_json::Value, String> {
-        serde_json::from_str(&self.editor.content()).map_err(|e| e.to_string())
+    /// The value as typed, if it parses as the config's type.
+    pub fn parse_value(&self) -> Result<Value, String> {
+        match &self.editor {
+            Editor::Text(input) => Ok(Value::String(input.value.clone())),
+            Editor::Number(input) => parse_number(&input.value),
+            Editor::Boolean(on) => Ok(Value::Bool(*on)),
+            Editor::Json(json) => json.parse(),
+        }
+    }
+
+    /// The value to save: it must parse and match the schema, if there is one.
+    pub fn value(&self) -> Result<Value, String> {
+        let value = self.parse_value()?;
+        let errors = self
+            .schema
+            .as_ref()
+            .map(|s| schema::validate(&value, s))
+            .unwrap_or_default();
+        match errors.as_slice() {
+            [] => Ok(value),
+            [error] => Err(format!("Doesn't match the schema: {}", error)),
+            [error, rest @ ..] => Err(format!(
+                "Doesn't match the schema: {} (and {} more)",
+                error,
+                rest.len()
+            )),
+        }
+    }
+
+    fn check(&mut self) {
+        self.errors = match self.parse_value() {
+            Ok(value) => self
+                .schema
+                .as_ref()
+                .map(|s| schema::validate(&value, s))
+                .unwrap_or_default(),
+            Err(e) => vec![SchemaError {
+                path: String::new(),
+                message: e,
+            }],
+        };
     }
 
     pub fn handle_event(&mut self, event: &Event) -> Option<Action> {
@@ -48,44 +196,98 @@ impl ConfigValueEditorView {
             if key.kind != KeyEventKind::Press {
                 return None;
             }
+            let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+            if let Editor::Json(json) = &mut self.editor {
+                if json.input.is_some() {
+                    json.handle_input_event(event);
+                    self.check();
+                    return None;
+                }
+            }
             match key.code {
                 KeyCode::Esc => {
                     return Some(Action::Navigate(View::ConfigDetail(
                         self.config_key.clone(),
                     )));
                 }
-                KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                KeyCode::Char('s') if ctrl => {
                     return Some(Action::SubmitConfigValueUpdate(self.config_key.clone()));
                 }
+                KeyCode::BackTab => self.next_environment(),
                 KeyCode::Tab if key.modifiers.contains(KeyModifiers::SHIFT) => {
-                    if !self.environments.is_empty() {
-                        self.selected_env = (self.selected_env + 1) % self.environments.len();
-                    }
-                }
-                _ => {
-                    self.editor.handle_event(event);
+                    self.next_environment()
                 }
+                _ => match &mut self.editor {
+                    Editor::Text(input) | Editor::Number(input) => {
+                        input.handle_event(event);
+                    }
+                    Editor::Boolean(on) => match key.code {
+                        KeyCode::Char('t') => *on = true,
+                        KeyCode::Char('f') => *on = false,
+                        KeyCode::Char(' ')
+                        | KeyCode::Enter
+                        | KeyCode::Left
+                        | KeyCode::Right
+                        | KeyCode::Char('h')
+                        | KeyCode::Char('l') => *on = !*on,
+                        _ => {}
+                    },
+                    Editor::Json(json) => {
+                        if key.code == KeyCode::Char('r') && ctrl {
+                            self.message = json.toggle_mode().unwrap_or_else(Some);
+                        } else if key.code == KeyCode::Char('f') && ctrl && json.mode == Mode::Raw {
+                            self.message = json.next_format().unwrap_or_else(Some);
+                        } else {
+                            json.handle_key(event);
+                        }
+                    }
+                },
             }
+            self.check();
         }
         None
     }
 
+    fn next_environment(&mut self) {
+        if !self.environments.is_empty() {
+            self.selected_env = (self.selected_env + 1) % self.environments.len();
+        }
+    }
+
     pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let editor_height = match &self.editor {
+            Editor::Text(_) | Editor::Number(_) => Constraint::Length(3),
+            Editor::Boolean(_) => Constraint::Length(1),
+            Editor::Json(json) => {
+                if json.input.is_some() {
+                    Constraint::Min(2)
+                } else {
+                    Constraint::Min(5)
+                }
+            }
+        };
+        let editing_leaf = matches!(&self.editor, Editor::Json(j) if j.input.is_some());
         let chunks = Layout::vertical([
             Constraint::Length(2),
             Constraint::Length(2),
-            Constraint::Min(5),
+            editor_height,
+            Constraint::Length(if editing_leaf { 3 } else { 0 }),
+            Constraint::Length(self.errors.len().min(MAX_ERRORS) as u16),
+            Constraint::Length(if self.message.is_some() { 1 } else { 0 }),
+            Constraint::Min(0),
             Constraint::Length(1),
         ])
         .split(area);
 
-        frame.render_widget(
-            Paragraph::new(Line::from(vec![
-                Span::styled("← ", theme::dim()),
-                Span::styled(format!("Set Value: {}", self.config_key), theme::heading()),
-            ])),
-            chunks[0],
-        );
+        let mut title = vec![
+            Span::styled("← ", theme::dim()),
+            Span::styled(format!("Set Value: {}", self.config_key), theme::heading()),
+            Span::styled(format!("  {}", self.config_type), theme::dim()),
+        ];
+        if self.has_schema() {
+            title.push(Span::styled(", checked against schema", theme::dim()));
+        }
+        frame.render_widget(Paragraph::new(Line::from(title)), chunks[0]);
 
         let env_name = self
             .environments
@@ -101,16 +303,560 @@ impl ConfigValueEditorView {
             chunks[1],
         );
 
-        self.editor.render(frame, chunks[2]);
+        match &self.editor {
+            Editor::Text(input) | Editor::Number(input) => input.render(frame, chunks[2]),
+            Editor::Boolean(on) => {
+                let option = |label: &'static str, selected: bool| {
+                    let style = if selected {
+                        theme::highlight()
+                    } else {
+                        theme::dim()
+                    };
+                    Span::styled(format!(" {} ", label), style)
+                };
+                frame.render_widget(
+                    Paragraph::new(Line::from(vec![
+                        Span::styled("Value: ", theme::title()),
+                        option("true", *on),
+                        Span::raw(" "),
+                        option("false", !*on),
+                    ])),
+                    chunks[2],
+                );
+            }
+            Editor::Json(json) => {
+                match json.mode {
+                    Mode::Raw => json.raw.render(frame, chunks[2]),
+                    Mode::Tree => json.render_tree(frame, chunks[2], &self.errors),
+                }
+                if let Some(input) = &json.input {
+                    input.render(frame, chunks[3]);
+                }
+            }
+        }
 
-        frame.render_widget(
-            Paragraph::new(Line::from(vec![
-                Span::styled("[Ctrl+S]", theme::title()),
-                Span::styled(" Save  ", theme::dim()),
-                Span::styled("[Esc]", theme::title()),
-                Span::styled(" Back", theme::dim()),
-            ])),
-            chunks[3],
+        self.render_errors(frame, chunks[4]);
+
+        if let Some(message) = &self.message {
+            frame.render_widget(
+                Paragraph::new(Span::styled(message.as_str(), theme::status_off())),
+                chunks[5],
+            );
+        }
+
+        frame.render_widget(Paragraph::new(Line::from(self.hints())), chunks[7]);
+    }
+
+    fn hints(&self) -> Vec<Span<'static>> {
+        let hint = |key: &'static str, label: &'static str| {
+            [
+                Span::styled(key, theme::title()),
+                Span::styled(label, theme::dim()),
+            ]
+        };
+        let mut hints: Vec<Span> = Vec::new();
+        match &self.editor {
+            Editor::Json(json) if json.input.is_some() => {
+                hints.extend(hint("[Enter]", " Apply  "));
+                hints.extend(hint("[Esc]", " Cancel"));
+                return hints;
+            }
+            Editor::Boolean(_) => hints.extend(hint("[Space]", " Toggle  ")),
+            Editor::Text(_) => hints.push(Span::styled("Saved as a string  ", theme::dim())),
+            Editor::Json(json) if json.mode == Mode::Tree => {
+                hints.extend(hint("[←→]", " Collapse/expand  "));
+                hints.extend(hint("[Enter]", " Edit  "));
+                hints.push(Span::styled("[Ctrl+R]", theme::title()));
+                hints.push(Span::styled(
+                    format!(" {}  ", json.format.label()),
+                    theme::dim(),
+                ));
+            }
+            Editor::Json(json) => {
+                hints.extend(hint("[Ctrl+R]", " Tree  "));
+                hints.push(Span::styled("[Ctrl+F]", theme::title()));
+                hints.push(Span::styled(
+                    format!(" {}  ", json.format.next().label()),
+                    theme::dim(),
+                ));
+            }
+            _ => {}
+        }
+        hints.extend(hint("[Ctrl+S]", " Save  "));
+        hints.extend(hint("[Esc]", " Back"));
+        hints
+    }
+
+    fn render_errors(&self, frame: &mut Frame, area: Rect) {
+        let mut lines: Vec<Line> = self
+            .errors
+            .iter()
+            .take(MAX_ERRORS)
+            .map(|e| {
+                let mut spans = vec![Span::styled("error: ", theme::status_off())];
+                if !e.path.is_empty() {
+                    spans.push(Span::styled(format!("{} ", e.path), theme::dim()));
+                }
+                spans.push(Span::styled(e.message.clone(), theme::normal()));
+                Line::from(spans)
+            })
+            .collect();
+        if self.errors.len() > MAX_ERRORS {
+            lines.pop();
+            lines.push(Line::from(Span::styled(
+                format!("… and {} more", self.errors.len() - MAX_ERRORS + 1),
+                theme::dim(),
+            )));
+        }
+        frame.render_widget(Paragraph::new(lines), area);
+    }
+}
+
+impl JsonEditor {
+    fn new(format: ValueFormat) -> Self {
+        let mut raw = TextArea::new(&editor_label(VALUE_KEY, format));
+        raw.focused = true;
+        Self {
+            mode: Mode::Tree,
+            value: Value::Null,
+            raw,
+            format,
+            collapsed: HashSet::new(),
+            cursor: 0,
+            input: None,
+        }
+    }
+
+    /// Returns a note if the value can't be shown in the chosen format.
+    fn set_value(&mut self, value: Value) -> Option<String> {
+        self.value = value;
+        self.collapsed.clear();
+        self.cursor = 0;
+        self.input = None;
+        match self.mode {
+            Mode::Raw => self.write_raw(self.format),
+            Mode::Tree => None,
+        }
+    }
+
+    /// Write the value into the text editor in `format`, falling back to JSON
+    /// (and saying why) if `format` can't hold it.
+    fn write_raw(&mut self, format: ValueFormat) -> Option<String> {
+        let (format, note) = write_editor(&mut self.raw, &self.value, format, VALUE_KEY);
+        self.format = format;
+        note
+    }
+
+    fn parse(&self) -> Result<Value, String> {
+        match self.mode {
+            Mode::Tree => Ok(self.value.clone()),
+            Mode::Raw => read_editor(&self.raw, self.format, VALUE_KEY),
+        }
+    }
+
+    /// Switch between the tree and the text. Returns a note to show, if any.
+    fn toggle_mode(&mut self) -> Result<Option<String>, String> {
+        match self.mode {
+            Mode::Tree => {
+                self.mode = Mode::Raw;
+                return Ok(self.write_raw(self.format));
+            }
+            Mode::Raw => {
+                self.value = self.parse()?;
+                self.mode = Mode::Tree;
+                self.cursor = self.cursor.min(self.rows().len() - 1);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Rewrite the text in the next format. The text must parse first.
+    fn next_format(&mut self) -> Result<Option<String>, String> {
+        let (value, format, note) = next_editor_format(&mut self.raw, self.format, VALUE_KEY)?;
+        self.value = value;
+        self.format = format;
+        Ok(note)
+    }
+
+    fn rows(&self) -> Vec<Row> {
+        let mut rows = Vec::new();
+        self.push_rows(&self.value, String::new(), 0, None, &mut rows);
+        rows
+    }
+
+    fn push_rows(
+        &self,
+        value: &Value,
+        pointer: String,
+        depth: usize,
+        key: Option<String>,
+        rows: &mut Vec<Row>,
+    ) {
+        let expanded = !self.collapsed.contains(&pointer);
+        let children: Vec<(String, &Value)> = match value {
+            Value::Object(fields) if expanded => {
+                fields.iter().map(|(k, v)| (k.clone(), v)).collect()
+            }
+            Value::Array(items) if expanded => items
+                .iter()
+                .enumerate()
+                .map(|(i, v)| (i.to_string(), v))
+                .collect(),
+            _ => Vec::new(),
+        };
+        rows.push(Row {
+            pointer: pointer.clone(),
+            depth,
+            key,
+        });
+        for (k, child) in children {
+            let child_pointer = format!("{}/{}", pointer, k.replace('~', "~0").replace('/', "~1"));
+            self.push_rows(child, child_pointer, depth + 1, Some(k), rows);
+        }
+    }
+
+    fn selected(&self) -> Option<(String, &Value)> {
+        let row = self.rows().into_iter().nth(self.cursor)?;
+        let value = self.value.pointer(&row.pointer)?;
+        Some((row.pointer, value))
+    }
+
+    fn handle_key(&mut self, event: &Event) {
+        let Event::Key(key) = event else { return };
+        if self.mode == Mode::Raw {
+            self.raw.handle_event(event);
+            return;
+        }
+        let rows = self.rows();
+        let Some((pointer, is_container, text)) = self
+            .selected()
+            .map(|(p, v)| (p, v.is_object() || v.is_array(), value_text(v)))
+        else {
+            return;
+        };
+        match key.code {
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.cursor = (self.cursor + 1) % rows.len();
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.cursor = self.cursor.checked_sub(1).unwrap_or(rows.len() - 1);
+            }
+            KeyCode::Right | KeyCode::Char('l') if is_container => {
+                self.collapsed.remove(&pointer);
+            }
+            KeyCode::Left | KeyCode::Char('h') => {
+                if is_container && !self.collapsed.contains(&pointer) {
+                    self.collapsed.insert(pointer);
+                } else if let Some(parent) = pointer.rfind('/').map(|i| &pointer[..i]) {
+                    if let Some(pos) = rows.iter().position(|r| r.pointer == parent) {
+                        self.cursor = pos;
+                    }
+                }
+            }
+            KeyCode::Char(' ') | KeyCode::Enter if is_container => {
+                let collapse = !self.collapsed.remove(&pointer);
+                if collapse {
+                    self.collapsed.insert(pointer);
+                }
+            }
+            KeyCode::Enter => {
+                let mut input = focused_input("Value (JSON literal; text is taken as a string)");
+                input.set_value(&text);
+                self.input = Some(input);
+            }
+            _ => {}
+        }
+    }
+
+    /// Keys for the leaf being edited. Enter writes it back into the value.
+    fn handle_input_event(&mut self, event: &Event) {
+        let (Event::Key(key), Some(input)) = (event, &mut self.input) else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => self.input = None,
+            KeyCode::Enter => {
+                let text = input.value.clone();
+                self.input = None;
+                let Some((pointer, was_string)) = self.selected().map(|(p, v)| (p, v.is_string()))
+                else {
+                    return;
+                };
+                if let Some(slot) = self.value.pointer_mut(&pointer) {
+                    *slot = parse_leaf(&text, was_string);
+                }
+            }
+            _ => {
+                input.handle_event(event);
+            }
+        }
+    }
+
+    fn render_tree(&self, frame: &mut Frame, area: Rect, errors: &[SchemaError]) {
+        let block = Block::default()
+            .title(" Value (JSON) ")
+            .title_style(theme::heading())
+            .borders(Borders::ALL)
+            .border_style(theme::active_border());
+        let items: Vec<ListItem> = self
+            .rows()
+            .iter()
+            .map(|row| {
+                let value = self.value.pointer(&row.pointer).unwrap_or(&Value::Null);
+                let collapsed = self.collapsed.contains(&row.pointer);
+                let has_error = errors.iter().any(|e| {
+                    e.path == row.pointer || (collapsed && e.path.starts_with(&row.pointer))
+                });
+                let mut spans = vec![
+                    if has_error {
+                        Span::styled("● ", theme::status_off())
+                    } else {
+                        Span::raw("  ")
+                    },
+                    Span::raw("  ".repeat(row.depth)),
+                ];
+                let (marker, summary) = match value {
+                    Value::Object(fields) => (
+                        if collapsed { "▸ " } else { "▾ " },
+                        format!("{{{}}}", count(fields.len(), "key")),
+                    ),
+                    Value::Array(items) => (
+                        if collapsed { "▸ " } else { "▾ " },
+                        format!("[{}]", count(items.len(), "item")),
+                    ),
+                    leaf => ("  ", leaf.to_string()),
+                };
+                spans.push(Span::styled(marker, theme::dim()));
+                if let Some(key) = &row.key {
+                    spans.push(Span::styled(format!("{}: ", key), theme::title()));
+                }
+                let style = if value.is_object() || value.is_array() {
+                    theme::dim()
+                } else {
+                    theme::normal()
+                };
+                spans.push(Span::styled(summary, style));
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(theme::selected());
+        let mut state = ListState::default().with_selected(Some(self.cursor));
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+}
+
+fn focused_input(label: &str) -> InputField {
+    let mut input = InputField::new(label);
+    input.focused = true;
+    input
+}
+
+fn count(n: usize, noun: &str) -> String {
+    if n == 1 {
+        format!("1 {}", noun)
+    } else {
+        format!("{} {}s", n, noun)
+    }
+}
+
+/// Show a value for editing: strings bare, everything else as JSON.
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn parse_number(text: &str) -> Result<Value, String> {
+    let text = text.trim();
+    if let Ok(n) = text.parse::<i64>() {
+        return Ok(Value::from(n));
+    }
+    text.parse::<f64>()
+        .ok()
+        .and_then(serde_json::Number::from_f64)
+        .map(Value::Number)
+        .ok_or_else(|| format!("Not a number: {:?}", text))
+}
+
+/// Parse an edited leaf. A string stays a string, unless it is typed as a
+/// quoted JSON string; other leaves take JSON literals, and text that isn't
+/// one becomes a string.
+fn parse_leaf(text: &str, was_string: bool) -> Value {
+    match serde_json::from_str::<Value>(text.trim()) {
+        Ok(Value::String(s)) => Value::String(s),
+        Ok(value) if !was_string => value,
+        _ => Value::String(text.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(crossterm::event::KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn type_text(view: &mut ConfigValueEditorView, text: &str) {
+        for c in text.chars() {
+            view.handle_event(&key(KeyCode::Char(c)));
+        }
+    }
+
+    #[test]
+    fn test_scalar_editors() {
+        let mut view = ConfigValueEditorView::new("banner", "string");
+        view.set_value(&json!("Free shipping"));
+        type_text(&mut view, " \"today\"");
+        assert_eq!(view.value().unwrap(), json!("Free shipping \"today\""));
+        view.set_value(&Value::Null);
+        assert_eq!(view.value().unwrap(), json!(""));
+        assert!(view.message.is_none());
+        view.set_value(&json!(42));
+        assert_eq!(view.value().unwrap(), json!(""));
+        assert!(view.message.is_some());
+
+        let mut view = ConfigValueEditorView::new("limit", "number")
+            .with_schema(Some(json!({"minimum": 1, "maximum": 500})));
+        view.set_value(&json!(50));
+        assert_eq!(view.value().unwrap(), json!(50));
+        type_text(&mut view, "00");
+        assert_eq!(view.errors[0].message, "must be at most 500");
+        assert_eq!(
+            view.value().unwrap_err(),
+            "Doesn't match the schema: must be at most 500"
         );
+        view.set_value(&json!(2.5));
+        assert_eq!(view.value().unwrap(), json!(2.5));
+        type_text(&mut view, "x");
+        assert_eq!(view.value().unwrap_err(), "Not a number: \"2.5x\"");
+
+        let mut view = ConfigValueEditorView::new("enabled", "boolean");
+        view.set_value(&json!(true));
+        view.handle_event(&key(KeyCode::Char(' ')));
+        assert_eq!(view.value().unwrap(), json!(false));
+        view.handle_event(&key(KeyCode::Char('t')));
+        assert_eq!(view.value().unwrap(), json!(true));
+    }
+
+    #[test]
+    fn test_json_tree_navigation_and_leaf_edits() {
+        let mut view = ConfigValueEditorView::new("theme", "json").with_schema(Some(json!({
+            "properties": {"radius": {"type": "integer", "maximum": 16}},
+        })));
+        view.set_value(&json!({"colors": {"primary": "#fff", "accent": "#000"}, "radius": 4}));
+        let Editor::Json(json) = &view.editor else {
+            unreachable!()
+        };
+        assert_eq!(json.rows().len(), 5);
+
+        view.handle_event(&key(KeyCode::Down)); // colors
+        view.handle_event(&key(KeyCode::Left)); // collapse it
+        let Editor::Json(json) = &view.editor else {
+            unreachable!()
+        };
+        assert_eq!(json.rows().len(), 3);
+        assert_eq!(json.selected().unwrap().0, "/colors");
+
+        view.handle_event(&key(KeyCode::Down)); // radius
+        view.handle_event(&key(KeyCode::Enter));
+        view.handle_event(&key(KeyCode::Backspace));
+        type_text(&mut view, "20");
+        view.handle_event(&key(KeyCode::Enter));
+        assert_eq!(view.parse_value().unwrap()["radius"], json!(20));
+        assert_eq!(view.errors[0].to_string(), "/radius: must be at most 16");
+        assert!(view.value().is_err());
+
+        // Strings stay strings unless quoted
+        view.handle_event(&key(KeyCode::Up));
+        view.handle_event(&key(KeyCode::Right));
+        view.handle_event(&key(KeyCode::Down)); // colors/accent
+        view.handle_event(&key(KeyCode::Enter));
+        view.handle_event(&Event::Key(crossterm::event::KeyEvent::new(
+            KeyCode::Char('u'),
+            KeyModifiers::CONTROL,
+        )));
+        type_text(&mut view, "12");
+        view.handle_event(&key(KeyCode::Enter));
+        assert_eq!(view.parse_value().unwrap()["colors"]["accent"], json!("12"));
+    }
+
+    #[test]
+    fn test_json_raw_mode() {
+        let mut view = ConfigValueEditorView::new("theme", "json");
+        view.set_value(&json!({"a": 1}));
+        let ctrl_r = Event::Key(crossterm::event::KeyEvent::new(
+            KeyCode::Char('r'),
+            KeyModifiers::CONTROL,
+        ));
+        view.handle_event(&ctrl_r);
+        type_text(&mut view, "x");
+        assert!(view.value().unwrap_err().starts_with("Invalid JSON"));
+        view.handle_event(&ctrl_r);
+        assert!(view.message.is_some());
+        view.handle_event(&key(KeyCode::Backspace));
+        view.handle_event(&ctrl_r);
+        assert_eq!(view.message, None);
+        assert_eq!(view.value().unwrap(), json!({"a": 1}));
+    }
+
+    #[test]
+    fn test_raw_mode_in_yaml_and_toml() {
+        let ctrl = |c| {
+            Event::Key(crossterm::event::KeyEvent::new(
+                KeyCode::Char(c),
+                KeyModifiers::CONTROL,
+            ))
+        };
+        let mut view = ConfigValueEditorView::new("theme", "json").with_format(ValueFormat::Yaml);
+        view.set_value(&json!({"primary": "#ff0000", "radius": 4}));
+        view.handle_event(&ctrl('r'));
+        let Editor::Json(json) = &view.editor else {
+            unreachable!()
+        };
+        assert_eq!(json.raw.label, "Value (YAML)");
+        assert_eq!(json.raw.content(), "primary: '#ff0000'\nradius: 4");
+
+        view.handle_event(&ctrl('f'));
+        let Editor::Json(json) = &view.editor else {
+            unreachable!()
+        };
+        assert_eq!(
+            json.raw.content(),
+            "[value]\nprimary = \"#ff0000\"\nradius = 4"
+        );
+        assert_eq!(
+            view.value().unwrap(),
+            json!({"primary": "#ff0000", "radius": 4})
+        );
+        type_text(&mut view, "= ");
+        assert!(view
+            .value()
+            .unwrap_err()
+            .starts_with("Invalid TOML: line 1"));
+
+        // A null can't be written as TOML, so it's shown as JSON instead
+        let mut view = ConfigValueEditorView::new("theme", "json").with_format(ValueFormat::Toml);
+        view.set_value(&json!({"accent": null}));
+        view.handle_event(&ctrl('r'));
+        let Editor::Json(json) = &view.editor else {
+            unreachable!()
+        };
+        assert_eq!(json.format, ValueFormat::Json);
+        assert!(view.message.as_deref().unwrap().ends_with("showing JSON"));
+        assert_eq!(view.value().unwrap(), json!({"accent": null}));
+    }
+
+    #[test]
+    fn test_parse_leaf() {
+        assert_eq!(parse_leaf("42", false), json!(42));
+        assert_eq!(parse_leaf("42", true), json!("42"));
+        assert_eq!(parse_leaf("\"42\"", false), json!("42"));
+        assert_eq!(parse_leaf("hello", false), json!("hello"));
+        assert_eq!(parse_leaf("[1, 2]", false), json!([1, 2]));
     }
 }
@@ -1,3 +1,4 @@
+use crate::action::{Action, View};
 use crate::api::types::Environment;
 use crate::components::table_view::TableView;
 use crate::event::Event;
@@ -26,17 +27,30 @@ impl EnvironmentListView {
         self.table.set_items(self.environments.len());
     }
 
-    pub fn handle_event(&mut self, event: &Event) {
+    pub fn selected_environment(&self) -> Option<&Environment> {
+        self.table
+            .selected_index()
+            .and_then(|i| self.environments.get(i))
+    }
+
+    pub fn handle_event(&mut self, event: &Event) -> Option<Action> {
         if let Event::Key(key) = event {
             if key.kind != KeyEventKind::Press {
-                return;
+                return None;
             }
             match key.code {
                 KeyCode::Down | KeyCode::Char('j') => self.table.select_next(),
                 KeyCode::Up | KeyCode::Char('k') => self.table.select_prev(),
+                KeyCode::Char('M') if !self.environments.is_empty() => {
+                    return Some(Action::Navigate(View::EnvironmentMatrix));
+                }
+                KeyCode::Char('P') if self.environments.len() > 1 => {
+                    return Some(Action::Navigate(View::Promote));
+                }
                 _ => {}
             }
         }
+        None
     }
 
     pub fn render(&mut self, frame: &mut Frame, area: Rect) {
@@ -87,10 +101,12 @@ impl EnvironmentListView {
         );
 
         frame.render_widget(
-            Paragraph::new(Line::from(vec![Span::styled(
-                "Read-only view",
-                theme::dim(),
-            )])),
+            Paragraph::new(Line::from(vec![
+                Span::styled("[M]", theme::title()),
+                Span::styled(" Compare environments  ", theme::dim()),
+                Span::styled("[P]", theme::title()),
+                Span::styled(" Promote from the selected environment", theme::dim()),
+            ])),
             chunks[2],
         );
     }
@@ -0,0 +1,443 @@
+use crate::action::{Action, View};
+use crate::api::types::{Environment, ManagedConfig, ManagedFlag};
+use crate::event::Event;
+use crate::theme;
+use crossterm::event::{KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState};
+use ratatui::Frame;
+use serde_json::Value;
+
+/// Longest config value shown in a matrix cell before it is truncated.
+const MAX_VALUE_WIDTH: usize = 24;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    Flags,
+    Configs,
+}
+
+/// A flag's state in one environment, as compared across the matrix.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlagCell {
+    pub enabled: bool,
+    pub rollout: i32,
+    pub rules: usize,
+    /// Short hash of the rules JSON, so equal counts with different rules still differ.
+    pub rules_hash: u32,
+}
+
+impl FlagCell {
+    pub fn of(flag: &ManagedFlag, environment_id: &str) -> Option<Self> {
+        let data = flag
+            .environments
+            .iter()
+            .find(|e| e.environment_id == environment_id)?;
+        let rules = match &data.rules {
+            Value::Array(rules) => rules.len(),
+            _ => 0,
+        };
+        Some(Self {
+            enabled: data.enabled,
+            rollout: data.rollout_percentage,
+            rules,
+            rules_hash: if rules == 0 { 0 } else { hash(&data.rules) },
+        })
+    }
+
+    fn label(&self) -> String {
+        let mut label = format!(
+            "{} {:>3}%",
+            if self.enabled { "●" } else { "○" },
+            self.rollout
+        );
+        if self.rules > 0 {
+            label.push_str(&format!(
+                "  {}r #{:04x}",
+                self.rules,
+                self.rules_hash & 0xffff
+            ));
+        }
+        label
+    }
+}
+
+/// A config's value in one environment, `None` when it has none there.
+pub fn config_cell(config: &ManagedConfig, environment_id: &str) -> Option<Value> {
+    config
+        .environments
+        .iter()
+        .find(|e| e.environment_id == environment_id)
+        .map(|e| e.value.clone())
+        .filter(|v| !v.is_null())
+}
+
+/// Every flag (or config) as a row and every environment as a column, with
+/// cells that differ from the default environment highlighted.
+pub struct EnvironmentMatrixView {
+    pub environments: Vec<Environment>,
+    pub flags: Vec<ManagedFlag>,
+    pub configs: Vec<ManagedConfig>,
+    mode: Mode,
+    /// Only show rows with at least one cell that differs from the default environment.
+    differences_only: bool,
+    state: TableState,
+    loaded: bool,
+}
+
+impl EnvironmentMatrixView {
+    pub fn new() -> Self {
+        Self {
+            environments: Vec::new(),
+            flags: Vec::new(),
+            configs: Vec::new(),
+            mode: Mode::Flags,
+            differences_only: false,
+            state: TableState::default(),
+            loaded: false,
+        }
+    }
+
+    pub fn set_data(
+        &mut self,
+        environments: Vec<Environment>,
+        mut flags: Vec<ManagedFlag>,
+        mut configs: Vec<ManagedConfig>,
+    ) {
+        flags.sort_by(|a, b| a.key.cmp(&b.key));
+        configs.sort_by(|a, b| a.key.cmp(&b.key));
+        self.environments = environments;
+        self.flags = flags;
+        self.configs = configs;
+        self.loaded = true;
+        self.clamp_selection();
+    }
+
+    /// The environment every other column is compared against: the one
+    /// marked default, or the first.
+    fn baseline(&self) -> Option<&Environment> {
+        self.environments
+            .iter()
+            .find(|e| e.is_default)
+            .or_else(|| self.environments.first())
+    }
+
+    /// Keys of the visible rows, each with whether its cell in each environment
+    /// differs from the baseline.
+    fn rows(&self) -> Vec<(&str, Vec<bool>)> {
+        let Some(baseline) = self.baseline() else {
+            return Vec::new();
+        };
+        let rows: Vec<(&str, Vec<bool>)> = match self.mode {
+            Mode::Flags => self
+                .flags
+                .iter()
+                .map(|f| {
+                    let base = FlagCell::of(f, &baseline.id);
+                    let diffs = self
+                        .environments
+                        .iter()
+                        .map(|e| FlagCell::of(f, &e.id) != base)
+                        .collect();
+                    (f.key.as_str(), diffs)
+                })
+                .collect(),
+            Mode::Configs => self
+                .configs
+                .iter()
+                .map(|c| {
+                    let base = config_cell(c, &baseline.id);
+                    let diffs = self
+                        .environments
+                        .iter()
+                        .map(|e| config_cell(c, &e.id) != base)
+                        .collect();
+                    (c.key.as_str(), diffs)
+                })
+                .collect(),
+        };
+        rows.into_iter()
+            .filter(|(_, diffs)| !self.differences_only || diffs.iter().any(|&d| d))
+            .collect()
+    }
+
+    fn clamp_selection(&mut self) {
+        let count = self.rows().len();
+        self.state.select(match (count, self.state.selected()) {
+            (0, _) => None,
+            (_, Some(i)) => Some(i.min(count - 1)),
+            (_, None) => Some(0),
+        });
+    }
+
+    fn selected_key(&self) -> Option<String> {
+        let rows = self.rows();
+        let (key, _) = rows.get(self.state.selected()?)?;
+        Some(key.to_string())
+    }
+
+    pub fn handle_event(&mut self, event: &Event) -> Option<Action> {
+        let Event::Key(key) = event else { return None };
+        if key.kind != KeyEventKind::Press {
+            return None;
+        }
+        let count = self.rows().len();
+        match key.code {
+            KeyCode::Esc | KeyCode::Backspace => {
+                return Some(Action::Navigate(View::EnvironmentList));
+            }
+            KeyCode::Down | KeyCode::Char('j') if count > 0 => {
+                let i = self.state.selected().unwrap_or(0);
+                self.state.select(Some((i + 1) % count));
+            }
+            KeyCode::Up | KeyCode::Char('k') if count > 0 => {
+                let i = self.state.selected().unwrap_or(0);
+                self.state.select(Some((i + count - 1) % count));
+            }
+            KeyCode::Tab | KeyCode::BackTab => {
+                self.mode = match self.mode {
+                    Mode::Flags => Mode::Configs,
+                    Mode::Configs => Mode::Flags,
+                };
+                self.state.select(None);
+                self.clamp_selection();
+            }
+            KeyCode::Char('d') => {
+                self.differences_only = !self.differences_only;
+                self.clamp_selection();
+            }
+            KeyCode::Char('R') => return Some(Action::LoadEnvironmentState),
+            KeyCode::Enter => {
+                let key = self.selected_key()?;
+                return Some(Action::Navigate(match self.mode {
+                    Mode::Flags => View::FlagDetail(key),
+                    Mode::Configs => View::ConfigDetail(key),
+                }));
+            }
+            _ => {}
+        }
+        None
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let chunks = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Min(5),
+            Constraint::Length(1),
+        ])
+        .split(area);
+
+        let tab = |label: &'static str, active: bool| {
+            Span::styled(
+                format!(" {} ", label),
+                if active {
+                    theme::selected()
+                } else {
+                    theme::dim()
+                },
+            )
+        };
+        let baseline = self.baseline().map(|e| e.name.clone()).unwrap_or_default();
+        frame.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::styled("Compare environments  ", theme::heading()),
+                tab("Flags", self.mode == Mode::Flags),
+                tab("Configs", self.mode == Mode::Configs),
+                Span::styled(
+                    format!("  highlighted cells differ from {}", baseline),
+                    theme::dim(),
+                ),
+            ])),
+            chunks[0],
+        );
+
+        let baseline_id = self.baseline().map(|e| e.id.clone()).unwrap_or_default();
+        let rows: Vec<Row> = self
+            .rows()
+            .into_iter()
+            .map(|(key, diffs)| {
+                let mut cells = vec![Cell::from(key.to_string()).style(theme::normal())];
+                for (env, differs) in self.environments.iter().zip(diffs) {
+                    let text = match self.mode {
+                        Mode::Flags => self
+                            .flags
+                            .iter()
+                            .find(|f| f.key == key)
+                            .and_then(|f| FlagCell::of(f, &env.id))
+                            .map(|c| c.label()),
+                        Mode::Configs => self
+                            .configs
+                            .iter()
+                            .find(|c| c.key == key)
+                            .and_then(|c| config_cell(c, &env.id))
+                            .map(|v| truncate(&v.to_string(), MAX_VALUE_WIDTH)),
+                    }
+                    .unwrap_or_else(|| "-".to_string());
+                    cells.push(Cell::from(text).style(cell_style(differs, env.id == baseline_id)));
+                }
+                Row::new(cells)
+            })
+            .collect();
+        let row_count = rows.len();
+
+        let mut widths = vec![Constraint::Percentage(25)];
+        let columns = self.environments.len().max(1) as u16;
+        widths.extend(
+            self.environments
+                .iter()
+                .map(|_| Constraint::Percentage(75 / columns)),
+        );
+        let mut header = vec![Cell::from("Key")];
+        header.extend(self.environments.iter().map(|e| {
+            Cell::from(if e.id == baseline_id {
+                format!("{} *", e.name)
+            } else {
+                e.name.clone()
+            })
+        }));
+
+        let title = match (self.loaded, self.mode) {
+            (false, _) => " Loading… ".to_string(),
+            (true, Mode::Flags) => format!(" Flags ({}) ", row_count),
+            (true, Mode::Configs) => format!(" Configs ({}) ", row_count),
+        };
+        let table = Table::new(rows, widths)
+            .header(Row::new(header).style(theme::heading()))
+            .block(
+                Block::default()
+                    .title(title)
+                    .title_style(theme::heading())
+                    .borders(Borders::ALL)
+                    .border_style(theme::border()),
+            )
+            .highlight_style(theme::highlight());
+        frame.render_stateful_widget(table, chunks[1], &mut self.state);
+
+        let hints = Paragraph::new(Line::from(vec![
+            Span::styled("[Tab]", theme::title()),
+            Span::styled(" Flags/configs  ", theme::dim()),
+            Span::styled("[d]", theme::title()),
+            Span::styled(
+                if self.differences_only {
+                    " Show all  "
+                } else {
+                    " Differences only  "
+                },
+                theme::dim(),
+            ),
+            Span::styled("[Enter]", theme::title()),
+            Span::styled(" Open  ", theme::dim()),
+            Span::styled("[R]", theme::title()),
+            Span::styled(" Refresh  ", theme::dim()),
+            Span::styled("[Esc]", theme::title()),
+            Span::styled(" Back", theme::dim()),
+        ]));
+        frame.render_widget(hints, chunks[2]);
+    }
+}
+
+fn cell_style(differs: bool, baseline: bool) -> Style {
+    if differs {
+        theme::warning()
+    } else if baseline {
+        theme::normal()
+    } else {
+        theme::dim()
+    }
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        text.to_string()
+    } else {
+        let mut out: String = text.chars().take(max - 1).collect();
+        out.push('…');
+        out
+    }
+}
+
+/// FNV-1a over the JSON text; stable across runs, unlike the std hasher.
+fn hash(value: &Value) -> u32 {
+    value
+        .to_string()
+        .bytes()
+        .fold(0x811c_9dc5, |h, b| (h ^ b as u32).wrapping_mul(0x0100_0193))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::types::FlagEnvironmentData;
+    use serde_json::json;
+
+    fn env(id: &str, is_default: bool) -> Environment {
+        Environment {
+            id: id.into(),
+            name: id.to_uppercase(),
+            slug: id.into(),
+            is_default,
+            created_at: "2026-01-01T00:00:00Z".parse().unwrap(),
+        }
+    }
+
+    fn state(env: &str, enabled: bool, rollout: i32, rules: Value) -> FlagEnvironmentData {
+        FlagEnvironmentData {
+            id: format!("fe_{}", env),
+            environment_id: env.into(),
+            enabled,
+            value: Value::Null,
+            rules,
+            rollout_percentage: rollout,
+        }
+    }
+
+    #[test]
+    fn test_rows_compare_against_default_environment() {
+        let rule = json!([{"clauses": [], "value": true}]);
+        let flag = ManagedFlag {
+            id: "flg_1".into(),
+            key: "checkout".into(),
+            name: "Checkout".into(),
+            description: String::new(),
+            flag_type: "boolean".into(),
+            default_value: json!(false),
+            tags: vec![],
+            is_archived: false,
+            created_at: "2026-01-01T00:00:00Z".parse().unwrap(),
+            updated_at: "2026-01-01T00:00:00Z".parse().unwrap(),
+            environments: vec![
+                state("dev", true, 100, rule.clone()),
+                state("staging", true, 100, rule),
+                state("prod", true, 100, json!([{"clauses": [], "value": false}])),
+            ],
+        };
+        let mut same = flag.clone();
+        same.key = "same".into();
+        same.environments.truncate(2);
+        same.environments.push(state(
+            "prod",
+            true,
+            100,
+            json!([{"clauses": [], "value": true}]),
+        ));
+
+        let mut view = EnvironmentMatrixView::new();
+        view.set_data(
+            vec![env("prod", false), env("dev", true), env("staging", false)],
+            vec![same, flag],
+            vec![],
+        );
+        assert_eq!(
+            view.rows(),
+            vec![
+                ("checkout", vec![true, false, false]),
+                ("same", vec![false, false, false]),
+            ]
+        );
+        view.differences_only = true;
+        assert_eq!(view.rows().len(), 1);
+        assert_eq!(view.selected_key().as_deref(), Some("checkout"));
+    }
+}
@@ -1 +1,3 @@
 pub mod list;
+pub mod matrix;
+pub mod promote;
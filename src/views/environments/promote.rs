@@ -0,0 +1,413 @@
+use crate::action::{Action, ConfirmAction, View};
+use crate::api::types::{Environment, ManagedConfig, ManagedFlag};
+use crate::config::KeyTier;
+use crate::event::Event;
+use crate::manifest::plan::Change;
+use crate::manifest::promote::{PromoteFilter, Promotion};
+use crate::theme;
+use crossterm::event::{KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState, Wrap};
+use ratatui::Frame;
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Focus {
+    From,
+    To,
+    Changes,
+}
+
+/// Copy flag and config state between environments: pick a source and a
+/// target, review the per-field diff, and apply the selected changes.
+pub struct PromoteView {
+    pub key_tier: KeyTier,
+    pub environments: Vec<Environment>,
+    flags: Vec<ManagedFlag>,
+    configs: Vec<ManagedConfig>,
+    from: usize,
+    to: usize,
+    focus: Focus,
+    promotion: Option<Promotion>,
+    /// Whether each change in `promotion` will be applied.
+    selected: Vec<bool>,
+    error: Option<String>,
+    state: TableState,
+    loaded: bool,
+    preferred_from: String,
+    preferred_to: String,
+}
+
+impl PromoteView {
+    /// Promote from `from_env` into `to_env` once data arrives.
+    pub fn new(from_env: &str, to_env: &str, key_tier: KeyTier) -> Self {
+        Self {
+            key_tier,
+            environments: Vec::new(),
+            flags: Vec::new(),
+            configs: Vec::new(),
+            from: 0,
+            to: 0,
+            focus: Focus::Changes,
+            promotion: None,
+            selected: Vec::new(),
+            error: None,
+            state: TableState::default(),
+            loaded: false,
+            preferred_from: from_env.to_string(),
+            preferred_to: to_env.to_string(),
+        }
+    }
+
+    pub fn set_data(
+        &mut self,
+        environments: Vec<Environment>,
+        flags: Vec<ManagedFlag>,
+        configs: Vec<ManagedConfig>,
+    ) {
+        let position = |id: &str| environments.iter().position(|e| e.id == id);
+        self.from = position(&self.preferred_from).unwrap_or(0);
+        self.to = position(&self.preferred_to)
+            .filter(|&i| i != self.from)
+            .unwrap_or_else(|| (self.from + 1) % environments.len().max(1));
+        self.environments = environments;
+        self.flags = flags;
+        self.configs = configs;
+        self.loaded = true;
+        self.recompute();
+    }
+
+    /// The changes ticked for applying.
+    pub fn selected_changes(&self) -> Vec<Change> {
+        self.promotion
+            .iter()
+            .flat_map(|p| p.changes.iter())
+            .zip(&self.selected)
+            .filter(|(_, &on)| on)
+            .map(|(c, _)| c.clone())
+            .collect()
+    }
+
+    fn recompute(&mut self) {
+        let (Some(from), Some(to)) = (
+            self.environments.get(self.from),
+            self.environments.get(self.to),
+        ) else {
+            return;
+        };
+        self.preferred_from = from.id.clone();
+        self.preferred_to = to.id.clone();
+        match Promotion::compute(
+            &self.flags,
+            &self.configs,
+            from,
+            to,
+            &PromoteFilter::default(),
+        ) {
+            Ok(p) => {
+                self.selected = vec![true; p.changes.len()];
+                self.state
+                    .select(if p.changes.is_empty() { None } else { Some(0) });
+                self.promotion = Some(p);
+                self.error = None;
+            }
+            Err(e) => {
+                self.selected.clear();
+                self.state.select(None);
+                self.promotion = None;
+                self.error = Some(e.to_string());
+            }
+        }
+    }
+
+    fn cycle_environment(&mut self, forward: bool) {
+        let n = self.environments.len();
+        if n == 0 {
+            return;
+        }
+        let index = match self.focus {
+            Focus::From => &mut self.from,
+            Focus::To => &mut self.to,
+            Focus::Changes => return,
+        };
+        *index = if forward {
+            (*index + 1) % n
+        } else {
+            (*index + n - 1) % n
+        };
+        self.recompute();
+    }
+
+    pub fn handle_event(&mut self, event: &Event) -> Option<Action> {
+        let Event::Key(key) = event else { return None };
+        if key.kind != KeyEventKind::Press {
+            return None;
+        }
+        let count = self.selected.len();
+        match key.code {
+            KeyCode::Esc | KeyCode::Backspace => {
+                return Some(Action::Navigate(View::EnvironmentList));
+            }
+            KeyCode::Tab => {
+                self.focus = match self.focus {
+                    Focus::From => Focus::To,
+                    Focus::To => Focus::Changes,
+                    Focus::Changes => Focus::From,
+                };
+            }
+            KeyCode::BackTab => {
+                self.focus = match self.focus {
+                    Focus::From => Focus::Changes,
+                    Focus::To => Focus::From,
+                    Focus::Changes => Focus::To,
+                };
+            }
+            KeyCode::Left | KeyCode::Char('h') => self.cycle_environment(false),
+            KeyCode::Right | KeyCode::Char('l') => self.cycle_environment(true),
+            KeyCode::Char('s') => {
+                std::mem::swap(&mut self.from, &mut self.to);
+                self.recompute();
+            }
+            KeyCode::Down | KeyCode::Char('j') if count > 0 => {
+                let i = self.state.selected().unwrap_or(0);
+                self.state.select(Some((i + 1) % count));
+            }
+            KeyCode::Up | KeyCode::Char('k') if count > 0 => {
+                let i = self.state.selected().unwrap_or(0);
+                self.state.select(Some((i + count - 1) % count));
+            }
+            KeyCode::Char(' ') => {
+                if let Some(on) = self.state.selected().and_then(|i| self.selected.get_mut(i)) {
+                    *on = !*on;
+                }
+            }
+            KeyCode::Char('a') => {
+                let all = self.selected.iter().all(|&on| on);
+                self.selected.iter_mut().for_each(|on| *on = !all);
+            }
+            KeyCode::Char('R') => return Some(Action::LoadEnvironmentState),
+            KeyCode::Enter if self.key_tier.can_mutate() => {
+                let changes = self.selected_changes();
+                let promotion = self.promotion.as_ref()?;
+                if changes.is_empty() {
+                    return None;
+                }
+                return Some(Action::ShowConfirm(ConfirmAction::Promote {
+                    from: promotion.from.clone(),
+                    to: promotion.to.clone(),
+                    changes,
+                }));
+            }
+            _ => {}
+        }
+        None
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let chunks = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Length(2),
+            Constraint::Min(5),
+            Constraint::Length(8),
+            Constraint::Length(1),
+        ])
+        .split(area);
+
+        frame.render_widget(
+            Paragraph::new(Line::from(Span::styled(
+                "Promote between environments",
+                theme::heading(),
+            ))),
+            chunks[0],
+        );
+
+        let env_name = |i: usize| {
+            self.environments
+                .get(i)
+                .map(|e| e.name.clone())
+                .unwrap_or_else(|| "(none)".to_string())
+        };
+        let picker = |label: &'static str, name: String, focused: bool| {
+            [
+                Span::styled(label, theme::dim()),
+                Span::styled("◀ ", theme::dim()),
+                Span::styled(
+                    name,
+                    if focused {
+                        theme::highlight()
+                    } else {
+                        theme::normal()
+                    },
+                ),
+                Span::styled(" ▶", theme::dim()),
+            ]
+        };
+        let mut line = picker("From: ", env_name(self.from), self.focus == Focus::From).to_vec();
+        line.push(Span::styled("   →   ", theme::dim()));
+        line.extend(picker("To: ", env_name(self.to), self.focus == Focus::To));
+        frame.render_widget(Paragraph::new(Line::from(line)), chunks[1]);
+
+        let changes: &[Change] = self
+            .promotion
+            .as_ref()
+            .map(|p| p.changes.as_slice())
+            .unwrap_or_default();
+        let rows: Vec<Row> = changes
+            .iter()
+            .zip(&self.selected)
+            .map(|(c, &on)| {
+                let (before, after) = c
+                    .fields
+                    .first()
+                    .map(|f| (summarize(&f.before), summarize(&f.after)))
+                    .unwrap_or_default();
+                Row::new(vec![
+                    Cell::from(if on { "[x]" } else { "[ ]" }).style(if on {
+                        theme::status_on()
+                    } else {
+                        theme::dim()
+                    }),
+                    Cell::from(format!("{} {}", c.kind.symbol(), c.resource))
+                        .style(theme::normal()),
+                    Cell::from(resource_key(&c.address)).style(theme::normal()),
+                    Cell::from(before).style(theme::dim()),
+                    Cell::from(after).style(theme::normal()),
+                ])
+            })
+            .collect();
+        let title = if !self.loaded {
+            " Changes (loading…) ".to_string()
+        } else {
+            format!(
+                " Changes ({} of {} selected) ",
+                self.selected.iter().filter(|&&on| on).count(),
+                changes.len()
+            )
+        };
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(4),
+                Constraint::Length(16),
+                Constraint::Percentage(30),
+                Constraint::Percentage(25),
+                Constraint::Percentage(25),
+            ],
+        )
+        .header(
+            Row::new(vec!["", "Change", "Key", "Target now", "From source"])
+                .style(theme::heading()),
+        )
+        .block(
+            Block::default()
+                .title(title)
+                .title_style(theme::heading())
+                .borders(Borders::ALL)
+                .border_style(if self.focus == Focus::Changes {
+                    theme::active_border()
+                } else {
+                    theme::border()
+                }),
+        )
+        .highlight_style(theme::highlight());
+        frame.render_stateful_widget(table, chunks[2], &mut self.state);
+
+        self.render_detail(frame, chunks[3]);
+
+        let mut hints = vec![
+            Span::styled("[Tab]", theme::title()),
+            Span::styled(" Focus  ", theme::dim()),
+            Span::styled("[←→]", theme::title()),
+            Span::styled(" Environment  ", theme::dim()),
+            Span::styled("[s]", theme::title()),
+            Span::styled(" Swap  ", theme::dim()),
+            Span::styled("[Space]", theme::title()),
+            Span::styled(" Select  ", theme::dim()),
+            Span::styled("[a]", theme::title()),
+            Span::styled(" All/none  ", theme::dim()),
+            Span::styled("[R]", theme::title()),
+            Span::styled(" Refresh  ", theme::dim()),
+        ];
+        if self.key_tier.can_mutate() {
+            hints.push(Span::styled("[Enter]", theme::title()));
+            hints.push(Span::styled(" Apply  ", theme::dim()));
+        }
+        hints.push(Span::styled("[Esc]", theme::title()));
+        hints.push(Span::styled(" Back", theme::dim()));
+        frame.render_widget(Paragraph::new(Line::from(hints)), chunks[4]);
+    }
+
+    fn render_detail(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .title(" Details ")
+            .title_style(theme::heading())
+            .borders(Borders::ALL)
+            .border_style(theme::border());
+
+        let mut lines = Vec::new();
+        if let Some(error) = &self.error {
+            lines.push(Line::from(Span::styled(
+                error.as_str(),
+                theme::status_off(),
+            )));
+        }
+        let change = self
+            .state
+            .selected()
+            .and_then(|i| self.promotion.as_ref()?.changes.get(i));
+        if let Some(change) = change {
+            lines.push(Line::from(Span::styled(
+                format!("{} {}", change.resource, change.address),
+                theme::heading(),
+            )));
+            for field in &change.fields {
+                lines.push(Line::from(vec![
+                    Span::styled(format!("{}: ", field.field), theme::dim()),
+                    Span::styled(field.before.to_string(), theme::status_off()),
+                ]));
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("{:width$}  ", "→", width = field.field.len()),
+                        theme::dim(),
+                    ),
+                    Span::styled(field.after.to_string(), theme::status_on()),
+                ]));
+            }
+        } else if self.loaded && self.error.is_none() {
+            lines.push(Line::from(Span::styled(
+                "No differences to promote.",
+                theme::dim(),
+            )));
+        }
+        for note in self.promotion.iter().flat_map(|p| &p.notes) {
+            lines.push(Line::from(Span::styled(
+                format!("Note: {}", note),
+                theme::dim(),
+            )));
+        }
+
+        frame.render_widget(
+            Paragraph::new(lines)
+                .block(block)
+                .wrap(Wrap { trim: false }),
+            area,
+        );
+    }
+}
+
+/// `checkout[production]` → `checkout`.
+fn resource_key(address: &str) -> &str {
+    address.split('[').next().unwrap_or(address)
+}
+
+/// A one-line summary of a field value for the change table.
+fn summarize(value: &Value) -> String {
+    match value {
+        Value::Null => "-".to_string(),
+        Value::Array(items) if items.is_empty() => "none".to_string(),
+        Value::Array(items) => format!("{} item(s)", items.len()),
+        Value::Object(_) => "{…}".to_string(),
+        other => other.to_string(),
+    }
+}
@@ -3,6 +3,7 @@ use crate::api::types::ManagedFlag;
 use crate::config::KeyTier;
 use crate::event::Event;
 use crate::theme;
+use crate::views::flags::simulate::SimulatePanel;
 use crossterm::event::{KeyCode, KeyEventKind};
 use ratatui::layout::{Constraint, Layout, Rect};
 use ratatui::text::{Line, Span};
@@ -12,6 +13,7 @@ use ratatui::Frame;
 pub struct FlagDetailView {
     pub flag: Option<ManagedFlag>,
     pub key_tier: KeyTier,
+    pub simulate: Option<SimulatePanel>,
 }
 
 impl FlagDetailView {
@@ -19,6 +21,7 @@ impl FlagDetailView {
         Self {
             flag: None,
             key_tier,
+            simulate: None,
         }
     }
 
@@ -28,6 +31,14 @@ impl FlagDetailView {
                 return None;
             }
             let flag = self.flag.as_ref()?;
+            if let Some(panel) = &mut self.simulate {
+                if panel.handle_event(event) {
+                    panel.refresh(Some(flag));
+                } else {
+                    self.simulate = None;
+                }
+                return None;
+            }
             match key.code {
                 KeyCode::Esc | KeyCode::Backspace => {
                     return Some(Action::Navigate(View::FlagList));
@@ -50,6 +61,9 @@ impl FlagDetailView {
                 KeyCode::Char('s') => {
                     return Some(Action::Navigate(View::FlagSchedules(flag.key.clone())));
                 }
+                KeyCode::Char('m') => {
+                    return Some(Action::OpenSimulator(flag.key.clone()));
+                }
                 _ => {}
             }
         }
@@ -121,6 +135,18 @@ impl FlagDetailView {
             }
         }
 
+        if let Some(panel) = &self.simulate {
+            panel.render(frame, chunks[2], Some(flag));
+            let hints = Paragraph::new(Line::from(vec![
+                Span::styled("[Esc]", theme::title()),
+                Span::styled("Close simulator ", theme::dim()),
+                Span::styled("[Shift+Tab]", theme::title()),
+                Span::styled("Environment", theme::dim()),
+            ]));
+            frame.render_widget(hints, chunks[3]);
+            return;
+        }
+
         // Environments table
         let env_rows: Vec<Row> = flag
             .environments
@@ -181,7 +207,9 @@ impl FlagDetailView {
         }
         spans.extend([
             Span::styled("[s]", theme::title()),
-            Span::styled("Schedules", theme::dim()),
+            Span::styled("Schedules ", theme::dim()),
+            Span::styled("[m]", theme::title()),
+            Span::styled("Simulate", theme::dim()),
         ]);
         frame.render_widget(Paragraph::new(Line::from(spans)), chunks[3]);
     }
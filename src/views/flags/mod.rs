@@ -4,5 +4,6 @@ pub mod list;
 pub mod rollout;
 pub mod rules;
 pub mod schedules;
+pub mod simulate;
 pub mod toggle;
 pub mod variations;
@@ -1,41 +1,207 @@
-use crate::action::{Action, View};
-use crate::api::types::Environment;
+use crate::action::{Action, ConfirmAction, View};
+use crate::api::types::{CreateScheduleRequest, Environment, ManagedFlag, Schedule};
+use crate::components::input_field::InputField;
+use crate::config::KeyTier;
 use crate::event::Event;
+use crate::rollout;
 use crate::theme;
+use chrono::{DateTime, Local, Utc};
 use crossterm::event::{KeyCode, KeyEventKind};
 use ratatui::layout::{Alignment, Constraint, Layout, Rect};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Gauge, Paragraph};
+use ratatui::widgets::{Block, Borders, Gauge, Paragraph, Wrap};
 use ratatui::Frame;
 
+/// New rollout plan: steps, the default gap between them, and when to start.
+struct PlanForm {
+    steps: InputField,
+    interval: InputField,
+    start: InputField,
+    focused: usize,
+    error: Option<String>,
+}
+
+impl PlanForm {
+    fn new(steps: &str) -> Self {
+        let mut form = Self {
+            steps: InputField::new("Steps (PERCENT[@DELAY], comma-separated)"),
+            interval: InputField::new("Default delay").with_placeholder("e.g. 12h, 1d, 1w"),
+            start: InputField::new("Start").with_placeholder("now, +2h or RFC 3339"),
+            focused: 0,
+            error: None,
+        };
+        form.steps.set_value(steps);
+        form.interval.set_value(rollout::DEFAULT_INTERVAL);
+        form.start.set_value("now");
+        form.update_focus();
+        form
+    }
+
+    fn update_focus(&mut self) {
+        self.steps.focused = self.focused == 0;
+        self.interval.focused = self.focused == 1;
+        self.start.focused = self.focused == 2;
+    }
+
+    fn focused_input(&mut self) -> &mut InputField {
+        match self.focused {
+            0 => &mut self.steps,
+            1 => &mut self.interval,
+            _ => &mut self.start,
+        }
+    }
+
+    /// When each step will run, or why the plan is invalid.
+    fn timeline(&self, now: DateTime<Utc>) -> Result<Vec<(i32, DateTime<Utc>)>, String> {
+        let interval = rollout::parse_duration(&self.interval.value)
+            .map_err(|e| format!("Default delay: {}", e))?;
+        let steps = rollout::parse_steps(&self.steps.value, interval)
+            .map_err(|e| format!("Steps: {:#}", e))?;
+        let start =
+            rollout::parse_start(&self.start.value, now).map_err(|e| format!("Start: {:#}", e))?;
+        rollout::timeline(&steps, start, now).map_err(|e| format!("Steps: {:#}", e))
+    }
+}
+
 pub struct FlagRolloutView {
     pub flag_key: String,
+    pub key_tier: KeyTier,
     pub environments: Vec<Environment>,
     pub selected_env: usize,
     pub percentage: i32,
+    pub flag: Option<ManagedFlag>,
+    /// Schedules for the selected environment; the `set_rollout` ones form the plan.
+    pub schedules: Vec<Schedule>,
+    form: Option<PlanForm>,
+    /// Steps cancelled by the last pause, offered again when a new plan is started.
+    paused: Option<String>,
+    preferred_env: String,
 }
 
 impl FlagRolloutView {
-    pub fn new(flag_key: &str) -> Self {
+    pub fn new(flag_key: &str, key_tier: KeyTier) -> Self {
         Self {
             flag_key: flag_key.to_string(),
+            key_tier,
             environments: Vec::new(),
             selected_env: 0,
             percentage: 0,
+            flag: None,
+            schedules: Vec::new(),
+            form: None,
+            paused: None,
+            preferred_env: String::new(),
         }
     }
 
+    /// Start on `environment_id` once environments arrive.
+    pub fn with_environment(mut self, environment_id: &str) -> Self {
+        self.preferred_env = environment_id.to_string();
+        self
+    }
+
+    pub fn set_environments(&mut self, environments: Vec<Environment>) {
+        self.selected_env = environments
+            .iter()
+            .position(|e| e.id == self.preferred_env)
+            .unwrap_or(0);
+        self.environments = environments;
+        self.sync_percentage();
+    }
+
+    pub fn set_flag(&mut self, flag: ManagedFlag) {
+        self.flag = Some(flag);
+        self.sync_percentage();
+    }
+
+    pub fn set_schedules(&mut self, schedules: Vec<Schedule>) {
+        self.schedules = schedules;
+    }
+
     pub fn selected_environment_id(&self) -> Option<&str> {
         self.environments
             .get(self.selected_env)
             .map(|e| e.id.as_str())
     }
 
+    /// The plan form is open and capturing keys.
+    pub fn is_editing(&self) -> bool {
+        self.form.is_some()
+    }
+
+    /// Called once the plan's schedules exist: close the form and forget any pause.
+    pub fn plan_created(&mut self) {
+        self.form = None;
+        self.paused = None;
+    }
+
+    /// Remember the steps a pause cancelled so `n` can resume them.
+    pub fn set_paused(&mut self, remaining: Option<String>) {
+        self.paused = remaining;
+    }
+
+    /// The schedules to create for the plan in the form, or why it can't be created.
+    pub fn plan_requests(&self) -> Result<Vec<CreateScheduleRequest>, String> {
+        let form = self.form.as_ref().ok_or("No rollout plan form open")?;
+        Ok(form
+            .timeline(Utc::now())?
+            .into_iter()
+            .map(|(pct, at)| rollout::step_request(at, pct))
+            .collect())
+    }
+
+    pub fn set_form_error(&mut self, error: String) {
+        if let Some(form) = &mut self.form {
+            form.error = Some(error);
+        }
+    }
+
+    /// The action that reloads schedules for the selected environment.
+    pub fn load_action(&self) -> Option<Action> {
+        Some(Action::LoadSchedules {
+            flag_key: self.flag_key.clone(),
+            environment_id: self.selected_environment_id()?.to_string(),
+        })
+    }
+
+    /// Show the selected environment's current rollout on the gauge.
+    fn sync_percentage(&mut self) {
+        let Some(env_id) = self.selected_environment_id() else {
+            return;
+        };
+        if let Some(state) = self
+            .flag
+            .as_ref()
+            .and_then(|f| f.environments.iter().find(|e| e.environment_id == env_id))
+        {
+            self.percentage = state.rollout_percentage;
+        }
+    }
+
+    /// Confirmation for cancelling the plan's remaining steps. Pausing keeps
+    /// them so the plan can be resumed with the same spacing.
+    fn stop_plan(&self, pause: bool) -> Option<Action> {
+        let pending = rollout::pending(&self.schedules);
+        if pending.is_empty() {
+            return None;
+        }
+        let resume_steps =
+            pause.then(|| rollout::format_steps(&rollout::remaining_steps(&pending, Utc::now())));
+        Some(Action::ShowConfirm(ConfirmAction::CancelRolloutPlan {
+            flag_key: self.flag_key.clone(),
+            schedule_ids: pending.iter().map(|s| s.id.clone()).collect(),
+            resume_steps,
+        }))
+    }
+
     pub fn handle_event(&mut self, event: &Event) -> Option<Action> {
         if let Event::Key(key) = event {
             if key.kind != KeyEventKind::Press {
                 return None;
             }
+            if self.form.is_some() {
+                return self.handle_form_event(event);
+            }
             match key.code {
                 KeyCode::Esc | KeyCode::Backspace => {
                     return Some(Action::Navigate(View::FlagDetail(self.flag_key.clone())));
@@ -55,22 +221,60 @@ impl FlagRolloutView {
                 KeyCode::Char('0') => self.percentage = 0,
                 KeyCode::Char('5') => self.percentage = 50,
                 KeyCode::Char('9') => self.percentage = 100,
-                KeyCode::Tab => {
-                    if !self.environments.is_empty() {
-                        self.selected_env = (self.selected_env + 1) % self.environments.len();
-                    }
+                KeyCode::Tab if !self.environments.is_empty() => {
+                    self.selected_env = (self.selected_env + 1) % self.environments.len();
+                    self.preferred_env = self.environments[self.selected_env].id.clone();
+                    self.schedules.clear();
+                    self.paused = None;
+                    self.sync_percentage();
+                    return self.load_action();
                 }
-                KeyCode::Enter => {
-                    if !self.environments.is_empty() {
-                        return Some(Action::SubmitRolloutUpdate(self.flag_key.clone()));
-                    }
+                KeyCode::Enter if !self.environments.is_empty() => {
+                    return Some(Action::SubmitRolloutUpdate(self.flag_key.clone()));
+                }
+                KeyCode::Char('n') if self.key_tier.can_mutate() => {
+                    let steps = self.paused.as_deref().unwrap_or(rollout::DEFAULT_STEPS);
+                    self.form = Some(PlanForm::new(steps));
                 }
+                KeyCode::Char('p') if self.key_tier.can_mutate() => return self.stop_plan(true),
+                KeyCode::Char('x') if self.key_tier.can_mutate() => return self.stop_plan(false),
+                KeyCode::Char('R') => return self.load_action(),
                 _ => {}
             }
         }
         None
     }
 
+    fn handle_form_event(&mut self, event: &Event) -> Option<Action> {
+        let Event::Key(key) = event else { return None };
+        let form = self.form.as_mut()?;
+        form.error = None;
+        match key.code {
+            KeyCode::Esc => self.form = None,
+            KeyCode::Tab | KeyCode::Down => {
+                form.focused = (form.focused + 1) % 3;
+                form.update_focus();
+            }
+            KeyCode::BackTab | KeyCode::Up => {
+                form.focused = (form.focused + 2) % 3;
+                form.update_focus();
+            }
+            KeyCode::Enter => {
+                return match self.plan_requests() {
+                    Ok(_) => Some(Action::SubmitRolloutPlan(self.flag_key.clone())),
+                    Err(e) => {
+                        self.set_form_error(e);
+                        None
+                    }
+                };
+            }
+            _ => {
+                form.focused_input().handle_event(event);
+            }
+        }
+        None
+    }
+
     pub fn render(&self, frame: &mut Frame, area: Rect) {
         let chunks = Layout::vertical([
             Constraint::Length(2),
@@ -114,7 +318,7 @@ impl FlagRolloutView {
         frame.render_widget(gauge, chunks[2]);
 
         // Hints
-        let hints = Paragraph::new(Line::from(vec![
+        let mut hints = vec![
             Span::styled("←→", theme::title()),
             Span::styled(" ±5%  ", theme::dim()),
             Span::styled("↑↓", theme::title()),
@@ -127,10 +331,134 @@ impl FlagRolloutView {
             Span::styled(" 0/50/100%  ", theme::dim()),
             Span::styled("[Enter]", theme::title()),
             Span::styled(" Save  ", theme::dim()),
-            Span::styled("[Esc]", theme::title()),
-            Span::styled(" Back", theme::dim()),
-        ]))
-        .alignment(Alignment::Center);
+        ];
+        if self.key_tier.can_mutate() {
+            hints.push(Span::styled("[n]", theme::title()));
+            hints.push(Span::styled(
+                if self.paused.is_some() {
+                    " Resume plan  "
+                } else {
+                    " New plan  "
+                },
+                theme::dim(),
+            ));
+            if !rollout::pending(&self.schedules).is_empty() {
+                hints.push(Span::styled("[p]", theme::title()));
+                hints.push(Span::styled(" Pause  ", theme::dim()));
+                hints.push(Span::styled("[x]", theme::title()));
+                hints.push(Span::styled(" Abort  ", theme::dim()));
+            }
+        }
+        hints.push(Span::styled("[Esc]", theme::title()));
+        hints.push(Span::styled(" Back", theme::dim()));
+        let hints = Paragraph::new(Line::from(hints))
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true });
         frame.render_widget(hints, chunks[3]);
+
+        match &self.form {
+            Some(form) => render_form(frame, chunks[4], form),
+            None => self.render_timeline(frame, chunks[4]),
+        }
+    }
+
+    fn render_timeline(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .title(" Rollout plan ")
+            .title_style(theme::heading())
+            .borders(Borders::ALL)
+            .border_style(theme::border());
+
+        let plan = rollout::plan_schedules(&self.schedules);
+        let mut lines: Vec<Line> = plan
+            .iter()
+            .filter_map(|s| {
+                let pct = rollout::step_percentage(s)?;
+                let (marker, style) = match s.status.as_str() {
+                    "executed" => ("✓", theme::status_on()),
+                    "cancelled" | "failed" => ("✗", theme::status_off()),
+                    _ => ("○", theme::normal()),
+                };
+                Some(Line::from(vec![
+                    Span::styled(format!(" {} ", marker), style),
+                    Span::styled(format!("{:>4}%  ", pct), theme::normal()),
+                    Span::styled(format_time(s.scheduled_at), theme::dim()),
+                    Span::styled(format!("  {}", s.status), style),
+                ]))
+            })
+            .collect();
+        if lines.is_empty() {
+            lines.push(Line::from(Span::styled(
+                " No rollout steps scheduled in this environment",
+                theme::dim(),
+            )));
+        }
+        if let Some(steps) = &self.paused {
+            lines.push(Line::from(""));
+            lines.push(Line::from(vec![
+                Span::styled(" Paused. ", theme::warning()),
+                Span::styled(format!("[n] resumes with {}", steps), theme::dim()),
+            ]));
+        }
+
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+}
+
+fn render_form(frame: &mut Frame, area: Rect, form: &PlanForm) {
+    let block = Block::default()
+        .title(" New rollout plan ")
+        .title_style(theme::heading())
+        .borders(Borders::ALL)
+        .border_style(theme::active_border());
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let rows = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Length(3),
+        Constraint::Min(0),
+    ])
+    .split(inner);
+    form.steps.render(frame, rows[0]);
+    let when =
+        Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)]).split(rows[1]);
+    form.interval.render(frame, when[0]);
+    form.start.render(frame, when[1]);
+
+    let mut lines = Vec::new();
+    match form.timeline(Utc::now()) {
+        Ok(timeline) => {
+            for (pct, at) in timeline {
+                lines.push(Line::from(vec![
+                    Span::styled(" ○ ", theme::dim()),
+                    Span::styled(format!("{:>4}%  ", pct), theme::normal()),
+                    Span::styled(format_time(at), theme::dim()),
+                ]));
+            }
+        }
+        Err(e) => lines.push(Line::from(Span::styled(e, theme::status_off()))),
+    }
+    if let Some(error) = &form.error {
+        lines.push(Line::from(Span::styled(
+            error.as_str(),
+            theme::status_off(),
+        )));
     }
+    lines.push(Line::from(vec![
+        Span::styled("[Enter]", theme::title()),
+        Span::styled(" Schedule  ", theme::dim()),
+        Span::styled("[Tab]", theme::title()),
+        Span::styled(" Next field  ", theme::dim()),
+        Span::styled("[Esc]", theme::title()),
+        Span::styled(" Cancel", theme::dim()),
+    ]));
+    frame.render_widget(Paragraph::new(lines), rows[2]);
+}
+
+/// Local time with its offset.
+fn format_time(at: DateTime<Utc>) -> String {
+    at.with_timezone(&Local)
+        .format("%Y-%m-%d %H:%M %:z")
+        .to_string()
 }
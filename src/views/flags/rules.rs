@@ -1,36 +1,277 @@
 use crate::action::{Action, View};
-use crate::api::types::Environment;
+use crate::api::types::{Environment, ManagedFlag, Variation};
+use crate::components::input_field::InputField;
 use crate::components::text_area::TextArea;
+use crate::eval::lint::{lint_rules, Diagnostic, LintContext, Severity};
+use crate::eval::rules::{parse_rules, rules_to_value, Clause, Operator, Rule};
 use crate::event::Event;
 use crate::theme;
+use crate::value_format::{
+    editor_label, next_editor_format, read_editor, write_editor, ValueFormat,
+};
 use crossterm::event::{KeyCode, KeyEventKind, KeyModifiers};
 use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::Modifier;
 use ratatui::text::{Line, Span};
-use ratatui::widgets::Paragraph;
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
 use ratatui::Frame;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Diagnostics shown under the editor; the rest are summarized.
+const MAX_DIAGNOSTICS: usize = 4;
+/// Key the rules go under when written as TOML.
+const RULES_KEY: &str = "rules";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    Builder,
+    Raw,
+}
+
+/// A line in the builder: a rule header, one of its clauses, or its serve line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RowRef {
+    Rule(usize),
+    Clause(usize, usize),
+    Serve(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Description,
+    Attribute,
+    Operator,
+    Value,
+    ServeKind,
+    ServeTarget,
+}
+
+impl Field {
+    fn label(self) -> &'static str {
+        match self {
+            Field::Description => "Description",
+            Field::Attribute => "Attribute",
+            Field::Operator => "Operator",
+            Field::Value => "Value (comma-separated for in / not_in)",
+            Field::ServeKind => "Serve",
+            Field::ServeTarget => "Serve",
+        }
+    }
+}
 
 pub struct FlagRulesView {
     pub flag_key: String,
     pub environments: Vec<Environment>,
     pub selected_env: usize,
     pub editor: TextArea,
+    /// The format the text editor shows the rules in.
+    format: ValueFormat,
+    pub flag: Option<ManagedFlag>,
+    /// Variations by environment ID, once loaded.
+    variations: Option<HashMap<String, Vec<Variation>>>,
+    diagnostics: Vec<Diagnostic>,
+    preferred_env: String,
+    mode: Mode,
+    rules: Vec<Rule>,
+    /// Rules as loaded, submitted unchanged if nothing was edited.
+    original: Value,
+    dirty: bool,
+    /// Set to Esc or BackTab when that key is pressed with unsaved changes;
+    /// pressing it again discards them.
+    discard_armed: Option<KeyCode>,
+    cursor: usize,
+    field: usize,
+    input: Option<(Field, InputField)>,
+    message: Option<String>,
 }
 
 impl FlagRulesView {
     pub fn new(flag_key: &str) -> Self {
-        let mut editor = TextArea::new("Rules (JSON)");
+        let mut editor = TextArea::new(&editor_label(RULES_KEY, ValueFormat::Json));
         editor.focused = true;
         Self {
             flag_key: flag_key.to_string(),
             environments: Vec::new(),
             selected_env: 0,
             editor,
+            format: ValueFormat::Json,
+            flag: None,
+            variations: None,
+            diagnostics: Vec::new(),
+            preferred_env: String::new(),
+            mode: Mode::Builder,
+            rules: Vec::new(),
+            original: Value::Array(vec![]),
+            dirty: false,
+            discard_armed: None,
+            cursor: 0,
+            field: 0,
+            input: None,
+            message: None,
+        }
+    }
+
+    /// Start on `environment_id` once environments arrive.
+    pub fn with_environment(mut self, environment_id: &str) -> Self {
+        self.preferred_env = environment_id.to_string();
+        self
+    }
+
+    /// Edit the rules as text in `format` rather than JSON.
+    pub fn with_format(mut self, format: ValueFormat) -> Self {
+        self.format = format;
+        self.editor.label = editor_label(RULES_KEY, format);
+        self
+    }
+
+    /// Keep unsaved edits when environments arrive again.
+    pub fn set_environments(&mut self, environments: Vec<Environment>) {
+        self.selected_env = environments
+            .iter()
+            .position(|e| e.id == self.preferred_env)
+            .unwrap_or(0);
+        self.environments = environments;
+        if !self.has_unsaved_changes() {
+            self.load_selected_environment();
+        }
+    }
+
+    /// Keep unsaved edits when a refreshed copy of the flag arrives.
+    pub fn set_flag(&mut self, flag: ManagedFlag) {
+        self.flag = Some(flag);
+        if !self.dirty && self.mode == Mode::Builder {
+            self.load_selected_environment();
+        }
+        self.lint();
+    }
+
+    pub fn set_variations(&mut self, variations: HashMap<String, Vec<Variation>>) {
+        self.variations = Some(variations);
+        self.lint();
+    }
+
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Re-check the rules being edited. In the builder, and when editing YAML
+    /// or TOML, the rules are checked as pretty JSON, so positions in it would
+    /// point at text that isn't on screen; those diagnostics get line 0 and
+    /// are located by their path instead.
+    fn lint(&mut self) {
+        let source = match self.mode {
+            Mode::Raw if self.format == ValueFormat::Json => self.editor.content(),
+            Mode::Raw => match self.format.read(&self.editor.content(), RULES_KEY) {
+                Ok(value) => serde_json::to_string_pretty(&value).unwrap_or_default(),
+                Err(e) => {
+                    let line = e.line.unwrap_or(1);
+                    self.diagnostics = vec![Diagnostic {
+                        severity: Severity::Error,
+                        line,
+                        column: e.column.unwrap_or(1),
+                        path: String::new(),
+                        message: format!("Invalid {}: {}", self.format.label(), e.message),
+                    }];
+                    self.editor.marked_lines = vec![line.saturating_sub(1)];
+                    return;
+                }
+            },
+            Mode::Builder => self
+                .parse_rules()
+                .ok()
+                .and_then(|v| serde_json::to_string_pretty(&v).ok())
+                .unwrap_or_default(),
+        };
+        let variation_keys: Option<Vec<String>> = self.variations.as_ref().map(|all| {
+            self.selected_environment_id()
+                .and_then(|env| all.get(env))
+                .map(|vs| vs.iter().map(|v| v.key.clone()).collect())
+                .unwrap_or_default()
+        });
+        self.diagnostics = lint_rules(
+            &source,
+            &LintContext {
+                flag_type: self.flag.as_ref().map(|f| f.flag_type.as_str()),
+                variations: variation_keys.as_deref(),
+            },
+        );
+        if self.mode == Mode::Raw && self.format == ValueFormat::Json {
+            self.editor.marked_lines = self
+                .diagnostics
+                .iter()
+                .filter(|d| d.is_error())
+                .map(|d| d.line.saturating_sub(1))
+                .collect();
+        } else {
+            for d in &mut self.diagnostics {
+                d.line = 0;
+                d.column = 0;
+            }
+            self.editor.marked_lines = Vec::new();
         }
     }
 
-    pub fn set_rules(&mut self, rules: &serde_json::Value) {
-        let formatted = serde_json::to_string_pretty(rules).unwrap_or_else(|_| "[]".to_string());
-        self.editor.set_content(&formatted);
+    /// Load the selected environment's rules from the flag into the editors.
+    fn load_selected_environment(&mut self) {
+        let (Some(flag), Some(env_id)) = (&self.flag, self.selected_environment_id()) else {
+            return;
+        };
+        let rules = flag
+            .environments
+            .iter()
+            .find(|e| e.environment_id == env_id)
+            .map(|e| e.rules.clone())
+            .unwrap_or(Value::Null);
+        self.set_rules(&rules);
+    }
+
+    pub fn set_rules(&mut self, rules: &Value) {
+        let rules = if rules.is_null() {
+            Value::Array(vec![])
+        } else {
+            rules.clone()
+        };
+        let note = self.write_raw(&rules, self.format);
+        self.dirty = false;
+        self.discard_armed = None;
+        self.cursor = 0;
+        self.field = 0;
+        self.input = None;
+        match parse_rules(&rules) {
+            Ok(parsed) => {
+                self.rules = parsed;
+                self.mode = Mode::Builder;
+                self.message = None;
+            }
+            Err(e) => {
+                self.rules = Vec::new();
+                self.mode = Mode::Raw;
+                self.message = Some(match note {
+                    Some(note) => format!("These rules don't fit the builder: {}; {}", e, note),
+                    None => format!(
+                        "These rules don't fit the builder, editing as {}: {}",
+                        self.format.label(),
+                        e
+                    ),
+                });
+            }
+        }
+        self.original = rules;
+        self.lint();
+    }
+
+    /// Write `rules` into the text editor in `format`, falling back to JSON
+    /// (and saying why) if `format` can't hold them.
+    fn write_raw(&mut self, rules: &Value, format: ValueFormat) -> Option<String> {
+        let (format, note) = write_editor(&mut self.editor, rules, format, RULES_KEY);
+        self.format = format;
+        note
+    }
+
+    /// The text editor's rules, read in its format.
+    fn read_raw(&self) -> Result<Value, String> {
+        read_editor(&self.editor, self.format, RULES_KEY)
     }
 
     pub fn selected_environment_id(&self) -> Option<&str> {
@@ -39,8 +280,13 @@ impl FlagRulesView {
             .map(|e| e.id.as_str())
     }
 
-    pub fn parse_rules(&self) -> Result<serde_json::Value, String> {
-        serde_json::from_str(&self.editor.content()).map_err(|e| e.to_string())
+    /// The rules to submit, in the shape `UpdateRulesRequest` expects.
+    pub fn parse_rules(&self) -> Result<Value, String> {
+        match self.mode {
+            Mode::Raw => self.read_raw(),
+            Mode::Builder if !self.dirty => Ok(self.original.clone()),
+            Mode::Builder => Ok(rules_to_value(&self.rules)),
+        }
     }
 
     pub fn handle_event(&mut self, event: &Event) -> Option<Action> {
@@ -48,32 +294,365 @@ impl FlagRulesView {
             if key.kind != KeyEventKind::Press {
                 return None;
             }
+            if self.input.is_some() {
+                self.handle_input_event(event);
+                self.lint();
+                return None;
+            }
+            let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+            let armed = self.discard_armed.take();
             match key.code {
                 KeyCode::Esc => {
+                    if self.has_unsaved_changes() && armed != Some(KeyCode::Esc) {
+                        self.discard_armed = Some(KeyCode::Esc);
+                        return None;
+                    }
                     return Some(Action::Navigate(View::FlagDetail(self.flag_key.clone())));
                 }
-                KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                KeyCode::Char('s') if ctrl => {
                     return Some(Action::SubmitRulesUpdate(self.flag_key.clone()));
                 }
+                KeyCode::Char('r') if ctrl => self.toggle_mode(),
+                KeyCode::Char('f') if ctrl && self.mode == Mode::Raw => self.next_format(),
+                KeyCode::BackTab => self.next_environment(armed),
                 KeyCode::Tab if key.modifiers.contains(KeyModifiers::SHIFT) => {
-                    if !self.environments.is_empty() {
-                        self.selected_env = (self.selected_env + 1) % self.environments.len();
-                    }
-                    return None;
+                    self.next_environment(armed)
                 }
-                _ => {
+                _ if self.mode == Mode::Raw => {
                     self.editor.handle_event(event);
                 }
+                _ => self.handle_builder_key(key.code),
             }
+            self.lint();
         }
         None
     }
 
+    /// Whether switching environment or leaving would lose edits.
+    fn has_unsaved_changes(&self) -> bool {
+        match self.mode {
+            Mode::Builder => self.dirty,
+            Mode::Raw => self.read_raw().map_or(true, |v| v != self.original),
+        }
+    }
+
+    fn next_environment(&mut self, armed: Option<KeyCode>) {
+        if self.has_unsaved_changes() && armed != Some(KeyCode::BackTab) {
+            self.discard_armed = Some(KeyCode::BackTab);
+            return;
+        }
+        if !self.environments.is_empty() {
+            self.selected_env = (self.selected_env + 1) % self.environments.len();
+            self.preferred_env = self.environments[self.selected_env].id.clone();
+            self.load_selected_environment();
+        }
+    }
+
+    fn toggle_mode(&mut self) {
+        match self.mode {
+            Mode::Builder => {
+                let value = self.parse_rules().unwrap_or_default();
+                self.message = self.write_raw(&value, self.format);
+                self.mode = Mode::Raw;
+            }
+            Mode::Raw => {
+                let parsed = self.read_raw().and_then(|v| {
+                    parse_rules(&v)
+                        .map(|rules| (v, rules))
+                        .map_err(|e| format!("These rules don't fit the builder: {}", e))
+                });
+                match parsed {
+                    Ok((value, rules)) => {
+                        self.dirty = value != self.original;
+                        self.rules = rules;
+                        self.mode = Mode::Builder;
+                        self.message = None;
+                        self.clamp_cursor();
+                    }
+                    Err(e) => self.message = Some(e),
+                }
+            }
+        }
+    }
+
+    /// Rewrite the text in the next format. The text must parse first.
+    fn next_format(&mut self) {
+        match next_editor_format(&mut self.editor, self.format, RULES_KEY) {
+            Ok((_, format, note)) => {
+                self.format = format;
+                self.message = note;
+            }
+            Err(e) => self.message = Some(e),
+        }
+    }
+
+    // ── Builder ──────────────────────────────────────────────────────
+
+    fn rows(&self) -> Vec<RowRef> {
+        let mut rows = Vec::new();
+        for (i, rule) in self.rules.iter().enumerate() {
+            rows.push(RowRef::Rule(i));
+            for j in 0..rule.clauses.len() {
+                rows.push(RowRef::Clause(i, j));
+            }
+            rows.push(RowRef::Serve(i));
+        }
+        rows
+    }
+
+    fn fields(row: RowRef) -> &'static [Field] {
+        match row {
+            RowRef::Rule(_) => &[Field::Description],
+            RowRef::Clause(..) => &[Field::Attribute, Field::Operator, Field::Value],
+            RowRef::Serve(_) => &[Field::ServeKind, Field::ServeTarget],
+        }
+    }
+
+    fn current_row(&self) -> Option<RowRef> {
+        self.rows().get(self.cursor).copied()
+    }
+
+    fn current_rule(&self) -> Option<usize> {
+        self.current_row().map(|row| match row {
+            RowRef::Rule(i) | RowRef::Clause(i, _) | RowRef::Serve(i) => i,
+        })
+    }
+
+    fn current_field(&self) -> Option<Field> {
+        self.current_row()
+            .and_then(|row| Self::fields(row).get(self.field).copied())
+    }
+
+    fn clamp_cursor(&mut self) {
+        let len = self.rows().len();
+        self.cursor = self.cursor.min(len.saturating_sub(1));
+        if let Some(row) = self.current_row() {
+            self.field = self.field.min(Self::fields(row).len() - 1);
+        }
+    }
+
+    fn select_row(&mut self, row: RowRef) {
+        if let Some(pos) = self.rows().iter().position(|r| *r == row) {
+            self.cursor = pos;
+        }
+        self.clamp_cursor();
+    }
+
+    fn handle_builder_key(&mut self, code: KeyCode) {
+        let rows = self.rows().len();
+        match code {
+            KeyCode::Down | KeyCode::Char('j') if rows > 0 => {
+                self.cursor = (self.cursor + 1) % rows;
+                self.clamp_cursor();
+            }
+            KeyCode::Up | KeyCode::Char('k') if rows > 0 => {
+                self.cursor = if self.cursor == 0 {
+                    rows - 1
+                } else {
+                    self.cursor - 1
+                };
+                self.clamp_cursor();
+            }
+            KeyCode::Right | KeyCode::Char('l') => {
+                if let Some(row) = self.current_row() {
+                    self.field = (self.field + 1).min(Self::fields(row).len() - 1);
+                }
+            }
+            KeyCode::Left | KeyCode::Char('h') => {
+                self.field = self.field.saturating_sub(1);
+            }
+            KeyCode::Enter => self.activate_field(),
+            KeyCode::Char('a') => self.add_rule(),
+            KeyCode::Char('c') => self.add_clause(),
+            KeyCode::Char('d') | KeyCode::Delete => self.remove_selected(),
+            KeyCode::Char('K') => self.move_rule(-1),
+            KeyCode::Char('J') => self.move_rule(1),
+            _ => {}
+        }
+    }
+
+    fn default_serve_value(&self) -> Value {
+        match self.flag.as_ref().map(|f| f.flag_type.as_str()) {
+            Some("boolean") | None => Value::Bool(true),
+            Some("number") => Value::from(0),
+            Some("json") => Value::Object(Default::default()),
+            Some(_) => Value::String(String::new()),
+        }
+    }
+
+    fn add_rule(&mut self) {
+        let at = self.current_rule().map(|i| i + 1).unwrap_or(0);
+        self.rules.insert(
+            at,
+            Rule {
+                description: String::new(),
+                clauses: vec![empty_clause()],
+                value: Some(self.default_serve_value()),
+                variation: None,
+                extra: Default::default(),
+                clauses_key: Default::default(),
+            },
+        );
+        self.dirty = true;
+        self.field = 0;
+        self.select_row(RowRef::Clause(at, 0));
+    }
+
+    fn add_clause(&mut self) {
+        let Some(i) = self.current_rule() else {
+            return self.add_rule();
+        };
+        let at = match self.current_row() {
+            Some(RowRef::Clause(_, j)) => j + 1,
+            Some(RowRef::Rule(_)) => 0,
+            _ => self.rules[i].clauses.len(),
+        };
+        self.rules[i].clauses.insert(at, empty_clause());
+        self.dirty = true;
+        self.field = 0;
+        self.select_row(RowRef::Clause(i, at));
+    }
+
+    fn remove_selected(&mut self) {
+        match self.current_row() {
+            Some(RowRef::Clause(i, j)) => {
+                self.rules[i].clauses.remove(j);
+            }
+            Some(RowRef::Rule(i)) | Some(RowRef::Serve(i)) => {
+                self.rules.remove(i);
+            }
+            None => return,
+        }
+        self.dirty = true;
+        self.clamp_cursor();
+    }
+
+    fn move_rule(&mut self, delta: isize) {
+        let Some(i) = self.current_rule() else { return };
+        let j = i as isize + delta;
+        if j < 0 || j as usize >= self.rules.len() {
+            return;
+        }
+        let j = j as usize;
+        self.rules.swap(i, j);
+        self.dirty = true;
+        self.select_row(RowRef::Rule(j));
+    }
+
+    /// Enter on a field: cycle the operator or serve kind, or start editing text.
+    fn activate_field(&mut self) {
+        let (Some(row), Some(field)) = (self.current_row(), self.current_field()) else {
+            return;
+        };
+        match (row, field) {
+            (RowRef::Clause(i, j), Field::Operator) => {
+                let clause = &mut self.rules[i].clauses[j];
+                let next = match clause.op() {
+                    Some(op) => {
+                        let pos = Operator::ALL.iter().position(|o| *o == op).unwrap_or(0);
+                        Operator::ALL[(pos + 1) % Operator::ALL.len()]
+                    }
+                    None => Operator::ALL[0],
+                };
+                clause.operator = next.as_str().to_string();
+                clause.value = convert_value(&clause.value, next);
+                self.dirty = true;
+            }
+            (RowRef::Serve(i), Field::ServeKind) => {
+                let default = self.default_serve_value();
+                let rule = &mut self.rules[i];
+                if rule.variation.is_some() {
+                    rule.variation = None;
+                    rule.value = Some(default);
+                } else {
+                    rule.value = None;
+                    rule.variation = Some(String::new());
+                }
+                self.dirty = true;
+            }
+            (RowRef::Clause(i, j), Field::Value)
+                if !self.rules[i].clauses[j]
+                    .op()
+                    .map(Operator::takes_value)
+                    .unwrap_or(true) => {}
+            _ => {
+                let text = self.field_text(row, field);
+                let mut input = InputField::new(field.label());
+                input.focused = true;
+                input.set_value(&text);
+                self.input = Some((field, input));
+            }
+        }
+    }
+
+    fn field_text(&self, row: RowRef, field: Field) -> String {
+        match (row, field) {
+            (RowRef::Rule(i), _) => self.rules[i].description.clone(),
+            (RowRef::Clause(i, j), Field::Attribute) => self.rules[i].clauses[j].attribute.clone(),
+            (RowRef::Clause(i, j), Field::Operator) => self.rules[i].clauses[j].operator.clone(),
+            (RowRef::Clause(i, j), _) => value_text(&self.rules[i].clauses[j].value),
+            (RowRef::Serve(i), Field::ServeKind) => serve_kind(&self.rules[i]).to_string(),
+            (RowRef::Serve(i), _) => match &self.rules[i].variation {
+                Some(key) => key.clone(),
+                None => self.rules[i]
+                    .value
+                    .as_ref()
+                    .map(value_text)
+                    .unwrap_or_default(),
+            },
+        }
+    }
+
+    fn handle_input_event(&mut self, event: &Event) {
+        let Event::Key(key) = event else { return };
+        match key.code {
+            KeyCode::Esc => self.input = None,
+            KeyCode::Enter => {
+                if let (Some((field, input)), Some(row)) = (self.input.take(), self.current_row()) {
+                    self.apply_text(row, field, &input.value);
+                }
+            }
+            _ => {
+                if let Some((_, input)) = &mut self.input {
+                    input.handle_event(event);
+                }
+            }
+        }
+    }
+
+    fn apply_text(&mut self, row: RowRef, field: Field, text: &str) {
+        match (row, field) {
+            (RowRef::Rule(i), _) => self.rules[i].description = text.to_string(),
+            (RowRef::Clause(i, j), Field::Attribute) => {
+                self.rules[i].clauses[j].attribute = text.trim().to_string()
+            }
+            (RowRef::Clause(i, j), _) => {
+                let clause = &mut self.rules[i].clauses[j];
+                let takes_list = clause.op().map(Operator::takes_list).unwrap_or(false);
+                clause.value = parse_value_text(text, takes_list);
+            }
+            (RowRef::Serve(i), _) => {
+                let rule = &mut self.rules[i];
+                if rule.variation.is_some() {
+                    rule.variation = Some(text.trim().to_string());
+                } else {
+                    rule.value = Some(parse_value_text(text, false));
+                }
+            }
+        }
+        self.dirty = true;
+    }
+
+    // ── Rendering ────────────────────────────────────────────────────
+
     pub fn render(&self, frame: &mut Frame, area: Rect) {
         let chunks = Layout::vertical([
             Constraint::Length(2),
             Constraint::Length(2),
             Constraint::Min(5),
+            Constraint::Length(self.diagnostics.len().min(MAX_DIAGNOSTICS) as u16),
+            Constraint::Length(if self.input.is_some() { 3 } else { 0 }),
+            Constraint::Length(if self.message.is_some() { 1 } else { 0 }),
             Constraint::Length(1),
         ])
         .split(area);
@@ -89,21 +668,471 @@ impl FlagRulesView {
             .get(self.selected_env)
             .map(|e| e.name.as_str())
             .unwrap_or("(none)");
+        let env_hint = match self.discard_armed {
+            Some(KeyCode::Esc) => Span::styled(
+                "  Unsaved changes: [Esc] again to discard them",
+                theme::warning(),
+            ),
+            Some(_) => Span::styled(
+                "  Unsaved changes: [Shift+Tab] again to discard them",
+                theme::warning(),
+            ),
+            None => Span::styled("  [Shift+Tab] to switch", theme::dim()),
+        };
         let env_line = Paragraph::new(Line::from(vec![
             Span::styled("Environment: ", theme::dim()),
             Span::styled(env_name, theme::normal()),
-            Span::styled("  [Shift+Tab] to switch", theme::dim()),
+            env_hint,
         ]));
         frame.render_widget(env_line, chunks[1]);
 
-        self.editor.render(frame, chunks[2]);
+        match self.mode {
+            Mode::Raw => self.editor.render(frame, chunks[2]),
+            Mode::Builder => self.render_builder(frame, chunks[2]),
+        }
+
+        self.render_diagnostics(frame, chunks[3]);
 
-        let hint = Paragraph::new(Line::from(vec![
+        if let Some((_, input)) = &self.input {
+            input.render(frame, chunks[4]);
+        }
+
+        if let Some(message) = &self.message {
+            frame.render_widget(
+                Paragraph::new(Span::styled(message.as_str(), theme::status_off())),
+                chunks[5],
+            );
+        }
+
+        let mut hints = vec![
             Span::styled("[Ctrl+S]", theme::title()),
             Span::styled(" Save  ", theme::dim()),
-            Span::styled("[Esc]", theme::title()),
-            Span::styled(" Back", theme::dim()),
-        ]));
-        frame.render_widget(hint, chunks[3]);
+        ];
+        match (self.mode, self.input.is_some()) {
+            (_, true) => {
+                hints = vec![
+                    Span::styled("[Enter]", theme::title()),
+                    Span::styled(" Apply  ", theme::dim()),
+                    Span::styled("[Esc]", theme::title()),
+                    Span::styled(" Cancel", theme::dim()),
+                ]
+            }
+            (Mode::Builder, false) => {
+                hints.extend([
+                    Span::styled("[a]", theme::title()),
+                    Span::styled(" Rule  ", theme::dim()),
+                    Span::styled("[c]", theme::title()),
+                    Span::styled(" Clause  ", theme::dim()),
+                    Span::styled("[d]", theme::title()),
+                    Span::styled(" Remove  ", theme::dim()),
+                    Span::styled("[K/J]", theme::title()),
+                    Span::styled(" Move  ", theme::dim()),
+                    Span::styled("[Enter]", theme::title()),
+                    Span::styled(" Edit  ", theme::dim()),
+                    Span::styled("[Ctrl+R]", theme::title()),
+                    Span::styled(format!(" {}  ", self.format.label()), theme::dim()),
+                    Span::styled("[Esc]", theme::title()),
+                    Span::styled(" Back", theme::dim()),
+                ]);
+            }
+            (Mode::Raw, false) => {
+                hints.extend([
+                    Span::styled("[Ctrl+R]", theme::title()),
+                    Span::styled(" Builder  ", theme::dim()),
+                    Span::styled("[Ctrl+F]", theme::title()),
+                    Span::styled(format!(" {}  ", self.format.next().label()), theme::dim()),
+                    Span::styled("[Esc]", theme::title()),
+                    Span::styled(" Back", theme::dim()),
+                ]);
+            }
+        }
+        frame.render_widget(Paragraph::new(Line::from(hints)), chunks[6]);
+    }
+
+    fn render_diagnostics(&self, frame: &mut Frame, area: Rect) {
+        let mut lines: Vec<Line> = self
+            .diagnostics
+            .iter()
+            .take(MAX_DIAGNOSTICS)
+            .map(|d| {
+                let location = match (self.mode, d.rule(), d.clause()) {
+                    _ if d.line > 0 => format!("{}:{}", d.line, d.column),
+                    _ if d.path.is_empty() => "rules".to_string(),
+                    (Mode::Raw, _, _) | (_, None, _) => d.path.clone(),
+                    (_, Some(i), Some(j)) => {
+                        format!("rule {}, clause {}", i + 1, j + 1)
+                    }
+                    (_, Some(i), None) => format!("rule {}", i + 1),
+                };
+                Line::from(vec![
+                    Span::styled(format!("{} ", location), theme::dim()),
+                    Span::styled(format!("{}: ", d.severity), severity_style(d.severity)),
+                    Span::styled(d.message.as_str(), theme::normal()),
+                ])
+            })
+            .collect();
+        if self.diagnostics.len() > MAX_DIAGNOSTICS {
+            lines.pop();
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "… and {} more",
+                    self.diagnostics.len() - MAX_DIAGNOSTICS + 1
+                ),
+                theme::dim(),
+            )));
+        }
+        frame.render_widget(Paragraph::new(lines), area);
+    }
+
+    /// The most severe diagnostic that belongs to a builder row.
+    fn row_severity(&self, row: RowRef) -> Option<Severity> {
+        self.diagnostics
+            .iter()
+            .filter(|d| {
+                let serve_field = d.path.ends_with("/value") || d.path.ends_with("/variation");
+                match row {
+                    RowRef::Rule(i) => d.rule() == Some(i) && d.clause().is_none() && !serve_field,
+                    RowRef::Clause(i, j) => d.rule() == Some(i) && d.clause() == Some(j),
+                    RowRef::Serve(i) => d.rule() == Some(i) && d.clause().is_none() && serve_field,
+                }
+            })
+            .map(|d| d.severity)
+            .min()
+    }
+
+    fn render_builder(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .title(" Rules ")
+            .title_style(theme::heading())
+            .borders(Borders::ALL)
+            .border_style(theme::active_border());
+
+        let rows = self.rows();
+        if rows.is_empty() {
+            let empty = Paragraph::new(Line::from(vec![
+                Span::styled("No rules. Press ", theme::dim()),
+                Span::styled("[a]", theme::title()),
+                Span::styled(" to add one.", theme::dim()),
+            ]))
+            .block(block);
+            frame.render_widget(empty, area);
+            return;
+        }
+
+        let items: Vec<ListItem> = rows
+            .iter()
+            .enumerate()
+            .map(|(idx, row)| {
+                let selected = idx == self.cursor;
+                let field_style = |n: usize, base| {
+                    if selected && n == self.field {
+                        theme::title().add_modifier(Modifier::REVERSED)
+                    } else {
+                        base
+                    }
+                };
+                let line = match *row {
+                    RowRef::Rule(i) => {
+                        let description = &self.rules[i].description;
+                        Line::from(vec![
+                            Span::styled(format!("Rule {}  ", i + 1), theme::heading()),
+                            if description.is_empty() {
+                                Span::styled("(no description)", field_style(0, theme::dim()))
+                            } else {
+                                Span::styled(description.clone(), field_style(0, theme::normal()))
+                            },
+                        ])
+                    }
+                    RowRef::Clause(i, j) => {
+                        let clause = &self.rules[i].clauses[j];
+                        let operator_style = if clause.op().is_some() {
+                            theme::normal()
+                        } else {
+                            theme::status_off()
+                        };
+                        let value = if clause.op().map(Operator::takes_value).unwrap_or(true) {
+                            value_text(&clause.value)
+                        } else {
+                            "—".to_string()
+                        };
+                        Line::from(vec![
+                            Span::styled(if j == 0 { "  if   " } else { "  and  " }, theme::dim()),
+                            Span::styled(
+                                format!("{:<20}", or_placeholder(&clause.attribute, "attribute")),
+                                field_style(0, theme::normal()),
+                            ),
+                            Span::raw(" "),
+                            Span::styled(
+                                format!("{:<12}", clause.operator),
+                                field_style(1, operator_style),
+                            ),
+                            Span::raw(" "),
+                            Span::styled(
+                                or_placeholder(&value, "value").to_string(),
+                                field_style(2, theme::normal()),
+                            ),
+                        ])
+                    }
+                    RowRef::Serve(i) => {
+                        let rule = &self.rules[i];
+                        let target = self.field_text(*row, Field::ServeTarget);
+                        Line::from(vec![
+                            Span::styled("  serve ", theme::dim()),
+                            Span::styled(
+                                format!("{:<10}", serve_kind(rule)),
+                                field_style(0, theme::normal()),
+                            ),
+                            Span::raw(" "),
+                            Span::styled(
+                                or_placeholder(&target, "key").to_string(),
+                                field_style(1, theme::status_on()),
+                            ),
+                        ])
+                    }
+                };
+                let marker = match self.row_severity(*row) {
+                    Some(severity) => Span::styled("● ", severity_style(severity)),
+                    None => Span::raw("  "),
+                };
+                let mut spans = vec![marker];
+                spans.extend(line.spans);
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(theme::selected());
+        let mut state = ListState::default().with_selected(Some(self.cursor));
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+}
+
+fn empty_clause() -> Clause {
+    Clause {
+        attribute: String::new(),
+        operator: Operator::Eq.as_str().to_string(),
+        value: Value::Null,
+        extra: Default::default(),
+        explicit_null: false,
+    }
+}
+
+fn serve_kind(rule: &Rule) -> &'static str {
+    if rule.variation.is_some() {
+        "variation"
+    } else {
+        "value"
+    }
+}
+
+fn severity_style(severity: Severity) -> ratatui::style::Style {
+    match severity {
+        Severity::Error => theme::status_off(),
+        Severity::Warning => theme::warning(),
+    }
+}
+
+fn or_placeholder<'a>(text: &'a str, placeholder: &'a str) -> &'a str {
+    if text.is_empty() {
+        placeholder
+    } else {
+        text
+    }
+}
+
+/// Show a value for editing: strings bare, lists comma-separated, everything else as JSON.
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) if items.iter().all(|i| !i.is_array() && !i.is_object()) => {
+            items.iter().map(value_text).collect::<Vec<_>>().join(", ")
+        }
+        other => other.to_string(),
+    }
+}
+
+/// Parse edited text back into a value. JSON literals are taken as-is and bare
+/// words become strings; list operators split on commas.
+fn parse_value_text(text: &str, takes_list: bool) -> Value {
+    let text = text.trim();
+    if let Ok(value) = serde_json::from_str::<Value>(text) {
+        if !takes_list || value.is_array() {
+            return value;
+        }
+    }
+    if takes_list {
+        return Value::Array(
+            text.split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(|s| parse_value_text(s, false))
+                .collect(),
+        );
+    }
+    if text.is_empty() {
+        Value::Null
+    } else {
+        Value::String(text.to_string())
+    }
+}
+
+/// Keep a clause value sensible when its operator changes between single and list forms.
+fn convert_value(value: &Value, op: Operator) -> Value {
+    match (value, op.takes_list(), op.takes_value()) {
+        (_, _, false) => Value::Null,
+        (Value::Array(_), true, _) | (Value::Null, _, _) => value.clone(),
+        (_, true, _) => Value::Array(vec![value.clone()]),
+        (Value::Array(items), false, _) => items.first().cloned().unwrap_or(Value::Null),
+        _ => value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(crossterm::event::KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn test_unedited_rules_round_trip_exactly() {
+        let rules = json!([
+            {"conditions": [{"attribute": "country", "operator": "eq", "value": "NZ"}],
+             "value": true, "segment": "beta"}
+        ]);
+        let mut view = FlagRulesView::new("checkout");
+        view.set_rules(&rules);
+        assert_eq!(view.mode, Mode::Builder);
+        assert_eq!(view.parse_rules().unwrap(), rules);
+        view.toggle_mode();
+        view.toggle_mode();
+        assert_eq!(view.parse_rules().unwrap(), rules);
+    }
+
+    #[test]
+    fn test_edited_rules_keep_their_shape() {
+        let rules = json!([
+            {"conditions": [
+                {"attribute": "country", "operator": "eq", "value": "NZ"},
+                {"attribute": "plan", "operator": "neq", "value": null}
+             ],
+             "value": true, "segment": "beta"},
+            {"clauses": [{"attribute": "email", "operator": "exists"}], "value": false}
+        ]);
+        let mut view = FlagRulesView::new("checkout");
+        view.set_rules(&rules);
+        view.apply_text(RowRef::Clause(0, 0), Field::Value, "AU");
+        assert!(view.dirty);
+        assert_eq!(
+            view.parse_rules().unwrap(),
+            json!([
+                {"conditions": [
+                    {"attribute": "country", "operator": "eq", "value": "AU"},
+                    {"attribute": "plan", "operator": "neq", "value": null}
+                 ],
+                 "value": true, "segment": "beta"},
+                {"clauses": [{"attribute": "email", "operator": "exists"}], "value": false}
+            ])
+        );
+    }
+
+    #[test]
+    fn test_unsaved_edits_need_a_second_press() {
+        let mut view = FlagRulesView::new("checkout");
+        view.set_rules(&json!([{"clauses": [], "value": true}]));
+        assert!(view.handle_event(&key(KeyCode::Esc)).is_some());
+
+        view.apply_text(RowRef::Rule(0), Field::Attribute, "beta users");
+        assert!(view.handle_event(&key(KeyCode::Esc)).is_none());
+        assert_eq!(view.discard_armed, Some(KeyCode::Esc));
+        assert!(matches!(
+            view.handle_event(&key(KeyCode::Esc)),
+            Some(Action::Navigate(View::FlagDetail(_)))
+        ));
+
+        view.handle_event(&key(KeyCode::BackTab));
+        assert_eq!(view.discard_armed, Some(KeyCode::BackTab));
+        assert!(view.dirty);
+
+        // Raw edits count too
+        view.set_rules(&json!([]));
+        view.toggle_mode();
+        view.editor
+            .set_content("[{\"clauses\": [], \"value\": false}]");
+        assert!(view.handle_event(&key(KeyCode::Esc)).is_none());
+    }
+
+    #[test]
+    fn test_builder_edits() {
+        let mut view = FlagRulesView::new("checkout");
+        view.set_rules(&Value::Null);
+        view.handle_event(&key(KeyCode::Char('a')));
+        view.apply_text(RowRef::Clause(0, 0), Field::Attribute, "country");
+        view.handle_event(&key(KeyCode::Right));
+        view.handle_event(&key(KeyCode::Right));
+        view.handle_event(&key(KeyCode::Left));
+        view.handle_event(&key(KeyCode::Enter)); // eq -> neq
+        view.handle_event(&key(KeyCode::Enter)); // neq -> in
+        view.apply_text(RowRef::Clause(0, 0), Field::Value, "NZ, AU, 3");
+        assert_eq!(
+            view.parse_rules().unwrap(),
+            json!([{"clauses": [{"attribute": "country", "operator": "in", "value": ["NZ", "AU", 3]}],
+                    "value": true}])
+        );
+    }
+
+    #[test]
+    fn test_rules_as_yaml() {
+        let rules = json!([
+            {"conditions": [{"attribute": "country", "operator": "eq", "value": "NZ"}],
+             "value": true}
+        ]);
+        let mut view = FlagRulesView::new("checkout").with_format(ValueFormat::Yaml);
+        view.set_rules(&rules);
+        view.toggle_mode();
+        assert_eq!(view.editor.label, "Rules (YAML)");
+        assert!(view.editor.content().starts_with("- conditions:\n"));
+        assert_eq!(view.parse_rules().unwrap(), rules);
+
+        view.editor.set_content("- conditions: [\n");
+        view.lint();
+        assert_eq!(view.diagnostics.len(), 1);
+        assert!(view.diagnostics[0].message.starts_with("Invalid YAML"));
+        assert_eq!(view.editor.marked_lines, vec![view.diagnostics[0].line - 1]);
+
+        // Lint positions would be in the JSON, so only the path is kept.
+        view.editor.set_content(
+            "- conditions:\n  - attribute: country\n    operator: bogus\n    value: NZ\n  value: true",
+        );
+        view.lint();
+        assert!(!view.diagnostics.is_empty());
+        assert!(view
+            .diagnostics
+            .iter()
+            .all(|d| d.line == 0 && d.path.starts_with("/0/conditions/0")));
+        assert!(view.editor.marked_lines.is_empty());
+
+        view.editor
+            .set_content("rules = [{ value = true, conditions = [] }]");
+        view.format = ValueFormat::Toml;
+        view.next_format();
+        assert_eq!(view.format, ValueFormat::Json);
+        assert_eq!(
+            view.parse_rules().unwrap(),
+            json!([{"value": true, "conditions": []}])
+        );
+    }
+
+    #[test]
+    fn test_unsupported_rules_open_as_json() {
+        let mut view = FlagRulesView::new("checkout");
+        view.set_rules(&json!({"not": "a list"}));
+        assert_eq!(view.mode, Mode::Raw);
+        assert!(view.message.is_some());
+        assert_eq!(view.parse_rules().unwrap(), json!({"not": "a list"}));
     }
 }
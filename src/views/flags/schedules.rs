@@ -1,37 +1,257 @@
-use crate::action::{Action, View};
-use crate::api::types::{Environment, Schedule};
+use crate::action::{Action, ConfirmAction, View};
+use crate::api::types::{
+    CreateScheduleRequest, Environment, ManagedFlag, Schedule, ScheduleAction,
+};
+use crate::components::input_field::InputField;
+use crate::config::KeyTier;
+use crate::eval::{describe_value, fits_type};
 use crate::event::Event;
 use crate::theme;
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveTime, TimeZone, Timelike, Utc};
 use crossterm::event::{KeyCode, KeyEventKind};
 use ratatui::layout::{Constraint, Layout, Rect};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState};
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState, Wrap};
 use ratatui::Frame;
+use serde_json::Value;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const TIME_FORMAT: &str = "%H:%M";
+
+/// Form fields, in focus order.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Action,
+    Date,
+    Time,
+    Zone,
+    Payload,
+}
+
+const FIELDS: [Field; 5] = [
+    Field::Action,
+    Field::Date,
+    Field::Time,
+    Field::Zone,
+    Field::Payload,
+];
+
+/// New-schedule form: action, when to run it, and the payload the action needs.
+struct ScheduleForm {
+    action: usize,
+    date: InputField,
+    time: InputField,
+    /// Whether date and time are entered in UTC rather than local time.
+    utc: bool,
+    /// Rollout percentage or value, depending on the action.
+    payload: InputField,
+    focused: usize,
+    error: Option<String>,
+}
+
+impl ScheduleForm {
+    fn new() -> Self {
+        // Default to about an hour from now, on a quarter hour.
+        let start = Local::now() + Duration::hours(1);
+        let start = start.with_minute(start.minute() / 15 * 15).unwrap_or(start);
+        let mut form = Self {
+            action: 0,
+            date: InputField::new("Date").with_placeholder("YYYY-MM-DD"),
+            time: InputField::new("Time").with_placeholder("HH:MM"),
+            utc: false,
+            payload: InputField::new("Payload"),
+            focused: 0,
+            error: None,
+        };
+        form.date.set_value(&start.format(DATE_FORMAT).to_string());
+        form.time.set_value(&start.format(TIME_FORMAT).to_string());
+        form.update_focus();
+        form
+    }
+
+    fn action(&self) -> ScheduleAction {
+        ScheduleAction::ALL[self.action]
+    }
+
+    fn field(&self) -> Field {
+        FIELDS[self.focused]
+    }
+
+    fn needs_payload(&self) -> bool {
+        matches!(
+            self.action(),
+            ScheduleAction::SetRollout | ScheduleAction::SetValue
+        )
+    }
+
+    fn update_focus(&mut self) {
+        let field = self.field();
+        self.date.focused = field == Field::Date;
+        self.time.focused = field == Field::Time;
+        self.payload.focused = field == Field::Payload;
+        self.payload.label = match self.action() {
+            ScheduleAction::SetRollout => "Rollout (%)".to_string(),
+            _ => "Value (JSON, bare words are strings)".to_string(),
+        };
+    }
+
+    fn move_focus(&mut self, forward: bool) {
+        let count = if self.needs_payload() {
+            FIELDS.len()
+        } else {
+            FIELDS.len() - 1
+        };
+        self.focused = if forward {
+            (self.focused + 1) % count
+        } else {
+            (self.focused + count - 1) % count
+        };
+        self.update_focus();
+    }
+
+    /// Zone label for the entered date and time, e.g. `Local (UTC+13:00)`.
+    fn zone_label(&self) -> String {
+        if self.utc {
+            "UTC".to_string()
+        } else {
+            format!("Local (UTC{})", Local::now().format("%:z"))
+        }
+    }
+
+    fn scheduled_at(&self) -> Result<DateTime<Utc>, String> {
+        let date = NaiveDate::parse_from_str(self.date.value.trim(), DATE_FORMAT)
+            .map_err(|_| "Date must look like 2026-01-31".to_string())?;
+        let time = NaiveTime::parse_from_str(self.time.value.trim(), TIME_FORMAT)
+            .map_err(|_| "Time must look like 09:30".to_string())?;
+        let naive = date.and_time(time);
+        if self.utc {
+            return Ok(Utc.from_utc_datetime(&naive));
+        }
+        Local
+            .from_local_datetime(&naive)
+            .single()
+            .map(|t| t.with_timezone(&Utc))
+            .ok_or_else(|| "That time doesn't exist or is ambiguous in local time".to_string())
+    }
+
+    /// Shift the date by days or the time by minutes with the arrow keys.
+    fn nudge(&mut self, step: i64) {
+        let Ok(at) = self.scheduled_at() else { return };
+        match self.field() {
+            Field::Date => self.set_instant(at + Duration::days(step)),
+            Field::Time => self.set_instant(at + Duration::minutes(15 * step)),
+            _ => {}
+        }
+    }
+
+    /// Fill in date and time for `at`, in the zone the form is using.
+    fn set_instant(&mut self, at: DateTime<Utc>) {
+        let (date, time) = if self.utc {
+            (at.format(DATE_FORMAT), at.format(TIME_FORMAT))
+        } else {
+            let local = at.with_timezone(&Local);
+            (local.format(DATE_FORMAT), local.format(TIME_FORMAT))
+        };
+        self.date.set_value(&date.to_string());
+        self.time.set_value(&time.to_string());
+    }
+
+    fn payload(&self, flag_type: Option<&str>) -> Result<Option<Value>, String> {
+        let raw = self.payload.value.trim();
+        match self.action() {
+            ScheduleAction::Enable | ScheduleAction::Disable => Ok(None),
+            ScheduleAction::SetRollout => {
+                let percentage: i32 = raw
+                    .parse()
+                    .ok()
+                    .filter(|p| (0..=100).contains(p))
+                    .ok_or_else(|| "Rollout must be a whole number from 0 to 100".to_string())?;
+                Ok(Some(
+                    serde_json::json!({ "rollout_percentage": percentage }),
+                ))
+            }
+            ScheduleAction::SetValue => {
+                if raw.is_empty() {
+                    return Err("Enter the value to set".to_string());
+                }
+                let value =
+                    serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()));
+                if let Some(flag_type) = flag_type {
+                    if !fits_type(&value, flag_type) {
+                        return Err(format!(
+                            "Value is {} but the flag type is {}",
+                            describe_value(&value),
+                            flag_type
+                        ));
+                    }
+                }
+                Ok(Some(serde_json::json!({ "value": value })))
+            }
+        }
+    }
+
+    fn request(&self, flag_type: Option<&str>) -> Result<CreateScheduleRequest, String> {
+        let scheduled_at = self.scheduled_at()?;
+        if scheduled_at <= Utc::now() {
+            return Err("The schedule must be in the future".to_string());
+        }
+        Ok(CreateScheduleRequest {
+            action: self.action().as_str().to_string(),
+            scheduled_at: scheduled_at.to_rfc3339(),
+            payload: self.payload(flag_type)?,
+        })
+    }
+}
 
 pub struct FlagSchedulesView {
     pub flag_key: String,
+    pub key_tier: KeyTier,
     pub environments: Vec<Environment>,
     pub selected_env: usize,
+    pub flag: Option<ManagedFlag>,
     pub schedules: Vec<Schedule>,
     state: TableState,
+    form: Option<ScheduleForm>,
+    preferred_env: String,
 }
 
 impl FlagSchedulesView {
-    pub fn new(flag_key: &str) -> Self {
+    pub fn new(flag_key: &str, key_tier: KeyTier) -> Self {
         Self {
             flag_key: flag_key.to_string(),
+            key_tier,
             environments: Vec::new(),
             selected_env: 0,
+            flag: None,
             schedules: Vec::new(),
             state: TableState::default(),
+            form: None,
+            preferred_env: String::new(),
         }
     }
 
+    /// Start on `environment_id` once environments arrive.
+    pub fn with_environment(mut self, environment_id: &str) -> Self {
+        self.preferred_env = environment_id.to_string();
+        self
+    }
+
+    pub fn set_environments(&mut self, environments: Vec<Environment>) {
+        self.selected_env = environments
+            .iter()
+            .position(|e| e.id == self.preferred_env)
+            .unwrap_or(0);
+        self.environments = environments;
+    }
+
     pub fn set_schedules(&mut self, schedules: Vec<Schedule>) {
+        let selected = self.state.selected().unwrap_or(0);
         self.schedules = schedules;
-        if !self.schedules.is_empty() {
-            self.state.select(Some(0));
-        }
+        self.state.select(if self.schedules.is_empty() {
+            None
+        } else {
+            Some(selected.min(self.schedules.len() - 1))
+        });
     }
 
     pub fn selected_environment_id(&self) -> Option<&str> {
@@ -44,43 +264,140 @@ impl FlagSchedulesView {
         self.state.selected().and_then(|i| self.schedules.get(i))
     }
 
+    /// The new-schedule form is open and capturing keys.
+    pub fn is_editing(&self) -> bool {
+        self.form.is_some()
+    }
+
+    pub fn close_form(&mut self) {
+        self.form = None;
+    }
+
+    /// The schedule to create from the form, or why it can't be created.
+    pub fn create_request(&self) -> Result<CreateScheduleRequest, String> {
+        let form = self.form.as_ref().ok_or("No schedule form open")?;
+        form.request(self.flag.as_ref().map(|f| f.flag_type.as_str()))
+    }
+
+    /// Report a problem with the form, e.g. a rejected create request.
+    pub fn set_form_error(&mut self, error: String) {
+        if let Some(form) = &mut self.form {
+            form.error = Some(error);
+        }
+    }
+
+    /// The action that reloads schedules for the selected environment.
+    pub fn load_action(&self) -> Option<Action> {
+        Some(Action::LoadSchedules {
+            flag_key: self.flag_key.clone(),
+            environment_id: self.selected_environment_id()?.to_string(),
+        })
+    }
+
     pub fn handle_event(&mut self, event: &Event) -> Option<Action> {
         if let Event::Key(key) = event {
             if key.kind != KeyEventKind::Press {
                 return None;
             }
+            if self.form.is_some() {
+                return self.handle_form_event(event);
+            }
             match key.code {
                 KeyCode::Esc | KeyCode::Backspace => {
                     return Some(Action::Navigate(View::FlagDetail(self.flag_key.clone())));
                 }
-                KeyCode::Down | KeyCode::Char('j') => {
-                    if !self.schedules.is_empty() {
-                        let i = self.state.selected().unwrap_or(0);
-                        self.state.select(Some((i + 1) % self.schedules.len()));
-                    }
+                KeyCode::Down | KeyCode::Char('j') if !self.schedules.is_empty() => {
+                    let i = self.state.selected().unwrap_or(0);
+                    self.state.select(Some((i + 1) % self.schedules.len()));
                 }
-                KeyCode::Up | KeyCode::Char('k') => {
-                    if !self.schedules.is_empty() {
-                        let i = self.state.selected().unwrap_or(0);
-                        let new = if i == 0 {
-                            self.schedules.len() - 1
-                        } else {
-                            i - 1
-                        };
-                        self.state.select(Some(new));
-                    }
+                KeyCode::Up | KeyCode::Char('k') if !self.schedules.is_empty() => {
+                    let i = self.state.selected().unwrap_or(0);
+                    let new = if i == 0 {
+                        self.schedules.len() - 1
+                    } else {
+                        i - 1
+                    };
+                    self.state.select(Some(new));
+                }
+                KeyCode::Tab if !self.environments.is_empty() => {
+                    self.selected_env = (self.selected_env + 1) % self.environments.len();
+                    self.preferred_env = self.environments[self.selected_env].id.clone();
+                    self.schedules.clear();
+                    self.state.select(None);
+                    return self.load_action();
                 }
-                KeyCode::Tab => {
-                    if !self.environments.is_empty() {
-                        self.selected_env = (self.selected_env + 1) % self.environments.len();
+                KeyCode::Char('c') | KeyCode::Char('n') if self.key_tier.can_mutate() => {
+                    self.form = Some(ScheduleForm::new());
+                }
+                KeyCode::Char('x') | KeyCode::Char('d') if self.key_tier.can_mutate() => {
+                    if let Some(s) = self.selected_schedule().filter(|s| s.status == "pending") {
+                        return Some(Action::ShowConfirm(ConfirmAction::CancelSchedule {
+                            flag_key: self.flag_key.clone(),
+                            schedule_id: s.id.clone(),
+                        }));
                     }
                 }
+                KeyCode::Char('R') => return self.load_action(),
                 _ => {}
             }
         }
         None
     }
 
+    fn handle_form_event(&mut self, event: &Event) -> Option<Action> {
+        let Event::Key(key) = event else { return None };
+        let form = self.form.as_mut()?;
+        form.error = None;
+        match (key.code, form.field()) {
+            (KeyCode::Esc, _) => self.form = None,
+            (KeyCode::Tab, _) => form.move_focus(true),
+            (KeyCode::BackTab, _) => form.move_focus(false),
+            (KeyCode::Enter, _) => {
+                return match self.create_request() {
+                    Ok(_) => Some(Action::SubmitScheduleCreate(self.flag_key.clone())),
+                    Err(e) => {
+                        self.set_form_error(e);
+                        None
+                    }
+                };
+            }
+            (KeyCode::Left | KeyCode::Right, Field::Action) => {
+                let n = ScheduleAction::ALL.len();
+                form.action = if key.code == KeyCode::Right {
+                    (form.action + 1) % n
+                } else {
+                    (form.action + n - 1) % n
+                };
+                form.payload.set_value(match form.action() {
+                    ScheduleAction::SetRollout => "100",
+                    _ => "",
+                });
+                form.update_focus();
+            }
+            (KeyCode::Left | KeyCode::Right, Field::Zone) => {
+                // Keep the same instant, shown in the other zone.
+                let at = form.scheduled_at();
+                form.utc = !form.utc;
+                if let Ok(at) = at {
+                    form.set_instant(at);
+                }
+            }
+            (KeyCode::Up, Field::Date | Field::Time) => form.nudge(1),
+            (KeyCode::Down, Field::Date | Field::Time) => form.nudge(-1),
+            (_, Field::Date) => {
+                form.date.handle_event(event);
+            }
+            (_, Field::Time) => {
+                form.time.handle_event(event);
+            }
+            (_, Field::Payload) => {
+                form.payload.handle_event(event);
+            }
+            _ => {}
+        }
+        None
+    }
+
     pub fn render(&mut self, frame: &mut Frame, area: Rect) {
         let chunks = Layout::vertical([
             Constraint::Length(2),
@@ -108,22 +425,18 @@ impl FlagSchedulesView {
         ]));
         frame.render_widget(env_line, chunks[1]);
 
+        let main = Layout::horizontal([Constraint::Percentage(55), Constraint::Percentage(45)])
+            .split(chunks[2]);
+
         let rows: Vec<Row> = self
             .schedules
             .iter()
             .map(|s| {
-                let status_style = match s.status.as_str() {
-                    "pending" => theme::dim(),
-                    "executed" => theme::status_on(),
-                    "cancelled" => theme::status_off(),
-                    "failed" => theme::status_off(),
-                    _ => theme::dim(),
-                };
                 Row::new(vec![
-                    Cell::from(s.action.as_str()).style(theme::normal()),
+                    Cell::from(action_label(&s.action)).style(theme::normal()),
                     Cell::from(s.scheduled_at.format("%Y-%m-%d %H:%M").to_string())
                         .style(theme::normal()),
-                    Cell::from(s.status.as_str()).style(status_style),
+                    Cell::from(s.status.as_str()).style(status_style(&s.status)),
                     Cell::from(
                         s.executed_at
                             .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
@@ -144,8 +457,13 @@ impl FlagSchedulesView {
             ],
         )
         .header(
-            Row::new(vec!["Action", "Scheduled At", "Status", "Executed At"])
-                .style(theme::heading()),
+            Row::new(vec![
+                "Action",
+                "Scheduled (UTC)",
+                "Status",
+                "Executed (UTC)",
+            ])
+            .style(theme::heading()),
         )
         .block(
             Block::default()
@@ -156,12 +474,253 @@ impl FlagSchedulesView {
         )
         .highlight_style(theme::highlight());
 
-        frame.render_stateful_widget(table, chunks[2], &mut self.state);
+        frame.render_stateful_widget(table, main[0], &mut self.state);
 
-        let hint = Paragraph::new(Line::from(vec![
-            Span::styled("[Esc]", theme::title()),
-            Span::styled(" Back", theme::dim()),
-        ]));
-        frame.render_widget(hint, chunks[3]);
+        match &self.form {
+            Some(form) => render_form(frame, main[1], form),
+            None => self.render_detail(frame, main[1]),
+        }
+
+        let mut hints = Vec::new();
+        if self.form.is_some() {
+            hints.extend([
+                Span::styled("[Tab]", theme::title()),
+                Span::styled(" Next field  ", theme::dim()),
+                Span::styled("[←→]", theme::title()),
+                Span::styled(" Choose  ", theme::dim()),
+                Span::styled("[↑↓]", theme::title()),
+                Span::styled(" Adjust date/time  ", theme::dim()),
+                Span::styled("[Enter]", theme::title()),
+                Span::styled(" Create  ", theme::dim()),
+                Span::styled("[Esc]", theme::title()),
+                Span::styled(" Cancel", theme::dim()),
+            ]);
+        } else {
+            if self.key_tier.can_mutate() {
+                hints.extend([
+                    Span::styled("[c]", theme::title()),
+                    Span::styled(" New  ", theme::dim()),
+                    Span::styled("[x]", theme::title()),
+                    Span::styled(" Cancel schedule  ", theme::dim()),
+                ]);
+            }
+            hints.extend([
+                Span::styled("[R]", theme::title()),
+                Span::styled(" Refresh  ", theme::dim()),
+                Span::styled("[Esc]", theme::title()),
+                Span::styled(" Back", theme::dim()),
+            ]);
+        }
+        frame.render_widget(Paragraph::new(Line::from(hints)), chunks[3]);
+    }
+
+    fn render_detail(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .title(" Details ")
+            .title_style(theme::heading())
+            .borders(Borders::ALL)
+            .border_style(theme::border());
+        let Some(s) = self.selected_schedule() else {
+            let empty =
+                Paragraph::new(Span::styled("No schedule selected", theme::dim())).block(block);
+            frame.render_widget(empty, area);
+            return;
+        };
+
+        let field = |label: &'static str, value: String| {
+            Line::from(vec![
+                Span::styled(format!("{:<11}", label), theme::dim()),
+                Span::styled(value, theme::normal()),
+            ])
+        };
+        let mut lines = vec![
+            field("ID", s.id.clone()),
+            field("Action", action_label(&s.action).to_string()),
+            Line::from(vec![
+                Span::styled(format!("{:<11}", "Status"), theme::dim()),
+                Span::styled(s.status.clone(), status_style(&s.status)),
+            ]),
+            field("Scheduled", format_time(s.scheduled_at)),
+            field(
+                "Executed",
+                s.executed_at
+                    .map(format_time)
+                    .unwrap_or_else(|| "-".to_string()),
+            ),
+            field("Created", format_time(s.created_at)),
+        ];
+        if !s.error_message.is_empty() {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled("Error", theme::status_off())));
+            lines.push(Line::from(Span::styled(
+                s.error_message.clone(),
+                theme::status_off(),
+            )));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled("Payload", theme::dim())));
+        let payload = if s.payload.is_null() {
+            "-".to_string()
+        } else {
+            serde_json::to_string_pretty(&s.payload).unwrap_or_default()
+        };
+        lines.extend(
+            payload
+                .lines()
+                .map(|l| Line::from(Span::styled(l.to_string(), theme::normal()))),
+        );
+
+        let detail = Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false });
+        frame.render_widget(detail, area);
+    }
+}
+
+fn render_form(frame: &mut Frame, area: Rect, form: &ScheduleForm) {
+    let block = Block::default()
+        .title(" New schedule ")
+        .title_style(theme::heading())
+        .borders(Borders::ALL)
+        .border_style(theme::active_border());
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let rows = Layout::vertical([
+        Constraint::Length(1), // Action
+        Constraint::Length(3), // Date + time
+        Constraint::Length(1), // Zone
+        Constraint::Length(1), // When, in the other zone
+        Constraint::Length(if form.needs_payload() { 3 } else { 0 }),
+        Constraint::Length(1), // Payload preview
+        Constraint::Min(0),    // Error
+    ])
+    .split(inner);
+
+    let choice = |label: &'static str, value: String, focused: bool| {
+        Paragraph::new(Line::from(vec![
+            Span::styled(format!("{:<10}", label), theme::dim()),
+            Span::styled("◀ ", theme::dim()),
+            Span::styled(
+                value,
+                if focused {
+                    theme::highlight()
+                } else {
+                    theme::normal()
+                },
+            ),
+            Span::styled(" ▶", theme::dim()),
+        ]))
+    };
+    frame.render_widget(
+        choice(
+            "Action",
+            form.action().label().to_string(),
+            form.field() == Field::Action,
+        ),
+        rows[0],
+    );
+
+    let when =
+        Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).split(rows[1]);
+    form.date.render(frame, when[0]);
+    form.time.render(frame, when[1]);
+
+    frame.render_widget(
+        choice("Timezone", form.zone_label(), form.field() == Field::Zone),
+        rows[2],
+    );
+
+    let resolved = match form.scheduled_at() {
+        Ok(at) if form.utc => Span::styled(
+            format!(
+                "= {} local",
+                at.with_timezone(&Local).format("%Y-%m-%d %H:%M %:z")
+            ),
+            theme::dim(),
+        ),
+        Ok(at) => Span::styled(
+            format!("= {} UTC", at.format("%Y-%m-%d %H:%M")),
+            theme::dim(),
+        ),
+        Err(e) => Span::styled(e, theme::status_off()),
+    };
+    frame.render_widget(Paragraph::new(Line::from(resolved)), rows[3]);
+
+    if form.needs_payload() {
+        form.payload.render(frame, rows[4]);
+    }
+
+    let preview = match form.payload(None) {
+        Ok(Some(payload)) => Span::styled(format!("Payload: {}", payload), theme::dim()),
+        Ok(None) => Span::styled("No payload", theme::dim()),
+        Err(_) => Span::raw(""),
+    };
+    frame.render_widget(Paragraph::new(Line::from(preview)), rows[5]);
+
+    if let Some(error) = &form.error {
+        let error = Paragraph::new(Span::styled(error.as_str(), theme::status_off()))
+            .wrap(Wrap { trim: true });
+        frame.render_widget(error, rows[6]);
+    }
+}
+
+fn action_label(action: &str) -> &str {
+    ScheduleAction::parse(action)
+        .map(ScheduleAction::label)
+        .unwrap_or(action)
+}
+
+fn status_style(status: &str) -> ratatui::style::Style {
+    match status {
+        "executed" => theme::status_on(),
+        "cancelled" | "failed" => theme::status_off(),
+        _ => theme::dim(),
+    }
+}
+
+/// Local time with its offset, followed by UTC.
+fn format_time(at: DateTime<Utc>) -> String {
+    format!(
+        "{} ({} UTC)",
+        at.with_timezone(&Local).format("%Y-%m-%d %H:%M %:z"),
+        at.format("%H:%M")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_form_builds_request() {
+        let mut form = ScheduleForm::new();
+        form.utc = true;
+        form.date.set_value("2999-01-31");
+        form.time.set_value("09:30");
+        form.action = ScheduleAction::ALL
+            .iter()
+            .position(|a| *a == ScheduleAction::SetRollout)
+            .unwrap();
+        form.payload.set_value("25");
+        let req = form.request(Some("boolean")).unwrap();
+        assert_eq!(req.action, "set_rollout");
+        assert_eq!(req.scheduled_at, "2999-01-31T09:30:00+00:00");
+        assert_eq!(req.payload, Some(json!({"rollout_percentage": 25})));
+
+        form.action = ScheduleAction::ALL
+            .iter()
+            .position(|a| *a == ScheduleAction::SetValue)
+            .unwrap();
+        form.payload.set_value("blue");
+        assert!(form.request(Some("boolean")).is_err());
+        assert_eq!(
+            form.request(Some("string")).unwrap().payload,
+            Some(json!({"value": "blue"}))
+        );
+
+        form.date.set_value("2001-01-01");
+        assert!(form.request(Some("string")).is_err());
     }
 }
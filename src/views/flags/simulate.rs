@@ -0,0 +1,200 @@
+use crate::api::types::{Environment, ManagedFlag, Variation};
+use crate::components::text_area::TextArea;
+use crate::eval::{evaluate, Evaluation};
+use crate::event::Event;
+use crate::theme;
+use crossterm::event::{KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+use ratatui::Frame;
+use std::collections::HashMap;
+
+/// "Simulate" panel on the flag detail view: paste a user context and see
+/// what the flag would serve, evaluated locally as you type.
+pub struct SimulatePanel {
+    pub environments: Vec<Environment>,
+    pub selected_env: usize,
+    /// Variations keyed by environment ID.
+    pub variations: HashMap<String, Vec<Variation>>,
+    pub editor: TextArea,
+    result: Option<Result<Evaluation, String>>,
+    preferred_env: String,
+}
+
+impl SimulatePanel {
+    pub fn new(preferred_env: &str) -> Self {
+        let mut editor = TextArea::new("User context (JSON)");
+        editor.focused = true;
+        editor.set_content("{\n  \"key\": \"user-1\"\n}");
+        Self {
+            environments: Vec::new(),
+            selected_env: 0,
+            variations: HashMap::new(),
+            editor,
+            result: None,
+            preferred_env: preferred_env.to_string(),
+        }
+    }
+
+    pub fn set_data(
+        &mut self,
+        environments: Vec<Environment>,
+        variations: HashMap<String, Vec<Variation>>,
+    ) {
+        self.selected_env = environments
+            .iter()
+            .position(|e| e.id == self.preferred_env)
+            .unwrap_or(0);
+        self.environments = environments;
+        self.variations = variations;
+    }
+
+    pub fn selected_environment(&self) -> Option<&Environment> {
+        self.environments.get(self.selected_env)
+    }
+
+    /// Handle a key press. Returns `false` when the panel should close.
+    pub fn handle_event(&mut self, event: &Event) -> bool {
+        if let Event::Key(key) = event {
+            if key.kind != KeyEventKind::Press {
+                return true;
+            }
+            match key.code {
+                KeyCode::Esc => return false,
+                KeyCode::BackTab => self.next_environment(),
+                KeyCode::Tab if key.modifiers.contains(KeyModifiers::SHIFT) => {
+                    self.next_environment()
+                }
+                _ => {
+                    self.editor.handle_event(event);
+                }
+            }
+        }
+        true
+    }
+
+    fn next_environment(&mut self) {
+        if !self.environments.is_empty() {
+            self.selected_env = (self.selected_env + 1) % self.environments.len();
+            self.preferred_env = self.environments[self.selected_env].id.clone();
+        }
+    }
+
+    /// Re-evaluate the flag against the current context and environment.
+    pub fn refresh(&mut self, flag: Option<&ManagedFlag>) {
+        let (Some(flag), Some(env)) = (flag, self.selected_environment()) else {
+            self.result = None;
+            return;
+        };
+        let Some(state) = flag
+            .environments
+            .iter()
+            .find(|e| e.environment_id == env.id)
+        else {
+            self.result = Some(Err(format!("No flag state in {}", env.name)));
+            return;
+        };
+        let variations = self
+            .variations
+            .get(&env.id)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        self.result = Some(
+            serde_json::from_str::<serde_json::Value>(&self.editor.content())
+                .map_err(|e| format!("Invalid JSON: {}", e))
+                .and_then(|ctx| {
+                    if ctx.is_object() {
+                        Ok(ctx)
+                    } else {
+                        Err("The context must be a JSON object".to_string())
+                    }
+                })
+                .and_then(|ctx| {
+                    evaluate(flag, state, variations, &ctx)
+                        .map_err(|e| format!("Invalid rules: {}", e))
+                }),
+        );
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, flag: Option<&ManagedFlag>) {
+        let cols = Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(area);
+        self.editor.render(frame, cols[0]);
+
+        let block = Block::default()
+            .title(" Simulate ")
+            .title_style(theme::heading())
+            .borders(Borders::ALL)
+            .border_style(theme::border());
+        let inner = block.inner(cols[1]);
+        frame.render_widget(block, cols[1]);
+
+        let env_name = self
+            .selected_environment()
+            .map(|e| e.name.as_str())
+            .unwrap_or("(loading)");
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled("Environment: ", theme::dim()),
+                Span::styled(env_name, theme::normal()),
+                Span::styled("  [Shift+Tab] to switch", theme::dim()),
+            ]),
+            Line::from(""),
+        ];
+
+        match &self.result {
+            None => lines.push(Line::from(Span::styled("Loading...", theme::dim()))),
+            Some(Err(e)) => lines.push(Line::from(Span::styled(e.as_str(), theme::status_off()))),
+            Some(Ok(result)) => {
+                let rollout = match (result.bucket, result.in_rollout) {
+                    (Some(bucket), Some(inside)) => {
+                        let percentage = flag
+                            .and_then(|f| {
+                                f.environments.iter().find(|e| {
+                                    Some(e.environment_id.as_str())
+                                        == self.selected_environment().map(|s| s.id.as_str())
+                                })
+                            })
+                            .map(|e| e.rollout_percentage)
+                            .unwrap_or(0);
+                        Span::styled(
+                            format!(
+                                "bucket {:.2} {} {}%",
+                                bucket,
+                                if inside { "inside" } else { "outside" },
+                                percentage
+                            ),
+                            if inside {
+                                theme::status_on()
+                            } else {
+                                theme::status_off()
+                            },
+                        )
+                    }
+                    _ => Span::styled("not consulted", theme::dim()),
+                };
+                lines.extend([
+                    Line::from(vec![
+                        Span::styled("Value:     ", theme::dim()),
+                        Span::styled(result.value.to_string(), theme::title()),
+                    ]),
+                    Line::from(vec![
+                        Span::styled("Reason:    ", theme::dim()),
+                        Span::styled(result.reason.to_string(), theme::normal()),
+                    ]),
+                    Line::from(vec![
+                        Span::styled("Variation: ", theme::dim()),
+                        Span::styled(
+                            result.variation.clone().unwrap_or_else(|| "—".to_string()),
+                            theme::normal(),
+                        ),
+                    ]),
+                    Line::from(vec![Span::styled("Rollout:   ", theme::dim()), rollout]),
+                ]);
+            }
+        }
+
+        frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
+    }
+}
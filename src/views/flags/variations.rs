@@ -1,19 +1,112 @@
-use crate::action::{Action, View};
-use crate::api::types::{Environment, Variation};
+use crate::action::{Action, ConfirmAction, View};
+use crate::api::types::{Environment, ManagedFlag, Variation, VariationInput};
+use crate::components::input_field::InputField;
+use crate::eval::{describe_value, fits_type};
 use crate::event::Event;
 use crate::theme;
-use crossterm::event::{KeyCode, KeyEventKind};
+use crossterm::event::{KeyCode, KeyEventKind, KeyModifiers};
 use ratatui::layout::{Constraint, Layout, Rect};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState};
 use ratatui::Frame;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Add/edit form for a single variation.
+struct VariationForm {
+    /// Row being edited, or `None` when adding.
+    index: Option<usize>,
+    inputs: [InputField; 4],
+    focused: usize,
+    error: Option<String>,
+}
+
+impl VariationForm {
+    fn new(index: Option<usize>, variation: Option<&VariationInput>) -> Self {
+        let mut inputs = [
+            InputField::new("Key").with_placeholder("treatment"),
+            InputField::new("Name").with_placeholder("Treatment"),
+            InputField::new("Value (JSON, bare words are strings)"),
+            InputField::new("Weight (%)"),
+        ];
+        if let Some(v) = variation {
+            inputs[0].set_value(&v.key);
+            inputs[1].set_value(&v.name);
+            inputs[2].set_value(&match &v.value {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            });
+            inputs[3].set_value(&v.weight.to_string());
+        }
+        let mut form = Self {
+            index,
+            inputs,
+            focused: 0,
+            error: None,
+        };
+        form.update_focus();
+        form
+    }
+
+    fn update_focus(&mut self) {
+        for (i, input) in self.inputs.iter_mut().enumerate() {
+            input.focused = i == self.focused;
+        }
+    }
+
+    fn to_input(&self, flag_type: Option<&str>) -> Result<VariationInput, String> {
+        let key = self.inputs[0].value.trim().to_string();
+        if key.is_empty() {
+            return Err("Key is required".to_string());
+        }
+        let raw = self.inputs[2].value.trim();
+        let value = serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()));
+        if let Some(flag_type) = flag_type {
+            if !fits_type(&value, flag_type) {
+                return Err(format!(
+                    "Value is {} but the flag type is {}",
+                    describe_value(&value),
+                    flag_type
+                ));
+            }
+        }
+        let weight: i32 = self.inputs[3]
+            .value
+            .trim()
+            .parse()
+            .map_err(|_| "Weight must be a whole number".to_string())?;
+        if !(0..=100).contains(&weight) {
+            return Err("Weight must be between 0 and 100".to_string());
+        }
+        let name = match self.inputs[1].value.trim() {
+            "" => key.clone(),
+            name => name.to_string(),
+        };
+        Ok(VariationInput {
+            key,
+            name,
+            value,
+            weight,
+        })
+    }
+}
 
 pub struct FlagVariationsView {
     pub flag_key: String,
     pub environments: Vec<Environment>,
     pub selected_env: usize,
-    pub variations: Vec<Variation>,
+    pub flag: Option<ManagedFlag>,
+    /// Saved variations by environment ID.
+    saved: HashMap<String, Vec<Variation>>,
+    /// The variations being edited for the selected environment.
+    pub variations: Vec<VariationInput>,
+    dirty: bool,
+    /// Set to Tab or Esc when that key is pressed with unsaved changes;
+    /// pressing it again discards them.
+    discard_armed: Option<KeyCode>,
+    form: Option<VariationForm>,
     state: TableState,
+    preferred_env: String,
 }
 
 impl FlagVariationsView {
@@ -22,16 +115,73 @@ impl FlagVariationsView {
             flag_key: flag_key.to_string(),
             environments: Vec::new(),
             selected_env: 0,
+            flag: None,
+            saved: HashMap::new(),
             variations: Vec::new(),
+            dirty: false,
+            discard_armed: None,
+            form: None,
             state: TableState::default(),
+            preferred_env: String::new(),
         }
     }
 
+    /// Start on `environment_id` once environments arrive.
+    pub fn with_environment(mut self, environment_id: &str) -> Self {
+        self.preferred_env = environment_id.to_string();
+        self
+    }
+
+    /// Variations for every environment, with the environments they belong to.
+    pub fn set_data(
+        &mut self,
+        environments: Vec<Environment>,
+        variations: HashMap<String, Vec<Variation>>,
+    ) {
+        self.selected_env = environments
+            .iter()
+            .position(|e| e.id == self.preferred_env)
+            .unwrap_or(0);
+        self.environments = environments;
+        self.saved = variations;
+        // Keep unsaved edits when the data is loaded again
+        if !self.dirty {
+            self.load_selected_environment();
+        }
+    }
+
+    /// Replace the saved variations of the selected environment.
     pub fn set_variations(&mut self, variations: Vec<Variation>) {
-        self.variations = variations;
-        if !self.variations.is_empty() {
-            self.state.select(Some(0));
+        if let Some(env_id) = self.selected_environment_id().map(String::from) {
+            self.saved.insert(env_id, variations);
         }
+        self.load_selected_environment();
+    }
+
+    fn load_selected_environment(&mut self) {
+        self.variations = self
+            .selected_environment_id()
+            .and_then(|env| self.saved.get(env))
+            .map(|vs| {
+                vs.iter()
+                    .map(|v| VariationInput {
+                        key: v.key.clone(),
+                        name: v.name.clone(),
+                        value: v.value.clone(),
+                        weight: v.weight,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        self.dirty = false;
+        self.discard_armed = None;
+        self.state
+            .select((!self.variations.is_empty()).then_some(0));
+    }
+
+    /// The add/edit form is open and capturing keys.
+    pub fn is_editing(&self) -> bool {
+        self.form.is_some()
     }
 
     pub fn selected_environment_id(&self) -> Option<&str> {
@@ -40,35 +190,152 @@ impl FlagVariationsView {
             .map(|e| e.id.as_str())
     }
 
+    fn flag_type(&self) -> Option<&str> {
+        self.flag.as_ref().map(|f| f.flag_type.as_str())
+    }
+
+    pub fn total_weight(&self) -> i32 {
+        self.variations.iter().map(|v| v.weight).sum()
+    }
+
+    /// Everything that would stop the variations from being saved.
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.variations.is_empty() {
+            return problems;
+        }
+        let total = self.total_weight();
+        if total != 100 {
+            problems.push(format!(
+                "Weights add up to {}%, not 100% — press [b] to balance",
+                total
+            ));
+        }
+        for (i, v) in self.variations.iter().enumerate() {
+            if self.variations[..i].iter().any(|other| other.key == v.key) {
+                problems.push(format!("Duplicate key '{}'", v.key));
+            }
+            if let Some(flag_type) = self.flag_type() {
+                if !fits_type(&v.value, flag_type) {
+                    problems.push(format!(
+                        "'{}' serves {} but the flag type is {}",
+                        v.key,
+                        describe_value(&v.value),
+                        flag_type
+                    ));
+                }
+            }
+        }
+        problems
+    }
+
+    /// The variations to save, or why they can't be.
+    pub fn validated(&self) -> Result<Vec<VariationInput>, String> {
+        if self.variations.is_empty() {
+            return Err("No variations to save — press [D] to delete them instead".to_string());
+        }
+        match self.problems().into_iter().next() {
+            Some(problem) => Err(problem),
+            None => Ok(self.variations.clone()),
+        }
+    }
+
+    /// Spread 100% as evenly as possible, giving the remainder to the first rows.
+    fn auto_balance(&mut self) {
+        let n = self.variations.len() as i32;
+        if n == 0 {
+            return;
+        }
+        for (i, v) in self.variations.iter_mut().enumerate() {
+            v.weight = 100 / n + i32::from((i as i32) < 100 % n);
+        }
+        self.dirty = true;
+    }
+
+    fn move_selected(&mut self, delta: isize) {
+        let Some(i) = self.state.selected() else {
+            return;
+        };
+        let j = i as isize + delta;
+        if j < 0 || j as usize >= self.variations.len() {
+            return;
+        }
+        self.variations.swap(i, j as usize);
+        self.state.select(Some(j as usize));
+        self.dirty = true;
+    }
+
     pub fn handle_event(&mut self, event: &Event) -> Option<Action> {
         if let Event::Key(key) = event {
             if key.kind != KeyEventKind::Press {
                 return None;
             }
+            if self.form.is_some() {
+                self.handle_form_event(event);
+                return None;
+            }
+            let armed = self.discard_armed.take();
             match key.code {
                 KeyCode::Esc | KeyCode::Backspace => {
-                    return Some(Action::Navigate(View::FlagDetail(self.flag_key.clone())));
+                    if self.dirty && armed != Some(KeyCode::Esc) {
+                        self.discard_armed = Some(KeyCode::Esc);
+                    } else {
+                        return Some(Action::Navigate(View::FlagDetail(self.flag_key.clone())));
+                    }
+                }
+                KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Some(Action::SubmitVariationsUpdate(self.flag_key.clone()));
+                }
+                KeyCode::Down | KeyCode::Char('j') if !self.variations.is_empty() => {
+                    let i = self.state.selected().unwrap_or(0);
+                    self.state.select(Some((i + 1) % self.variations.len()));
                 }
-                KeyCode::Down | KeyCode::Char('j') => {
-                    if !self.variations.is_empty() {
-                        let i = self.state.selected().unwrap_or(0);
-                        self.state.select(Some((i + 1) % self.variations.len()));
+                KeyCode::Up | KeyCode::Char('k') if !self.variations.is_empty() => {
+                    let i = self.state.selected().unwrap_or(0);
+                    let new = if i == 0 {
+                        self.variations.len() - 1
+                    } else {
+                        i - 1
+                    };
+                    self.state.select(Some(new));
+                }
+                KeyCode::Char('J') => self.move_selected(1),
+                KeyCode::Char('K') => self.move_selected(-1),
+                KeyCode::Char('a') | KeyCode::Char('c') => {
+                    self.form = Some(VariationForm::new(None, None));
+                }
+                KeyCode::Enter | KeyCode::Char('e') => {
+                    if let Some(i) = self.state.selected() {
+                        self.form = Some(VariationForm::new(Some(i), self.variations.get(i)));
                     }
                 }
-                KeyCode::Up | KeyCode::Char('k') => {
-                    if !self.variations.is_empty() {
-                        let i = self.state.selected().unwrap_or(0);
-                        let new = if i == 0 {
-                            self.variations.len() - 1
+                KeyCode::Char('d') | KeyCode::Delete => {
+                    if let Some(i) = self.state.selected() {
+                        self.variations.remove(i);
+                        self.dirty = true;
+                        self.state.select(if self.variations.is_empty() {
+                            None
                         } else {
-                            i - 1
-                        };
-                        self.state.select(Some(new));
+                            Some(i.min(self.variations.len() - 1))
+                        });
                     }
                 }
-                KeyCode::Tab => {
-                    if !self.environments.is_empty() {
+                KeyCode::Char('b') => self.auto_balance(),
+                KeyCode::Char('D') => {
+                    if let Some(environment_id) = self.selected_environment_id() {
+                        return Some(Action::ShowConfirm(ConfirmAction::DeleteVariations {
+                            flag_key: self.flag_key.clone(),
+                            environment_id: environment_id.to_string(),
+                        }));
+                    }
+                }
+                KeyCode::Tab if !self.environments.is_empty() => {
+                    if self.dirty && armed != Some(KeyCode::Tab) {
+                        self.discard_armed = Some(KeyCode::Tab);
+                    } else {
                         self.selected_env = (self.selected_env + 1) % self.environments.len();
+                        self.preferred_env = self.environments[self.selected_env].id.clone();
+                        self.load_selected_environment();
                     }
                 }
                 _ => {}
@@ -77,18 +344,68 @@ impl FlagVariationsView {
         None
     }
 
+    fn handle_form_event(&mut self, event: &Event) {
+        let Event::Key(key) = event else { return };
+        let flag_type = self.flag_type().map(String::from);
+        let Some(form) = &mut self.form else { return };
+        match key.code {
+            KeyCode::Esc => self.form = None,
+            KeyCode::Tab | KeyCode::Down => {
+                form.focused = (form.focused + 1) % form.inputs.len();
+                form.update_focus();
+            }
+            KeyCode::BackTab | KeyCode::Up => {
+                form.focused = (form.focused + form.inputs.len() - 1) % form.inputs.len();
+                form.update_focus();
+            }
+            KeyCode::Enter => match form.to_input(flag_type.as_deref()) {
+                Ok(variation) => {
+                    let index = form.index;
+                    let duplicate = self
+                        .variations
+                        .iter()
+                        .enumerate()
+                        .any(|(i, v)| v.key == variation.key && Some(i) != index);
+                    if duplicate {
+                        form.error = Some(format!("Key '{}' is already used", variation.key));
+                        return;
+                    }
+                    match index {
+                        Some(i) => self.variations[i] = variation,
+                        None => {
+                            self.variations.push(variation);
+                            self.state.select(Some(self.variations.len() - 1));
+                        }
+                    }
+                    self.dirty = true;
+                    self.form = None;
+                }
+                Err(e) => form.error = Some(e),
+            },
+            _ => {
+                form.inputs[form.focused].handle_event(event);
+                form.error = None;
+            }
+        }
+    }
+
     pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let problems = self.problems();
         let chunks = Layout::vertical([
             Constraint::Length(2),
             Constraint::Length(2),
             Constraint::Min(5),
             Constraint::Length(1),
+            Constraint::Length(problems.len().min(3) as u16),
+            Constraint::Length(if self.form.is_some() { 13 } else { 0 }),
+            Constraint::Length(1),
         ])
         .split(area);
 
         let title = Paragraph::new(Line::from(vec![
             Span::styled("← ", theme::dim()),
             Span::styled(format!("Variations: {}", self.flag_key), theme::heading()),
+            Span::styled(if self.dirty { "  (unsaved)" } else { "" }, theme::dim()),
         ]));
         frame.render_widget(title, chunks[0]);
 
@@ -97,22 +414,38 @@ impl FlagVariationsView {
             .get(self.selected_env)
             .map(|e| e.name.as_str())
             .unwrap_or("(none)");
+        let env_hint = match self.discard_armed {
+            Some(KeyCode::Esc) => Span::styled(
+                "  Unsaved changes: [Esc] again to discard them",
+                theme::warning(),
+            ),
+            Some(_) => Span::styled(
+                "  Unsaved changes: [Tab] again to discard them",
+                theme::warning(),
+            ),
+            None => Span::styled("  [Tab] to switch", theme::dim()),
+        };
         let env_line = Paragraph::new(Line::from(vec![
             Span::styled("Environment: ", theme::dim()),
             Span::styled(env_name, theme::normal()),
-            Span::styled("  [Tab] to switch", theme::dim()),
+            env_hint,
         ]));
         frame.render_widget(env_line, chunks[1]);
 
         // Variations table
+        let flag_type = self.flag_type();
         let rows: Vec<Row> = self
             .variations
             .iter()
             .map(|v| {
+                let value_style = match flag_type {
+                    Some(t) if !fits_type(&v.value, t) => theme::status_off(),
+                    _ => theme::dim(),
+                };
                 Row::new(vec![
                     Cell::from(v.key.as_str()).style(theme::normal()),
                     Cell::from(v.name.as_str()).style(theme::normal()),
-                    Cell::from(format!("{}", v.value)).style(theme::dim()),
+                    Cell::from(format!("{}", v.value)).style(value_style),
                     Cell::from(format!("{}%", v.weight)).style(theme::normal()),
                 ])
             })
@@ -139,10 +472,154 @@ impl FlagVariationsView {
 
         frame.render_stateful_widget(table, chunks[2], &mut self.state);
 
-        let hint = Paragraph::new(Line::from(vec![
-            Span::styled("[Esc]", theme::title()),
-            Span::styled(" Back", theme::dim()),
+        let total = self.total_weight();
+        let total_style = if total == 100 || self.variations.is_empty() {
+            theme::status_on()
+        } else {
+            theme::status_off()
+        };
+        let total_line = Paragraph::new(Line::from(vec![
+            Span::styled("Total weight: ", theme::dim()),
+            Span::styled(format!("{}%", total), total_style),
         ]));
-        frame.render_widget(hint, chunks[3]);
+        frame.render_widget(total_line, chunks[3]);
+
+        let problem_lines: Vec<Line> = problems
+            .iter()
+            .take(3)
+            .map(|p| Line::from(Span::styled(p.as_str(), theme::status_off())))
+            .collect();
+        frame.render_widget(Paragraph::new(problem_lines), chunks[4]);
+
+        if let Some(form) = &self.form {
+            let block = Block::default()
+                .title(if form.index.is_some() {
+                    " Edit variation "
+                } else {
+                    " Add variation "
+                })
+                .title_style(theme::heading())
+                .borders(Borders::ALL)
+                .border_style(theme::active_border());
+            let inner = block.inner(chunks[5]);
+            frame.render_widget(block, chunks[5]);
+            let fields = Layout::vertical([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+            ])
+            .split(inner);
+            let top = Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(fields[0]);
+            form.inputs[0].render(frame, top[0]);
+            form.inputs[1].render(frame, top[1]);
+            form.inputs[2].render(frame, fields[1]);
+            let bottom =
+                Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)])
+                    .split(fields[2]);
+            form.inputs[3].render(frame, bottom[0]);
+            if let Some(error) = &form.error {
+                let error = Paragraph::new(Span::styled(error.as_str(), theme::status_off()));
+                frame.render_widget(error, bottom[1].inner(ratatui::layout::Margin::new(1, 1)));
+            }
+        }
+
+        let hints = if self.form.is_some() {
+            vec![
+                Span::styled("[Tab]", theme::title()),
+                Span::styled(" Next field  ", theme::dim()),
+                Span::styled("[Enter]", theme::title()),
+                Span::styled(" Apply  ", theme::dim()),
+                Span::styled("[Esc]", theme::title()),
+                Span::styled(" Cancel", theme::dim()),
+            ]
+        } else {
+            vec![
+                Span::styled("[a]", theme::title()),
+                Span::styled(" Add  ", theme::dim()),
+                Span::styled("[Enter]", theme::title()),
+                Span::styled(" Edit  ", theme::dim()),
+                Span::styled("[d]", theme::title()),
+                Span::styled(" Remove  ", theme::dim()),
+                Span::styled("[K/J]", theme::title()),
+                Span::styled(" Move  ", theme::dim()),
+                Span::styled("[b]", theme::title()),
+                Span::styled(" Balance  ", theme::dim()),
+                Span::styled("[D]", theme::title()),
+                Span::styled(" Delete all  ", theme::dim()),
+                Span::styled("[Ctrl+S]", theme::title()),
+                Span::styled(" Save  ", theme::dim()),
+                Span::styled("[Esc]", theme::title()),
+                Span::styled(" Back", theme::dim()),
+            ]
+        };
+        frame.render_widget(Paragraph::new(Line::from(hints)), chunks[6]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyEvent;
+    use serde_json::json;
+
+    fn variation(key: &str, value: Value, weight: i32) -> VariationInput {
+        VariationInput {
+            key: key.to_string(),
+            name: key.to_string(),
+            value,
+            weight,
+        }
+    }
+
+    #[test]
+    fn test_auto_balance_sums_to_100() {
+        let mut view = FlagVariationsView::new("checkout");
+        view.variations = vec![
+            variation("a", json!(1), 10),
+            variation("b", json!(2), 10),
+            variation("c", json!(3), 10),
+        ];
+        assert!(view.validated().is_err());
+        view.auto_balance();
+        let weights: Vec<i32> = view.variations.iter().map(|v| v.weight).collect();
+        assert_eq!(weights, vec![34, 33, 33]);
+        assert!(view.validated().is_ok());
+    }
+
+    #[test]
+    fn test_value_type_is_checked() {
+        let mut form = VariationForm::new(None, None);
+        form.inputs[0].set_value("on");
+        form.inputs[2].set_value("yes");
+        form.inputs[3].set_value("50");
+        assert!(form.to_input(Some("boolean")).is_err());
+        form.inputs[2].set_value("true");
+        let input = form.to_input(Some("boolean")).unwrap();
+        assert_eq!(input.value, json!(true));
+        assert_eq!(input.name, "on");
+        form.inputs[3].set_value("150");
+        assert!(form.to_input(Some("boolean")).is_err());
+    }
+
+    #[test]
+    fn test_esc_with_unsaved_changes_needs_a_second_press() {
+        let press = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        let mut view = FlagVariationsView::new("checkout");
+        assert!(view.handle_event(&press(KeyCode::Esc)).is_some());
+
+        view.variations = vec![variation("a", json!(1), 100)];
+        view.dirty = true;
+        assert!(view.handle_event(&press(KeyCode::Backspace)).is_none());
+        assert_eq!(view.discard_armed, Some(KeyCode::Esc));
+        assert!(matches!(
+            view.handle_event(&press(KeyCode::Esc)),
+            Some(Action::Navigate(View::FlagDetail(_)))
+        ));
+
+        // Any other key in between disarms the guard
+        assert!(view.handle_event(&press(KeyCode::Esc)).is_none());
+        view.handle_event(&press(KeyCode::Char('j')));
+        assert!(view.handle_event(&press(KeyCode::Esc)).is_none());
     }
 }
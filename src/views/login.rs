@@ -22,6 +22,8 @@ enum LoginState {
 pub struct LoginView {
     state: LoginState,
     spinner_tick: u8,
+    /// Why the user is being asked to log in again, shown above the login screen.
+    notice: Option<String>,
 }
 
 impl LoginView {
@@ -29,9 +31,15 @@ impl LoginView {
         Self {
             state: LoginState::Idle,
             spinner_tick: 0,
+            notice: None,
         }
     }
 
+    pub fn with_notice(mut self, notice: &str) -> Self {
+        self.notice = Some(notice.to_string());
+        self
+    }
+
     pub fn set_error(&mut self, msg: &str) {
         self.state = LoginState::Error(msg.to_string());
     }
@@ -43,6 +51,11 @@ impl LoginView {
         };
     }
 
+    /// A device code is shown and the browser login is in progress.
+    pub fn is_waiting(&self) -> bool {
+        matches!(self.state, LoginState::WaitingForBrowser { .. })
+    }
+
     pub fn set_success(&mut self) {
         self.state = LoginState::Success;
     }
@@ -96,6 +109,18 @@ impl LoginView {
     }
 
     pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let area = match &self.notice {
+            Some(notice) if self.state != LoginState::Success => {
+                let chunks =
+                    Layout::vertical([Constraint::Length(2), Constraint::Min(0)]).split(area);
+                let notice =
+                    Paragraph::new(Line::from(Span::styled(notice.as_str(), theme::warning())))
+                        .alignment(Alignment::Center);
+                frame.render_widget(notice, chunks[0]);
+                chunks[1]
+            }
+            _ => area,
+        };
         match &self.state {
             LoginState::Idle => self.render_idle(frame, area),
             LoginState::WaitingForBrowser {
@@ -135,6 +160,8 @@ impl LoginView {
         let instructions = Paragraph::new(Line::from(vec![
             Span::styled("Enter", theme::title()),
             Span::styled(" to log in  ", theme::dim()),
+            Span::styled("C", theme::title()),
+            Span::styled(" to switch profile  ", theme::dim()),
             Span::styled("Esc", theme::title()),
             Span::styled(" to quit", theme::dim()),
         ]))